@@ -345,7 +345,7 @@ fn bench_concurrent_operations(c: &mut Criterion) {
 
     // Simulate concurrent searches
     group.bench_function("multiple_concurrent_searches", |b| {
-        let queries = vec![
+        let queries = [
             SearchQuery::with_terms(vec!["test".to_string()]),
             SearchQuery::with_phrase("lorem ipsum".to_string()),
             SearchQuery::parse_query("test AND memo"),
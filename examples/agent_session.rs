@@ -0,0 +1,117 @@
+//! Walks through the lifecycle an MCP agent goes through against memoranda:
+//! initialize, list tools, create/update/search memos, pull context, and shut
+//! down, printing every JSON-RPC request and response.
+//!
+//! Runs against an empty temporary repository:
+//!
+//! ```bash
+//! cargo run --example agent_session
+//! ```
+
+use memoranda::mcp::server::McpServer;
+use memoranda::memo::MemoStore;
+use serde_json::{json, Value};
+use std::fs;
+
+struct Session {
+    server: McpServer,
+    initialized: bool,
+    next_id: u64,
+}
+
+impl Session {
+    async fn send(&mut self, method: &str, params: Value) -> anyhow::Result<Value> {
+        self.next_id += 1;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": method,
+            "params": params
+        });
+        println!("--> {}", serde_json::to_string_pretty(&request)?);
+
+        let response = self
+            .server
+            .handle_message(request, &mut self.initialized)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("no response to {method}"))?;
+        println!("<-- {}\n", serde_json::to_string_pretty(&response)?);
+
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("{method} failed: {error}");
+        }
+        Ok(response)
+    }
+
+    /// Calls a tool and parses its text result, which is JSON for most tools.
+    async fn call(&mut self, name: &str, arguments: Value) -> anyhow::Result<Value> {
+        let response = self
+            .send(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        let text = response["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap_or_default();
+        Ok(serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string())))
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let temp_dir = tempfile::TempDir::new()?;
+    fs::create_dir(temp_dir.path().join(".git"))?;
+    fs::create_dir(temp_dir.path().join(".memoranda"))?;
+
+    let mut session = Session {
+        server: McpServer::new_with_memo_store(
+            "agent-session".to_string(),
+            MemoStore::new(temp_dir.path().to_path_buf()),
+        ),
+        initialized: false,
+        next_id: 0,
+    };
+
+    session
+        .send(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "agent-session-example", "version": "1.0.0" }
+            }),
+        )
+        .await?;
+    session.send("tools/list", json!({})).await?;
+
+    let memo = session
+        .call(
+            "create_memo",
+            json!({
+                "title": "Error Handling",
+                "content": "Use anyhow for application errors and thiserror for library errors."
+            }),
+        )
+        .await?;
+    session
+        .call(
+            "update_memo",
+            json!({
+                "id": memo["id"],
+                "content": "Use anyhow for application errors and thiserror for library errors.\nSee src/main.rs for the top-level handler."
+            }),
+        )
+        .await?;
+
+    let results = session
+        .call("search_memos", json!({ "query": "thiserror" }))
+        .await?;
+    assert_eq!(results["total"], 1);
+    session.call("get_all_context", json!({})).await?;
+
+    // The server has no shutdown request; an agent ends the session by closing
+    // stdin, and dropping the server here does the same for this walkthrough.
+    drop(session);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Uses `MemoStore` as a plain library, without the CLI or MCP server.
+//!
+//! Builds with only the storage layer enabled:
+//!
+//! ```bash
+//! cargo run --example embedded_store --no-default-features
+//! ```
+
+use memoranda::memo::MemoStore;
+use std::fs;
+
+fn main() -> anyhow::Result<()> {
+    let temp_dir = tempfile::TempDir::new()?;
+    fs::create_dir(temp_dir.path().join(".memoranda"))?;
+    let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+    let memo = store.create_memo(
+        "Error Handling".to_string(),
+        "Use anyhow for application errors and thiserror for library errors.".to_string(),
+    )?;
+    let memo = store.update_memo(
+        &memo.id,
+        format!(
+            "{}\nAdd context with .context() at I/O boundaries.",
+            memo.content
+        ),
+    )?;
+
+    let loaded = store
+        .get_memo(&memo.id)?
+        .ok_or_else(|| anyhow::anyhow!("memo {} was not found", memo.id))?;
+    assert_eq!(loaded.content, memo.content);
+
+    let results = store.search_memos("thiserror")?;
+    assert_eq!(results.len(), 1);
+    println!(
+        "Found '{}' with score {:.2}",
+        results[0].memo.title, results[0].score
+    );
+
+    println!("{}", store.get_all_context()?);
+
+    store.delete_memo(&memo.id)?;
+    assert!(store.list_memos()?.is_empty());
+
+    Ok(())
+}
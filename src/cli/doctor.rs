@@ -4,6 +4,9 @@ use std::fs;
 use std::path::Path;
 use tracing::debug;
 
+/// Saved memo versions beyond this total size are reported by the history check.
+const HISTORY_SIZE_WARN_BYTES: u64 = 50 * 1024 * 1024;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DiagnosticResult {
     Pass,
@@ -16,6 +19,9 @@ pub struct DiagnosticCheck {
     pub description: String,
     pub check_fn: fn(&DoctorCommand) -> DiagnosticResult,
     pub fix_fn: Option<fn(&DoctorCommand) -> Result<()>>,
+    /// Whether `--auto-fix` also applies `fix_fn` when the check only warns.
+    /// Errors are always fixed when there is a fix.
+    pub fix_warnings: bool,
 }
 
 pub struct DoctorCommand {
@@ -90,37 +96,29 @@ impl DoctorCommand {
                         println!("   {}", check.description.dimmed());
                     }
                 }
-                DiagnosticResult::Warning(msg) => {
+                DiagnosticResult::Warning(ref msg) => {
                     println!("{} {}", "⚠️".yellow(), check.name.yellow().bold());
                     println!("   {}", msg.yellow());
+                    if let Some(fix_fn) = self.fix_to_apply(&check, &result) {
+                        self.apply_fix(fix_fn);
+                    }
                     warnings += 1;
                 }
-                DiagnosticResult::Error(msg) => {
+                DiagnosticResult::Error(ref msg) => {
                     println!("{} {}", "❌".red(), check.name.red().bold());
                     println!("   {}", msg.red());
-                    if self.auto_fix {
-                        if let Some(fix_fn) = check.fix_fn {
-                            println!("   {}", "Attempting automatic fix...".bright_blue());
-                            match fix_fn(self) {
-                                Ok(()) => println!(
-                                    "   {} {}",
-                                    "✅".green(),
-                                    "Fix applied successfully".green()
-                                ),
-                                Err(e) => println!(
-                                    "   {} {}: {}",
-                                    "❌".red(),
-                                    "Fix failed".red(),
-                                    e.to_string().red()
-                                ),
-                            }
-                        }
+                    if let Some(fix_fn) = self.fix_to_apply(&check, &result) {
+                        self.apply_fix(fix_fn);
                     }
                     errors += 1;
                 }
             }
         }
 
+        if self.verbose {
+            self.print_store_consistency().await;
+        }
+
         println!();
         if errors == 0 && warnings == 0 {
             println!(
@@ -163,6 +161,92 @@ impl DoctorCommand {
         Ok(())
     }
 
+    /// The memo directory doctor checks, relative to the current directory.
+    fn memo_dir(&self) -> &Path {
+        Path::new(&self.settings.memo_dir_name)
+    }
+
+    /// A store over the current directory using the doctor's settings.
+    fn memo_store(&self) -> crate::memo::MemoStore {
+        crate::memo::MemoStore::new_with_settings(std::path::PathBuf::from("."), &self.settings)
+    }
+
+    /// Prints a report-only consistency scan of the store, how many files
+    /// `.memorandaignore` skips, and how much space saved memo versions use,
+    /// in verbose mode.
+    async fn print_store_consistency(&self) {
+        use colored::Colorize;
+
+        if !self.memo_dir().is_dir() {
+            return;
+        }
+
+        let store = self.memo_store();
+        println!();
+        println!("{}", "Store consistency:".bright_cyan().bold());
+        match store.verify_consistency(false).await {
+            Ok(report) => {
+                println!("   Memo files scanned: {}", report.files_scanned);
+                println!(
+                    "   Stale cache entries: {}",
+                    report.stale_cache_entries.len()
+                );
+                println!(
+                    "   Missing files still indexed: {}",
+                    report.missing_indexed.len()
+                );
+                println!("   New unindexed files: {}", report.unindexed_files.len());
+            }
+            Err(e) => println!(
+                "   {} {}",
+                "Could not verify store consistency:".red(),
+                e.to_string().red()
+            ),
+        }
+        if let Ok(ignored) = store.ignored_memo_files() {
+            println!("   Files skipped by .memorandaignore: {}", ignored.len());
+        }
+        if let Ok(usage) = store.history_usage() {
+            println!(
+                "   Saved memo versions: {} ({} bytes)",
+                usage.versions, usage.bytes
+            );
+        }
+    }
+
+    /// The fix `--auto-fix` applies for a check's result, if any.
+    fn fix_to_apply(
+        &self,
+        check: &DiagnosticCheck,
+        result: &DiagnosticResult,
+    ) -> Option<fn(&DoctorCommand) -> Result<()>> {
+        let fixable = match result {
+            DiagnosticResult::Pass => false,
+            DiagnosticResult::Warning(_) => check.fix_warnings,
+            DiagnosticResult::Error(_) => true,
+        };
+        if self.auto_fix && fixable {
+            check.fix_fn
+        } else {
+            None
+        }
+    }
+
+    fn apply_fix(&self, fix_fn: fn(&DoctorCommand) -> Result<()>) {
+        use colored::Colorize;
+
+        println!("   {}", "Attempting automatic fix...".bright_blue());
+        match fix_fn(self) {
+            Ok(()) => println!("   {} {}", "✅".green(), "Fix applied successfully".green()),
+            Err(e) => println!(
+                "   {} {}: {}",
+                "❌".red(),
+                "Fix failed".red(),
+                e.to_string().red()
+            ),
+        }
+    }
+
     fn get_diagnostic_checks() -> Vec<DiagnosticCheck> {
         vec![
             DiagnosticCheck {
@@ -171,42 +255,91 @@ impl DoctorCommand {
                     .to_string(),
                 check_fn: Self::check_rust_toolchain_diagnostic,
                 fix_fn: None,
+                fix_warnings: false,
             },
             DiagnosticCheck {
                 name: "System dependencies".to_string(),
                 description: "Checks if required system dependencies are available".to_string(),
                 check_fn: Self::check_system_dependencies_diagnostic,
                 fix_fn: None,
+                fix_warnings: false,
             },
             DiagnosticCheck {
                 name: "Git repository".to_string(),
                 description: "Checks if a git repository is present".to_string(),
                 check_fn: Self::check_git_repository_diagnostic,
                 fix_fn: None,
+                fix_warnings: false,
             },
             DiagnosticCheck {
                 name: "Memoranda directory".to_string(),
-                description: "Checks if .memoranda directory exists and is accessible".to_string(),
+                description: "Checks if the memo directory exists and is accessible".to_string(),
                 check_fn: Self::check_memoranda_directory_diagnostic,
                 fix_fn: Some(Self::fix_memoranda_directory),
+                fix_warnings: false,
             },
             DiagnosticCheck {
                 name: "File permissions".to_string(),
                 description: "Checks read/write permissions on critical directories".to_string(),
                 check_fn: Self::check_file_permissions_diagnostic,
                 fix_fn: None,
+                fix_warnings: false,
             },
             DiagnosticCheck {
                 name: "Memo file formats".to_string(),
                 description: "Validates memo file formats and content".to_string(),
                 check_fn: Self::check_memo_formats_diagnostic,
                 fix_fn: Some(Self::fix_memo_formats),
+                fix_warnings: false,
+            },
+            DiagnosticCheck {
+                name: "Memo filenames".to_string(),
+                description: "Checks that memo file names match their titles".to_string(),
+                check_fn: Self::check_memo_filenames_diagnostic,
+                fix_fn: Some(Self::fix_memo_filenames),
+                fix_warnings: true,
+            },
+            DiagnosticCheck {
+                name: "Memo titles".to_string(),
+                description: "Checks that frontmatter titles match memo file names".to_string(),
+                check_fn: Self::check_title_mismatches_diagnostic,
+                fix_fn: None,
+                fix_warnings: false,
+            },
+            DiagnosticCheck {
+                name: "Duplicate memo ids".to_string(),
+                description: "Checks that no two memo files share an id".to_string(),
+                check_fn: Self::check_duplicate_ids_diagnostic,
+                fix_fn: Some(Self::fix_duplicate_ids),
+                fix_warnings: false,
+            },
+            DiagnosticCheck {
+                name: "Memo sizes".to_string(),
+                description: "Checks for memos approaching the maximum memo file size".to_string(),
+                check_fn: Self::check_memo_sizes_diagnostic,
+                fix_fn: None,
+                fix_warnings: false,
+            },
+            DiagnosticCheck {
+                name: "Memo history".to_string(),
+                description: "Reports how much space saved memo versions use".to_string(),
+                check_fn: Self::check_memo_history_diagnostic,
+                fix_fn: None,
+                fix_warnings: false,
+            },
+            DiagnosticCheck {
+                name: "Retention policy".to_string(),
+                description: "Checks for memos older than the configured retention age".to_string(),
+                check_fn: Self::check_retention_policy_diagnostic,
+                fix_fn: None,
+                fix_warnings: false,
             },
             DiagnosticCheck {
                 name: "MCP integration".to_string(),
                 description: "Checks MCP server initialization and tool registration".to_string(),
                 check_fn: Self::check_mcp_integration_diagnostic,
                 fix_fn: None,
+                fix_warnings: false,
             },
         ]
     }
@@ -302,24 +435,31 @@ impl DoctorCommand {
     }
 
     fn check_memoranda_directory_diagnostic(&self) -> DiagnosticResult {
-        let memoranda_path = Path::new(".memoranda");
+        let memoranda_path = self.memo_dir();
 
         if memoranda_path.exists() {
             if memoranda_path.is_dir() {
                 DiagnosticResult::Pass
             } else {
-                DiagnosticResult::Error(".memoranda exists but is not a directory. Remove .memoranda file and create directory.".to_string())
+                DiagnosticResult::Error(format!("{name} exists but is not a directory. Remove the {name} file and create the directory.", name = memoranda_path.display()))
             }
         } else {
-            DiagnosticResult::Warning(".memoranda directory not found. Directory will be created automatically on first use.".to_string())
+            DiagnosticResult::Warning(format!(
+                "{} directory not found. Directory will be created automatically on first use.",
+                memoranda_path.display()
+            ))
         }
     }
 
     fn check_file_permissions_diagnostic(&self) -> DiagnosticResult {
         let current_dir_result =
             self.check_directory_permissions_diagnostic(".", "Current directory");
-        let memoranda_result = if Path::new(".memoranda").exists() {
-            self.check_directory_permissions_diagnostic(".memoranda", ".memoranda directory")
+        let memo_dir_name = &self.settings.memo_dir_name;
+        let memoranda_result = if self.memo_dir().exists() {
+            self.check_directory_permissions_diagnostic(
+                memo_dir_name,
+                &format!("{memo_dir_name} directory"),
+            )
         } else {
             DiagnosticResult::Pass
         };
@@ -336,12 +476,13 @@ impl DoctorCommand {
     }
 
     fn check_memo_formats_diagnostic(&self) -> DiagnosticResult {
-        let memoranda_path = Path::new(".memoranda");
+        let memoranda_path = self.memo_dir();
 
         if !memoranda_path.exists() {
-            return DiagnosticResult::Warning(
-                "No memo files to validate (no .memoranda directory)".to_string(),
-            );
+            return DiagnosticResult::Warning(format!(
+                "No memo files to validate (no {} directory)",
+                memoranda_path.display()
+            ));
         }
 
         match fs::read_dir(memoranda_path) {
@@ -351,7 +492,11 @@ impl DoctorCommand {
 
                 for entry in entries.flatten() {
                     let path = entry.path();
-                    if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                    let extension = path.extension().and_then(|s| s.to_str());
+                    // Markdown memos are checked by the filename and id checks
+                    if extension.is_some_and(|ext| self.settings.is_memo_file_extension(ext)) {
+                        memo_count += 1;
+                    } else if extension == Some("json") {
                         memo_count += 1;
 
                         // Enhanced validation
@@ -374,7 +519,197 @@ impl DoctorCommand {
                     ))
                 }
             }
-            Err(e) => DiagnosticResult::Error(format!("Could not read .memoranda directory: {e}")),
+            Err(e) => DiagnosticResult::Error(format!(
+                "Could not read {} directory: {e}",
+                memoranda_path.display()
+            )),
+        }
+    }
+
+    fn check_memo_filenames_diagnostic(&self) -> DiagnosticResult {
+        let memoranda_path = self.memo_dir();
+        if !memoranda_path.is_dir() {
+            return DiagnosticResult::Pass;
+        }
+
+        let store = self.memo_store();
+        match store.find_filename_drift() {
+            Ok(drift) if drift.is_empty() => DiagnosticResult::Pass,
+            Ok(drift) => {
+                let renames = drift
+                    .iter()
+                    .map(|entry| {
+                        format!(
+                            "{} -> {}",
+                            entry.file_path.display(),
+                            entry.expected_path.display()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                DiagnosticResult::Warning(format!(
+                    "{} memo file(s) named differently from their titles: {}. Set keep_filename to keep a name.",
+                    drift.len(),
+                    renames
+                ))
+            }
+            Err(e) => DiagnosticResult::Error(format!("Could not check memo filenames: {e}")),
+        }
+    }
+
+    fn check_title_mismatches_diagnostic(&self) -> DiagnosticResult {
+        use crate::memo::TitleAuthority;
+
+        let memoranda_path = self.memo_dir();
+        if !memoranda_path.is_dir() {
+            return DiagnosticResult::Pass;
+        }
+
+        let store = self.memo_store();
+        match store.find_title_mismatches() {
+            Ok(mismatches) if mismatches.is_empty() => DiagnosticResult::Pass,
+            Ok(mismatches) => {
+                let files = mismatches
+                    .iter()
+                    .map(|mismatch| {
+                        format!(
+                            "{} (title \"{}\")",
+                            mismatch.file_path.display(),
+                            mismatch.frontmatter_title
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let authority = match self.settings.title_authority {
+                    TitleAuthority::Frontmatter => {
+                        "The frontmatter title is used (title_authority: frontmatter)"
+                    }
+                    TitleAuthority::Filename => {
+                        "The file name is used as the title (title_authority: filename)"
+                    }
+                };
+                DiagnosticResult::Warning(format!(
+                    "{} memo file(s) with a frontmatter title that differs from the file name: {}. {}.",
+                    mismatches.len(),
+                    files,
+                    authority
+                ))
+            }
+            Err(e) => DiagnosticResult::Error(format!("Could not check memo titles: {e}")),
+        }
+    }
+
+    fn check_duplicate_ids_diagnostic(&self) -> DiagnosticResult {
+        let memoranda_path = self.memo_dir();
+        if !memoranda_path.is_dir() {
+            return DiagnosticResult::Pass;
+        }
+
+        let store = self.memo_store();
+        match store.find_duplicate_ids() {
+            Ok(duplicates) if duplicates.is_empty() => DiagnosticResult::Pass,
+            Ok(duplicates) => {
+                let conflicts = duplicates
+                    .iter()
+                    .map(|duplicate| {
+                        let paths = duplicate
+                            .paths
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        format!("{} ({paths})", duplicate.id)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                DiagnosticResult::Error(format!(
+                    "{} memo id(s) shared by multiple files: {}. Lookups by these ids fail until the duplicates get new ids.",
+                    duplicates.len(),
+                    conflicts
+                ))
+            }
+            Err(e) => DiagnosticResult::Error(format!("Could not check memo ids: {e}")),
+        }
+    }
+
+    fn check_memo_sizes_diagnostic(&self) -> DiagnosticResult {
+        let memoranda_path = self.memo_dir();
+        if !memoranda_path.is_dir() {
+            return DiagnosticResult::Pass;
+        }
+
+        let store = self.memo_store();
+        let memos = match store.list_memos() {
+            Ok(memos) => memos,
+            Err(e) => return DiagnosticResult::Error(format!("Could not check memo sizes: {e}")),
+        };
+
+        let large = memos
+            .iter()
+            .filter_map(|memo| {
+                let path = memo.file_path.as_ref()?;
+                let size = fs::metadata(path).ok()?.len();
+                (size > self.settings.memo_size_warn_threshold)
+                    .then(|| format!("{} ({size} bytes)", path.display()))
+            })
+            .collect::<Vec<_>>();
+
+        if large.is_empty() {
+            DiagnosticResult::Pass
+        } else {
+            DiagnosticResult::Warning(format!(
+                "{} memo(s) larger than {} bytes (maximum {} bytes): {}. Consider splitting them into smaller memos.",
+                large.len(),
+                self.settings.memo_size_warn_threshold,
+                self.settings.max_memo_file_size,
+                large.join(", ")
+            ))
+        }
+    }
+
+    fn check_memo_history_diagnostic(&self) -> DiagnosticResult {
+        let memoranda_path = self.memo_dir();
+        if !memoranda_path.is_dir() {
+            return DiagnosticResult::Pass;
+        }
+
+        let store = self.memo_store();
+        let usage = match store.history_usage() {
+            Ok(usage) => usage,
+            Err(e) => {
+                return DiagnosticResult::Error(format!("Could not measure memo history: {e}"))
+            }
+        };
+
+        if usage.bytes <= HISTORY_SIZE_WARN_BYTES {
+            DiagnosticResult::Pass
+        } else {
+            DiagnosticResult::Warning(format!(
+                "{} saved memo version(s) use {} bytes in {}/.history (more than {HISTORY_SIZE_WARN_BYTES} bytes). Lower memo_history_max_versions or delete old versions.",
+                usage.versions, usage.bytes, memoranda_path.display()
+            ))
+        }
+    }
+
+    fn check_retention_policy_diagnostic(&self) -> DiagnosticResult {
+        use crate::memo::RetentionPolicy;
+
+        let memoranda_path = self.memo_dir();
+        if !memoranda_path.is_dir() {
+            return DiagnosticResult::Pass;
+        }
+
+        let store = self.memo_store();
+        let policy = RetentionPolicy::from(&self.settings);
+        match store.preview_retention_policy(&policy) {
+            Ok(report) if report.affected.is_empty() => DiagnosticResult::Pass,
+            Ok(report) => DiagnosticResult::Warning(format!(
+                "{} memo(s) not updated in {} days (retention action: {}). Run 'memoranda maintain --dry-run' to review them.",
+                report.affected.len(),
+                self.settings.retention_max_age_days,
+                report.action
+            )),
+            Err(e) => DiagnosticResult::Error(format!("Could not evaluate retention policy: {e}")),
         }
     }
 
@@ -455,15 +790,16 @@ impl DoctorCommand {
         }
     }
 
-    /// Fixes the .memoranda directory by creating it if missing or removing it if it's a file.
+    /// Fixes the memo directory (`memo_dir_name`) by creating it if missing or
+    /// removing it if it's a file.
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - Cannot remove existing .memoranda file
-    /// - Cannot create .memoranda directory due to permissions or filesystem issues
+    /// - Cannot remove an existing file in the memo directory's place
+    /// - Cannot create the memo directory due to permissions or filesystem issues
     fn fix_memoranda_directory(&self) -> Result<()> {
-        let memoranda_path = Path::new(".memoranda");
+        let memoranda_path = self.memo_dir();
 
         if memoranda_path.exists() && !memoranda_path.is_dir() {
             fs::remove_file(memoranda_path)?;
@@ -476,15 +812,47 @@ impl DoctorCommand {
         Ok(())
     }
 
+    /// Renames memo files to match their titles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memo files cannot be listed or renamed.
+    fn fix_memo_filenames(&self) -> Result<()> {
+        let store = self.memo_store();
+        for entry in store.normalize_filenames(false)? {
+            println!(
+                "   📝 Renamed {} to {}",
+                entry.file_path.display(),
+                entry.expected_path.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    fn fix_duplicate_ids(&self) -> Result<()> {
+        let store = self.memo_store();
+        for reassignment in store.fix_duplicate_ids()? {
+            println!(
+                "   🆔 Gave {} new id {} (was {})",
+                reassignment.file_path.display(),
+                reassignment.new_id,
+                reassignment.old_id
+            );
+        }
+
+        Ok(())
+    }
+
     /// Fixes invalid memo file formats by renaming files with non-ULID names.
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - Cannot read the .memoranda directory
+    /// - Cannot read the memo directory
     /// - File operations (rename, validation) fail due to permissions or I/O issues
     fn fix_memo_formats(&self) -> Result<()> {
-        let memoranda_path = Path::new(".memoranda");
+        let memoranda_path = self.memo_dir();
 
         if !memoranda_path.exists() {
             return Ok(()); // Nothing to fix
@@ -515,7 +883,12 @@ impl DoctorCommand {
                     }
                 }
             }
-            Err(_) => return Err(anyhow::anyhow!("Could not read .memoranda directory")),
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "Could not read {} directory",
+                    memoranda_path.display()
+                ))
+            }
         }
 
         if fixes_applied > 0 {
@@ -693,6 +1066,26 @@ mod tests {
         assert!(matches!(result, DiagnosticResult::Error(_)));
     }
 
+    #[test]
+    fn test_memoranda_directory_custom_name() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let mut doctor = DoctorCommand::new();
+        doctor.settings.memo_dir_name = "notes".to_string();
+
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+        match doctor.check_memoranda_directory_diagnostic() {
+            DiagnosticResult::Warning(message) => assert!(message.starts_with("notes directory")),
+            other => panic!("expected a warning, got {other:?}"),
+        }
+        doctor.fix_memoranda_directory().unwrap();
+        assert!(Path::new("notes").is_dir());
+        assert_eq!(
+            doctor.check_memoranda_directory_diagnostic(),
+            DiagnosticResult::Pass
+        );
+    }
+
     #[test]
     fn test_git_repository_exists() {
         let temp_dir = TempDir::new().unwrap();
@@ -766,6 +1159,23 @@ mod tests {
         assert_eq!(result, DiagnosticResult::Pass);
     }
 
+    #[test]
+    fn test_memo_formats_counts_markdown_memos() {
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_path = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_path).unwrap();
+        fs::write(memoranda_path.join("Notes.markdown"), "# Notes").unwrap();
+
+        let mut doctor = DoctorCommand::new();
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+        let result = doctor.check_memo_formats_diagnostic();
+        assert_eq!(result, DiagnosticResult::Pass);
+
+        doctor.settings.memo_file_extensions = vec!["md".to_string()];
+        let result = doctor.check_memo_formats_diagnostic();
+        assert!(matches!(result, DiagnosticResult::Warning(_)));
+    }
+
     #[test]
     fn test_memo_formats_with_invalid_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -881,6 +1291,155 @@ mod tests {
         assert!(matches!(result, DiagnosticResult::Error(_)));
     }
 
+    #[test]
+    fn test_retention_policy_diagnostic() {
+        let temp_dir = TempDir::new().unwrap();
+        let doctor = DoctorCommand::new();
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+
+        // No memoranda directory means nothing to retain
+        assert_eq!(
+            doctor.check_retention_policy_diagnostic(),
+            DiagnosticResult::Pass
+        );
+
+        // A memo older than the retention age produces a warning
+        fs::create_dir(".memoranda").unwrap();
+        let mut memo = crate::memo::Memo::new("Old".to_string(), "old".to_string()).unwrap();
+        memo.updated_at = chrono::Utc::now()
+            - chrono::Duration::days(doctor.settings.retention_max_age_days as i64 + 1);
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(".memoranda/Old.md", format!("---\n{frontmatter}\n---\nold")).unwrap();
+
+        assert!(matches!(
+            doctor.check_retention_policy_diagnostic(),
+            DiagnosticResult::Warning(_)
+        ));
+    }
+
+    #[test]
+    fn test_memo_filenames_diagnostic_and_fix() {
+        let temp_dir = TempDir::new().unwrap();
+        let doctor = DoctorCommand::new();
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+
+        fs::create_dir(".memoranda").unwrap();
+        let memo = crate::memo::Memo::new("New architecture plan".to_string(), "plan".to_string())
+            .unwrap();
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(
+            ".memoranda/Old plan.md",
+            format!("---\n{frontmatter}\n---\nplan"),
+        )
+        .unwrap();
+
+        match doctor.check_memo_filenames_diagnostic() {
+            DiagnosticResult::Warning(msg) => assert!(msg.contains("New architecture plan.md")),
+            other => panic!("expected a warning, got {other:?}"),
+        }
+
+        doctor.fix_memo_filenames().unwrap();
+        assert!(Path::new(".memoranda/New architecture plan.md").exists());
+        assert_eq!(
+            doctor.check_memo_filenames_diagnostic(),
+            DiagnosticResult::Pass
+        );
+    }
+
+    #[test]
+    fn test_title_mismatches_diagnostic() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut doctor = DoctorCommand::new();
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+
+        fs::create_dir(".memoranda").unwrap();
+        assert_eq!(
+            doctor.check_title_mismatches_diagnostic(),
+            DiagnosticResult::Pass
+        );
+
+        let memo = crate::memo::Memo::new("Release plan".to_string(), "plan".to_string()).unwrap();
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(
+            ".memoranda/Launch checklist.md",
+            format!("---\n{frontmatter}\n---\nplan"),
+        )
+        .unwrap();
+
+        match doctor.check_title_mismatches_diagnostic() {
+            DiagnosticResult::Warning(msg) => {
+                assert!(msg.contains("Launch checklist.md"));
+                assert!(msg.contains("frontmatter title is used"));
+            }
+            other => panic!("expected a warning, got {other:?}"),
+        }
+
+        doctor.settings.title_authority = crate::memo::TitleAuthority::Filename;
+        match doctor.check_title_mismatches_diagnostic() {
+            DiagnosticResult::Warning(msg) => assert!(msg.contains("file name is used")),
+            other => panic!("expected a warning, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_memo_sizes_diagnostic() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut doctor = DoctorCommand::new();
+        doctor.settings.memo_size_warn_threshold = 1_000;
+        doctor.settings.max_memo_file_size = 10_000;
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+
+        fs::create_dir(".memoranda").unwrap();
+        let memo = crate::memo::Memo::new("Small".to_string(), "small".to_string()).unwrap();
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(
+            ".memoranda/Small.md",
+            format!("---\n{frontmatter}\n---\nsmall"),
+        )
+        .unwrap();
+        assert_eq!(doctor.check_memo_sizes_diagnostic(), DiagnosticResult::Pass);
+
+        let content = "x".repeat(5_000);
+        let memo = crate::memo::Memo::new("Large".to_string(), content.clone()).unwrap();
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(
+            ".memoranda/Large.md",
+            format!("---\n{frontmatter}\n---\n{content}"),
+        )
+        .unwrap();
+
+        match doctor.check_memo_sizes_diagnostic() {
+            DiagnosticResult::Warning(msg) => {
+                assert!(msg.contains("Large.md"));
+                assert!(!msg.contains("Small.md"));
+            }
+            other => panic!("expected a warning, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_memo_history_diagnostic() {
+        let temp_dir = TempDir::new().unwrap();
+        let doctor = DoctorCommand::new();
+        let _guard = TestDirectoryGuard::new(temp_dir.path());
+
+        fs::create_dir(".memoranda").unwrap();
+        assert_eq!(
+            doctor.check_memo_history_diagnostic(),
+            DiagnosticResult::Pass
+        );
+
+        let history = Path::new(".memoranda/.history/01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        fs::create_dir_all(history).unwrap();
+        let file = fs::File::create(history.join("20240101T000000.000000Z.md")).unwrap();
+        file.set_len(HISTORY_SIZE_WARN_BYTES + 1).unwrap();
+
+        match doctor.check_memo_history_diagnostic() {
+            DiagnosticResult::Warning(msg) => assert!(msg.contains("1 saved memo version")),
+            other => panic!("expected a warning, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_doctor_with_options() {
         let doctor = DoctorCommand::with_options(true, true);
@@ -888,6 +1447,38 @@ mod tests {
         assert!(doctor.auto_fix);
     }
 
+    #[test]
+    fn test_auto_fix_leaves_warnings_alone_unless_the_check_opts_in() {
+        let mut doctor = DoctorCommand::with_options(false, true);
+        let warning = DiagnosticResult::Warning("drift".to_string());
+        let error = DiagnosticResult::Error("broken".to_string());
+        let mut check = DiagnosticCheck {
+            name: "Example".to_string(),
+            description: "An example check".to_string(),
+            check_fn: |_| DiagnosticResult::Pass,
+            fix_fn: Some(|_| Ok(())),
+            fix_warnings: false,
+        };
+
+        assert!(doctor
+            .fix_to_apply(&check, &DiagnosticResult::Pass)
+            .is_none());
+        assert!(doctor.fix_to_apply(&check, &warning).is_none());
+        assert!(doctor.fix_to_apply(&check, &error).is_some());
+        check.fix_warnings = true;
+        assert!(doctor.fix_to_apply(&check, &warning).is_some());
+        doctor.auto_fix = false;
+        assert!(doctor.fix_to_apply(&check, &error).is_none());
+
+        // A missing memo directory, say, is only reported
+        let opted_in: Vec<_> = DoctorCommand::get_diagnostic_checks()
+            .into_iter()
+            .filter(|check| check.fix_warnings)
+            .map(|check| check.name)
+            .collect();
+        assert_eq!(opted_in, vec!["Memo filenames"]);
+    }
+
     #[test]
     fn test_fix_memoranda_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -17,12 +17,16 @@ impl HelpCommand {
         println!("    memoranda [COMMAND]");
         println!();
         println!("Commands:");
-        println!("    doctor    Check system health and configuration");
-        println!("    serve     Start the MCP server on stdio");
+        println!("    doctor      Check system health and configuration");
+        println!("    import-dir  Import a directory of markdown files as memos");
+        println!("    maintain    Apply the memo retention policy");
+        println!("    serve       Start the MCP server on stdio");
         println!();
         println!("EXAMPLES:");
-        println!("    memoranda doctor           # Run diagnostics");
-        println!("    memoranda serve            # Start MCP server");
+        println!("    memoranda doctor              # Run diagnostics");
+        println!("    memoranda maintain --dry-run  # Preview retention changes");
+        println!("    memoranda import-dir ./notes  # Import existing notes");
+        println!("    memoranda serve               # Start MCP server");
         println!();
         println!("MCP INTEGRATION:");
         println!("To use with Claude Code, add this to your MCP settings:");
@@ -0,0 +1,89 @@
+use crate::config::Settings;
+use crate::memo::{find_git_root, ImportReport, MemoStore};
+use anyhow::Result;
+use std::path::PathBuf;
+use tracing::debug;
+
+pub struct ImportCommand {
+    /// Directory whose markdown files are imported.
+    pub path: PathBuf,
+    /// Tag added to every imported memo.
+    pub tag: Option<String>,
+    pub settings: Settings,
+}
+
+impl ImportCommand {
+    #[must_use]
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tag: None,
+            settings: Settings::new_or_default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_tag(mut self, tag: Option<String>) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Imports every markdown file under `path` into the memo store at the git
+    /// root. A relative `path` is taken relative to the current directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memo store cannot be located or `path` is not a
+    /// directory. Files that fail to import are listed in the report instead.
+    pub async fn run(&self) -> Result<()> {
+        debug!(path = %self.path.display(), tag = ?self.tag, "Running import-dir command");
+        let store = MemoStore::new_with_settings(find_git_root()?, &self.settings);
+        let path = std::env::current_dir()?.join(&self.path);
+        let report = store.import_directory(&path, self.tag.as_deref())?;
+        Self::print_report(&report);
+        Ok(())
+    }
+
+    fn print_report(report: &ImportReport) {
+        use colored::Colorize;
+
+        println!("{}", "Memoranda Import".bright_cyan().bold());
+        println!("{}", "================".bright_cyan());
+        println!();
+
+        for memo in &report.imported {
+            println!(
+                "{} {} as \"{}\"",
+                "Imported".green(),
+                memo.path.display(),
+                memo.title
+            );
+        }
+        for issue in &report.skipped {
+            println!(
+                "{} {} ({})",
+                "Skipped".yellow(),
+                issue.path.display(),
+                issue.reason
+            );
+        }
+        for issue in &report.failed {
+            println!(
+                "{} {}: {}",
+                "Failed".red(),
+                issue.path.display(),
+                issue.reason
+            );
+        }
+
+        if !report.imported.is_empty() || !report.skipped.is_empty() || !report.failed.is_empty() {
+            println!();
+        }
+        println!(
+            "{} imported, {} skipped, {} failed",
+            report.imported.len(),
+            report.skipped.len(),
+            report.failed.len()
+        );
+    }
+}
@@ -0,0 +1,271 @@
+use crate::config::Settings;
+use crate::memo::{
+    find_git_root, CompactOptions, CompactReport, MemoStore, RetentionAction, RetentionPolicy,
+    RetentionReport,
+};
+use anyhow::Result;
+use tracing::debug;
+
+pub struct MaintainCommand {
+    pub dry_run: bool,
+    /// Also prune internal artifacts such as expired trash.
+    pub compact: bool,
+    pub settings: Settings,
+}
+
+impl Default for MaintainCommand {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            compact: false,
+            settings: Settings::new_or_default(),
+        }
+    }
+}
+
+impl MaintainCommand {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_options(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Applies the configured retention policy to the memo store at the git root,
+    /// then compacts internal artifacts if `compact` is set.
+    ///
+    /// With `dry_run` set, lists exactly which memos would be archived or trashed
+    /// (and which artifacts would be purged) without modifying any files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memo store cannot be located or a memo file cannot be moved.
+    pub async fn run(&self) -> Result<()> {
+        debug!(
+            dry_run = self.dry_run,
+            compact = self.compact,
+            "Running maintain command"
+        );
+        let store = MemoStore::new_with_settings(find_git_root()?, &self.settings);
+        let report = self.apply(&store)?;
+        Self::print_report(&report);
+
+        if self.compact {
+            let compact_report = self.run_compaction(&store)?;
+            println!();
+            Self::print_compact_report(&compact_report);
+        }
+        Ok(())
+    }
+
+    /// Applies (or previews, on a dry run) the retention policy to the given store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if listing memos or moving a memo file fails.
+    pub fn apply(&self, store: &MemoStore) -> Result<RetentionReport> {
+        let policy = RetentionPolicy::from(&self.settings);
+        let report = if self.dry_run {
+            store.preview_retention_policy(&policy)?
+        } else {
+            store.apply_retention_policy(&policy)?
+        };
+        Ok(report)
+    }
+
+    /// Compacts (or previews, on a dry run) the store's internal artifacts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an artifact directory cannot be read or a file cannot be removed.
+    pub fn run_compaction(&self, store: &MemoStore) -> Result<CompactReport> {
+        let options = CompactOptions::from(&self.settings).with_dry_run(self.dry_run);
+        Ok(store.compact(&options)?)
+    }
+
+    fn print_report(report: &RetentionReport) {
+        use colored::Colorize;
+
+        println!(
+            "{}",
+            "Memoranda Maintenance - Retention Policy"
+                .bright_cyan()
+                .bold()
+        );
+        println!(
+            "{}",
+            "========================================".bright_cyan()
+        );
+        println!();
+
+        let verb = match (report.action, report.dry_run) {
+            (RetentionAction::Report, _) => "Expired",
+            (RetentionAction::Archive, true) => "Would archive",
+            (RetentionAction::Archive, false) => "Archived",
+            (RetentionAction::Trash, true) => "Would trash",
+            (RetentionAction::Trash, false) => "Trashed",
+        };
+
+        for entry in &report.affected {
+            let age = chrono::Utc::now()
+                .signed_duration_since(entry.updated_at)
+                .num_days();
+            match &entry.destination {
+                Some(destination) => println!(
+                    "{} {} ({} days old) -> {}",
+                    verb.yellow(),
+                    entry.file_path.display(),
+                    age,
+                    destination.display()
+                ),
+                None => println!(
+                    "{} {} ({} days old)",
+                    verb.yellow(),
+                    entry.file_path.display(),
+                    age
+                ),
+            }
+        }
+
+        if !report.affected.is_empty() {
+            println!();
+        }
+        println!(
+            "Scanned {} memo(s): {} {}, {} exempt",
+            report.scanned,
+            report.affected.len(),
+            verb.to_lowercase(),
+            report.exempt
+        );
+        if report.dry_run && !report.affected.is_empty() {
+            println!(
+                "Run {} to apply these changes",
+                "'memoranda maintain'".bright_white()
+            );
+        }
+    }
+
+    fn print_compact_report(report: &CompactReport) {
+        use colored::Colorize;
+
+        println!(
+            "{}",
+            "Memoranda Maintenance - Compaction".bright_cyan().bold()
+        );
+        println!("{}", "==================================".bright_cyan());
+        println!();
+
+        let verb = if report.dry_run {
+            "Would purge"
+        } else {
+            "Purged"
+        };
+        for entry in report.trash_purged.iter().chain(&report.temp_files_purged) {
+            println!(
+                "{} {} ({} bytes)",
+                verb.yellow(),
+                entry.path.display(),
+                entry.bytes
+            );
+        }
+
+        if !report.trash_purged.is_empty() || !report.temp_files_purged.is_empty() {
+            println!();
+        }
+        println!(
+            "Scanned {} trashed memo(s): {} {}, {} orphaned temporary file(s) {}, {} bytes reclaimed",
+            report.trash_scanned,
+            report.trash_purged.len(),
+            verb.to_lowercase(),
+            report.temp_files_purged.len(),
+            verb.to_lowercase(),
+            report.bytes_reclaimed()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn store_with_stale_memo() -> (TempDir, MemoStore, std::path::PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let mut memo = store
+            .create_memo("Stale".to_string(), "old notes".to_string())
+            .unwrap();
+        let file_path = memo.file_path.take().unwrap();
+        memo.updated_at = chrono::Utc::now() - chrono::Duration::days(365);
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(
+            &file_path,
+            format!("---\n{frontmatter}\n---\n{}", memo.content),
+        )
+        .unwrap();
+
+        (temp_dir, store, file_path)
+    }
+
+    #[test]
+    fn test_maintain_dry_run_leaves_files() {
+        let (_temp_dir, store, file_path) = store_with_stale_memo();
+        let command = MaintainCommand::with_options(true);
+
+        let report = command.apply(&store).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.affected.len(), 1);
+        assert!(file_path.exists());
+    }
+
+    #[test]
+    fn test_maintain_applies_policy() {
+        let (_temp_dir, store, file_path) = store_with_stale_memo();
+        let command = MaintainCommand::new();
+
+        let report = command.apply(&store).unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.affected.len(), 1);
+        assert!(!file_path.exists());
+        assert!(report.affected[0].destination.as_ref().unwrap().exists());
+    }
+
+    #[test]
+    fn test_maintain_compact_purges_expired_trash() {
+        let (temp_dir, store, _file_path) = store_with_stale_memo();
+        let trash_dir = temp_dir.path().join(".memoranda").join(".trash");
+        fs::create_dir_all(&trash_dir).unwrap();
+        let expired = trash_dir.join("Gone.20200101T000000Z.md");
+        fs::write(&expired, "old").unwrap();
+
+        let preview = MaintainCommand::with_options(true)
+            .with_compact(true)
+            .run_compaction(&store)
+            .unwrap();
+        assert_eq!(preview.trash_purged.len(), 1);
+        assert!(expired.exists());
+
+        let report = MaintainCommand::new()
+            .with_compact(true)
+            .run_compaction(&store)
+            .unwrap();
+        assert_eq!(report.bytes_reclaimed(), 3);
+        assert!(!expired.exists());
+        assert_eq!(store.list_memos().unwrap().len(), 1);
+    }
+}
@@ -1,5 +1,9 @@
 pub mod doctor;
 pub mod help;
+pub mod import_dir;
+pub mod maintain;
 
 pub use doctor::*;
 pub use help::*;
+pub use import_dir::*;
+pub use maintain::*;
@@ -1,19 +1,61 @@
 use crate::error::{MemorandaError, Result};
+use crate::memo::{
+    is_valid_date_format, ContextTimezone, FrontmatterFormat, LineEnding, RetentionAction,
+    TitleAuthority, DEFAULT_CONTEXT_DATE_FORMAT, MAX_CACHE_TTL_SECONDS, MAX_TAG_LENGTH,
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::info;
 
+/// File name of both the repo-local and the user settings file.
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+
 // Default configuration constants
 const DEFAULT_DATA_DIR: &str = "./data";
+const DEFAULT_MEMO_DIR_NAME: &str = ".memoranda";
+const DEFAULT_MEMO_FILE_EXTENSIONS: &[&str] = &["md", "markdown"];
 const DEFAULT_LOG_LEVEL: &str = "info";
 const DEFAULT_MCP_SERVER_PORT: u16 = 8080;
 const DEFAULT_MINIMUM_RUST_VERSION: &str = "1.70.0";
 const DEFAULT_MAX_MEMO_FILE_SIZE: u64 = 1_000_000; // 1MB
+const DEFAULT_MEMO_SIZE_WARN_THRESHOLD: u64 = DEFAULT_MAX_MEMO_FILE_SIZE / 2;
+const DEFAULT_FILE_LOCK_TIMEOUT_MS: u64 = 5_000;
 
 // Search configuration constants
 const DEFAULT_RECENCY_BOOST_DAYS: f64 = 365.0;
 const DEFAULT_SNIPPET_LENGTH: usize = 100;
 const DEFAULT_SNIPPET_CONTEXT_PADDING: usize = 2;
+const DEFAULT_SNIPPET_MIN_TERM_SCORE: f64 = 1.0;
+const DEFAULT_SEARCH_INDEX_DEBOUNCE_MS: u64 = 500;
+const DEFAULT_SEARCH_REGEX_MAX_LENGTH: usize = 1000;
+const DEFAULT_SEARCH_REGEX_SIZE_LIMIT: usize = 1 << 20; // 1MB
+const DEFAULT_SEARCH_REGEX_DFA_SIZE_LIMIT: usize = 1 << 20; // 1MB
+const DEFAULT_SEARCH_CACHE_SIZE: usize = 64;
+
+// Memo cache configuration constants
+const DEFAULT_MEMO_CACHE_SIZE: u64 = 1000;
+const DEFAULT_METADATA_CACHE_SIZE: u64 = 5000;
+const DEFAULT_MEMO_TTL_SECONDS: u64 = 3600;
+const DEFAULT_METADATA_TTL_MULTIPLIER: u64 = 2;
+
+// Retention configuration constants
+const DEFAULT_RETENTION_MAX_AGE_DAYS: u64 = 180;
+const DEFAULT_RETENTION_EXEMPT_TAGS: &[&str] = &["keep", "pinned"];
+const DEFAULT_TRASH_RETENTION_DAYS: u64 = 30;
+/// Longest retention window accepted, about a century.
+const MAX_RETENTION_DAYS: u64 = 36_500;
+/// Longest interval between scheduled retention runs, the same century.
+const MAX_RETENTION_INTERVAL_HOURS: u64 = MAX_RETENTION_DAYS * 24;
+
+// Context configuration constants
+const DEFAULT_MAX_MEMO_CONTEXT_CHARS: usize = 50_000;
+
+// Link configuration constants
+const DEFAULT_LINK_PATH_MAX_DEPTH: usize = 6;
+
+// MCP configuration constants
+const DEFAULT_ERROR_LOG_DEDUPE_WINDOW_MS: u64 = 10_000;
+const DEFAULT_CALL_HISTORY_SIZE: usize = 100;
 
 // MCP tool configuration
 const DEFAULT_EXPECTED_TOOLS: &[&str] = &[
@@ -38,35 +80,412 @@ const MIN_MEMO_FILE_SIZE: u64 = 1;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub data_dir: PathBuf,
+    /// Name of the directories memos are kept in, found anywhere under the
+    /// project root.
+    #[serde(default = "default_memo_dir_name")]
+    pub memo_dir_name: String,
+    /// Memo directory new memos are written to, relative to the project root.
+    /// May name the memo directory or the directory containing it. When unset,
+    /// the memo directory closest to the working directory is used.
+    #[serde(default)]
+    pub default_memo_dir: Option<String>,
+    /// When a memo is created in a directory that has no memo directory,
+    /// create one there instead of failing, so memos can be organized into
+    /// new project folders as they come up.
+    #[serde(default)]
+    pub auto_create_scope_dir: bool,
+    /// Extensions, without the dot, of the files in memo directories that are
+    /// memos. New memo files get the first.
+    #[serde(default = "default_memo_file_extensions")]
+    pub memo_file_extensions: Vec<String>,
     pub log_level: String,
     pub mcp_server_port: u16,
     pub minimum_rust_version: String,
     pub max_memo_file_size: u64,
+    /// Memos larger than this many bytes are reported by `memoranda doctor` as
+    /// candidates for splitting, well before they hit `max_memo_file_size`.
+    #[serde(default = "default_memo_size_warn_threshold")]
+    pub memo_size_warn_threshold: u64,
+    /// Creates and updates with content longer than this many lines are rejected;
+    /// `None` allows any length.
+    #[serde(default)]
+    pub max_memo_lines: Option<usize>,
+    /// Hold a lock file in a memo directory while writing or deleting a memo
+    /// in it, so processes sharing the store never clobber each other's
+    /// writes. Can be turned off for read-only or single-process use.
+    #[serde(default = "default_file_locking")]
+    pub file_locking: bool,
+    /// How long a write waits for another process to release its lock
+    /// before failing.
+    #[serde(default = "default_file_lock_timeout_ms")]
+    pub file_lock_timeout_ms: u64,
+    /// Tags longer than this many characters are rejected.
+    #[serde(default = "default_max_tag_length")]
+    pub max_tag_length: usize,
+    /// Regex every tag must match as a whole, such as `[a-z0-9_-]+`; `None`
+    /// allows any tag without whitespace, commas or control characters.
+    #[serde(default)]
+    pub tag_charset: Option<String>,
+    /// Normalize tags that would be rejected instead: lowercase them and join
+    /// their words with hyphens. Tags still invalid afterwards are rejected.
+    #[serde(default)]
+    pub tag_auto_slugify: bool,
 
     // Search configuration
     pub search_recency_boost_days: f64,
     pub search_snippet_length: usize,
     pub search_snippet_context_padding: usize,
+    /// Minimum score a query term must contribute (2 for a title match, 1 for a
+    /// content match) before a snippet is generated for it.
+    #[serde(default = "default_search_snippet_min_term_score")]
+    pub search_snippet_min_term_score: f64,
+    /// Results scoring below this floor are returned without snippets.
+    #[serde(default)]
+    pub search_snippet_relevance_floor: f64,
+    /// Minimum time between search index rebuilds; changes made sooner are
+    /// indexed by a later search.
+    #[serde(default = "default_search_index_debounce_ms")]
+    pub search_index_debounce_ms: u64,
+    /// Regex and wildcard patterns longer than this many characters are rejected
+    /// before compilation.
+    #[serde(default = "default_search_regex_max_length")]
+    pub search_regex_max_length: usize,
+    /// Maximum compiled size in bytes of a search regex.
+    #[serde(default = "default_search_regex_size_limit")]
+    pub search_regex_size_limit: usize,
+    /// Maximum size in bytes of the lazy DFA cache used to run a search regex.
+    #[serde(default = "default_search_regex_dfa_size_limit")]
+    pub search_regex_dfa_size_limit: usize,
+    /// Reduce words to their stems when indexing and matching search terms,
+    /// so "running" also finds "run" and "runs".
+    #[serde(default)]
+    pub search_enable_stemming: bool,
+    /// Words left out of the search index and dropped from search queries.
+    #[serde(default)]
+    pub search_stop_words: Vec<String>,
+    /// Most single-character edits a fuzzy search term (`term~`) may be away
+    /// from a word in a memo and still match it; 0 keeps fuzzy terms exact.
+    #[serde(default)]
+    pub search_max_edit_distance: usize,
+    /// Multiplier on the score of query terms found in a memo's title.
+    #[serde(default = "default_search_boost")]
+    pub search_title_boost: f64,
+    /// Multiplier on the score of query terms found in a memo's content.
+    #[serde(default = "default_search_boost")]
+    pub search_content_boost: f64,
+    /// Multiplier on the extra score recently created memos get; 0 turns the
+    /// recency boost off.
+    #[serde(default = "default_search_boost")]
+    pub search_recency_boost: f64,
+    /// Number of distinct queries whose results are cached until the next
+    /// change to the store; 0 disables the cache.
+    #[serde(default = "default_search_cache_size")]
+    pub search_cache_size: usize,
+    /// Most memos kept in the in-memory memo cache.
+    #[serde(default = "default_memo_cache_size")]
+    pub memo_cache_size: u64,
+    /// Most memo file metadata entries kept in memory.
+    #[serde(default = "default_metadata_cache_size")]
+    pub metadata_cache_size: u64,
+    /// Seconds a cached memo is kept before it is read from its file again.
+    #[serde(default = "default_memo_ttl_seconds")]
+    pub memo_ttl_seconds: u64,
+    /// Cached file metadata is kept this many times as long as memos.
+    #[serde(default = "default_metadata_ttl_multiplier")]
+    pub metadata_ttl_multiplier: u64,
+    /// Most bytes memos evicted from the in-memory memo cache may take in
+    /// `.cache/` inside the primary memo directory, from where they are read back instead of being
+    /// searched for among the memo files; 0 disables the disk tier.
+    #[serde(default)]
+    pub disk_cache_max_bytes: u64,
 
     // MCP configuration
     pub expected_mcp_tools: Vec<String>,
+    /// Identical tool failures within this window are logged once, followed by a
+    /// "repeated N times" line when the window closes; 0 logs every failure.
+    #[serde(default = "default_error_log_dedupe_window_ms")]
+    pub error_log_dedupe_window_ms: u64,
+    /// Number of recent tool calls kept for the `call_history` tool; 0 disables it.
+    #[serde(default = "default_call_history_size")]
+    pub call_history_size: usize,
+    /// Tools named here lead `tools/list` in this order; the rest follow with
+    /// read tools before mutating ones. Unknown names are ignored.
+    #[serde(default)]
+    pub tool_order: Vec<String>,
+
+    // Storage configuration
+    /// Follow symlinks when discovering `.memoranda` directories.
+    ///
+    /// Off by default: following links can pull in directories outside the
+    /// repository and makes memo discovery depend on where those links point.
+    /// Symlink cycles are detected and skipped rather than traversed forever.
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    /// Skip directories ignored by `.gitignore` files, such as `target/` or
+    /// `node_modules/`, when discovering `.memoranda` directories. A
+    /// `.memoranda` directory is found even if it is ignored itself.
+    #[serde(default = "default_respect_gitignore")]
+    pub respect_gitignore: bool,
+    /// Append a record of every memo create, update, delete and tag change to
+    /// `.memoranda/.audit.ndjson`.
+    #[serde(default)]
+    pub audit_log: bool,
+    /// Run a report-only consistency scan of the store when `serve` starts.
+    #[serde(default)]
+    pub verify_store_on_start: bool,
+    /// Load memo files that are not valid UTF-8 with the invalid bytes replaced,
+    /// flagged with `encoding_warning`, instead of skipping them.
+    #[serde(default)]
+    pub lenient_encoding: bool,
+    /// Rewrite memo files whose frontmatter has no `id` with the completed
+    /// frontmatter when they are loaded. Such memos load either way, with an
+    /// id derived from the file path. Under `title_authority: filename`, also
+    /// rewrites frontmatter titles that differ from the file name.
+    #[serde(default)]
+    pub complete_partial_frontmatter: bool,
+    /// Whether a memo's title comes from its frontmatter or, when the two
+    /// disagree, from its file name.
+    #[serde(default)]
+    pub title_authority: TitleAuthority,
+    /// Line endings memo bodies are normalized to when written.
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    /// Frontmatter format memos are written with. Any format is read.
+    #[serde(default)]
+    pub frontmatter_format: FrontmatterFormat,
+    /// Read and write memos as Obsidian notes: the title comes from the file
+    /// name, the body is the content, and only `id`, `created`, `updated`,
+    /// `tags`, and (when it differs from the file name) `title` are written to
+    /// YAML frontmatter, leaving every other key untouched. Overrides
+    /// `frontmatter_format`.
+    #[serde(default)]
+    pub obsidian_compat: bool,
+    /// Earlier versions kept per memo in `.memoranda/.history/<id>/`, saved
+    /// each time `update_memo` overwrites it; 0 disables history.
+    #[serde(default)]
+    pub memo_history_max_versions: usize,
+
+    // Retention configuration
+    /// Memos not updated for this many days are subject to the retention action.
+    #[serde(default = "default_retention_max_age_days")]
+    pub retention_max_age_days: u64,
+    /// Memos carrying any of these tags are never archived or trashed.
+    #[serde(default = "default_retention_exempt_tags")]
+    pub retention_exempt_tags: Vec<String>,
+    #[serde(default)]
+    pub retention_action: RetentionAction,
+    /// How often `serve` applies the retention policy; `None` disables periodic runs.
+    #[serde(default)]
+    pub retention_interval_hours: Option<u64>,
+    /// Trashed memos older than this many days are permanently deleted by compaction.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u64,
+
+    // Context configuration
+    /// Timezone for timestamps in aggregated context: "utc", "local", or an IANA zone name.
+    #[serde(default)]
+    pub context_timezone: ContextTimezone,
+    /// strftime-style format for timestamps in aggregated context.
+    #[serde(default = "default_context_date_format")]
+    pub context_date_format: String,
+    /// Memos longer than this many characters are elided from aggregated context.
+    #[serde(default = "default_max_memo_context_chars")]
+    pub max_memo_context_chars: usize,
+    /// Memos carrying any of these tags, such as coding standards, lead every
+    /// aggregated context in full, whatever its filters and budget.
+    #[serde(default)]
+    pub always_include_tags: Vec<String>,
+
+    /// Regexes whose matches are replaced with `[REDACTED]` in memo content
+    /// served by read tools. Memo files are left unchanged.
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+
+    // Link configuration
+    /// Longest chain of wiki-links `link_path` follows before giving up.
+    #[serde(default = "default_link_path_max_depth")]
+    pub link_path_max_depth: usize,
+}
+
+fn default_memo_dir_name() -> String {
+    DEFAULT_MEMO_DIR_NAME.to_string()
+}
+
+fn default_memo_file_extensions() -> Vec<String> {
+    DEFAULT_MEMO_FILE_EXTENSIONS
+        .iter()
+        .map(|extension| extension.to_string())
+        .collect()
+}
+
+fn default_memo_size_warn_threshold() -> u64 {
+    DEFAULT_MEMO_SIZE_WARN_THRESHOLD
+}
+
+fn default_file_locking() -> bool {
+    true
+}
+
+fn default_file_lock_timeout_ms() -> u64 {
+    DEFAULT_FILE_LOCK_TIMEOUT_MS
+}
+
+fn default_max_tag_length() -> usize {
+    MAX_TAG_LENGTH
+}
+
+fn default_respect_gitignore() -> bool {
+    true
+}
+
+fn default_search_snippet_min_term_score() -> f64 {
+    DEFAULT_SNIPPET_MIN_TERM_SCORE
+}
+
+fn default_search_index_debounce_ms() -> u64 {
+    DEFAULT_SEARCH_INDEX_DEBOUNCE_MS
+}
+
+fn default_search_regex_max_length() -> usize {
+    DEFAULT_SEARCH_REGEX_MAX_LENGTH
+}
+
+fn default_search_regex_size_limit() -> usize {
+    DEFAULT_SEARCH_REGEX_SIZE_LIMIT
+}
+
+fn default_search_regex_dfa_size_limit() -> usize {
+    DEFAULT_SEARCH_REGEX_DFA_SIZE_LIMIT
+}
+
+fn default_search_boost() -> f64 {
+    1.0
+}
+
+fn default_search_cache_size() -> usize {
+    DEFAULT_SEARCH_CACHE_SIZE
+}
+
+fn default_memo_cache_size() -> u64 {
+    DEFAULT_MEMO_CACHE_SIZE
+}
+
+fn default_metadata_cache_size() -> u64 {
+    DEFAULT_METADATA_CACHE_SIZE
+}
+
+fn default_memo_ttl_seconds() -> u64 {
+    DEFAULT_MEMO_TTL_SECONDS
+}
+
+fn default_metadata_ttl_multiplier() -> u64 {
+    DEFAULT_METADATA_TTL_MULTIPLIER
+}
+
+fn default_error_log_dedupe_window_ms() -> u64 {
+    DEFAULT_ERROR_LOG_DEDUPE_WINDOW_MS
+}
+
+fn default_call_history_size() -> usize {
+    DEFAULT_CALL_HISTORY_SIZE
+}
+
+fn default_retention_max_age_days() -> u64 {
+    DEFAULT_RETENTION_MAX_AGE_DAYS
+}
+
+fn default_trash_retention_days() -> u64 {
+    DEFAULT_TRASH_RETENTION_DAYS
+}
+
+fn default_context_date_format() -> String {
+    DEFAULT_CONTEXT_DATE_FORMAT.to_string()
+}
+
+fn default_max_memo_context_chars() -> usize {
+    DEFAULT_MAX_MEMO_CONTEXT_CHARS
+}
+
+fn default_link_path_max_depth() -> usize {
+    DEFAULT_LINK_PATH_MAX_DEPTH
+}
+
+fn default_retention_exempt_tags() -> Vec<String> {
+    DEFAULT_RETENTION_EXEMPT_TAGS
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
             data_dir: PathBuf::from(DEFAULT_DATA_DIR),
+            memo_dir_name: DEFAULT_MEMO_DIR_NAME.to_string(),
+            default_memo_dir: None,
+            auto_create_scope_dir: false,
+            memo_file_extensions: default_memo_file_extensions(),
             log_level: DEFAULT_LOG_LEVEL.to_string(),
             mcp_server_port: DEFAULT_MCP_SERVER_PORT,
             minimum_rust_version: DEFAULT_MINIMUM_RUST_VERSION.to_string(),
             max_memo_file_size: DEFAULT_MAX_MEMO_FILE_SIZE,
+            memo_size_warn_threshold: DEFAULT_MEMO_SIZE_WARN_THRESHOLD,
+            max_memo_lines: None,
+            file_locking: true,
+            file_lock_timeout_ms: DEFAULT_FILE_LOCK_TIMEOUT_MS,
+            max_tag_length: MAX_TAG_LENGTH,
+            tag_charset: None,
+            tag_auto_slugify: false,
             search_recency_boost_days: DEFAULT_RECENCY_BOOST_DAYS,
             search_snippet_length: DEFAULT_SNIPPET_LENGTH,
             search_snippet_context_padding: DEFAULT_SNIPPET_CONTEXT_PADDING,
+            search_snippet_min_term_score: DEFAULT_SNIPPET_MIN_TERM_SCORE,
+            search_snippet_relevance_floor: 0.0,
+            search_index_debounce_ms: DEFAULT_SEARCH_INDEX_DEBOUNCE_MS,
+            search_regex_max_length: DEFAULT_SEARCH_REGEX_MAX_LENGTH,
+            search_regex_size_limit: DEFAULT_SEARCH_REGEX_SIZE_LIMIT,
+            search_regex_dfa_size_limit: DEFAULT_SEARCH_REGEX_DFA_SIZE_LIMIT,
+            search_enable_stemming: false,
+            search_stop_words: Vec::new(),
+            search_max_edit_distance: 0,
+            search_title_boost: 1.0,
+            search_content_boost: 1.0,
+            search_recency_boost: 1.0,
+            search_cache_size: DEFAULT_SEARCH_CACHE_SIZE,
+            memo_cache_size: DEFAULT_MEMO_CACHE_SIZE,
+            metadata_cache_size: DEFAULT_METADATA_CACHE_SIZE,
+            memo_ttl_seconds: DEFAULT_MEMO_TTL_SECONDS,
+            metadata_ttl_multiplier: DEFAULT_METADATA_TTL_MULTIPLIER,
+            disk_cache_max_bytes: 0,
             expected_mcp_tools: DEFAULT_EXPECTED_TOOLS
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            error_log_dedupe_window_ms: DEFAULT_ERROR_LOG_DEDUPE_WINDOW_MS,
+            call_history_size: DEFAULT_CALL_HISTORY_SIZE,
+            tool_order: Vec::new(),
+            follow_symlinks: false,
+            respect_gitignore: true,
+            audit_log: false,
+            verify_store_on_start: false,
+            lenient_encoding: false,
+            complete_partial_frontmatter: false,
+            title_authority: TitleAuthority::default(),
+            line_ending: LineEnding::default(),
+            frontmatter_format: FrontmatterFormat::default(),
+            obsidian_compat: false,
+            memo_history_max_versions: 0,
+            retention_max_age_days: DEFAULT_RETENTION_MAX_AGE_DAYS,
+            retention_exempt_tags: default_retention_exempt_tags(),
+            retention_action: RetentionAction::default(),
+            retention_interval_hours: None,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
+            context_timezone: ContextTimezone::default(),
+            context_date_format: default_context_date_format(),
+            max_memo_context_chars: DEFAULT_MAX_MEMO_CONTEXT_CHARS,
+            always_include_tags: Vec::new(),
+            redaction_patterns: Vec::new(),
+            link_path_max_depth: DEFAULT_LINK_PATH_MAX_DEPTH,
         }
     }
 }
@@ -86,6 +505,20 @@ impl Settings {
         Self::new().unwrap_or_default()
     }
 
+    /// Whether files with `extension` are memos, ignoring case.
+    pub fn is_memo_file_extension(&self, extension: &str) -> bool {
+        self.memo_file_extensions
+            .iter()
+            .any(|memo_extension| memo_extension.eq_ignore_ascii_case(extension))
+    }
+
+    /// The extension new memo files are created with.
+    pub fn new_memo_file_extension(&self) -> &str {
+        self.memo_file_extensions
+            .first()
+            .map_or(DEFAULT_MEMO_FILE_EXTENSIONS[0], String::as_str)
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.mcp_server_port < MIN_VALID_PORT {
             return Err(MemorandaError::validation(format!(
@@ -94,6 +527,42 @@ impl Settings {
             )));
         }
 
+        let memo_dir_name = self.memo_dir_name.as_str();
+        if memo_dir_name.is_empty()
+            || memo_dir_name == "."
+            || memo_dir_name == ".."
+            || memo_dir_name.contains(['/', '\\'])
+        {
+            return Err(MemorandaError::validation(format!(
+                "Invalid memo directory name: {memo_dir_name:?}. Use a single directory name without path separators"
+            )));
+        }
+
+        if self.memo_file_extensions.is_empty() {
+            return Err(MemorandaError::validation(
+                "Memo file extensions cannot be empty",
+            ));
+        }
+        if let Some(extension) = self
+            .memo_file_extensions
+            .iter()
+            .find(|extension| extension.is_empty() || extension.contains(['.', '/', '\\']))
+        {
+            return Err(MemorandaError::validation(format!(
+                "Invalid memo file extension: {extension:?}. Write extensions without the leading dot, such as \"md\""
+            )));
+        }
+
+        if self
+            .default_memo_dir
+            .as_ref()
+            .is_some_and(|dir| dir.trim().is_empty())
+        {
+            return Err(MemorandaError::validation(
+                "Default memo directory cannot be empty; leave it unset to pick the closest memo directory",
+            ));
+        }
+
         if self.log_level.is_empty() {
             return Err(MemorandaError::validation("Log level cannot be empty"));
         }
@@ -128,6 +597,25 @@ impl Settings {
             )));
         }
 
+        if self.memo_size_warn_threshold == 0 {
+            return Err(MemorandaError::validation(
+                "Memo size warn threshold must be greater than 0",
+            ));
+        }
+
+        if self.memo_size_warn_threshold > self.max_memo_file_size {
+            return Err(MemorandaError::validation(format!(
+                "Memo size warn threshold ({}) must not exceed the maximum memo file size ({})",
+                self.memo_size_warn_threshold, self.max_memo_file_size
+            )));
+        }
+
+        if self.max_memo_lines == Some(0) {
+            return Err(MemorandaError::validation(
+                "Max memo lines must be greater than 0 when set",
+            ));
+        }
+
         if self.search_recency_boost_days <= 0.0 {
             return Err(MemorandaError::validation(
                 "Search recency boost days must be positive",
@@ -140,12 +628,123 @@ impl Settings {
             ));
         }
 
+        if self.search_snippet_min_term_score < 0.0 || self.search_snippet_relevance_floor < 0.0 {
+            return Err(MemorandaError::validation(
+                "Search snippet thresholds cannot be negative",
+            ));
+        }
+
+        for boost in [
+            self.search_title_boost,
+            self.search_content_boost,
+            self.search_recency_boost,
+        ] {
+            if !(boost.is_finite() && boost >= 0.0) {
+                return Err(MemorandaError::validation(
+                    "Search boosts must be non-negative numbers",
+                ));
+            }
+        }
+
+        if self.search_regex_max_length == 0
+            || self.search_regex_size_limit == 0
+            || self.search_regex_dfa_size_limit == 0
+        {
+            return Err(MemorandaError::validation(
+                "Search regex limits must be greater than 0",
+            ));
+        }
+
+        if self.memo_cache_size == 0 || self.metadata_cache_size == 0 {
+            return Err(MemorandaError::validation(
+                "Memo and metadata cache sizes must be greater than 0",
+            ));
+        }
+
+        if self.memo_ttl_seconds == 0 || self.metadata_ttl_multiplier == 0 {
+            return Err(MemorandaError::validation(
+                "Memo TTL seconds and metadata TTL multiplier must be greater than 0",
+            ));
+        }
+
+        if self
+            .memo_ttl_seconds
+            .checked_mul(self.metadata_ttl_multiplier)
+            .map_or(true, |ttl| ttl > MAX_CACHE_TTL_SECONDS)
+        {
+            return Err(MemorandaError::validation(format!(
+                "Memo TTL seconds times the metadata TTL multiplier must be at most {MAX_CACHE_TTL_SECONDS} (a year)"
+            )));
+        }
+
         if self.expected_mcp_tools.is_empty() {
             return Err(MemorandaError::validation(
                 "Expected MCP tools list cannot be empty",
             ));
         }
 
+        if !(1..=MAX_RETENTION_DAYS).contains(&self.retention_max_age_days) {
+            return Err(MemorandaError::validation(format!(
+                "Retention max age days must be between 1 and {MAX_RETENTION_DAYS}"
+            )));
+        }
+
+        if self
+            .retention_interval_hours
+            .is_some_and(|hours| !(1..=MAX_RETENTION_INTERVAL_HOURS).contains(&hours))
+        {
+            return Err(MemorandaError::validation(format!(
+                "Retention interval hours must be between 1 and {MAX_RETENTION_INTERVAL_HOURS} when set"
+            )));
+        }
+
+        if !(1..=MAX_RETENTION_DAYS).contains(&self.trash_retention_days) {
+            return Err(MemorandaError::validation(format!(
+                "Trash retention days must be between 1 and {MAX_RETENTION_DAYS}"
+            )));
+        }
+
+        if self.max_memo_context_chars == 0 {
+            return Err(MemorandaError::validation(
+                "Max memo context chars must be greater than 0",
+            ));
+        }
+
+        if self.max_tag_length == 0 {
+            return Err(MemorandaError::validation(
+                "Max tag length must be greater than 0",
+            ));
+        }
+
+        if let Some(pattern) = &self.tag_charset {
+            if let Err(e) = regex::Regex::new(&format!("^(?:{pattern})$")) {
+                return Err(MemorandaError::validation(format!(
+                    "Invalid tag charset '{pattern}': {e}"
+                )));
+            }
+        }
+
+        for pattern in &self.redaction_patterns {
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(MemorandaError::validation(format!(
+                    "Invalid redaction pattern '{pattern}': {e}"
+                )));
+            }
+        }
+
+        if self.link_path_max_depth == 0 {
+            return Err(MemorandaError::validation(
+                "Link path max depth must be greater than 0",
+            ));
+        }
+
+        if self.context_date_format.is_empty() || !is_valid_date_format(&self.context_date_format) {
+            return Err(MemorandaError::validation(format!(
+                "Invalid context date format: '{}'",
+                self.context_date_format
+            )));
+        }
+
         Ok(())
     }
 
@@ -166,6 +765,46 @@ impl Settings {
         }
     }
 
+    /// Loads settings for a repository, layering the repo-local
+    /// `.memoranda/settings.json` over the user settings file (see
+    /// [`user_config_path`]) over the defaults. Keys missing from a file fall
+    /// through to the layer beneath it.
+    pub fn discover(repo_root: Option<&Path>) -> Result<Self> {
+        let repo_config = repo_root.map(|root| root.join(".memoranda").join(SETTINGS_FILE_NAME));
+        let settings = Self::load_layered(user_config_path().as_deref(), repo_config.as_deref())?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Merges the user and repo settings files over the defaults, the repo file
+    /// taking precedence key by key. Missing files are skipped.
+    pub fn load_layered(user_config: Option<&Path>, repo_config: Option<&Path>) -> Result<Self> {
+        let mut merged = match serde_json::to_value(Self::default())? {
+            serde_json::Value::Object(defaults) => defaults,
+            _ => serde_json::Map::new(),
+        };
+        for path in [user_config, repo_config].into_iter().flatten() {
+            if !path.exists() {
+                continue;
+            }
+            info!("Loading settings from file: {:?}", path);
+            let content = std::fs::read_to_string(path)?;
+            if content.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&content)? {
+                serde_json::Value::Object(layer) => merged.extend(layer),
+                _ => {
+                    return Err(MemorandaError::validation(format!(
+                        "Settings file {} must contain a JSON object",
+                        path.display()
+                    )))
+                }
+            }
+        }
+        Ok(serde_json::from_value(serde_json::Value::Object(merged))?)
+    }
+
     pub fn save_to_file(&self, path: &PathBuf) -> Result<()> {
         info!("Saving settings to file: {:?}", path);
         if let Some(parent) = path.parent() {
@@ -177,6 +816,38 @@ impl Settings {
     }
 }
 
+/// Location of the user settings file: `$XDG_CONFIG_HOME/memoranda/settings.json`
+/// when `XDG_CONFIG_HOME` is set (on every platform), otherwise the platform
+/// config directory (`~/.config` on Linux, `~/Library/Application Support` on
+/// macOS, `%APPDATA%` on Windows).
+pub fn user_config_path() -> Option<PathBuf> {
+    let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from);
+    user_config_dir(xdg_config_home).map(|dir| dir.join("memoranda").join(SETTINGS_FILE_NAME))
+}
+
+fn user_config_dir(xdg_config_home: Option<PathBuf>) -> Option<PathBuf> {
+    // The XDG spec says relative paths are invalid and must be ignored
+    if let Some(dir) = xdg_config_home.filter(|dir| dir.is_absolute()) {
+        return Some(dir);
+    }
+    platform_config_dir()
+}
+
+#[cfg(windows)]
+fn platform_config_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn platform_config_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +879,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_ok());
     }
@@ -227,6 +899,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_err());
     }
@@ -246,6 +919,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_err());
     }
@@ -290,6 +964,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_err());
     }
@@ -309,6 +984,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_err());
     }
@@ -328,6 +1004,296 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_validation_memo_size_warn_threshold() {
+        let settings = Settings {
+            memo_size_warn_threshold: DEFAULT_MAX_MEMO_FILE_SIZE + 1,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            max_memo_file_size: 100_000,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            max_memo_file_size: 100_000,
+            memo_size_warn_threshold: 100_000,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_settings_validation_retention() {
+        for retention_max_age_days in [0, MAX_RETENTION_DAYS + 1, u64::MAX] {
+            let settings = Settings {
+                retention_max_age_days,
+                ..Default::default()
+            };
+            assert!(settings.validate().is_err());
+        }
+        for trash_retention_days in [0, MAX_RETENTION_DAYS + 1, u64::MAX] {
+            let settings = Settings {
+                trash_retention_days,
+                ..Default::default()
+            };
+            assert!(settings.validate().is_err());
+        }
+        let settings = Settings {
+            retention_max_age_days: MAX_RETENTION_DAYS,
+            trash_retention_days: MAX_RETENTION_DAYS,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        for retention_interval_hours in [0, MAX_RETENTION_INTERVAL_HOURS + 1, u64::MAX] {
+            let settings = Settings {
+                retention_interval_hours: Some(retention_interval_hours),
+                ..Default::default()
+            };
+            assert!(settings.validate().is_err());
+        }
+
+        for retention_interval_hours in [24, MAX_RETENTION_INTERVAL_HOURS] {
+            let settings = Settings {
+                retention_interval_hours: Some(retention_interval_hours),
+                ..Default::default()
+            };
+            assert!(settings.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_settings_validation_memo_cache() {
+        for settings in [
+            Settings {
+                memo_cache_size: 0,
+                ..Default::default()
+            },
+            Settings {
+                metadata_cache_size: 0,
+                ..Default::default()
+            },
+            Settings {
+                memo_ttl_seconds: 0,
+                ..Default::default()
+            },
+            Settings {
+                metadata_ttl_multiplier: 0,
+                ..Default::default()
+            },
+            Settings {
+                memo_ttl_seconds: MAX_CACHE_TTL_SECONDS + 1,
+                metadata_ttl_multiplier: 1,
+                ..Default::default()
+            },
+            Settings {
+                memo_ttl_seconds: MAX_CACHE_TTL_SECONDS,
+                metadata_ttl_multiplier: 2,
+                ..Default::default()
+            },
+            Settings {
+                memo_ttl_seconds: u64::MAX,
+                metadata_ttl_multiplier: u64::MAX,
+                ..Default::default()
+            },
+        ] {
+            assert!(settings.validate().is_err());
+        }
+
+        let settings = Settings {
+            memo_cache_size: 10,
+            memo_ttl_seconds: 60,
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_settings_retention_defaults_when_missing() {
+        // Settings files written before retention existed must still load
+        let mut value = serde_json::to_value(Settings::default()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("retention_max_age_days");
+        object.remove("retention_exempt_tags");
+        object.remove("retention_action");
+        object.remove("retention_interval_hours");
+
+        let settings: Settings = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            settings.retention_max_age_days,
+            DEFAULT_RETENTION_MAX_AGE_DAYS
+        );
+        assert_eq!(settings.retention_exempt_tags, vec!["keep", "pinned"]);
+        assert_eq!(settings.retention_action, RetentionAction::Archive);
+        assert_eq!(settings.retention_interval_hours, None);
+    }
+
+    #[test]
+    fn test_settings_context_timezone_load() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_path_buf();
+
+        let mut value = serde_json::to_value(Settings::default()).unwrap();
+        value["context_timezone"] = serde_json::json!("America/New_York");
+        std::fs::write(&path, value.to_string()).unwrap();
+        let settings = Settings::load_from_file(&path).unwrap();
+        assert_eq!(settings.context_timezone.to_string(), "America/New_York");
+        assert_eq!(settings.context_date_format, DEFAULT_CONTEXT_DATE_FORMAT);
+
+        value["context_timezone"] = serde_json::json!("Nowhere/Bogus");
+        std::fs::write(&path, value.to_string()).unwrap();
+        let error = Settings::load_from_file(&path).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Unknown timezone 'Nowhere/Bogus'"));
+    }
+
+    #[test]
+    fn test_user_config_applies_beneath_repo_config() {
+        let config_home = tempfile::TempDir::new().unwrap();
+        let user_config = user_config_dir(Some(config_home.path().to_path_buf()))
+            .unwrap()
+            .join("memoranda")
+            .join(SETTINGS_FILE_NAME);
+        std::fs::create_dir_all(user_config.parent().unwrap()).unwrap();
+        std::fs::write(
+            &user_config,
+            r#"{"log_level": "debug", "search_cache_size": 8}"#,
+        )
+        .unwrap();
+
+        let repo = tempfile::TempDir::new().unwrap();
+        let repo_config = repo.path().join(".memoranda").join(SETTINGS_FILE_NAME);
+
+        // No repo config: the user config applies over the defaults
+        let settings = Settings::load_layered(Some(&user_config), Some(&repo_config)).unwrap();
+        assert_eq!(settings.log_level, "debug");
+        assert_eq!(settings.search_cache_size, 8);
+        assert_eq!(settings.mcp_server_port, DEFAULT_MCP_SERVER_PORT);
+
+        // A repo config overrides only the keys it sets
+        std::fs::create_dir_all(repo_config.parent().unwrap()).unwrap();
+        std::fs::write(&repo_config, r#"{"log_level": "warn"}"#).unwrap();
+        let settings = Settings::load_layered(Some(&user_config), Some(&repo_config)).unwrap();
+        assert_eq!(settings.log_level, "warn");
+        assert_eq!(settings.search_cache_size, 8);
+    }
+
+    #[test]
+    fn test_user_config_dir_ignores_relative_xdg_config_home() {
+        let absolute = std::env::temp_dir();
+        assert_eq!(user_config_dir(Some(absolute.clone())), Some(absolute));
+        assert_eq!(
+            user_config_dir(Some(PathBuf::from("relative/config"))),
+            platform_config_dir()
+        );
+    }
+
+    #[test]
+    fn test_settings_validation_redaction_patterns() {
+        let mut settings = Settings {
+            redaction_patterns: vec![r"sk-[A-Za-z0-9]{16,}".to_string()],
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.redaction_patterns.push("[unclosed".to_string());
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_validation_tag_rules() {
+        let mut settings = Settings {
+            tag_charset: Some("[a-z0-9_-]+".to_string()),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.tag_charset = Some("[a-z".to_string());
+        assert!(settings.validate().is_err());
+
+        settings.tag_charset = None;
+        settings.max_tag_length = 0;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_validation_memo_dir_name() {
+        let mut settings = Settings {
+            memo_dir_name: "notes".to_string(),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        for invalid in ["", ".", "..", "docs/notes", "docs\\notes"] {
+            settings.memo_dir_name = invalid.to_string();
+            assert!(
+                settings.validate().is_err(),
+                "{invalid:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_settings_validation_memo_file_extensions() {
+        let mut settings = Settings::default();
+        assert!(settings.is_memo_file_extension("MARKDOWN"));
+        assert!(!settings.is_memo_file_extension("txt"));
+        assert_eq!(settings.new_memo_file_extension(), "md");
+
+        settings.memo_file_extensions = vec!["txt".to_string(), "md".to_string()];
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.new_memo_file_extension(), "txt");
+
+        for invalid in [vec![], vec![String::new()], vec![".md".to_string()]] {
+            settings.memo_file_extensions = invalid.clone();
+            assert!(
+                settings.validate().is_err(),
+                "{invalid:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_settings_validation_default_memo_dir() {
+        let mut settings = Settings {
+            default_memo_dir: Some("docs".to_string()),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.default_memo_dir = Some("  ".to_string());
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_validation_search_boosts() {
+        let mut settings = Settings {
+            search_recency_boost: 0.0,
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.search_title_boost = -1.0;
+        assert!(settings.validate().is_err());
+        settings.search_title_boost = f64::NAN;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_validation_context_date_format() {
+        let settings = Settings {
+            context_date_format: "%Y-%Q".to_string(),
+            ..Default::default()
         };
         assert!(settings.validate().is_err());
     }
@@ -348,6 +1314,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_ok());
     }
@@ -368,6 +1335,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_ok());
 
@@ -385,6 +1353,7 @@ mod tests {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            ..Default::default()
         };
         assert!(settings.validate().is_ok());
     }
@@ -116,6 +116,15 @@ pub enum McpError {
 
     #[error("Server initialization failed: {reason}")]
     ServerInitializationFailed { reason: String },
+
+    #[error("Tool {tool_name} is forbidden by policy for client {client}")]
+    ForbiddenByPolicy { tool_name: String, client: String },
+
+    #[error("Memo store is initializing; retry {tool_name} shortly")]
+    StoreInitializing { tool_name: String },
+
+    #[error("Resource not found: {uri}")]
+    ResourceNotFound { uri: String },
 }
 
 /// Specific error type for CLI operations
@@ -404,6 +413,23 @@ impl McpError {
             reason: reason.into(),
         }
     }
+
+    pub fn forbidden_by_policy(tool_name: impl Into<String>, client: impl Into<String>) -> Self {
+        Self::ForbiddenByPolicy {
+            tool_name: tool_name.into(),
+            client: client.into(),
+        }
+    }
+
+    pub fn store_initializing(tool_name: impl Into<String>) -> Self {
+        Self::StoreInitializing {
+            tool_name: tool_name.into(),
+        }
+    }
+
+    pub fn resource_not_found(uri: impl Into<String>) -> Self {
+        Self::ResourceNotFound { uri: uri.into() }
+    }
 }
 
 impl CliError {
@@ -491,6 +517,12 @@ mod tests {
             error.to_string(),
             "Server initialization failed: memo store not found"
         );
+
+        let error = McpError::forbidden_by_policy("delete_memo", "agent");
+        assert_eq!(
+            error.to_string(),
+            "Tool delete_memo is forbidden by policy for client agent"
+        );
     }
 
     #[test]
@@ -1,13 +1,20 @@
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod config;
 pub mod error;
+#[cfg(feature = "cli")]
 pub mod logging;
+#[cfg(feature = "mcp")]
 pub mod mcp;
 pub mod memo;
+pub mod observer;
 pub mod utils;
 
+#[cfg(feature = "cli")]
 pub use cli::*;
 pub use config::*;
 pub use error::MemorandaError;
+#[cfg(feature = "mcp")]
 pub use mcp::*;
 pub use memo::*;
+pub use observer::*;
@@ -1,10 +1,12 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use memoranda::cli::{DoctorCommand, HelpCommand};
+use memoranda::cli::{DoctorCommand, HelpCommand, ImportCommand, MaintainCommand};
 use memoranda::config::Settings;
 use memoranda::error::{CliError, MemorandaError};
 use memoranda::logging;
 use memoranda::mcp::McpServer;
+use memoranda::memo::find_git_root;
+use std::path::PathBuf;
 use tracing::{debug, error, info, span, warn, Level};
 
 #[derive(Parser)]
@@ -28,6 +30,25 @@ enum Commands {
         #[arg(long)]
         auto_fix: bool,
     },
+    /// Import a directory of markdown files as memos
+    ImportDir {
+        /// Directory to import
+        path: PathBuf,
+
+        /// Tag to add to every imported memo
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Apply the memo retention policy
+    Maintain {
+        /// List what would change without modifying any files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Also purge expired trash and other internal artifacts
+        #[arg(long)]
+        compact: bool,
+    },
     /// Start the MCP server
     Serve,
 }
@@ -190,6 +211,36 @@ fn print_doctor_help() {
     println!("        --verbose     Show verbose output with detailed information");
 }
 
+/// Print help for the import-dir subcommand
+fn print_import_dir_help() {
+    println!("memoranda-import-dir");
+    println!("Import a directory of markdown files as memos");
+    println!();
+    println!("Usage:");
+    println!("    memoranda import-dir [OPTIONS] <PATH>");
+    println!();
+    println!("Arguments:");
+    println!("    <PATH>    Directory to import");
+    println!();
+    println!("Options:");
+    println!("        --tag <TAG>    Tag to add to every imported memo");
+    println!("    -h, --help         Print help");
+}
+
+/// Print help for the maintain subcommand
+fn print_maintain_help() {
+    println!("memoranda-maintain");
+    println!("Apply the memo retention policy");
+    println!();
+    println!("Usage:");
+    println!("    memoranda maintain [OPTIONS]");
+    println!();
+    println!("Options:");
+    println!("        --compact    Also purge expired trash and other internal artifacts");
+    println!("        --dry-run    List what would change without modifying any files");
+    println!("    -h, --help       Print help");
+}
+
 /// Print help for the serve subcommand  
 fn print_serve_help() {
     println!("memoranda-serve");
@@ -244,6 +295,18 @@ async fn run_cli() -> Result<()> {
                 print_doctor_help();
                 return Ok(());
             }
+            "import-dir" => {
+                let _cmd_span = span!(Level::INFO, "import_dir_help").entered();
+                info!("Showing import-dir command help");
+                print_import_dir_help();
+                return Ok(());
+            }
+            "maintain" => {
+                let _cmd_span = span!(Level::INFO, "maintain_help").entered();
+                info!("Showing maintain command help");
+                print_maintain_help();
+                return Ok(());
+            }
             "serve" => {
                 let _cmd_span = span!(Level::INFO, "serve_help").entered();
                 info!("Showing serve command help");
@@ -266,7 +329,7 @@ async fn run_cli() -> Result<()> {
     })?;
 
     // Initialize settings with better error handling and context
-    let _settings = Settings::new()
+    let settings = Settings::discover(find_git_root().ok().as_deref())
         .context("Failed to initialize application settings")
         .map_err(|e| {
             warn!("Settings initialization failed, using defaults");
@@ -289,22 +352,60 @@ async fn run_cli() -> Result<()> {
                 "Running doctor command"
             );
 
-            let doctor = DoctorCommand::with_options(*verbose, *auto_fix);
+            let doctor = DoctorCommand {
+                verbose: *verbose,
+                auto_fix: *auto_fix,
+                settings,
+            };
             doctor
                 .run()
                 .await
                 .context("Doctor command execution failed")?;
         }
+        Some(Commands::ImportDir { path, tag }) => {
+            let _cmd_span = span!(Level::INFO, "import_dir_command").entered();
+            debug!(path = %path.display(), tag = ?tag, "Running import-dir command");
+
+            let import = ImportCommand {
+                path: path.clone(),
+                tag: tag.clone(),
+                settings,
+            };
+            import
+                .run()
+                .await
+                .context("Import command execution failed")?;
+        }
+        Some(Commands::Maintain { dry_run, compact }) => {
+            let _cmd_span = span!(
+                Level::INFO,
+                "maintain_command",
+                dry_run = dry_run,
+                compact = compact
+            )
+            .entered();
+            debug!(
+                dry_run = dry_run,
+                compact = compact,
+                "Running maintain command"
+            );
+
+            let maintain = MaintainCommand {
+                dry_run: *dry_run,
+                compact: *compact,
+                settings,
+            };
+            maintain
+                .run()
+                .await
+                .context("Maintain command execution failed")?;
+        }
         Some(Commands::Serve) => {
             let _cmd_span = span!(Level::INFO, "serve_command").entered();
             info!("Starting MCP server");
 
-            let mut server = McpServer::new("memoranda".to_string())
-                .context("Failed to initialize MCP server")
-                .map_err(|e| {
-                    error!(error = %e, "MCP server initialization failed");
-                    e
-                })?;
+            // The memo store initializes in the background so clients can connect at once
+            let mut server = McpServer::with_deferred_store("memoranda".to_string(), settings);
 
             server
                 .start()
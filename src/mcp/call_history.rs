@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// One tool call made during the session. Arguments are deliberately not
+/// recorded so the history never exposes memo content.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallRecord {
+    pub ts: DateTime<Utc>,
+    pub tool: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+}
+
+/// Ring buffer of the most recent tool calls, oldest first.
+#[derive(Debug)]
+pub struct CallHistory {
+    capacity: usize,
+    records: VecDeque<CallRecord>,
+}
+
+impl CallHistory {
+    /// A zero capacity records nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a call, evicting the oldest once the buffer is full.
+    pub fn record(&mut self, tool: &str, ok: bool, duration: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(CallRecord {
+            ts: Utc::now(),
+            tool: tool.to_string(),
+            ok,
+            duration_ms: duration.as_millis().try_into().unwrap_or(u64::MAX),
+        });
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &CallRecord> {
+        self.records.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_most_recent_calls() {
+        let mut history = CallHistory::new(2);
+        history.record("list_memos", true, Duration::from_millis(3));
+        history.record("get_memo", false, Duration::ZERO);
+        history.record("search_memos", true, Duration::ZERO);
+
+        let tools: Vec<_> = history.records().map(|r| r.tool.as_str()).collect();
+        assert_eq!(tools, ["get_memo", "search_memos"]);
+
+        let mut disabled = CallHistory::new(0);
+        disabled.record("list_memos", true, Duration::ZERO);
+        assert_eq!(disabled.records().count(), 0);
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::error::McpError;
+
+/// Arguments that identify what a failing call was about, checked in order.
+const KEY_ARGUMENTS: &[&str] = &["id", "ids", "title", "path", "query", "alias"];
+
+/// Identifies failures that are "the same" for log deduplication.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorKey {
+    pub method: String,
+    pub tool: String,
+    pub kind: String,
+    pub key_argument: Option<String>,
+}
+
+impl ErrorKey {
+    pub fn new(
+        method: &str,
+        tool: &str,
+        arguments: &serde_json::Value,
+        error: &anyhow::Error,
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            tool: tool.to_string(),
+            kind: error_kind(error),
+            key_argument: KEY_ARGUMENTS
+                .iter()
+                .find_map(|name| arguments.get(*name))
+                .map(|value| value.to_string()),
+        }
+    }
+}
+
+/// Typed errors are grouped by variant; anything else by its message.
+fn error_kind(error: &anyhow::Error) -> String {
+    match error.downcast_ref::<McpError>() {
+        Some(mcp_error) => {
+            let debug = format!("{mcp_error:?}");
+            debug
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        }
+        None => error.to_string(),
+    }
+}
+
+/// A closed window in which an error was suppressed at least once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepeatSummary {
+    pub key: ErrorKey,
+    pub message: String,
+    /// Occurrences after the first, which was logged when the window opened.
+    pub repeats: u64,
+}
+
+#[derive(Debug)]
+struct OpenWindow {
+    opened_at: Instant,
+    message: String,
+    repeats: u64,
+}
+
+/// Collapses identical failures within a time window into one log line plus a
+/// "repeated N times" summary when the window closes.
+///
+/// Only logging is affected; every failing call still gets its own response.
+#[derive(Debug)]
+pub struct ErrorDeduper {
+    window: Duration,
+    open: HashMap<ErrorKey, OpenWindow>,
+}
+
+impl ErrorDeduper {
+    /// A zero window disables deduplication.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            open: HashMap::new(),
+        }
+    }
+
+    /// Records a failure, returning true if it opens a window and should be logged.
+    pub fn record(&mut self, key: ErrorKey, message: &str, now: Instant) -> bool {
+        if self.window.is_zero() {
+            return true;
+        }
+        match self.open.get_mut(&key) {
+            Some(open) if now.duration_since(open.opened_at) < self.window => {
+                open.repeats += 1;
+                open.message = message.to_string();
+                false
+            }
+            _ => {
+                self.open.insert(
+                    key,
+                    OpenWindow {
+                        opened_at: now,
+                        message: message.to_string(),
+                        repeats: 0,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Closes windows older than the configured size.
+    pub fn close_expired(&mut self, now: Instant) -> Vec<RepeatSummary> {
+        let window = self.window;
+        self.close_where(|open| now.duration_since(open.opened_at) >= window)
+    }
+
+    /// Closes every open window, e.g. on shutdown.
+    pub fn close_all(&mut self) -> Vec<RepeatSummary> {
+        self.close_where(|_| true)
+    }
+
+    fn close_where(
+        &mut self,
+        mut should_close: impl FnMut(&OpenWindow) -> bool,
+    ) -> Vec<RepeatSummary> {
+        let closing: Vec<ErrorKey> = self
+            .open
+            .iter()
+            .filter(|(_, open)| should_close(open))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        closing
+            .into_iter()
+            .filter_map(|key| {
+                let open = self.open.remove(&key)?;
+                (open.repeats > 0).then_some(RepeatSummary {
+                    key,
+                    message: open.message,
+                    repeats: open.repeats,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(id: &str) -> ErrorKey {
+        ErrorKey::new(
+            "tools/call",
+            "get_memo",
+            &json!({ "id": id }),
+            &anyhow::anyhow!("Memo not found with ID: {id}"),
+        )
+    }
+
+    #[test]
+    fn test_repeats_are_counted_per_key_and_window() {
+        let mut deduper = ErrorDeduper::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(deduper.record(key("a"), "missing a", start));
+        for _ in 0..4 {
+            assert!(!deduper.record(key("a"), "missing a", start));
+        }
+        assert!(deduper.record(key("b"), "missing b", start));
+        assert!(deduper
+            .close_expired(start + Duration::from_secs(5))
+            .is_empty());
+
+        let summaries = deduper.close_expired(start + Duration::from_secs(10));
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].key, key("a"));
+        assert_eq!(summaries[0].repeats, 4);
+
+        // A new window opens after the old one closed
+        assert!(deduper.record(key("a"), "missing a", start + Duration::from_secs(11)));
+        assert!(deduper.close_all().is_empty());
+    }
+
+    #[test]
+    fn test_zero_window_logs_everything() {
+        let mut deduper = ErrorDeduper::new(Duration::ZERO);
+        let now = Instant::now();
+        assert!(deduper.record(key("a"), "missing a", now));
+        assert!(deduper.record(key("a"), "missing a", now));
+        assert!(deduper.close_all().is_empty());
+    }
+
+    #[test]
+    fn test_policy_errors_group_by_variant() {
+        let forbidden = |client: &str| {
+            ErrorKey::new(
+                "tools/call",
+                "delete_memo",
+                &json!({}),
+                &McpError::forbidden_by_policy("delete_memo", client).into(),
+            )
+        };
+        assert_eq!(forbidden("a").kind, "ForbiddenByPolicy");
+        assert_eq!(forbidden("a"), forbidden("b"));
+    }
+}
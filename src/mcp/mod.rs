@@ -1,8 +1,14 @@
+pub mod call_history;
+pub mod dedupe;
+pub mod policy;
 pub mod server;
 pub mod tools;
 
 #[cfg(test)]
 mod tests;
 
+pub use call_history::*;
+pub use dedupe::*;
+pub use policy::*;
 pub use server::*;
 pub use tools::*;
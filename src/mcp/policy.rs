@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File name of the tool policy inside the primary `.memoranda` directory.
+pub const POLICY_FILE_NAME: &str = "policy.json";
+
+/// Tools every client may call, so a restricted client can still inspect its access.
+const ALWAYS_ALLOWED_TOOLS: &[&str] = &["server_status", "retry_memo_store"];
+
+/// Coarse access levels, each including everything allowed by the levels below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolRole {
+    /// Tools that only read memos.
+    Read,
+    /// Adds tools that create, change, or delete memos.
+    Write,
+    /// Adds store maintenance tools and any tool not classified above.
+    Admin,
+}
+
+impl ToolRole {
+    /// Returns the role required to call a tool.
+    pub fn required_for(tool_name: &str) -> Self {
+        match tool_name {
+            "list_memos"
+            | "get_memo"
+            | "search_memos"
+            | "get_all_context"
+            | "context_for_tag"
+            | "memos_mentioning_path"
+            | "get_memo_numbered"
+            | "server_status"
+            | "retry_memo_store"
+            | "store_layout"
+            | "cache_consistency"
+            | "check_duplicate_ids"
+            | "call_history"
+            | "diagnostics"
+            | "session_memos"
+            | "list_trash"
+            | "list_tags"
+            | "get_tag_meta"
+            | "link_path"
+            | "hub_memos"
+            | "link_graph"
+            | "activity"
+            | "validate_regex" => ToolRole::Read,
+            "create_memo" | "update_memo" | "rename_memo" | "sync_filename" | "delete_memo"
+            | "delete_memos" | "add_tags" | "remove_tags" | "add_alias" | "remove_alias"
+            | "begin_memo_write" | "write_memo_chunk" | "commit_memo_write"
+            | "abort_memo_write" | "trash_memo" | "untrash_memo" | "set_tag_meta"
+            | "set_tag_alias" => ToolRole::Write,
+            _ => ToolRole::Admin,
+        }
+    }
+}
+
+/// What a client is allowed to call: a role, or an explicit list of tool names.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ClientAccess {
+    Role(ToolRole),
+    Tools(Vec<String>),
+}
+
+impl Default for ClientAccess {
+    fn default() -> Self {
+        ClientAccess::Role(ToolRole::Admin)
+    }
+}
+
+impl ClientAccess {
+    pub fn allows(&self, tool_name: &str) -> bool {
+        if ALWAYS_ALLOWED_TOOLS.contains(&tool_name) {
+            return true;
+        }
+        match self {
+            ClientAccess::Role(role) => *role >= ToolRole::required_for(tool_name),
+            ClientAccess::Tools(tools) => tools.iter().any(|tool| tool == tool_name),
+        }
+    }
+
+    /// Name of the effective role, or `"custom"` for an explicit tool list.
+    pub fn role_name(&self) -> &'static str {
+        match self {
+            ClientAccess::Role(ToolRole::Read) => "read",
+            ClientAccess::Role(ToolRole::Write) => "write",
+            ClientAccess::Role(ToolRole::Admin) => "admin",
+            ClientAccess::Tools(_) => "custom",
+        }
+    }
+}
+
+/// Per-client tool access, keyed by the client name sent in `initialize`.
+///
+/// ```json
+/// {
+///   "default": "read",
+///   "clients": {
+///     "experimental-agent": ["list_memos", "get_memo", "create_memo"],
+///     "main-session": "admin"
+///   }
+/// }
+/// ```
+///
+/// Clients not listed get `default`, which allows everything when omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolPolicy {
+    #[serde(default)]
+    pub default: ClientAccess,
+    #[serde(default)]
+    pub clients: HashMap<String, ClientAccess>,
+}
+
+impl ToolPolicy {
+    /// Loads a policy file, returning `None` if it does not exist.
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub fn access_for(&self, client_name: Option<&str>) -> ClientAccess {
+        client_name
+            .and_then(|name| self.clients.get(name))
+            .unwrap_or(&self.default)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_include_lower_levels() {
+        let read = ClientAccess::Role(ToolRole::Read);
+        assert!(read.allows("search_memos"));
+        assert!(!read.allows("create_memo"));
+        assert!(!read.allows("compact_store"));
+
+        let write = ClientAccess::Role(ToolRole::Write);
+        assert!(write.allows("search_memos"));
+        assert!(write.allows("delete_memo"));
+        assert!(!write.allows("compact_store"));
+
+        assert!(ClientAccess::default().allows("compact_store"));
+        assert!(ClientAccess::Tools(Vec::new()).allows("server_status"));
+    }
+
+    #[test]
+    fn test_policy_parsing_and_lookup() {
+        let policy: ToolPolicy = serde_json::from_str(
+            r#"{
+                "default": "read",
+                "clients": {
+                    "agent": ["get_memo", "create_memo"],
+                    "main": "admin"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let agent = policy.access_for(Some("agent"));
+        assert!(agent.allows("create_memo"));
+        assert!(!agent.allows("list_memos"));
+        assert_eq!(agent.role_name(), "custom");
+        assert_eq!(policy.access_for(Some("main")).role_name(), "admin");
+        assert_eq!(policy.access_for(Some("other")).role_name(), "read");
+        assert_eq!(policy.access_for(None).role_name(), "read");
+
+        assert_eq!(
+            ToolPolicy::default().access_for(Some("x")).role_name(),
+            "admin"
+        );
+    }
+}
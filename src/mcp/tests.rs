@@ -1,9 +1,10 @@
 #[cfg(test)]
 mod unit_tests {
     use super::super::*;
-    use crate::memo::{Memo, MemoStore};
+    use crate::memo::{Memo, MemoId, MemoStore};
     use anyhow::Result;
     use serde_json::json;
+    use std::collections::HashMap;
     use std::fs;
     use tempfile::TempDir;
 
@@ -146,7 +147,8 @@ mod unit_tests {
 
         // Delete the memo
         let delete_args = json!({
-            "id": memo.id.to_string()
+            "id": memo.id.to_string(),
+            "permanent": true
         });
         let result = server.execute_tool("delete_memo", delete_args).await?;
 
@@ -200,6 +202,191 @@ mod unit_tests {
         assert!(result2.contains("Rust Programming"));
         assert!(result2.contains("Python Notes"));
 
+        // Results are ranked and carry scores; boolean queries are honored
+        let results: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("search_memos", json!({"query": "Rust OR Python"}))
+                .await?,
+        )?;
+        let results = results["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0]["score"].as_f64().unwrap() >= results[1]["score"].as_f64().unwrap());
+        assert!(results[0]["snippets"].is_array());
+
+        let results: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("search_memos", json!({"query": "programming NOT Python"}))
+                .await?,
+        )?;
+        assert_eq!(results["total"], 1);
+        assert_eq!(results["results"][0]["memo"]["title"], "Rust Programming");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_memos_boosts_apply_to_one_call() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Deploy", "content": "Steps for the release"}),
+            )
+            .await?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Release notes", "content": "How we deploy on Fridays"}),
+            )
+            .await?;
+
+        async fn titles(
+            server: &mut McpServer,
+            arguments: serde_json::Value,
+        ) -> Result<Vec<String>> {
+            let results: serde_json::Value =
+                serde_json::from_str(&server.execute_tool("search_memos", arguments).await?)?;
+            Ok(results["results"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|result| result["memo"]["title"].as_str().unwrap().to_string())
+                .collect())
+        }
+
+        // Title matches outrank content matches by default
+        let default_order = ["Deploy", "Release notes"];
+        assert_eq!(
+            titles(&mut server, json!({"query": "deploy"})).await?,
+            default_order
+        );
+        assert_eq!(
+            titles(&mut server, json!({"query": "deploy", "content_boost": 5})).await?,
+            ["Release notes", "Deploy"]
+        );
+        // The boost does not outlive its call
+        assert_eq!(
+            titles(&mut server, json!({"query": "deploy"})).await?,
+            default_order
+        );
+
+        let error = server
+            .execute_tool(
+                "search_memos",
+                json!({"query": "deploy", "title_boost": -1}),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("title_boost"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_memos_title_only_and_content_only() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Deploy", "content": "Steps for the release"}),
+            )
+            .await?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Release notes", "content": "How we deploy on Fridays"}),
+            )
+            .await?;
+
+        async fn search(
+            server: &mut McpServer,
+            arguments: serde_json::Value,
+        ) -> Result<serde_json::Value> {
+            Ok(serde_json::from_str(
+                &server.execute_tool("search_memos", arguments).await?,
+            )?)
+        }
+        let results = search(&mut server, json!({"query": "deploy", "title_only": true})).await?;
+        assert_eq!(results["total"], 1);
+        assert_eq!(results["results"][0]["memo"]["title"], "Deploy");
+        let results = search(
+            &mut server,
+            json!({"query": "deploy", "content_only": true}),
+        )
+        .await?;
+        assert_eq!(results["total"], 1);
+        assert_eq!(results["results"][0]["memo"]["title"], "Release notes");
+
+        let error = server
+            .execute_tool(
+                "search_memos",
+                json!({"query": "deploy", "title_only": true, "content_only": true}),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("title_only and content_only"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_memos_markdown_digest() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        for (title, content) in [
+            ("Deploy", "How to deploy the service"),
+            ("Release notes", "We deploy on Fridays"),
+            ("Lunch", "Tacos"),
+        ] {
+            server
+                .execute_tool("create_memo", json!({"title": title, "content": content}))
+                .await?;
+        }
+
+        let json: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("search_memos", json!({"query": "deploy"}))
+                .await?,
+        )?;
+        let digest = server
+            .execute_tool(
+                "search_memos",
+                json!({"query": "deploy", "format": "markdown"}),
+            )
+            .await?;
+
+        // Each result's heading and snippets appear in rank order
+        let mut position = 0;
+        for result in json["results"].as_array().unwrap() {
+            let heading = format!(
+                "## {} ({:.2})",
+                result["memo"]["title"].as_str().unwrap(),
+                result["score"].as_f64().unwrap()
+            );
+            let found = digest[position..].find(&heading).unwrap() + position;
+            position = found + heading.len();
+            for snippet in result["snippets"].as_array().unwrap() {
+                let snippet = snippet.as_str().unwrap();
+                position = digest[position..].find(snippet).unwrap() + position + snippet.len();
+            }
+        }
+        assert!(digest.starts_with("## Deploy"));
+        assert!(digest.contains("deploy on Fridays"));
+        assert!(!digest.contains("Lunch"));
+
+        let page = server
+            .execute_tool(
+                "search_memos",
+                json!({"query": "deploy", "format": "markdown", "offset": 1}),
+            )
+            .await?;
+        assert!(page.starts_with("## Release notes"));
+
+        let error = server
+            .execute_tool("search_memos", json!({"query": "deploy", "format": "xml"}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Invalid format"));
+
         Ok(())
     }
 
@@ -296,6 +483,2305 @@ mod unit_tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_memos_mentioning_path_tool() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Unix style", "content": "Refactored src/mcp/server.rs"}),
+            )
+            .await?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Windows style", "content": "Line one\nBug in src\\mcp\\server.rs"}),
+            )
+            .await?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Unrelated", "content": "Nothing to see"}),
+            )
+            .await?;
+
+        let result = server
+            .execute_tool(
+                "memos_mentioning_path",
+                json!({"path": "src/mcp/server.rs"}),
+            )
+            .await?;
+        let mentions: serde_json::Value = serde_json::from_str(&result)?;
+        let mentions = mentions.as_array().unwrap();
+        assert_eq!(mentions.len(), 2);
+
+        let windows = mentions
+            .iter()
+            .find(|m| m["memo"]["title"] == "Windows style")
+            .unwrap();
+        assert_eq!(windows["lines"][0]["line_number"], 2);
+        assert_eq!(windows["lines"][0]["text"], "Bug in src\\mcp\\server.rs");
+
+        // Missing path parameter is an error
+        assert!(server
+            .execute_tool("memos_mentioning_path", json!({}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_memo_numbered_tool() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+
+        let created = server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Numbered", "content": "first\n\nthird\n"}),
+            )
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&created)?;
+        let id = memo["id"].as_str().unwrap();
+
+        let result = server
+            .execute_tool("get_memo_numbered", json!({"id": id}))
+            .await?;
+        let numbered: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(numbered["line_count"], 3);
+        assert_eq!(numbered["lines"][0], json!({"line": 1, "text": "first"}));
+        assert_eq!(numbered["lines"][1], json!({"line": 2, "text": ""}));
+        assert_eq!(numbered["lines"][2], json!({"line": 3, "text": "third"}));
+
+        let text = server
+            .execute_tool("get_memo_numbered", json!({"id": id, "format": "text"}))
+            .await?;
+        assert_eq!(text, "1: first\n2: \n3: third");
+
+        assert!(server
+            .execute_tool("get_memo_numbered", json!({"id": id, "format": "xml"}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_memos_tool_reports_per_id() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+
+        let mut ids = Vec::new();
+        for title in ["First", "Second"] {
+            let created = server
+                .execute_tool("create_memo", json!({"title": title, "content": "bye"}))
+                .await?;
+            let memo: serde_json::Value = serde_json::from_str(&created)?;
+            ids.push(memo["id"].as_str().unwrap().to_string());
+        }
+        let missing = crate::memo::MemoId::new().to_string();
+
+        let result = server
+            .execute_tool(
+                "delete_memos",
+                json!({"ids": [ids[0], missing, "not-a-ulid", ids[1], ids[0]]}),
+            )
+            .await?;
+        let outcomes: serde_json::Value = serde_json::from_str(&result)?;
+        let outcomes = outcomes.as_array().unwrap();
+        assert_eq!(outcomes.len(), 5);
+        assert_eq!(outcomes[0], json!({"id": ids[0], "deleted": true}));
+        assert_eq!(outcomes[1]["deleted"], false);
+        assert!(outcomes[1]["error"].as_str().unwrap().contains("not found"));
+        assert_eq!(outcomes[2]["id"], "not-a-ulid");
+        assert!(outcomes[2]["error"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid memo ID"));
+        assert_eq!(outcomes[3], json!({"id": ids[1], "deleted": true}));
+        // The same id twice: the second attempt finds nothing left to delete
+        assert_eq!(outcomes[4]["deleted"], false);
+
+        let listed = server.execute_tool("list_memos", json!({})).await?;
+        let listed: serde_json::Value = serde_json::from_str(&listed)?;
+        assert!(listed["memos"].as_array().unwrap().is_empty());
+        assert!(!temp_dir.path().join(".memoranda").join(".trash").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_memos_tool_soft_moves_to_trash() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+
+        let created = server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Soft", "content": "recoverable"}),
+            )
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&created)?;
+
+        server
+            .execute_tool("delete_memos", json!({"ids": [memo["id"]], "soft": true}))
+            .await?;
+
+        let trash_dir = temp_dir.path().join(".memoranda").join(".trash");
+        let trashed: Vec<_> = std::fs::read_dir(&trash_dir)?.collect();
+        assert_eq!(trashed.len(), 1);
+        assert!(!temp_dir.path().join(".memoranda").join("Soft.md").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compact_store_tool_requires_confirm() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+
+        let trash_dir = temp_dir.path().join(".memoranda").join(".trash");
+        std::fs::create_dir_all(&trash_dir)?;
+        let expired = trash_dir.join("Gone.20200101T000000Z.md");
+        std::fs::write(&expired, "old notes")?;
+
+        assert!(server
+            .execute_tool("compact_store", json!({}))
+            .await
+            .is_err());
+        assert!(server
+            .execute_tool("compact_store", json!({"confirm": false}))
+            .await
+            .is_err());
+        assert!(expired.exists());
+
+        let result = server
+            .execute_tool("compact_store", json!({"confirm": true}))
+            .await?;
+        let report: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(report["bytes_reclaimed"], 9);
+        assert_eq!(report["trash_purged"].as_array().unwrap().len(), 1);
+        assert!(!expired.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_alias_tools() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+
+        let mut ids = Vec::new();
+        for title in ["Error Handling", "Logging"] {
+            let result = server
+                .execute_tool("create_memo", json!({"title": title, "content": "notes"}))
+                .await?;
+            let memo: serde_json::Value = serde_json::from_str(&result)?;
+            ids.push(memo["id"].as_str().unwrap().to_string());
+        }
+        // Cache the memo, so the alias change has to replace the cached copy
+        server
+            .execute_tool("get_memo", json!({"id": ids[0]}))
+            .await?;
+
+        let result = server
+            .execute_tool("add_alias", json!({"id": ids[0], "alias": "Errors"}))
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(memo["aliases"], json!(["Errors"]));
+        let result = server
+            .execute_tool("get_memo", json!({"id": ids[0]}))
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(memo["aliases"], json!(["Errors"]));
+
+        let duplicate = server
+            .execute_tool("add_alias", json!({"id": ids[1], "alias": "errors"}))
+            .await;
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("already used by memo"));
+
+        let result = server
+            .execute_tool("remove_alias", json!({"id": ids[0], "alias": "Errors"}))
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&result)?;
+        assert!(memo.get("aliases").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tag_tools() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let created: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Tagged", "content": "notes", "tags": ["rust"]}),
+                )
+                .await?,
+        )?;
+
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "add_tags",
+                    json!({"id": created.id, "tags": ["cli", "RUST", "errors", "cli"]}),
+                )
+                .await?,
+        )?;
+        assert_eq!(memo.tags, vec!["rust", "cli", "errors"]);
+        assert!(memo.updated_at > created.updated_at);
+
+        // Tags live in frontmatter, so they survive a fresh read of the file
+        let search: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("list_memos", json!({"tags": ["errors"]}))
+                .await?,
+        )?;
+        assert_eq!(search["memos"].as_array().unwrap().len(), 1);
+
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "remove_tags",
+                    json!({"id": created.id, "tags": ["Rust", "missing"]}),
+                )
+                .await?,
+        )?;
+        assert_eq!(memo.tags, vec!["cli", "errors"]);
+        let text = fs::read_to_string(memo.file_path.as_ref().unwrap())?;
+        assert!(!text.contains("rust"), "{text}");
+
+        for (tool, tags) in [("add_tags", json!([])), ("add_tags", json!(["two words"]))] {
+            assert!(server
+                .execute_tool(tool, json!({"id": created.id, "tags": tags}))
+                .await
+                .is_err());
+        }
+        assert!(server
+            .execute_tool("remove_tags", json!({"id": MemoId::new(), "tags": ["cli"]}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_memos_tool_stays_inside_the_store_root() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let outside = TempDir::new()?;
+        let target = outside.path().join("victim.txt");
+        fs::write(&target, "keep me")?;
+        fs::write(temp_dir.path().join("notes.txt"), "keep me too")?;
+
+        for path in [
+            target.to_string_lossy().into_owned(),
+            format!(
+                "../{}/victim.txt",
+                outside.path().file_name().unwrap().to_string_lossy()
+            ),
+            "exports/../../victim.txt".to_string(),
+            "notes.txt".to_string(),
+            ".memoranda".to_string(),
+        ] {
+            assert!(
+                server
+                    .execute_tool("export_memos", json!({"path": path}))
+                    .await
+                    .is_err(),
+                "{path}"
+            );
+        }
+        assert_eq!(fs::read_to_string(&target)?, "keep me");
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("notes.txt"))?,
+            "keep me too"
+        );
+
+        // A bundle from an earlier export is replaced
+        for _ in 0..2 {
+            server
+                .execute_tool("export_memos", json!({"path": "exports/memos.json"}))
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_memos_tool_overwrites_cached_memos() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let created: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Bundled", "content": "original"}),
+                )
+                .await?,
+        )?;
+        server
+            .execute_tool("export_memos", json!({"path": "memos.json"}))
+            .await?;
+        server
+            .execute_tool(
+                "update_memo",
+                json!({"id": created.id, "content": "edited"}),
+            )
+            .await?;
+        // Read it once so the edited memo is cached
+        server
+            .execute_tool("get_memo", json!({"id": created.id}))
+            .await?;
+
+        let report: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("import_memos", json!({"path": "memos.json"}))
+                .await?,
+        )?;
+        assert_eq!(report["skipped"], 1);
+
+        let report: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "import_memos",
+                    json!({"path": "memos.json", "strategy": "overwrite"}),
+                )
+                .await?,
+        )?;
+        assert_eq!(report["overwritten"], 1);
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool("get_memo", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert_eq!(memo.content, "original");
+        assert_eq!(memo.updated_at, created.updated_at);
+
+        assert!(server
+            .execute_tool(
+                "import_memos",
+                json!({"path": "memos.json", "strategy": "replace"}),
+            )
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_directory_tool_stays_inside_the_store_root() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let outside = TempDir::new()?;
+        fs::write(outside.path().join("secret.md"), "from outside the store")?;
+
+        for path in [
+            outside.path().to_string_lossy().into_owned(),
+            format!(
+                "../{}",
+                outside.path().file_name().unwrap().to_string_lossy()
+            ),
+            "notes/../..".to_string(),
+        ] {
+            assert!(
+                server
+                    .execute_tool("import_directory", json!({"path": path}))
+                    .await
+                    .is_err(),
+                "{path}"
+            );
+        }
+
+        fs::create_dir(temp_dir.path().join("notes"))?;
+        fs::write(temp_dir.path().join("notes/inside.md"), "from the store")?;
+        let report: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("import_directory", json!({"path": "notes"}))
+                .await?,
+        )?;
+        assert_eq!(report["imported"].as_array().unwrap().len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_memos_tool_stays_inside_the_store_root() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let outside = TempDir::new()?;
+        let bundle = json!({
+            "format_version": 1,
+            "memos": [{
+                "id": MemoId::new(),
+                "title": "Smuggled",
+                "content": "from outside the store",
+                "tags": [],
+                "created_at": "2024-01-02T03:04:05Z",
+                "updated_at": "2024-01-02T03:04:05Z",
+                "path": null
+            }]
+        });
+        fs::write(outside.path().join("memos.json"), bundle.to_string())?;
+
+        for path in [
+            outside
+                .path()
+                .join("memos.json")
+                .to_string_lossy()
+                .into_owned(),
+            format!(
+                "../{}/memos.json",
+                outside.path().file_name().unwrap().to_string_lossy()
+            ),
+        ] {
+            assert!(
+                server
+                    .execute_tool("import_memos", json!({"path": path}))
+                    .await
+                    .is_err(),
+                "{path}"
+            );
+        }
+
+        fs::write(temp_dir.path().join("memos.json"), bundle.to_string())?;
+        server
+            .execute_tool("import_memos", json!({"path": "./memos.json"}))
+            .await?;
+        let listed = server.execute_tool("list_memos", json!({})).await?;
+        assert!(listed.contains("Smuggled"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_link_path_tool() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+
+        let mut ids = Vec::new();
+        for (title, content) in [
+            ("A", "Depends on [[B]]"),
+            ("B", "See [[C]]"),
+            ("C", "Leaf"),
+            ("D", "Unconnected"),
+        ] {
+            let memo: serde_json::Value = serde_json::from_str(
+                &server
+                    .execute_tool("create_memo", json!({"title": title, "content": content}))
+                    .await?,
+            )?;
+            ids.push(memo["id"].clone());
+        }
+
+        let path: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("link_path", json!({"from_id": ids[0], "to_id": ids[2]}))
+                .await?,
+        )?;
+        assert_eq!(
+            path,
+            json!([
+                {"id": ids[0], "title": "A"},
+                {"id": ids[1], "title": "B"},
+                {"id": ids[2], "title": "C"},
+            ])
+        );
+
+        let path: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("link_path", json!({"from_id": ids[0], "to_id": ids[3]}))
+                .await?,
+        )?;
+        assert!(path.is_null());
+
+        assert!(server
+            .execute_tool(
+                "link_path",
+                json!({"from_id": ids[0], "to_id": MemoId::new().to_string()})
+            )
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_memo_tool() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let created: Memo = serde_json::from_str(
+            &server
+                .execute_tool("create_memo", json!({"title": "Draft", "content": "body"}))
+                .await?,
+        )?;
+
+        let renamed: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "rename_memo",
+                    json!({"id": created.id, "title": "Final Plan"}),
+                )
+                .await?,
+        )?;
+        assert_eq!(renamed.id, created.id);
+        assert_eq!(renamed.title, "Final Plan");
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        assert!(memoranda_dir.join("Final Plan.md").exists());
+        assert!(!memoranda_dir.join("Draft.md").exists());
+
+        let fetched: Memo = serde_json::from_str(
+            &server
+                .execute_tool("get_memo", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert_eq!(fetched.title, "Final Plan");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_filename_tool() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let created = store.create_memo("Draft".to_string(), "body".to_string())?;
+        store.update_memo_title(&created.id, "Release Notes".to_string())?;
+
+        let result: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("sync_filename", json!({"id": created.id}))
+                .await?,
+        )?;
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        assert_eq!(
+            result["renamed"]["expected_path"],
+            json!(memoranda_dir.join("Release Notes.md"))
+        );
+        assert!(memoranda_dir.join("Release Notes.md").exists());
+        assert!(!memoranda_dir.join("Draft.md").exists());
+
+        let fetched: Memo = serde_json::from_str(
+            &server
+                .execute_tool("get_memo", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert_eq!(fetched.title, "Release Notes");
+
+        let result: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("sync_filename", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert!(result["renamed"].is_null());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_consistency_tool_reports_divergence() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir(temp_dir.path().join(".memoranda"))?;
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo_async("Cached".to_string(), "original".to_string())
+            .await?;
+        let mut server = McpServer::new_with_memo_store("test-server".to_string(), store);
+
+        let report: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("cache_consistency", json!({})).await?)?;
+        assert_eq!(report[0]["diverged"], false);
+
+        let file_path = memo.file_path.clone().unwrap();
+        let text = fs::read_to_string(&file_path)?;
+        fs::write(&file_path, text.replace("original", "edited elsewhere"))?;
+
+        let report: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("cache_consistency", json!({})).await?)?;
+        assert_eq!(
+            report,
+            json!([{
+                "id": memo.id,
+                "file_path": file_path,
+                "diverged": true,
+                "file_missing": false
+            }])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memo_tools_are_served_from_the_cache() -> Result<()> {
+        async fn cache_stats(server: &mut McpServer) -> Result<serde_json::Value> {
+            let report: serde_json::Value =
+                serde_json::from_str(&server.execute_tool("diagnostics", json!({})).await?)?;
+            Ok(report["cache_stats"].clone())
+        }
+
+        let (mut server, _temp_dir) = create_test_server()?;
+        let created: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Cached", "content": "from a tool call"}),
+                )
+                .await?,
+        )?;
+        let before = cache_stats(&mut server).await?;
+
+        let fetched: Memo = serde_json::from_str(
+            &server
+                .execute_tool("get_memo", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert_eq!(fetched.content, "from a tool call");
+        server
+            .execute_tool(
+                "update_memo",
+                json!({"id": created.id, "content": "updated through the cache"}),
+            )
+            .await?;
+
+        let after = cache_stats(&mut server).await?;
+        assert_eq!(
+            after["memo_hits"],
+            before["memo_hits"].as_u64().unwrap() + 2
+        );
+        assert_eq!(after["memo_misses"], before["memo_misses"]);
+
+        let fetched: Memo = serde_json::from_str(
+            &server
+                .execute_tool("get_memo", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert_eq!(fetched.content, "updated through the cache");
+
+        server
+            .execute_tool("delete_memo", json!({"id": created.id, "permanent": true}))
+            .await?;
+        assert!(server
+            .execute_tool("get_memo", json!({"id": created.id}))
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redaction_patterns_apply_to_tool_output_only() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir(temp_dir.path().join(".memoranda"))?;
+        let settings = crate::config::Settings {
+            redaction_patterns: vec![r"sk-[A-Za-z0-9]{16,}".to_string()],
+            ..Default::default()
+        };
+        let mut server = McpServer::new_with_memo_store(
+            "test-server".to_string(),
+            MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings),
+        );
+
+        let secret = "sk-abcdefghijklmnop1234";
+        let created: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Deploy Keys", "content": format!("deploy key {secret} here")}),
+                )
+                .await?,
+        )?;
+
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool("get_memo", json!({"id": created.id}))
+                .await?,
+        )?;
+        assert_eq!(memo.content, "deploy key [REDACTED] here");
+
+        let results = server
+            .execute_tool("search_memos", json!({"query": "deploy"}))
+            .await?;
+        let context = server.execute_tool("get_all_context", json!({})).await?;
+        let listed = server.execute_tool("list_memos", json!({})).await?;
+        for output in [&results, &context, &listed] {
+            assert!(!output.contains(secret), "{output}");
+            assert!(output.contains("[REDACTED]"), "{output}");
+        }
+
+        server
+            .execute_tool("export_memos", json!({"path": "memos.json"}))
+            .await?;
+        let bundle = fs::read_to_string(temp_dir.path().join("memos.json"))?;
+        assert!(!bundle.contains(secret), "{bundle}");
+        assert!(bundle.contains("[REDACTED]"), "{bundle}");
+
+        let on_disk = fs::read_to_string(temp_dir.path().join(".memoranda/Deploy Keys.md"))?;
+        assert!(on_disk.contains(secret));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_redaction_applies_to_every_tool_returning_a_memo() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir(temp_dir.path().join(".memoranda"))?;
+        let settings = crate::config::Settings {
+            redaction_patterns: vec![r"sk-[A-Za-z0-9]{16,}".to_string()],
+            ..Default::default()
+        };
+        let mut server = McpServer::new_with_memo_store(
+            "test-server".to_string(),
+            MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings),
+        );
+        let content = "deploy with src/deploy.rs and key sk-abcdefghijklmnop1234";
+
+        let created = server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Deploy", "content": content}),
+            )
+            .await?;
+        let id = serde_json::from_str::<Memo>(&created)?.id;
+        let token: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("begin_memo_write", json!({"title": "Streamed"}))
+                .await?,
+        )?;
+        let token = token["token"].as_str().unwrap().to_string();
+        server
+            .execute_tool("write_memo_chunk", json!({"token": token, "data": content}))
+            .await?;
+
+        let mut outputs = vec![("create_memo", created)];
+        for (tool, arguments) in [
+            ("update_memo", json!({"id": id, "content": content})),
+            ("rename_memo", json!({"id": id, "title": "Deploy Notes"})),
+            ("add_alias", json!({"id": id, "alias": "Shipping"})),
+            ("remove_alias", json!({"id": id, "alias": "Shipping"})),
+            ("add_tags", json!({"id": id, "tags": ["ops"]})),
+            ("remove_tags", json!({"id": id, "tags": ["ops"]})),
+            ("memos_mentioning_path", json!({"path": "src/deploy.rs"})),
+            ("trash_memo", json!({"id": id})),
+            ("untrash_memo", json!({"id": id})),
+            ("commit_memo_write", json!({"token": token})),
+        ] {
+            outputs.push((tool, server.execute_tool(tool, arguments).await?));
+        }
+
+        for (tool, output) in outputs {
+            assert!(
+                !output.contains("sk-abcdefghijklmnop1234"),
+                "{tool}: {output}"
+            );
+            if tool != "trash_memo" {
+                assert!(output.contains("[REDACTED]"), "{tool}: {output}");
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retention_schedule_never_overflows() {
+        let now = tokio::time::Instant::now();
+        let (start, period) = McpServer::retention_schedule(24, now).unwrap();
+        assert_eq!(period, std::time::Duration::from_secs(24 * 3600));
+        assert_eq!(start, now + period);
+
+        assert!(McpServer::retention_schedule(u64::MAX, now).is_none());
+        assert!(McpServer::retention_schedule(u64::MAX / 3600, now).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_hub_memos_ranks_most_linked_first() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        for (title, content) in [
+            ("Architecture", "The core design"),
+            ("Storage", "Part of [[Architecture]]"),
+            ("Search", "Part of [[Architecture]], uses [[Storage]]"),
+            ("Server", "Part of [[architecture]], uses [[Storage]]"),
+            ("Scratch", "Unlinked"),
+        ] {
+            server
+                .execute_tool("create_memo", json!({"title": title, "content": content}))
+                .await?;
+        }
+
+        let hubs: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("hub_memos", json!({})).await?)?;
+        let ranked: Vec<_> = hubs
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hub| {
+                (
+                    hub["title"].as_str().unwrap(),
+                    hub["backlinks"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(ranked, vec![("Architecture", 3), ("Storage", 2)]);
+
+        let hubs: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("hub_memos", json!({"limit": 1}))
+                .await?,
+        )?;
+        assert_eq!(hubs.as_array().unwrap().len(), 1);
+        assert_eq!(hubs[0]["title"], "Architecture");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_link_graph_tool() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let mut ids = HashMap::new();
+        for (title, content) in [
+            ("Architecture", "Built from [[Storage]] and [[Search]]"),
+            ("Storage", "Indexed by [[Search]], see [[Roadmap]]"),
+            ("Search", "Reads [[Storage]]"),
+        ] {
+            let memo: Memo = serde_json::from_str(
+                &server
+                    .execute_tool("create_memo", json!({"title": title, "content": content}))
+                    .await?,
+            )?;
+            ids.insert(title, memo.id.to_string());
+        }
+
+        let graph: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("link_graph", json!({"include_unresolved": true}))
+                .await?,
+        )?;
+        let mut nodes: Vec<_> = graph["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| (node["title"].as_str().unwrap(), node["phantom"] == true))
+            .collect();
+        nodes.sort();
+        assert_eq!(
+            nodes,
+            vec![
+                ("Architecture", false),
+                ("Roadmap", true),
+                ("Search", false),
+                ("Storage", false),
+            ]
+        );
+
+        let mut edges: Vec<_> = graph["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| {
+                (
+                    edge["from"].as_str().unwrap().to_string(),
+                    edge["to"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        edges.sort();
+        let mut expected = vec![
+            (ids["Architecture"].clone(), ids["Storage"].clone()),
+            (ids["Architecture"].clone(), ids["Search"].clone()),
+            (ids["Storage"].clone(), ids["Search"].clone()),
+            (ids["Storage"].clone(), "phantom:Roadmap".to_string()),
+            (ids["Search"].clone(), ids["Storage"].clone()),
+        ];
+        expected.sort();
+        assert_eq!(edges, expected);
+        assert_eq!(graph["truncated"], false);
+
+        let graph: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("link_graph", json!({})).await?)?;
+        assert_eq!(graph["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(graph["edges"].as_array().unwrap().len(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_store_tool() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Checked", "content": "on disk"}),
+            )
+            .await?;
+
+        let result = server.execute_tool("verify_store", json!({})).await?;
+        let report: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(report["fixed"], false);
+        assert_eq!(report["files_scanned"], 1);
+        assert!(report["stale_cache_entries"].as_array().unwrap().is_empty());
+        assert!(report["missing_indexed"].as_array().unwrap().is_empty());
+
+        let result = server
+            .execute_tool("verify_store", json!({"fix": true}))
+            .await?;
+        let report: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(report["fixed"], true);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_layout_tool() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let nested_dir = temp_dir.path().join("docs/.memoranda");
+        fs::create_dir_all(&nested_dir)?;
+
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Layout Memo", "content": "on disk"}),
+            )
+            .await?;
+        fs::write(nested_dir.join("broken.md"), "---\n{ not json\n---\nbody")?;
+        fs::write(nested_dir.join("plain.md"), "# Plain markdown")?;
+        fs::write(nested_dir.join("notes.txt"), "not a memo")?;
+
+        let result = server.execute_tool("store_layout", json!({})).await?;
+        let layout: serde_json::Value = serde_json::from_str(&result)?;
+        let dirs = layout.as_array().unwrap();
+        assert_eq!(dirs.len(), 2);
+
+        let root = dirs
+            .iter()
+            .find(|dir| dir["path"] == json!(temp_dir.path().join(".memoranda")))
+            .unwrap();
+        let root_files = root["files"].as_array().unwrap();
+        assert_eq!(root_files.len(), 1);
+        assert_eq!(root_files[0]["filename"], "Layout Memo.md");
+        assert_eq!(root_files[0]["has_valid_frontmatter"], true);
+        assert!(root_files[0]["id"].is_string());
+        assert!(root_files[0]["mtime"].is_string());
+
+        let nested = dirs
+            .iter()
+            .find(|dir| dir["path"] == json!(nested_dir))
+            .unwrap();
+        let nested_files = nested["files"].as_array().unwrap();
+        let names: Vec<_> = nested_files.iter().map(|f| &f["filename"]).collect();
+        assert_eq!(names, [&json!("broken.md"), &json!("plain.md")]);
+        for file in nested_files {
+            assert_eq!(file["has_valid_frontmatter"], false);
+            assert!(file.get("id").is_none());
+        }
+        assert_eq!(nested_files[1]["size"], "# Plain markdown".len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chunked_memo_write_commit_and_abort() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+
+        let result = server
+            .execute_tool(
+                "begin_memo_write",
+                json!({"title": "Streamed", "tags": ["large"]}),
+            )
+            .await?;
+        let token = serde_json::from_str::<serde_json::Value>(&result)?["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let chunks = ["# Part one\n", "second chunk\n", "third chunk"];
+        let mut total = 0;
+        for chunk in chunks {
+            let result = server
+                .execute_tool("write_memo_chunk", json!({"token": token, "data": chunk}))
+                .await?;
+            total += chunk.len();
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(&result)?["bytes_written"],
+                total
+            );
+        }
+        // Nothing is visible as a memo until the write is committed
+        let result = server.execute_tool("list_memos", json!({})).await?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&result)?["memos"],
+            json!([])
+        );
+
+        let result = server
+            .execute_tool("commit_memo_write", json!({"token": token}))
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(memo["title"], "Streamed");
+        assert_eq!(memo["content"], chunks.concat());
+        assert_eq!(memo["tags"], json!(["large"]));
+
+        let result = server
+            .execute_tool("get_memo", json!({"id": memo["id"]}))
+            .await?;
+        let fetched: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(fetched["content"], chunks.concat());
+
+        // The token cannot be reused once committed
+        assert!(server
+            .execute_tool("write_memo_chunk", json!({"token": token, "data": "late"}))
+            .await
+            .is_err());
+
+        let result = server
+            .execute_tool("begin_memo_write", json!({"title": "Abandoned"}))
+            .await?;
+        let token = serde_json::from_str::<serde_json::Value>(&result)?["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        server
+            .execute_tool("write_memo_chunk", json!({"token": token, "data": "draft"}))
+            .await?;
+        server
+            .execute_tool("abort_memo_write", json!({"token": token}))
+            .await?;
+
+        let result = server.execute_tool("list_memos", json!({})).await?;
+        let memos = serde_json::from_str::<serde_json::Value>(&result)?["memos"].clone();
+        assert_eq!(memos.as_array().unwrap().len(), 1);
+        assert_eq!(memos[0]["title"], "Streamed");
+        let staging_dir = temp_dir
+            .path()
+            .join(".memoranda")
+            .join(crate::memo::STAGING_DIR_NAME);
+        assert_eq!(fs::read_dir(staging_dir)?.count(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memo_id_argument_shapes() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+
+        let result = server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Shapes", "content": "any id"}),
+            )
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&result)?;
+        let id = memo["id"].as_str().unwrap().to_string();
+
+        // A plain string, a wrapped ULID, and the memo object get_memo returns
+        for id_arg in [json!(id), json!({"ulid": id}), memo.clone()] {
+            let result = server
+                .execute_tool("get_memo", json!({"id": id_arg}))
+                .await?;
+            let fetched: serde_json::Value = serde_json::from_str(&result)?;
+            assert_eq!(fetched["id"], id);
+        }
+
+        let error = server
+            .execute_tool("get_memo", json!({"id": 12345}))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Invalid memo ID format"), "{error}");
+        assert!(error.ends_with("got 12345"), "{error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_duplicate_ids_tool() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+
+        let result = server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Copied", "content": "duplicated"}),
+            )
+            .await?;
+        let memo: serde_json::Value = serde_json::from_str(&result)?;
+        let id = memo["id"].as_str().unwrap().to_string();
+
+        let result = server
+            .execute_tool("check_duplicate_ids", json!({}))
+            .await?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&result)?,
+            json!([])
+        );
+
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::copy(
+            memoranda_dir.join("Copied.md"),
+            memoranda_dir.join("Copied (1).md"),
+        )?;
+
+        let result = server
+            .execute_tool("check_duplicate_ids", json!({}))
+            .await?;
+        let duplicates: serde_json::Value = serde_json::from_str(&result)?;
+        assert_eq!(duplicates.as_array().unwrap().len(), 1);
+        assert_eq!(duplicates[0]["id"], id);
+        assert_eq!(duplicates[0]["paths"].as_array().unwrap().len(), 2);
+
+        let error = server
+            .execute_tool("get_memo", json!({"id": id}))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("used by multiple files"), "{error}");
+        assert!(error.contains("Copied (1).md"), "{error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_repeated_errors_are_logged_once() -> Result<()> {
+        use crate::mcp::server::ERROR_LOG_LINES;
+
+        let (mut server, _temp_dir) = create_test_server()?;
+        let missing_id = MemoId::new().to_string();
+        let mut initialized = true;
+        ERROR_LOG_LINES.with(|lines| lines.borrow_mut().clear());
+
+        for i in 0..50 {
+            let call = json!({
+                "jsonrpc": "2.0",
+                "id": i,
+                "method": "tools/call",
+                "params": {"name": "get_memo", "arguments": {"id": missing_id}}
+            });
+            let response = server.handle_message(call, &mut initialized).await.unwrap();
+            assert_eq!(response["id"], i);
+            assert_eq!(response["error"]["code"], -32000);
+        }
+        assert_eq!(ERROR_LOG_LINES.with(|lines| lines.borrow().len()), 1);
+
+        server.flush_error_repeats();
+        let lines = ERROR_LOG_LINES.with(|lines| lines.borrow().clone());
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("tools/call get_memo failed"));
+        assert!(lines[1].ends_with("(repeated 49 times)"), "{}", lines[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_report_has_every_section() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Diagnosed", "content": "content"}),
+            )
+            .await?;
+
+        let report: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("diagnostics", json!({})).await?)?;
+        for section in [
+            "versions",
+            "settings",
+            "server_status",
+            "tools",
+            "store_layout",
+            "cache_stats",
+        ] {
+            assert!(!report[section].is_null(), "missing section {section}");
+        }
+        assert_eq!(report["versions"]["memoranda"], env!("CARGO_PKG_VERSION"));
+        assert!(report["settings"]["search_cache_size"].is_u64());
+        assert_eq!(report["server_status"]["memo_store_available"], true);
+        assert_eq!(
+            report["store_layout"][0]["files"][0]["filename"],
+            "Diagnosed.md"
+        );
+        assert!(report["cache_stats"]["memo_hits"].is_u64());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_client_name_as_actor() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir(temp_dir.path().join(".memoranda"))?;
+        let settings = crate::config::Settings {
+            audit_log: true,
+            ..Default::default()
+        };
+        let mut server = McpServer::new_with_memo_store(
+            "test-server".to_string(),
+            MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings),
+        );
+
+        let mut initialized = false;
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"clientInfo": {"name": "auditor", "version": "1.0.0"}}
+        });
+        server.handle_message(initialize, &mut initialized).await;
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Audited", "content": "body"}),
+                )
+                .await?,
+        )?;
+        server.execute_tool("list_memos", json!({})).await?;
+
+        let records = crate::memo::read_audit_log(&temp_dir.path().join(".memoranda"))?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].op, "create_memo");
+        assert_eq!(records[0].id, Some(memo.id));
+        assert_eq!(records[0].actor.as_deref(), Some("auditor"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_memos_returns_only_memos_created_this_session() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        // Created by someone else before this session started
+        MemoStore::new(temp_dir.path().to_path_buf())
+            .create_memo("Other Agent".to_string(), "not ours".to_string())?;
+
+        let mut initialized = false;
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"clientInfo": {"name": "agent", "version": "1.0.0"}}
+        });
+        server.handle_message(initialize, &mut initialized).await;
+
+        let mut created = Vec::new();
+        for title in ["First", "Second"] {
+            let memo: Memo = serde_json::from_str(
+                &server
+                    .execute_tool("create_memo", json!({"title": title, "content": "ours"}))
+                    .await?,
+            )?;
+            created.push(memo.id);
+        }
+
+        let memos: Vec<Memo> =
+            serde_json::from_str(&server.execute_tool("session_memos", json!({})).await?)?;
+        let ids: Vec<MemoId> = memos.iter().map(|memo| memo.id).collect();
+        assert_eq!(ids, created);
+        let all: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_memos", json!({})).await?)?;
+        assert_eq!(all["total"], 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trash_list_untrash_round_trip() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Binned", "content": "keep me"}),
+                )
+                .await?,
+        )?;
+        let original_path = memo.file_path.clone().unwrap();
+
+        let trashed: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("trash_memo", json!({"id": memo.id.to_string()}))
+                .await?,
+        )?;
+        assert!(!original_path.exists());
+        assert!(trashed["trashed_path"].as_str().unwrap().contains(".trash"));
+        assert!(server
+            .execute_tool("get_memo", json!({"id": memo.id.to_string()}))
+            .await
+            .is_err());
+
+        let trash: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_trash", json!({})).await?)?;
+        assert_eq!(trash.as_array().unwrap().len(), 1);
+        assert_eq!(trash[0]["id"], memo.id.to_string());
+        assert_eq!(
+            trash[0]["original_path"],
+            original_path.display().to_string()
+        );
+
+        let restored: Memo = serde_json::from_str(
+            &server
+                .execute_tool("untrash_memo", json!({"id": memo.id.to_string()}))
+                .await?,
+        )?;
+        assert_eq!(restored.id, memo.id);
+        assert_eq!(restored.content, "keep me");
+        assert!(original_path.exists());
+        let trash: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_trash", json!({})).await?)?;
+        assert!(trash.as_array().unwrap().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_memo_moves_to_trash_by_default() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Oops", "content": "deleted by mistake"}),
+                )
+                .await?,
+        )?;
+        let original_path = memo.file_path.clone().unwrap();
+
+        let deleted: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("delete_memo", json!({"id": memo.id.to_string()}))
+                .await?,
+        )?;
+        assert_eq!(deleted["success"], true);
+        assert!(!original_path.exists());
+        assert!(std::path::Path::new(deleted["trashed_path"].as_str().unwrap()).exists());
+
+        let memos: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_memos", json!({})).await?)?;
+        assert!(memos["memos"].as_array().unwrap().is_empty());
+
+        server
+            .execute_tool("untrash_memo", json!({"id": memo.id.to_string()}))
+            .await?;
+        assert!(original_path.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_untrash_unknown_id_fails_cleanly() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let error = server
+            .execute_tool("untrash_memo", json!({"id": MemoId::new().to_string()}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("not in trash"), "{error}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_and_search_memos_paginate() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        for i in 0..60 {
+            server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": format!("Note {i:02}"), "content": "shared"}),
+                )
+                .await?;
+        }
+
+        let first: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_memos", json!({})).await?)?;
+        assert_eq!(first["memos"].as_array().unwrap().len(), 50);
+        assert_eq!(first["total"], 60);
+        assert_eq!(first["has_more"], true);
+        assert_eq!(first["memos"][0]["title"], "Note 00");
+
+        let last: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("list_memos", json!({"offset": 50, "limit": 20}))
+                .await?,
+        )?;
+        assert_eq!(last["memos"].as_array().unwrap().len(), 10);
+        assert_eq!(last["memos"][0]["title"], "Note 50");
+        assert_eq!(last["has_more"], false);
+
+        let all: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("list_memos", json!({"limit": 0}))
+                .await?,
+        )?;
+        assert_eq!(all["memos"].as_array().unwrap().len(), 60);
+
+        let results: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "search_memos",
+                    json!({"query": "shared", "limit": 5, "offset": 58}),
+                )
+                .await?,
+        )?;
+        assert_eq!(results["results"].as_array().unwrap().len(), 2);
+        assert_eq!(results["total"], 60);
+        assert_eq!(results["has_more"], false);
+
+        assert!(server
+            .execute_tool("list_memos", json!({"limit": -1}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_memos_filters_by_tags() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        for (title, tags) in [
+            ("Both", vec!["Rust", "api"]),
+            ("Rust Only", vec!["rust"]),
+            ("Neither", vec!["ops"]),
+        ] {
+            let mut memo = Memo::new(title.to_string(), "content".to_string())?;
+            memo.tags = tags.into_iter().map(String::from).collect();
+            fs::write(
+                memoranda_dir.join(format!("{title}.md")),
+                format!("---\n{}\n---\ncontent", serde_json::to_string(&memo)?),
+            )?;
+        }
+        let cases = [
+            (json!({}), vec!["Both", "Neither", "Rust Only"]),
+            (
+                json!({"tags": ["RUST", "ops"]}),
+                vec!["Both", "Neither", "Rust Only"],
+            ),
+            (
+                json!({"tags": ["rust", " API "], "match": "all"}),
+                vec!["Both"],
+            ),
+            (json!({"tags": ["missing"]}), vec![]),
+        ];
+        for (arguments, expected) in cases {
+            let listed: serde_json::Value =
+                serde_json::from_str(&server.execute_tool("list_memos", arguments).await?)?;
+            let memos: Vec<Memo> = serde_json::from_value(listed["memos"].clone())?;
+            let mut titles: Vec<_> = memos.into_iter().map(|memo| memo.title).collect();
+            titles.sort();
+            assert_eq!(titles, expected);
+        }
+
+        assert!(server
+            .execute_tool("list_memos", json!({"tags": ["rust"], "match": "some"}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_memo_with_tags() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let created: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Tagged", "content": "body", "tags": ["rust", "api"]}),
+                )
+                .await?,
+        )?;
+        assert_eq!(created.tags, vec!["rust", "api"]);
+
+        let listed: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("list_memos", json!({"tags": ["api"]}))
+                .await?,
+        )?;
+        assert_eq!(listed["total"], 1);
+        assert_eq!(listed["memos"][0]["id"], json!(created.id));
+
+        for tags in [json!(["has space"]), json!([""]), json!("rust")] {
+            assert!(server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Bad", "content": "body", "tags": tags}),
+                )
+                .await
+                .is_err());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_memo_in_directory() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let docs_dir = temp_dir.path().join("docs").join(".memoranda");
+        fs::create_dir_all(&docs_dir)?;
+
+        for directory in ["docs", "docs/.memoranda"] {
+            let created: Memo = serde_json::from_str(
+                &server
+                    .execute_tool(
+                        "create_memo",
+                        json!({"title": directory, "content": "body", "directory": directory}),
+                    )
+                    .await?,
+            )?;
+            assert!(created.file_path.unwrap().starts_with(&docs_dir));
+        }
+
+        let error = server
+            .execute_tool(
+                "create_memo",
+                json!({"title": "Lost", "content": "body", "directory": "src"}),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("not a memo directory"),
+            "{error}"
+        );
+        assert!(error.to_string().contains("auto_create_scope_dir"));
+        assert!(!temp_dir.path().join("src").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_memo_refuses_to_overwrite_existing_file() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let todo_path = temp_dir.path().join(".memoranda").join("TODO.md");
+        fs::write(&todo_path, "- water the plants\n")?;
+
+        for (tool, args) in [
+            ("create_memo", json!({"title": "TODO", "content": "new"})),
+            ("begin_memo_write", json!({"title": "TODO"})),
+        ] {
+            let error = server.execute_tool(tool, args).await.unwrap_err();
+            assert!(
+                error.to_string().contains("choose a different title"),
+                "{tool}: {error}"
+            );
+        }
+        assert_eq!(fs::read_to_string(&todo_path)?, "- water the plants\n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tools_list_changed_on_store_reinitialization() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        server.spawn_store_initialization(|| None);
+        let mut initialized = false;
+        let ping = json!({"jsonrpc": "2.0", "id": 1, "method": "ping"});
+        for _ in 0..200 {
+            let state = server
+                .handle_message(ping.clone(), &mut initialized)
+                .await
+                .unwrap()["result"]["store_state"]
+                .clone();
+            if state != "initializing" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(server.store_state(), StoreState::Unavailable);
+        let limited_tools = server.get_tools().len();
+        let list_changed = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/tools/list_changed"
+        });
+        assert_eq!(server.take_notifications(), vec![list_changed.clone()]);
+
+        assert!(!server.install_reinitialized_store(None));
+        assert!(server.take_notifications().is_empty());
+
+        assert!(
+            server.install_reinitialized_store(Some(MemoStore::new(temp_dir.path().to_path_buf())))
+        );
+        assert!(server.get_tools().len() > limited_tools);
+        assert_eq!(server.take_notifications(), vec![list_changed]);
+        assert!(server.take_notifications().is_empty());
+
+        // Already full: nothing changes, so nothing is announced
+        assert!(server.retry_memo_store_initialization()?);
+        assert!(server.take_notifications().is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memos_as_resources() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let mut initialized = false;
+        let request = |id: u64, method: &str, params: serde_json::Value| json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+
+        let response = server
+            .handle_message(request(1, "resources/list", json!({})), &mut initialized)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32002);
+
+        let init = server
+            .handle_message(request(2, "initialize", json!({})), &mut initialized)
+            .await
+            .unwrap();
+        assert!(init["result"]["capabilities"]["resources"].is_object());
+
+        let memo: Memo = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": "Runbook", "content": "# Deploy\n\nShip it"}),
+                )
+                .await?,
+        )?;
+        let uri = format!("memo://{}", memo.id);
+
+        let listed = server
+            .handle_message(request(3, "resources/list", json!({})), &mut initialized)
+            .await
+            .unwrap();
+        assert_eq!(
+            listed["result"]["resources"],
+            json!([{"uri": uri, "name": "Runbook", "mimeType": "text/markdown"}])
+        );
+
+        let read = server
+            .handle_message(
+                request(4, "resources/read", json!({"uri": uri})),
+                &mut initialized,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            read["result"]["contents"],
+            json!([{"uri": uri, "mimeType": "text/markdown", "text": "# Deploy\n\nShip it"}])
+        );
+
+        let missing = format!("memo://{}", MemoId::new());
+        let response = server
+            .handle_message(
+                request(5, "resources/read", json!({"uri": missing})),
+                &mut initialized,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32002);
+        assert_eq!(response["error"]["data"]["uri"], missing);
+
+        let response = server
+            .handle_message(
+                request(6, "resources/read", json!({"uri": "file:///etc/passwd"})),
+                &mut initialized,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32602);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tools_wait_for_deferred_store_initialization() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let root = temp_dir.path().to_path_buf();
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        server.spawn_store_initialization(move || {
+            released.recv().ok()?;
+            Some(MemoStore::new(root))
+        });
+
+        let mut initialized = false;
+        let request = |id: u64, method: &str, params: serde_json::Value| json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+
+        let ping = server
+            .handle_message(request(1, "ping", json!({})), &mut initialized)
+            .await
+            .unwrap();
+        assert_eq!(ping["result"]["store_state"], "initializing");
+        let init = server
+            .handle_message(request(2, "initialize", json!({})), &mut initialized)
+            .await
+            .unwrap();
+        assert!(init["result"]["serverInfo"].is_object());
+
+        let call = json!({"name": "list_memos", "arguments": {}});
+        let response = server
+            .handle_message(request(3, "tools/call", call.clone()), &mut initialized)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32004);
+        assert_eq!(response["error"]["data"]["retryable"], true);
+        assert!(response["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("initializing"));
+
+        release.send(()).unwrap();
+        let mut state = json!("initializing");
+        for id in 4..200 {
+            state = server
+                .handle_message(request(id, "ping", json!({})), &mut initialized)
+                .await
+                .unwrap()["result"]["store_state"]
+                .clone();
+            if state != "initializing" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(state, "ready");
+
+        let response = server
+            .handle_message(request(200, "tools/call", call), &mut initialized)
+            .await
+            .unwrap();
+        let listed: serde_json::Value =
+            serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())?;
+        assert_eq!(listed["total"], 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_policy_applies_to_clients_initialized_before_the_store() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        fs::write(
+            temp_dir.path().join(".memoranda/policy.json"),
+            json!({"default": "read", "clients": {"early-agent": "write"}}).to_string(),
+        )?;
+        let root = temp_dir.path().to_path_buf();
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        server.spawn_store_initialization(move || {
+            released.recv().ok()?;
+            Some(MemoStore::new(root))
+        });
+
+        let mut initialized = false;
+        let request = |id: u64, method: &str, params: serde_json::Value| json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        server
+            .handle_message(
+                request(
+                    1,
+                    "initialize",
+                    json!({"clientInfo": {"name": "early-agent"}}),
+                ),
+                &mut initialized,
+            )
+            .await
+            .unwrap();
+
+        // Access is not known yet, so a write is retryable rather than allowed
+        let compact = json!({"name": "compact_store", "arguments": {"confirm": true}});
+        let response = server
+            .handle_message(request(2, "tools/call", compact.clone()), &mut initialized)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32004, "{response}");
+
+        release.send(()).unwrap();
+        for id in 3..200 {
+            let state = server
+                .handle_message(request(id, "ping", json!({})), &mut initialized)
+                .await
+                .unwrap()["result"]["store_state"]
+                .clone();
+            if state != "initializing" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(server.store_state(), StoreState::Ready);
+
+        let response = server
+            .handle_message(request(200, "tools/call", compact), &mut initialized)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32003, "{response}");
+        let create =
+            json!({"name": "create_memo", "arguments": {"title": "Early", "content": "hi"}});
+        let response = server
+            .handle_message(request(201, "tools/call", create), &mut initialized)
+            .await
+            .unwrap();
+        assert!(response.get("error").is_none(), "{response}");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tools_list_order() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let mut initialized = true;
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"});
+        let response = server
+            .handle_message(request, &mut initialized)
+            .await
+            .unwrap();
+        let listed: Vec<String> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap().to_string())
+            .collect();
+
+        // By default read tools come first, each role keeping registration order
+        let mut expected = server.get_tools().to_vec();
+        expected.sort_by_key(|tool| ToolRole::required_for(&tool.name));
+        let expected: Vec<_> = expected.into_iter().map(|tool| tool.name).collect();
+        assert_eq!(listed, expected);
+        assert_eq!(listed[0], "list_memos");
+
+        let mut tools = server.get_tools().to_vec();
+        order_tools(
+            &mut tools,
+            &[
+                "compact_store".to_string(),
+                "create_memo".to_string(),
+                "unknown".to_string(),
+            ],
+        );
+        let names: Vec<_> = tools.iter().map(|tool| tool.name.as_str()).collect();
+        assert_eq!(&names[..3], ["compact_store", "create_memo", "list_memos"]);
+        assert_eq!(names.len(), server.get_tools().len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_tags_counts_and_sorts_tags() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let tags: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_tags", json!({})).await?)?;
+        assert_eq!(tags, json!([]));
+
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        for (title, tags) in [
+            ("One", vec!["rust", "notes"]),
+            ("Two", vec!["Rust", "api"]),
+            ("Three", vec!["RUST", "notes", "Notes"]),
+            ("Untagged", vec![]),
+        ] {
+            let mut memo = Memo::new(title.to_string(), "content".to_string())?;
+            memo.tags = tags.into_iter().map(String::from).collect();
+            fs::write(
+                memoranda_dir.join(format!("{title}.md")),
+                format!("---\n{}\n---\ncontent", serde_json::to_string(&memo)?),
+            )?;
+        }
+
+        let tags: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_tags", json!({})).await?)?;
+        assert_eq!(
+            tags,
+            json!([
+                {"tag": "rust", "count": 3},
+                {"tag": "notes", "count": 2},
+                {"tag": "api", "count": 1},
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tag_meta_tools_and_list_tags() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::write(
+            memoranda_dir.join(".tags.json"),
+            r##"{"rust": {"color": "#dea584", "description": "Rust notes"}}"##,
+        )?;
+        for (title, tags) in [("One", vec!["rust", "ops"]), ("Two", vec!["rust"])] {
+            server
+                .execute_tool(
+                    "create_memo",
+                    json!({"title": title, "content": "body", "tags": tags}),
+                )
+                .await?;
+        }
+
+        let tags: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_tags", json!({})).await?)?;
+        assert_eq!(
+            tags,
+            json!([
+                {"tag": "rust", "count": 2, "color": "#dea584", "description": "Rust notes"},
+                {"tag": "ops", "count": 1},
+            ])
+        );
+
+        let set: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("set_tag_meta", json!({"tag": "Ops", "color": "red"}))
+                .await?,
+        )?;
+        assert_eq!(set, json!({"tag": "Ops", "color": "red"}));
+        let got: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("get_tag_meta", json!({"tag": "ops"}))
+                .await?,
+        )?;
+        assert_eq!(got, json!({"tag": "ops", "color": "red"}));
+
+        let tags: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("list_tags", json!({})).await?)?;
+        assert_eq!(tags[1], json!({"tag": "ops", "count": 1, "color": "red"}));
+
+        // Neither field clears the entry
+        server
+            .execute_tool("set_tag_meta", json!({"tag": "rust"}))
+            .await?;
+        let got: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("get_tag_meta", json!({"tag": "rust"}))
+                .await?,
+        )?;
+        assert_eq!(got, json!({"tag": "rust"}));
+        assert!(server
+            .execute_tool("set_tag_meta", json!({"tag": "two words", "color": "red"}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tag_alias_makes_search_and_filters_match_both_tags() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        for (title, tags) in [
+            ("Bundler Notes", vec!["javascript"]),
+            ("Quick Hacks", vec!["js"]),
+            ("Borrow Checker", vec!["rust"]),
+        ] {
+            let mut memo = Memo::new(title.to_string(), "notes".to_string())?;
+            memo.tags = tags.into_iter().map(String::from).collect();
+            fs::write(
+                memoranda_dir.join(format!("{title}.md")),
+                format!("---\n{}\n---\nnotes", serde_json::to_string(&memo)?),
+            )?;
+        }
+
+        async fn titles(
+            server: &mut McpServer,
+            tool: &str,
+            arguments: serde_json::Value,
+        ) -> Vec<String> {
+            let response: serde_json::Value =
+                serde_json::from_str(&server.execute_tool(tool, arguments).await.unwrap()).unwrap();
+            let items = response["results"]
+                .as_array()
+                .or_else(|| response["memos"].as_array())
+                .unwrap();
+            let mut titles: Vec<String> = items
+                .iter()
+                .map(|item| {
+                    item.get("memo").unwrap_or(item)["title"]
+                        .as_str()
+                        .unwrap()
+                        .to_string()
+                })
+                .collect();
+            titles.sort();
+            titles
+        }
+
+        let search = json!({"query": "tag:js"});
+        assert_eq!(
+            titles(&mut server, "search_memos", search.clone()).await,
+            vec!["Quick Hacks"]
+        );
+
+        let set: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool(
+                    "set_tag_alias",
+                    json!({"alias": "js", "canonical": "javascript"}),
+                )
+                .await?,
+        )?;
+        assert_eq!(
+            set,
+            json!({"alias": "js", "canonical": "javascript", "aliases": {"js": "javascript"}})
+        );
+        assert!(memoranda_dir
+            .join(crate::memo::TAG_ALIASES_FILE_NAME)
+            .exists());
+
+        let both = vec!["Bundler Notes", "Quick Hacks"];
+        assert_eq!(titles(&mut server, "search_memos", search).await, both);
+        assert_eq!(
+            titles(
+                &mut server,
+                "search_memos",
+                json!({"query": "tag:javascript"})
+            )
+            .await,
+            both
+        );
+        assert_eq!(
+            titles(&mut server, "list_memos", json!({"tags": ["JS"]})).await,
+            both
+        );
+
+        // Memo files keep the tags they were written with
+        let quick_hacks = fs::read_to_string(memoranda_dir.join("Quick Hacks.md"))?;
+        assert!(quick_hacks.contains("\"js\""));
+        assert!(!quick_hacks.contains("javascript"));
+
+        assert!(server
+            .execute_tool("set_tag_alias", json!({"alias": "js", "canonical": "JS"}))
+            .await
+            .is_err());
+
+        let removed: serde_json::Value = serde_json::from_str(
+            &server
+                .execute_tool("set_tag_alias", json!({"alias": "js"}))
+                .await?,
+        )?;
+        assert_eq!(
+            removed,
+            json!({"alias": "js", "canonical": null, "aliases": {}})
+        );
+        assert_eq!(
+            titles(&mut server, "search_memos", json!({"query": "tag:js"})).await,
+            vec!["Quick Hacks"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_context_for_tag_includes_only_tagged_memos_within_budget() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        for (title, tags, content) in [
+            ("Deploy Steps", vec!["deploy"], "ship it".to_string()),
+            ("Rollback", vec!["Deploy", "ops"], "undo it".to_string()),
+            ("Unrelated", vec!["ops"], "not this".to_string()),
+            ("Deploy Log", vec!["deploy"], "x".repeat(5_000)),
+        ] {
+            let mut memo = Memo::new(title.to_string(), content.clone())?;
+            memo.tags = tags.into_iter().map(String::from).collect();
+            fs::write(
+                memoranda_dir.join(format!("{title}.md")),
+                format!("---\n{}\n---\n{content}", serde_json::to_string(&memo)?),
+            )?;
+        }
+
+        let context = server
+            .execute_tool("context_for_tag", json!({"tag": "deploy"}))
+            .await?;
+        assert!(context.contains("# Deploy Steps"));
+        assert!(context.contains("# Rollback"));
+        assert!(context.contains("# Deploy Log"));
+        assert!(!context.contains("# Unrelated"));
+
+        let budgeted = server
+            .execute_tool(
+                "context_for_tag",
+                json!({"tag": "deploy", "max_bytes": 1024}),
+            )
+            .await?;
+        assert!(budgeted.len() <= 1024);
+        assert!(budgeted.contains("# Deploy Steps"));
+        assert!(budgeted.contains("# Rollback"));
+        assert!(!budgeted.contains("# Deploy Log"));
+        assert!(budgeted.contains("1 more matching memo(s) omitted"));
+
+        assert!(server
+            .execute_tool("context_for_tag", json!({"tag": "deploy", "max_bytes": 0}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_activity_counts_creation_and_later_update() -> Result<()> {
+        let (mut server, temp_dir) = create_test_server()?;
+        let today = chrono::Utc::now();
+        let day = chrono::Duration::days(1);
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        for (title, created, updated) in [
+            ("Old", today - day * 2, today - day * 2),
+            ("Revised", today - day * 2, today),
+            ("Yesterday", today - day, today - day),
+        ] {
+            let mut memo = Memo::new(title.to_string(), "content".to_string())?;
+            memo.created_at = created;
+            memo.updated_at = updated;
+            fs::write(
+                memoranda_dir.join(format!("{title}.md")),
+                format!("---\n{}\n---\ncontent", serde_json::to_string(&memo)?),
+            )?;
+        }
+
+        let buckets: serde_json::Value =
+            serde_json::from_str(&server.execute_tool("activity", json!({"days": 3})).await?)?;
+        let counts: Vec<(u64, u64)> = buckets
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| {
+                (
+                    b["created"].as_u64().unwrap(),
+                    b["updated"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(counts, [(2, 0), (1, 0), (0, 1)]);
+        assert_eq!(buckets[2]["date"], today.date_naive().to_string());
+
+        assert!(server
+            .execute_tool("activity", json!({"days": 0}))
+            .await
+            .is_err());
+        assert!(server
+            .execute_tool("activity", json!({"granularity": "hour"}))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_regex_reports_invalid_and_oversized_patterns() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let cases = [
+            (r"fn\s+\w+", None),
+            ("(unclosed", Some("unclosed group")),
+            // Unicode word classes repeated this often compile far past the size limit
+            (r"(?:\w{100}){100}", Some("size limit")),
+        ];
+
+        for (pattern, expected_error) in cases {
+            let result = server
+                .execute_tool("validate_regex", json!({ "pattern": pattern }))
+                .await?;
+            let result: serde_json::Value = serde_json::from_str(&result)?;
+            match expected_error {
+                None => assert_eq!(result, json!({ "valid": true })),
+                Some(expected) => {
+                    assert_eq!(result["valid"], false);
+                    assert!(result["error"].as_str().unwrap().contains(expected));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_call_history_records_calls_in_order() -> Result<()> {
+        let (mut server, _temp_dir) = create_test_server()?;
+        let mut initialized = true;
+        let calls = [
+            (
+                "create_memo",
+                json!({"title": "History", "content": "secret body"}),
+            ),
+            ("list_memos", json!({})),
+            ("get_memo", json!({"id": MemoId::new().to_string()})),
+            ("search_memos", json!({"query": "secret"})),
+            ("call_history", json!({})),
+        ];
+
+        let mut response = serde_json::Value::Null;
+        for (i, (name, arguments)) in calls.iter().enumerate() {
+            let call = json!({
+                "jsonrpc": "2.0",
+                "id": i,
+                "method": "tools/call",
+                "params": {"name": name, "arguments": arguments}
+            });
+            response = server.handle_message(call, &mut initialized).await.unwrap();
+        }
+
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(!text.contains("secret"));
+        let history: serde_json::Value = serde_json::from_str(text)?;
+        let recorded: Vec<(&str, bool)> = history["calls"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|call| {
+                (
+                    call["tool"].as_str().unwrap(),
+                    call["ok"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                ("create_memo", true),
+                ("list_memos", true),
+                ("get_memo", false),
+                ("search_memos", true),
+            ]
+        );
+        assert!(history["calls"][0]["duration_ms"].is_u64());
+        assert!(history["calls"][0]["ts"].is_string());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_observer_receives_tool_calls() -> Result<()> {
+        use crate::observer::EventObserver;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct RecordingObserver {
+            events: Mutex<Vec<String>>,
+        }
+
+        impl EventObserver for RecordingObserver {
+            fn on_memo_created(&self, memo: &Memo) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(format!("created:{}", memo.title));
+            }
+
+            fn on_tool_call(&self, tool_name: &str, _duration: std::time::Duration, success: bool) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(format!("tool:{tool_name}:{success}"));
+            }
+
+            fn on_error(&self, operation: &str, _error: &str) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push(format!("error:{operation}"));
+            }
+        }
+
+        let (server, _temp_dir) = create_test_server()?;
+        let observer = Arc::new(RecordingObserver::default());
+        let mut server = server.with_observer(observer.clone());
+        let mut initialized = true;
+
+        let create = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "create_memo", "arguments": {"title": "Observed", "content": "body"}}
+        });
+        server.handle_message(create, &mut initialized).await;
+
+        let bad_get = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {"name": "get_memo", "arguments": {}}
+        });
+        server.handle_message(bad_get, &mut initialized).await;
+
+        let events = observer.events.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![
+                "created:Observed",
+                "tool:create_memo:true",
+                "tool:get_memo:false",
+                "error:get_memo"
+            ]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_tool_schemas() {
         let tools = vec![
@@ -1,9 +1,17 @@
+use super::policy::ToolRole;
 use serde_json::Value;
 use tracing::info;
 
 const MEMO_TITLE_MAX_LENGTH: u32 = 255;
 const MEMO_CONTENT_MAX_LENGTH: u32 = 1_048_576;
 const SEARCH_QUERY_MAX_LENGTH: u32 = 1000;
+/// Page size for `list_memos` and `search_memos` when no `limit` is given.
+pub(crate) const DEFAULT_PAGE_LIMIT: usize = 50;
+
+/// Number of memos `hub_memos` returns when no `limit` is given.
+pub(crate) const DEFAULT_HUB_MEMOS_LIMIT: usize = 10;
+/// Nodes `link_graph` returns when the client does not ask for a limit.
+pub(crate) const DEFAULT_LINK_GRAPH_LIMIT: usize = 500;
 
 #[derive(Debug, Clone)]
 pub struct McpTool {
@@ -40,6 +48,15 @@ impl McpTool {
                             "type": "string",
                             "description": "The content of the memo",
                             "maxLength": MEMO_CONTENT_MAX_LENGTH
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Optional tags for the memo; each must be non-empty and free of whitespace and commas"
+                        },
+                        "directory": {
+                            "type": "string",
+                            "description": "Optional memo directory to write to, relative to the repository root; either the memo directory, the directory containing it, or a subfolder inside it such as .memoranda/architecture, created if needed. With auto_create_scope_dir set, a directory without a memo directory gets one. Defaults to the default_memo_dir setting, or the memo directory closest to the working directory"
                         }
                     },
                     "required": ["title", "content"]
@@ -63,14 +80,156 @@ impl McpTool {
                     "required": ["id", "content"]
                 })
             }
-            "list_memos" | "get_all_context" => {
+            "rename_memo" => {
                 serde_json::json!({
                     "type": "object",
-                    "properties": {},
+                    "properties": {
+                        "id": {
+                            "type": "string",
+                            "description": "The ID of the memo to rename",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "The new title; the file is renamed to match",
+                            "minLength": 1,
+                            "maxLength": MEMO_TITLE_MAX_LENGTH
+                        }
+                    },
+                    "required": ["id", "title"]
+                })
+            }
+            "sync_filename" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "id": {
+                            "type": "string",
+                            "description": "The ID of the memo whose file to rename",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        }
+                    },
+                    "required": ["id"]
+                })
+            }
+            "list_memos" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Only list memos carrying these tags (case-insensitive)"
+                        },
+                        "match": {
+                            "type": "string",
+                            "enum": ["any", "all"],
+                            "description": "Whether memos need any or all of the tags",
+                            "default": "any"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of memos to return; 0 returns all",
+                            "minimum": 0,
+                            "default": DEFAULT_PAGE_LIMIT
+                        },
+                        "offset": {
+                            "type": "integer",
+                            "description": "Number of memos to skip",
+                            "minimum": 0,
+                            "default": 0
+                        }
+                    },
                     "required": []
                 })
             }
-            "get_memo" | "delete_memo" => {
+            "get_all_context" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Only include memos carrying at least one of these tags (case-insensitive)"
+                        },
+                        "directory": {
+                            "type": "string",
+                            "description": "Only include memos stored under this directory, relative to the repository root"
+                        }
+                    },
+                    "required": []
+                })
+            }
+            "context_for_tag" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tag": {
+                            "type": "string",
+                            "description": "Include memos carrying this tag (case-insensitive)",
+                            "minLength": 1
+                        },
+                        "max_bytes": {
+                            "type": "integer",
+                            "description": "Largest document to return; memos that would overflow it are left out",
+                            "minimum": 1
+                        }
+                    },
+                    "required": ["tag"]
+                })
+            }
+            "get_tag_meta" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tag": {
+                            "type": "string",
+                            "description": "The tag to look up (case-insensitive)",
+                            "minLength": 1
+                        }
+                    },
+                    "required": ["tag"]
+                })
+            }
+            "set_tag_meta" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tag": {
+                            "type": "string",
+                            "description": "The tag to describe",
+                            "minLength": 1
+                        },
+                        "color": {
+                            "type": "string",
+                            "description": "Display color, e.g. \"#dea584\"; omit to clear"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "What the tag is used for; omit to clear"
+                        }
+                    },
+                    "required": ["tag"]
+                })
+            }
+            "set_tag_alias" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "alias": {
+                            "type": "string",
+                            "description": "The tag to make an alias, e.g. \"js\"",
+                            "minLength": 1
+                        },
+                        "canonical": {
+                            "type": "string",
+                            "description": "The tag it stands for, e.g. \"javascript\"; omit to remove the alias"
+                        }
+                    },
+                    "required": ["alias"]
+                })
+            }
+            "get_memo" | "trash_memo" | "untrash_memo" => {
                 serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -83,20 +242,382 @@ impl McpTool {
                     "required": ["id"]
                 })
             }
+            "validate_regex" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "pattern": {
+                            "type": "string",
+                            "description": "The regex pattern to check"
+                        }
+                    },
+                    "required": ["pattern"]
+                })
+            }
             "search_memos" => {
                 serde_json::json!({
                     "type": "object",
                     "properties": {
                         "query": {
                             "type": "string",
-                            "description": "The search query to match against memo titles and content",
+                            "description": "Terms, a \"quoted phrase\", a wildcard pattern, or terms joined by AND, OR, and NOT with (parentheses) for grouping; a trailing ~ on a word (rust~) tolerates typos; after:YYYY-MM-DD, before:YYYY-MM-DD and created:YYYY-MM-DD..YYYY-MM-DD filter by creation date; tag:NAME keeps memos with that tag; title:TERM and content:TERM match one field only",
                             "minLength": 1,
                             "maxLength": SEARCH_QUERY_MAX_LENGTH
+                        },
+                        "title_boost": {
+                            "type": "number",
+                            "description": "Multiplier on title match scores for this search; defaults to the search_title_boost setting",
+                            "minimum": 0
+                        },
+                        "content_boost": {
+                            "type": "number",
+                            "description": "Multiplier on content match scores for this search; defaults to the search_content_boost setting",
+                            "minimum": 0
+                        },
+                        "recency_boost": {
+                            "type": "number",
+                            "description": "Multiplier on the boost for recently created memos for this search, 0 to ignore age; defaults to the search_recency_boost setting",
+                            "minimum": 0
+                        },
+                        "title_only": {
+                            "type": "boolean",
+                            "description": "Match the query against memo titles only; cannot be combined with content_only",
+                            "default": false
+                        },
+                        "content_only": {
+                            "type": "boolean",
+                            "description": "Match the query against memo content only; cannot be combined with title_only",
+                            "default": false
+                        },
+                        "format": {
+                            "type": "string",
+                            "description": "\"json\" for the paged results object, \"markdown\" for a digest of the page with a \"## title (score)\" heading and the snippets of each result",
+                            "enum": ["json", "markdown"],
+                            "default": "json"
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of results to return; 0 returns all",
+                            "minimum": 0,
+                            "default": DEFAULT_PAGE_LIMIT
+                        },
+                        "offset": {
+                            "type": "integer",
+                            "description": "Number of results to skip",
+                            "minimum": 0,
+                            "default": 0
                         }
                     },
                     "required": ["query"]
                 })
             }
+            "memos_mentioning_path" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "The file path to look for; forward and back slashes are treated alike",
+                            "minLength": 1,
+                            "maxLength": SEARCH_QUERY_MAX_LENGTH
+                        }
+                    },
+                    "required": ["path"]
+                })
+            }
+            "get_memo_numbered" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "id": {
+                            "type": "string",
+                            "description": "The ID of the memo",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        },
+                        "format": {
+                            "type": "string",
+                            "description": "\"lines\" for an array of { line, text } objects, \"text\" for a single string with \"n: \" prefixes",
+                            "enum": ["lines", "text"],
+                            "default": "lines"
+                        }
+                    },
+                    "required": ["id"]
+                })
+            }
+            "delete_memo" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "id": {
+                            "type": "string",
+                            "description": "The ID of the memo",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        },
+                        "permanent": {
+                            "type": "boolean",
+                            "description": "Delete the memo file instead of moving it to the trash",
+                            "default": false
+                        }
+                    },
+                    "required": ["id"]
+                })
+            }
+            "delete_memos" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "ids": {
+                            "type": "array",
+                            "description": "The IDs of the memos to delete",
+                            "items": {
+                                "type": "string",
+                                "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                            },
+                            "minItems": 1
+                        },
+                        "soft": {
+                            "type": "boolean",
+                            "description": "Move memos to the trash instead of deleting them permanently",
+                            "default": false
+                        }
+                    },
+                    "required": ["ids"]
+                })
+            }
+            "compact_store" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Must be true; compaction permanently deletes expired artifacts"
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Report what would be removed without deleting anything",
+                            "default": false
+                        }
+                    },
+                    "required": ["confirm"]
+                })
+            }
+            "import_directory" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Directory to import; relative paths are resolved against the store root"
+                        },
+                        "tag": {
+                            "type": "string",
+                            "description": "Tag to add to every imported memo"
+                        }
+                    },
+                    "required": ["path"]
+                })
+            }
+            "export_memos" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "File to write the bundle to; relative paths are resolved against the store root"
+                        }
+                    },
+                    "required": ["path"]
+                })
+            }
+            "import_memos" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Bundle to import; relative paths are resolved against the store root"
+                        },
+                        "strategy": {
+                            "type": "string",
+                            "description": "What to do with a memo whose ID is already in the store",
+                            "enum": ["skip_existing", "overwrite", "duplicate_as_new"],
+                            "default": "skip_existing"
+                        }
+                    },
+                    "required": ["path"]
+                })
+            }
+            "add_tags" | "remove_tags" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "id": {
+                            "type": "string",
+                            "description": "The ID of the memo",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "minItems": 1,
+                            "description": "Tags to add or remove, compared case-insensitively"
+                        }
+                    },
+                    "required": ["id", "tags"]
+                })
+            }
+            "add_alias" | "remove_alias" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "id": {
+                            "type": "string",
+                            "description": "The ID of the memo",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        },
+                        "alias": {
+                            "type": "string",
+                            "description": "Alternate title, matched case-insensitively and unique across memos",
+                            "minLength": 1,
+                            "maxLength": MEMO_TITLE_MAX_LENGTH
+                        }
+                    },
+                    "required": ["id", "alias"]
+                })
+            }
+            "hub_memos" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of memos to return; 0 returns all",
+                            "minimum": 0,
+                            "default": DEFAULT_HUB_MEMOS_LIMIT
+                        }
+                    },
+                    "required": []
+                })
+            }
+            "link_graph" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "include_unresolved": {
+                            "type": "boolean",
+                            "description": "Add a phantom node for each [[wiki-link]] target no memo answers to",
+                            "default": false
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of nodes to return, most linked first; 0 returns all",
+                            "minimum": 0,
+                            "default": DEFAULT_LINK_GRAPH_LIMIT
+                        }
+                    },
+                    "required": []
+                })
+            }
+            "link_path" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "from_id": {
+                            "type": "string",
+                            "description": "The ID of the memo the path starts at",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        },
+                        "to_id": {
+                            "type": "string",
+                            "description": "The ID of the memo the path ends at",
+                            "pattern": "^[0-9A-HJKMNP-TV-Z]{26}$"
+                        }
+                    },
+                    "required": ["from_id", "to_id"]
+                })
+            }
+            "begin_memo_write" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "The title of the memo",
+                            "minLength": 1,
+                            "maxLength": MEMO_TITLE_MAX_LENGTH
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Tags to give the memo when it is committed"
+                        }
+                    },
+                    "required": ["title"]
+                })
+            }
+            "write_memo_chunk" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "token": {
+                            "type": "string",
+                            "description": "Token returned by begin_memo_write"
+                        },
+                        "data": {
+                            "type": "string",
+                            "description": "Content to append; all chunks together may not exceed the memo content limit",
+                            "maxLength": MEMO_CONTENT_MAX_LENGTH
+                        }
+                    },
+                    "required": ["token", "data"]
+                })
+            }
+            "commit_memo_write" | "abort_memo_write" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "token": {
+                            "type": "string",
+                            "description": "Token returned by begin_memo_write"
+                        }
+                    },
+                    "required": ["token"]
+                })
+            }
+            "activity" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "days": {
+                            "type": "integer",
+                            "description": "Number of days, ending today, to report on",
+                            "minimum": 1,
+                            "maximum": 3650,
+                            "default": 30
+                        },
+                        "granularity": {
+                            "type": "string",
+                            "description": "Bucket width; weeks start on Monday",
+                            "enum": ["day", "week", "month"],
+                            "default": "day"
+                        }
+                    },
+                    "required": []
+                })
+            }
+            "verify_store" => {
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "fix": {
+                            "type": "boolean",
+                            "description": "Evict stale cache entries and rebuild a drifted search index",
+                            "default": false
+                        }
+                    },
+                    "required": []
+                })
+            }
             _ => {
                 serde_json::json!({
                     "type": "object",
@@ -113,3 +634,17 @@ impl McpTool {
         }
     }
 }
+
+/// Orders tools for listing: those named in `order` first, in that order, then
+/// the rest with read tools before write and admin tools.
+///
+/// The sort is stable, so tools of the same role keep their registration order.
+pub fn order_tools(tools: &mut [McpTool], order: &[String]) {
+    tools.sort_by_key(|tool| {
+        let position = order
+            .iter()
+            .position(|name| *name == tool.name)
+            .unwrap_or(order.len());
+        (position, ToolRole::required_for(&tool.name))
+    });
+}
@@ -0,0 +1,178 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::models::Memo;
+
+/// Default number of days covered by an activity report.
+pub const DEFAULT_ACTIVITY_DAYS: u32 = 30;
+
+/// Width of each bucket in an activity report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityGranularity {
+    #[default]
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+}
+
+impl ActivityGranularity {
+    /// Returns the first day of the bucket containing `date`.
+    fn bucket_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ActivityGranularity::Day => date,
+            ActivityGranularity::Week => {
+                date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            ActivityGranularity::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next_bucket(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            ActivityGranularity::Day => start + Duration::days(1),
+            ActivityGranularity::Week => start + Duration::days(7),
+            ActivityGranularity::Month => {
+                let (year, month) = if start.month() == 12 {
+                    (start.year() + 1, 1)
+                } else {
+                    (start.year(), start.month() + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(start)
+            }
+        }
+    }
+}
+
+impl FromStr for ActivityGranularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(ActivityGranularity::Day),
+            "week" => Ok(ActivityGranularity::Week),
+            "month" => Ok(ActivityGranularity::Month),
+            other => Err(format!(
+                "Invalid granularity: {other} (expected \"day\", \"week\", or \"month\")"
+            )),
+        }
+    }
+}
+
+/// Memos created and updated within one bucket, identified by its first day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivityBucket {
+    pub date: NaiveDate,
+    pub created: usize,
+    pub updated: usize,
+}
+
+/// Buckets memo creations and updates over the `days` days ending on `today`
+/// (UTC dates), returning every bucket in the window, oldest first, including
+/// empty ones.
+///
+/// Only frontmatter timestamps are used, so a memo counts once as created and,
+/// if it was changed after creation, once as updated on its latest update.
+pub fn activity(
+    memos: &[Memo],
+    days: u32,
+    granularity: ActivityGranularity,
+    today: NaiveDate,
+) -> Vec<ActivityBucket> {
+    let window_start = today - Duration::days(i64::from(days.saturating_sub(1)));
+    let mut buckets = BTreeMap::new();
+    let mut start = granularity.bucket_start(window_start);
+    while start <= today {
+        buckets.insert(start, (0, 0));
+        start = granularity.next_bucket(start);
+    }
+
+    let in_window = |date: NaiveDate| (window_start..=today).contains(&date);
+    for memo in memos {
+        let created = memo.created_at.date_naive();
+        if in_window(created) {
+            if let Some(counts) = buckets.get_mut(&granularity.bucket_start(created)) {
+                counts.0 += 1;
+            }
+        }
+        let updated = memo.updated_at.date_naive();
+        if memo.updated_at != memo.created_at && in_window(updated) {
+            if let Some(counts) = buckets.get_mut(&granularity.bucket_start(updated)) {
+                counts.1 += 1;
+            }
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(date, (created, updated))| ActivityBucket {
+            date,
+            created,
+            updated,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn memo_at(created: (u32, u32), updated: Option<(u32, u32)>) -> Memo {
+        let at = |(day, hour): (u32, u32)| Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap();
+        let mut memo = Memo::new("Activity".to_string(), "content".to_string()).unwrap();
+        memo.created_at = at(created);
+        memo.updated_at = updated.map(at).unwrap_or(memo.created_at);
+        memo
+    }
+
+    #[test]
+    fn test_daily_activity_counts() {
+        let memos = vec![
+            memo_at((1, 9), None),
+            // Created on the 1st, last updated on the 4th
+            memo_at((1, 15), Some((4, 10))),
+            memo_at((3, 8), Some((3, 9))),
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+
+        let buckets = activity(&memos, 4, ActivityGranularity::Day, today);
+        let counts: Vec<_> = buckets
+            .iter()
+            .map(|b| (b.date.day(), b.created, b.updated))
+            .collect();
+        assert_eq!(counts, [(1, 2, 0), (2, 0, 0), (3, 1, 1), (4, 0, 1)]);
+
+        // Activity before the window is not counted
+        let buckets = activity(&memos, 2, ActivityGranularity::Day, today);
+        let counts: Vec<_> = buckets
+            .iter()
+            .map(|b| (b.date.day(), b.created, b.updated))
+            .collect();
+        assert_eq!(counts, [(3, 1, 1), (4, 0, 1)]);
+    }
+
+    #[test]
+    fn test_weekly_and_monthly_buckets() {
+        let memos = vec![memo_at((4, 9), None), memo_at((10, 9), None)];
+        let today = NaiveDate::from_ymd_opt(2024, 3, 11).unwrap();
+
+        // 2024-03-04 and 2024-03-11 are Mondays
+        let weeks = activity(&memos, 8, ActivityGranularity::Week, today);
+        let counts: Vec<_> = weeks.iter().map(|b| (b.date.day(), b.created)).collect();
+        assert_eq!(counts, [(4, 2), (11, 0)]);
+
+        let months = activity(&memos, 20, ActivityGranularity::Month, today);
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].date, NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(months[1].created, 2);
+
+        assert_eq!(
+            "fortnight".parse::<ActivityGranularity>().unwrap_err(),
+            "Invalid granularity: fortnight (expected \"day\", \"week\", or \"month\")"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use super::models::MemoId;
+
+/// File (inside the primary `.memoranda` directory) the audit log is appended to.
+pub const AUDIT_LOG_FILE_NAME: &str = ".audit.ndjson";
+
+/// One line of the audit log: a store operation that changed something.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub ts: DateTime<Utc>,
+    /// The store operation, such as `create_memo` or `set_tag_meta`.
+    pub op: String,
+    /// The memo the operation changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<MemoId>,
+    /// The tag the operation changed, for tag operations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Who asked for the change: the MCP client name sent in `initialize`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+}
+
+impl AuditRecord {
+    pub fn new(op: &str, actor: Option<String>) -> Self {
+        Self {
+            ts: Utc::now(),
+            op: op.to_string(),
+            id: None,
+            tag: None,
+            actor,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: MemoId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    #[must_use]
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+}
+
+/// Appends audit records as newline-delimited JSON, one write per record.
+///
+/// Appends are serialized by a lock and flushed to disk before returning, so
+/// records from concurrent operations never interleave and survive a crash.
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    lock: Mutex<()>,
+    /// Where records go, found on the first append.
+    path: OnceLock<PathBuf>,
+}
+
+impl AuditLog {
+    /// Appends `record` to the log in `memoranda_dir()`, which is only called
+    /// the first time, since finding the directory can mean walking the tree.
+    pub fn append(
+        &self,
+        memoranda_dir: impl FnOnce() -> io::Result<PathBuf>,
+        record: &AuditRecord,
+    ) -> io::Result<()> {
+        let path = match self.path.get() {
+            Some(path) => path,
+            None => {
+                let path = memoranda_dir()?.join(AUDIT_LOG_FILE_NAME);
+                self.path.get_or_init(|| path)
+            }
+        };
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()
+    }
+}
+
+/// Reads the audit log in `memoranda_dir`, oldest record first. Lines that are
+/// not valid records are skipped; a missing log reads as empty.
+pub fn read_audit_log(memoranda_dir: &Path) -> io::Result<Vec<AuditRecord>> {
+    let text = match fs::read_to_string(memoranda_dir.join(AUDIT_LOG_FILE_NAME)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
@@ -1,12 +1,21 @@
 use moka::future::Cache;
-use std::path::PathBuf;
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, instrument, warn};
 
+use super::disk_cache::DiskCache;
 use super::models::{Memo, MemoId};
 use super::storage::{MemoStoreError, Result};
+use crate::config::Settings;
+
+/// Longest time, a year, an entry is kept in the memo or metadata cache;
+/// longer TTLs are shortened to it.
+pub const MAX_CACHE_TTL_SECONDS: u64 = 365 * 24 * 60 * 60;
 
 /// Configuration for the memo cache system
 #[derive(Debug, Clone)]
@@ -15,6 +24,13 @@ pub struct CacheConfig {
     pub metadata_cache_size: u64,
     pub memo_ttl_seconds: u64,
     pub metadata_ttl_multiplier: u64, // Metadata TTL = memo_ttl * multiplier
+    /// Directory memos evicted from memory are written to, checked on a
+    /// memory miss before memos are loaded from their files; `None` keeps
+    /// the cache in memory only.
+    pub disk_cache_dir: Option<PathBuf>,
+    /// Most bytes the disk tier may take; least recently used entries are
+    /// deleted beyond it.
+    pub disk_cache_max_bytes: u64,
 }
 
 impl Default for CacheConfig {
@@ -24,6 +40,24 @@ impl Default for CacheConfig {
             metadata_cache_size: 5000,
             memo_ttl_seconds: 3600,     // 1 hour
             metadata_ttl_multiplier: 2, // Metadata lives twice as long as memos
+            disk_cache_dir: None,
+            disk_cache_max_bytes: 0,
+        }
+    }
+}
+
+impl From<&Settings> for CacheConfig {
+    /// The in-memory cache configured by `settings`. The disk tier's
+    /// directory depends on where the store is, so it is left for the store
+    /// to set.
+    fn from(settings: &Settings) -> Self {
+        Self {
+            memo_cache_size: settings.memo_cache_size,
+            metadata_cache_size: settings.metadata_cache_size,
+            memo_ttl_seconds: settings.memo_ttl_seconds,
+            metadata_ttl_multiplier: settings.metadata_ttl_multiplier,
+            disk_cache_dir: None,
+            disk_cache_max_bytes: settings.disk_cache_max_bytes,
         }
     }
 }
@@ -37,7 +71,7 @@ pub struct MemoMetadata {
     pub file_size: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct CacheStats {
     pub memo_hits: u64,
     pub memo_misses: u64,
@@ -45,13 +79,47 @@ pub struct CacheStats {
     pub metadata_misses: u64,
     pub memo_cache_size: u64,
     pub metadata_cache_size: u64,
+    /// In-memory misses served from the disk tier.
+    pub disk_hits: u64,
+    /// In-memory misses the disk tier could not serve either.
+    pub disk_misses: u64,
+    pub disk_cache_bytes: u64,
+}
+
+/// Entries removed by callers that are not async, until the next async call
+/// removes them from the in-memory caches.
+#[derive(Debug, Default)]
+struct StaleEntries {
+    memos: HashSet<MemoId>,
+    files: HashSet<PathBuf>,
+}
+
+/// A memo in the in-memory cache, with when it was cached so the disk tier
+/// can tell whether its file changed since.
+#[derive(Debug, Clone)]
+struct CachedMemo {
+    memo: Arc<Memo>,
+    cached_at: SystemTime,
+}
+
+impl CachedMemo {
+    fn new(memo: Arc<Memo>) -> Self {
+        Self {
+            memo,
+            cached_at: SystemTime::now(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct MemoCache {
-    cache: Cache<MemoId, Arc<Memo>>,
+    cache: Cache<MemoId, CachedMemo>,
     metadata_cache: Cache<PathBuf, Arc<MemoMetadata>>,
+    disk: Option<Arc<DiskCache>>,
+    /// Evicted memos still being written to the disk tier.
+    disk_writes: Arc<Mutex<Vec<JoinHandle<()>>>>,
     stats: Arc<RwLock<CacheStats>>,
+    stale: Mutex<StaleEntries>,
     config: CacheConfig,
 }
 
@@ -68,15 +136,47 @@ impl MemoCache {
             metadata_cache_size = config.metadata_cache_size,
             memo_ttl_seconds = config.memo_ttl_seconds,
             metadata_ttl_multiplier = config.metadata_ttl_multiplier,
+            disk_cache_max_bytes = config.disk_cache_max_bytes,
             "Creating memo cache with configuration"
         );
 
-        let memo_cache = Cache::builder()
+        let disk = config
+            .disk_cache_dir
+            .clone()
+            .map(|dir| Arc::new(DiskCache::open(dir, config.disk_cache_max_bytes)));
+        let disk_writes: Arc<Mutex<Vec<JoinHandle<()>>>> = Arc::default();
+        let mut memo_cache = Cache::builder()
             .max_capacity(config.memo_cache_size)
-            .time_to_live(Duration::from_secs(config.memo_ttl_seconds))
-            .build();
+            .time_to_live(Duration::from_secs(
+                config.memo_ttl_seconds.min(MAX_CACHE_TTL_SECONDS),
+            ));
+        if let Some(disk) = &disk {
+            let disk = Arc::clone(disk);
+            let disk_writes = Arc::clone(&disk_writes);
+            memo_cache = memo_cache.eviction_listener(move |_, cached: CachedMemo, cause| {
+                if !cause.was_evicted() {
+                    return;
+                }
+                // The listener runs on whichever task touched the cache, so
+                // the write goes to the blocking pool when there is a runtime.
+                let disk = Arc::clone(&disk);
+                let store = move || disk.store(&cached.memo, cached.cached_at);
+                match tokio::runtime::Handle::try_current() {
+                    Ok(runtime) => {
+                        let mut writes = lock_ignoring_poison(&disk_writes);
+                        writes.retain(|write| !write.is_finished());
+                        writes.push(runtime.spawn_blocking(store));
+                    }
+                    Err(_) => store(),
+                }
+            });
+        }
+        let memo_cache = memo_cache.build();
 
-        let metadata_ttl = config.memo_ttl_seconds * config.metadata_ttl_multiplier;
+        let metadata_ttl = config
+            .memo_ttl_seconds
+            .checked_mul(config.metadata_ttl_multiplier)
+            .map_or(MAX_CACHE_TTL_SECONDS, |ttl| ttl.min(MAX_CACHE_TTL_SECONDS));
         let metadata_cache = Cache::builder()
             .max_capacity(config.metadata_cache_size)
             .time_to_live(Duration::from_secs(metadata_ttl))
@@ -85,14 +185,10 @@ impl MemoCache {
         Self {
             cache: memo_cache,
             metadata_cache,
-            stats: Arc::new(RwLock::new(CacheStats {
-                memo_hits: 0,
-                memo_misses: 0,
-                metadata_hits: 0,
-                metadata_misses: 0,
-                memo_cache_size: 0,
-                metadata_cache_size: 0,
-            })),
+            disk,
+            disk_writes,
+            stats: Arc::new(RwLock::new(CacheStats::default())),
+            stale: Mutex::default(),
             config,
         }
     }
@@ -105,21 +201,57 @@ impl MemoCache {
             metadata_cache_size: 5000, // Use default for metadata
             memo_ttl_seconds: ttl_seconds,
             metadata_ttl_multiplier: 2,
+            disk_cache_dir: None,
+            disk_cache_max_bytes: 0,
         };
         Self::with_cache_config(config)
     }
 
+    /// Returns the cached memo, looking in the disk tier, when there is one,
+    /// after a miss in memory. Memos found on disk move back into memory
+    /// along with their file metadata.
     #[instrument(skip(self), fields(memo_id = %id))]
     pub async fn get_memo(&self, id: &MemoId) -> Option<Arc<Memo>> {
-        match self.cache.get(id).await {
-            Some(memo) => {
-                debug!("Cache hit for memo {}", id);
-                self.increment_memo_hits().await;
+        self.remove_stale_entries().await;
+        if let Some(cached) = self.cache.get(id).await {
+            debug!("Cache hit for memo {}", id);
+            self.increment_memo_hits().await;
+            return Some(cached.memo);
+        }
+        debug!("Cache miss for memo {}", id);
+        self.increment_memo_misses().await;
+
+        let disk = Arc::clone(self.disk.as_ref()?);
+        self.finish_disk_writes().await;
+        let memo_id = *id;
+        let loaded = tokio::task::spawn_blocking(move || {
+            let loaded = disk.load(&memo_id);
+            if loaded.is_some() {
+                disk.remove(&memo_id);
+            }
+            loaded
+        })
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Disk cache lookup for memo {} failed: {}", id, e);
+            None
+        });
+        match loaded {
+            Some((memo, metadata)) => {
+                debug!("Disk cache hit for memo {}", id);
+                self.stats.write().await.disk_hits += 1;
+                let memo = Arc::new(memo);
+                self.cache
+                    .insert(*id, CachedMemo::new(Arc::clone(&memo)))
+                    .await;
+                self.metadata_cache
+                    .insert(metadata.file_path.clone(), Arc::new(metadata))
+                    .await;
                 Some(memo)
             }
             None => {
-                debug!("Cache miss for memo {}", id);
-                self.increment_memo_misses().await;
+                debug!("Disk cache miss for memo {}", id);
+                self.stats.write().await.disk_misses += 1;
                 None
             }
         }
@@ -127,21 +259,47 @@ impl MemoCache {
 
     #[instrument(skip(self, memo), fields(memo_id = %memo.id, memo_title = %memo.title))]
     pub async fn put_memo(&self, memo: Memo) {
+        self.remove_stale_entries().await;
         debug!("Caching memo {}", memo.id);
         let memo_id = memo.id;
-        self.cache.insert(memo_id, Arc::new(memo)).await;
+        self.remove_from_disk(&memo_id);
+        self.cache
+            .insert(memo_id, CachedMemo::new(Arc::new(memo)))
+            .await;
         self.update_memo_cache_size().await;
     }
 
+    /// Caches a memo together with the metadata of its backing file, updating
+    /// the cache size statistics under a single lock acquisition.
+    #[instrument(skip(self, memo, metadata), fields(memo_id = %memo.id, file_path = %metadata.file_path.display()))]
+    pub async fn put_memo_with_metadata(&self, memo: Memo, metadata: MemoMetadata) {
+        self.remove_stale_entries().await;
+        debug!("Caching memo {} with metadata", memo.id);
+        let file_path = metadata.file_path.clone();
+        self.remove_from_disk(&memo.id);
+        self.cache
+            .insert(memo.id, CachedMemo::new(Arc::new(memo)))
+            .await;
+        self.metadata_cache
+            .insert(file_path, Arc::new(metadata))
+            .await;
+
+        let mut stats = self.stats.write().await;
+        stats.memo_cache_size = self.cache.entry_count();
+        stats.metadata_cache_size = self.metadata_cache.entry_count();
+    }
+
     #[instrument(skip(self), fields(memo_id = %id))]
     pub async fn remove_memo(&self, id: &MemoId) {
         debug!("Removing memo {} from cache", id);
         self.cache.remove(id).await;
+        self.remove_from_disk(id);
         self.update_memo_cache_size().await;
     }
 
     #[instrument(skip(self), fields(file_path = %file_path.display()))]
     pub async fn get_metadata(&self, file_path: &PathBuf) -> Option<Arc<MemoMetadata>> {
+        self.remove_stale_entries().await;
         match self.metadata_cache.get(file_path).await {
             Some(metadata) => {
                 debug!("Cache hit for metadata {}", file_path.display());
@@ -158,6 +316,7 @@ impl MemoCache {
 
     #[instrument(skip(self, metadata), fields(file_path = %file_path.display(), memo_id = %metadata.id))]
     pub async fn put_metadata(&self, file_path: PathBuf, metadata: MemoMetadata) {
+        self.remove_stale_entries().await;
         debug!("Caching metadata for {}", file_path.display());
         self.metadata_cache
             .insert(file_path, Arc::new(metadata))
@@ -171,23 +330,112 @@ impl MemoCache {
         self.update_metadata_cache_size().await;
     }
 
+    /// Removes a memo and the metadata of its file from the cache, for callers
+    /// that are not async. Never waits on the async caches: the entries are
+    /// recorded as stale, so no lookup returns them from here on, and the next
+    /// async call removes them.
+    pub fn remove_memo_sync(&self, id: &MemoId, file_path: &Path) {
+        debug!("Marking memo {} stale in cache", id);
+        let mut stale = self.stale();
+        stale.memos.insert(*id);
+        stale.files.insert(file_path.to_path_buf());
+        drop(stale);
+        self.remove_from_disk(id);
+    }
+
+    fn stale(&self) -> MutexGuard<'_, StaleEntries> {
+        lock_ignoring_poison(&self.stale)
+    }
+
+    /// Waits for evicted memos still being written to the disk tier.
+    async fn finish_disk_writes(&self) {
+        let writes = std::mem::take(&mut *lock_ignoring_poison(&self.disk_writes));
+        for write in writes {
+            if let Err(e) = write.await {
+                warn!("Writing a memo to the disk cache failed: {}", e);
+            }
+        }
+    }
+
+    /// Removes the entries [`Self::remove_memo_sync`] recorded as stale.
+    async fn remove_stale_entries(&self) {
+        let stale = std::mem::take(&mut *self.stale());
+        if stale.memos.is_empty() && stale.files.is_empty() {
+            return;
+        }
+        for id in &stale.memos {
+            self.cache.remove(id).await;
+        }
+        for file_path in &stale.files {
+            self.metadata_cache.remove(file_path).await;
+        }
+        self.update_memo_cache_size().await;
+        self.update_metadata_cache_size().await;
+    }
+
     #[instrument(skip(self), fields(memo_id = %id))]
     pub async fn invalidate_memo(&self, id: &MemoId) {
         warn!("Invalidating memo {} from cache", id);
         self.cache.invalidate(id).await;
+        self.remove_from_disk(id);
         self.update_memo_cache_size().await;
     }
 
     #[instrument(skip(self))]
     pub async fn invalidate_all(&self) {
         warn!("Invalidating entire cache");
+        *self.stale() = StaleEntries::default();
         self.cache.invalidate_all();
         self.metadata_cache.invalidate_all();
+        self.finish_disk_writes().await;
+        if let Some(disk) = &self.disk {
+            let disk = Arc::clone(disk);
+            if let Err(e) = tokio::task::spawn_blocking(move || disk.clear()).await {
+                warn!("Clearing the disk cache failed: {}", e);
+            }
+        }
         self.reset_stats().await;
     }
 
+    /// Snapshot of every cached memo, for consistency checks.
+    pub fn cached_memos(&self) -> Vec<Arc<Memo>> {
+        let stale = self.stale();
+        self.cache
+            .iter()
+            .filter(|(id, _)| !stale.memos.contains(id))
+            .map(|(_, cached)| cached.memo)
+            .collect()
+    }
+
+    /// Snapshot of every cached file metadata entry, for consistency checks.
+    pub fn cached_metadata(&self) -> Vec<Arc<MemoMetadata>> {
+        let stale = self.stale();
+        self.metadata_cache
+            .iter()
+            .filter(|(file_path, _)| !stale.files.contains(file_path.as_ref()))
+            .map(|(_, metadata)| metadata)
+            .collect()
+    }
+
     pub async fn get_stats(&self) -> CacheStats {
-        self.stats.read().await.clone()
+        self.remove_stale_entries().await;
+        let mut stats = self.stats.read().await.clone();
+        stats.disk_cache_bytes = self.disk.as_ref().map_or(0, |disk| disk.total_bytes());
+        stats
+    }
+
+    /// Writes memos due for eviction from memory to the disk tier now, rather
+    /// than whenever the in-memory cache next does its housekeeping.
+    pub async fn run_pending_tasks(&self) {
+        self.remove_stale_entries().await;
+        self.cache.run_pending_tasks().await;
+        self.finish_disk_writes().await;
+    }
+
+    fn remove_from_disk(&self, id: &MemoId) {
+        if let Some(disk) = &self.disk {
+            disk.remove(id);
+        }
     }
 
     pub fn get_config(&self) -> &CacheConfig {
@@ -257,14 +505,7 @@ impl MemoCache {
 
     async fn reset_stats(&self) {
         let mut stats = self.stats.write().await;
-        *stats = CacheStats {
-            memo_hits: 0,
-            memo_misses: 0,
-            metadata_hits: 0,
-            metadata_misses: 0,
-            memo_cache_size: 0,
-            metadata_cache_size: 0,
-        };
+        *stats = CacheStats::default();
     }
 
     /// Check if a cached memo is still valid based on file modification time
@@ -318,6 +559,10 @@ impl Default for MemoCache {
     }
 }
 
+fn lock_ignoring_poison<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,6 +622,60 @@ mod tests {
         assert!(cache.get_memo(&memo_id).await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_memo_cache_remove_sync_never_waits() {
+        let cache = MemoCache::new();
+        let memo = create_test_memo(7);
+        let memo_id = memo.id;
+        let file_path = PathBuf::from("/test/memo_7.md");
+        cache
+            .put_memo_with_metadata(
+                memo.clone(),
+                MemoMetadata {
+                    id: memo_id,
+                    title: memo.title,
+                    file_path: file_path.clone(),
+                    last_modified: SystemTime::now(),
+                    file_size: 0,
+                },
+            )
+            .await;
+
+        // Another task on this thread holding a cache lock cannot hold up
+        // the sync removal
+        let stats = cache.stats.write().await;
+        cache.remove_memo_sync(&memo_id, &file_path);
+        assert!(cache.cached_memos().is_empty());
+        assert!(cache.cached_metadata().is_empty());
+        drop(stats);
+
+        assert!(cache.get_memo(&memo_id).await.is_none());
+        assert!(cache.get_metadata(&file_path).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_put_memo_with_metadata() {
+        let cache = MemoCache::new();
+        let memo = create_test_memo(3);
+        let memo_id = memo.id;
+        let file_path = PathBuf::from("/test/memo_3.md");
+
+        let metadata = MemoMetadata {
+            id: memo_id,
+            title: memo.title.clone(),
+            file_path: file_path.clone(),
+            last_modified: SystemTime::now(),
+            file_size: 42,
+        };
+
+        cache.put_memo_with_metadata(memo, metadata).await;
+
+        assert!(cache.get_memo(&memo_id).await.is_some());
+        let cached_metadata = cache.get_metadata(&file_path).await.unwrap();
+        assert_eq!(cached_metadata.id, memo_id);
+        assert_eq!(cached_metadata.file_size, 42);
+    }
+
     #[tokio::test]
     async fn test_metadata_cache() {
         let cache = MemoCache::new();
@@ -443,6 +742,57 @@ mod tests {
         assert!(memo_after_clear.is_none());
     }
 
+    #[tokio::test]
+    async fn test_ttls_beyond_the_maximum_are_shortened() {
+        let cache = MemoCache::with_cache_config(CacheConfig {
+            memo_ttl_seconds: u64::MAX,
+            metadata_ttl_multiplier: u64::MAX,
+            ..CacheConfig::default()
+        });
+        let memo = create_test_memo(1);
+        cache.put_memo(memo.clone()).await;
+        assert!(cache.get_memo(&memo.id).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_evicted_memos_are_served_from_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = MemoCache::with_cache_config(CacheConfig {
+            memo_cache_size: 1,
+            disk_cache_dir: Some(temp_dir.path().join(".cache")),
+            disk_cache_max_bytes: 1 << 20,
+            ..CacheConfig::default()
+        });
+
+        let mut memos = Vec::new();
+        for i in 1..=3 {
+            let path = temp_dir.path().join(format!("memo_{i}.md"));
+            fs::write(&path, format!("memo {i}")).unwrap();
+            let mut memo = create_test_memo(i);
+            memo.file_path = Some(path);
+            memos.push(memo);
+        }
+        for memo in &memos {
+            cache.put_memo(memo.clone()).await;
+        }
+        cache.run_pending_tasks().await;
+        assert!(cache.get_stats().await.disk_cache_bytes > 0);
+
+        for memo in &memos {
+            let cached = cache.get_memo(&memo.id).await.unwrap();
+            assert_eq!(cached.title, memo.title);
+        }
+        let stats = cache.get_stats().await;
+        assert!(stats.disk_hits >= 1);
+        assert_eq!(stats.disk_misses, 0);
+
+        assert!(cache.get_memo(&MemoId::new()).await.is_none());
+        assert_eq!(cache.get_stats().await.disk_misses, 1);
+
+        cache.invalidate_all().await;
+        assert_eq!(cache.get_stats().await.disk_cache_bytes, 0);
+    }
+
     #[tokio::test]
     async fn test_memo_validity_check() {
         let temp_dir = TempDir::new().unwrap();
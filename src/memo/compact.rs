@@ -0,0 +1,166 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::Settings;
+
+/// Thresholds for pruning internal `.memoranda` artifacts.
+///
+/// Compaction only ever removes files from internal directories such as
+/// `.trash`, and temporary files left behind by interrupted writes; active
+/// memos are never touched, nor are saved versions in `.history` or the
+/// audit log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactOptions {
+    /// Trashed memos older than this are permanently deleted.
+    pub trash_max_age: Duration,
+    /// Temporary files of atomic memo writes older than this are deleted. Young
+    /// ones may belong to a write still in progress.
+    pub temp_file_max_age: Duration,
+    /// Report what would be removed without deleting anything.
+    pub dry_run: bool,
+}
+
+impl CompactOptions {
+    pub fn new(trash_max_age: Duration) -> Self {
+        Self {
+            trash_max_age,
+            temp_file_max_age: Duration::hours(1),
+            dry_run: false,
+        }
+    }
+
+    pub fn with_temp_file_max_age(mut self, temp_file_max_age: Duration) -> Self {
+        self.temp_file_max_age = temp_file_max_age;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl From<&Settings> for CompactOptions {
+    fn from(settings: &Settings) -> Self {
+        // Validated settings are well within range; anything beyond it
+        // saturates so no trash is old enough to purge
+        Self::new(
+            i64::try_from(settings.trash_retention_days)
+                .ok()
+                .and_then(Duration::try_days)
+                .unwrap_or(Duration::MAX),
+        )
+    }
+}
+
+/// An artifact file removed (or selected for removal) by compaction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompactEntry {
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// When the artifact was created, from its timestamped name or modification time.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of compacting the store's internal artifacts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompactReport {
+    pub dry_run: bool,
+    /// Number of trashed memos examined.
+    pub trash_scanned: usize,
+    /// Trashed memos purged for being older than the trash retention window.
+    pub trash_purged: Vec<CompactEntry>,
+    /// Temporary files orphaned by interrupted memo writes.
+    pub temp_files_purged: Vec<CompactEntry>,
+}
+
+impl CompactReport {
+    pub fn new(dry_run: bool) -> Self {
+        Self {
+            dry_run,
+            trash_scanned: 0,
+            trash_purged: Vec::new(),
+            temp_files_purged: Vec::new(),
+        }
+    }
+
+    /// Total bytes reclaimed (or that would be reclaimed on a dry run).
+    pub fn bytes_reclaimed(&self) -> u64 {
+        self.trash_purged
+            .iter()
+            .chain(&self.temp_files_purged)
+            .map(|entry| entry.bytes)
+            .sum()
+    }
+}
+
+/// Extracts the trash timestamp from a `<stem>.<%Y%m%dT%H%M%SZ>.<ext>` file name.
+pub fn trashed_at(path: &Path) -> Option<DateTime<Utc>> {
+    let stem = path.file_stem()?.to_str()?;
+    let (_, timestamp) = stem.rsplit_once('.')?;
+    // Memos of one name trashed within a second are told apart by ` (N)`
+    let timestamp = timestamp
+        .split_once(' ')
+        .map_or(timestamp, |(timestamp, _)| timestamp);
+    NaiveDateTime::parse_from_str(timestamp, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_trashed_at_parses_timestamped_names() {
+        assert_eq!(
+            trashed_at(Path::new(".trash/notes.20240310T070000Z.md")),
+            Some(Utc.with_ymd_and_hms(2024, 3, 10, 7, 0, 0).unwrap())
+        );
+        assert_eq!(
+            trashed_at(Path::new(".trash/notes.20240310T070000Z (2).md")),
+            Some(Utc.with_ymd_and_hms(2024, 3, 10, 7, 0, 0).unwrap())
+        );
+        assert_eq!(trashed_at(Path::new(".trash/notes.md")), None);
+        assert_eq!(trashed_at(Path::new(".trash/v1.2.md")), None);
+    }
+
+    #[test]
+    fn test_options_from_settings() {
+        let settings = Settings::default();
+        assert_eq!(
+            CompactOptions::from(&settings).trash_max_age,
+            Duration::days(30)
+        );
+
+        let settings = Settings {
+            trash_retention_days: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(CompactOptions::from(&settings).trash_max_age, Duration::MAX);
+    }
+
+    #[test]
+    fn test_report_bytes_reclaimed() {
+        let mut report = CompactReport::new(false);
+        assert_eq!(report.bytes_reclaimed(), 0);
+
+        for bytes in [100, 250] {
+            report.trash_purged.push(CompactEntry {
+                path: PathBuf::from("t.md"),
+                bytes,
+                created_at: Utc::now(),
+            });
+        }
+        assert_eq!(report.bytes_reclaimed(), 350);
+
+        report.temp_files_purged.push(CompactEntry {
+            path: PathBuf::from("t.md.1-01.tmp"),
+            bytes: 50,
+            created_at: Utc::now(),
+        });
+        assert_eq!(report.bytes_reclaimed(), 400);
+    }
+}
@@ -0,0 +1,85 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::models::MemoId;
+
+/// Drift between the filesystem and the store's in-memory state.
+///
+/// Produced by [`MemoStore::verify_consistency`](super::MemoStore::verify_consistency)
+/// after files are changed behind the store's back, for example by a git pull or
+/// branch switch while the server is running.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConsistencyReport {
+    /// Whether stale entries were evicted and the search index scheduled for rebuild.
+    pub fixed: bool,
+    /// Number of memo files found on disk.
+    pub files_scanned: usize,
+    /// Cached memos or metadata whose backing file is gone or has changed.
+    pub stale_cache_entries: Vec<PathBuf>,
+    /// Memos still in the search index whose files no longer exist.
+    pub missing_indexed: Vec<MemoId>,
+    /// Memo files on disk that the search index has never seen.
+    pub unindexed_files: Vec<PathBuf>,
+    /// Indexed memos whose title or content changed since they were indexed.
+    pub stale_index_entries: Vec<PathBuf>,
+}
+
+impl ConsistencyReport {
+    pub fn new(fixed: bool) -> Self {
+        Self {
+            fixed,
+            files_scanned: 0,
+            stale_cache_entries: Vec::new(),
+            missing_indexed: Vec::new(),
+            unindexed_files: Vec::new(),
+            stale_index_entries: Vec::new(),
+        }
+    }
+
+    /// Returns true if no drift was found.
+    pub fn is_consistent(&self) -> bool {
+        self.stale_cache_entries.is_empty()
+            && self.missing_indexed.is_empty()
+            && self.unindexed_files.is_empty()
+            && self.stale_index_entries.is_empty()
+    }
+
+    /// Returns true if the search index disagrees with the filesystem.
+    pub fn index_drifted(&self) -> bool {
+        !self.missing_indexed.is_empty()
+            || !self.unindexed_files.is_empty()
+            || !self.stale_index_entries.is_empty()
+    }
+}
+
+/// How a cached memo compares with a fresh read of its file, as reported by
+/// [`MemoStore::cache_consistency`](super::MemoStore::cache_consistency).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheDivergence {
+    pub id: MemoId,
+    pub file_path: PathBuf,
+    /// Whether the cached title, content or tags differ from the file's, or
+    /// the file is gone.
+    pub diverged: bool,
+    /// Whether the memo's file no longer exists.
+    pub file_missing: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_consistency_flags() {
+        let mut report = ConsistencyReport::new(false);
+        assert!(report.is_consistent());
+        assert!(!report.index_drifted());
+
+        report.stale_cache_entries.push(PathBuf::from("a.md"));
+        assert!(!report.is_consistent());
+        assert!(!report.index_drifted());
+
+        report.unindexed_files.push(PathBuf::from("b.md"));
+        assert!(report.index_drifted());
+    }
+}
@@ -0,0 +1,480 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::warn;
+
+use super::models::Memo;
+use crate::config::Settings;
+
+/// Date format used for context timestamps when none is configured.
+pub const DEFAULT_CONTEXT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %Z";
+
+/// Returned in place of context when no memo survives the tag and directory filters.
+pub const NO_MATCHING_MEMOS: &str = "No matching memos.";
+
+/// Timezone in which context timestamps are rendered.
+///
+/// Configured as `"utc"`, `"local"`, or an IANA zone name such as
+/// `"America/New_York"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContextTimezone {
+    #[default]
+    Utc,
+    Local,
+    Named(Tz),
+}
+
+impl ContextTimezone {
+    /// Formats a UTC timestamp in this timezone using a strftime-style format.
+    pub fn format(&self, timestamp: &DateTime<Utc>, date_format: &str) -> String {
+        match self {
+            ContextTimezone::Utc => timestamp.format(date_format).to_string(),
+            ContextTimezone::Local => timestamp
+                .with_timezone(&chrono::Local)
+                .format(date_format)
+                .to_string(),
+            ContextTimezone::Named(tz) => {
+                timestamp.with_timezone(tz).format(date_format).to_string()
+            }
+        }
+    }
+}
+
+impl FromStr for ContextTimezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        if trimmed.eq_ignore_ascii_case("utc") {
+            return Ok(ContextTimezone::Utc);
+        }
+        if trimmed.eq_ignore_ascii_case("local") {
+            return Ok(ContextTimezone::Local);
+        }
+        trimmed.parse::<Tz>().map(ContextTimezone::Named).map_err(|_| {
+            format!("Unknown timezone '{trimmed}': expected \"utc\", \"local\", or an IANA zone name")
+        })
+    }
+}
+
+impl fmt::Display for ContextTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextTimezone::Utc => write!(f, "utc"),
+            ContextTimezone::Local => write!(f, "local"),
+            ContextTimezone::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+impl Serialize for ContextTimezone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ContextTimezone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Returns true if `date_format` is a strftime-style format chrono can render.
+pub fn is_valid_date_format(date_format: &str) -> bool {
+    !StrftimeItems::new(date_format).any(|item| matches!(item, Item::Error))
+}
+
+/// How timestamps are rendered in aggregated memo context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextFormat {
+    pub timezone: ContextTimezone,
+    pub date_format: String,
+}
+
+impl Default for ContextFormat {
+    fn default() -> Self {
+        Self {
+            timezone: ContextTimezone::default(),
+            date_format: DEFAULT_CONTEXT_DATE_FORMAT.to_string(),
+        }
+    }
+}
+
+impl From<&Settings> for ContextFormat {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            timezone: settings.context_timezone,
+            date_format: settings.context_date_format.clone(),
+        }
+    }
+}
+
+impl ContextFormat {
+    /// Formats a timestamp, falling back to the default format if the configured
+    /// one cannot be rendered.
+    pub fn format_timestamp(&self, timestamp: &DateTime<Utc>) -> String {
+        let date_format = if is_valid_date_format(&self.date_format) {
+            self.date_format.as_str()
+        } else {
+            DEFAULT_CONTEXT_DATE_FORMAT
+        };
+        self.timezone.format(timestamp, date_format)
+    }
+}
+
+/// Rules for assembling memos into aggregated context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextOptions {
+    pub format: ContextFormat,
+    /// Memos whose content exceeds this many characters are elided: only their
+    /// header and a marker pointing at `get_memo` are included. `None` includes
+    /// every memo in full.
+    pub max_memo_context_chars: Option<usize>,
+    /// Only memos carrying at least one of these tags are included, compared
+    /// case-insensitively after trimming. Empty includes every memo.
+    pub tags: Vec<String>,
+    /// Only memos stored under this directory are included.
+    pub directory: Option<PathBuf>,
+    /// Upper bound on the size of the whole document in bytes. Memos whose
+    /// section would overflow it are left out and counted in a closing note.
+    /// `None` is unbounded.
+    pub max_bytes: Option<usize>,
+    /// Memos carrying any of these tags come first, in full, and are never
+    /// left out: they pass every filter, are not elided, and count toward
+    /// `max_bytes` without being dropped by it.
+    pub always_include_tags: Vec<String>,
+}
+
+impl Default for ContextOptions {
+    fn default() -> Self {
+        Self::from(&Settings::default())
+    }
+}
+
+impl From<&Settings> for ContextOptions {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            format: ContextFormat::from(settings),
+            max_memo_context_chars: Some(settings.max_memo_context_chars),
+            tags: Vec::new(),
+            directory: None,
+            max_bytes: None,
+            always_include_tags: settings.always_include_tags.clone(),
+        }
+    }
+}
+
+impl ContextOptions {
+    /// Returns true if the memo passes the tag and directory filters.
+    pub fn matches(&self, memo: &Memo) -> bool {
+        let tag_match = self.tags.is_empty() || has_any_tag(memo, &self.tags);
+        let directory_match = self.directory.as_ref().map_or(true, |directory| {
+            memo.file_path
+                .as_ref()
+                .is_some_and(|path| path.starts_with(directory))
+        });
+
+        tag_match && directory_match
+    }
+
+    /// Returns true if the memo carries one of the always-included tags.
+    pub fn is_always_included(&self, memo: &Memo) -> bool {
+        has_any_tag(memo, &self.always_include_tags)
+    }
+
+    fn section(&self, memo: &Memo, body: &str) -> String {
+        format!(
+            "# {}\n\n**Created:** {}\n**Updated:** {}\n**Tags:** {}\n\n{}\n\n---\n\n",
+            memo.title,
+            self.format.format_timestamp(&memo.created_at),
+            self.format.format_timestamp(&memo.updated_at),
+            memo.tags.join(", "),
+            body
+        )
+    }
+
+    fn is_oversized(&self, memo: &Memo) -> bool {
+        self.max_memo_context_chars
+            .is_some_and(|limit| memo.content.len() > limit && memo.content.chars().count() > limit)
+    }
+
+    fn fits(&self, context: &str, addition: &str) -> bool {
+        self.max_bytes.map_or(true, |max_bytes| {
+            context.len() + addition.len() <= max_bytes
+        })
+    }
+}
+
+/// Whether the memo carries one of `tags`, compared case-insensitively after trimming.
+fn has_any_tag(memo: &Memo, tags: &[String]) -> bool {
+    memo.tags.iter().any(|tag| {
+        tags.iter()
+            .any(|wanted| tag.trim().to_lowercase() == wanted.trim().to_lowercase())
+    })
+}
+
+/// Renders memos as a single markdown document suitable for LLM context.
+///
+/// Oversized memos are replaced by a truncation marker and listed in a trailing
+/// "Elided memos" section so one pasted log cannot crowd out everything else.
+/// Memos rejected by the tag and directory filters are skipped before any
+/// budget is spent on them; if none remain, [`NO_MATCHING_MEMOS`] is returned.
+/// With a `max_bytes` budget, memos that do not fit are skipped (later, smaller
+/// memos may still be included) and the document never exceeds the budget,
+/// unless the always-included memos, which lead the document, are over it alone.
+pub fn format_context(memos: &[Memo], options: &ContextOptions) -> String {
+    let mut context = String::new();
+    let mut elided = Vec::new();
+    let mut over_budget = 0;
+
+    let (always_included, others): (Vec<&Memo>, Vec<&Memo>) = memos
+        .iter()
+        .partition(|memo| options.is_always_included(memo));
+    for memo in always_included {
+        context.push_str(&options.section(memo, &memo.content));
+    }
+
+    for memo in others.into_iter().filter(|memo| options.matches(memo)) {
+        let oversized = options.is_oversized(memo);
+        let omitted_kb = (memo.content.len() + 1023) / 1024;
+        let body = if oversized {
+            format!(
+                "_[truncated, {omitted_kb} KB omitted — fetch with get_memo using id {}]_",
+                memo.id
+            )
+        } else {
+            memo.content.clone()
+        };
+
+        let section = options.section(memo, &body);
+        if !options.fits(&context, &section) {
+            over_budget += 1;
+            continue;
+        }
+        if oversized {
+            warn!(
+                memo_id = %memo.id,
+                title = %memo.title,
+                size_bytes = memo.content.len(),
+                "Eliding oversized memo from context"
+            );
+            elided.push((memo, omitted_kb));
+        }
+        context.push_str(&section);
+    }
+
+    if !elided.is_empty() {
+        let mut section = String::from("## Elided memos\n\n");
+        for (memo, omitted_kb) in elided {
+            let _ = writeln!(section, "- {} ({}): {} KB", memo.title, memo.id, omitted_kb);
+        }
+        if options.fits(&context, &section) {
+            context.push_str(&section);
+        }
+    }
+
+    if over_budget > 0 {
+        let note = format!(
+            "_{over_budget} more matching memo(s) omitted to stay within {} bytes._\n",
+            options.max_bytes.unwrap_or_default()
+        );
+        if options.fits(&context, &note) {
+            context.push_str(&note);
+        }
+    }
+
+    if context.is_empty() {
+        return NO_MATCHING_MEMOS.to_string();
+    }
+
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_timestamp_in_utc() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 15, 9, 30, 0).unwrap();
+        let format = ContextFormat::default();
+
+        assert_eq!(
+            format.format_timestamp(&timestamp),
+            "2024-01-15 09:30:00 UTC"
+        );
+    }
+
+    #[test]
+    fn test_format_timestamp_named_zone_across_dst() {
+        let format = ContextFormat {
+            timezone: "America/New_York".parse().unwrap(),
+            date_format: "%Y-%m-%d %H:%M %Z".to_string(),
+        };
+
+        // US daylight saving time began at 2024-03-10 07:00 UTC
+        let before = Utc.with_ymd_and_hms(2024, 3, 10, 6, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 3, 10, 8, 0, 0).unwrap();
+
+        assert_eq!(format.format_timestamp(&before), "2024-03-10 01:00 EST");
+        assert_eq!(format.format_timestamp(&after), "2024-03-10 04:00 EDT");
+    }
+
+    #[test]
+    fn test_context_timezone_parsing() {
+        assert_eq!("UTC".parse::<ContextTimezone>(), Ok(ContextTimezone::Utc));
+        assert_eq!(
+            "local".parse::<ContextTimezone>(),
+            Ok(ContextTimezone::Local)
+        );
+        assert_eq!(
+            "Europe/Berlin"
+                .parse::<ContextTimezone>()
+                .unwrap()
+                .to_string(),
+            "Europe/Berlin"
+        );
+        assert!("Mars/Olympus_Mons".parse::<ContextTimezone>().is_err());
+    }
+
+    #[test]
+    fn test_invalid_date_format_falls_back() {
+        assert!(!is_valid_date_format("%Y-%Q"));
+
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 15, 9, 30, 0).unwrap();
+        let format = ContextFormat {
+            timezone: ContextTimezone::Utc,
+            date_format: "%Y-%Q".to_string(),
+        };
+        assert_eq!(
+            format.format_timestamp(&timestamp),
+            "2024-01-15 09:30:00 UTC"
+        );
+    }
+
+    #[test]
+    fn test_oversized_memo_is_elided() {
+        let huge = Memo::new("Pasted Log".to_string(), "x".repeat(500 * 1024)).unwrap();
+        let small: Vec<Memo> = (0..5)
+            .map(|i| Memo::new(format!("Small {i}"), format!("small content {i}")).unwrap())
+            .collect();
+        let mut memos = vec![huge.clone()];
+        memos.extend(small.iter().cloned());
+
+        let options = ContextOptions {
+            max_memo_context_chars: Some(10_000),
+            ..Default::default()
+        };
+        let context = format_context(&memos, &options);
+
+        for memo in &small {
+            assert!(context.contains(&format!("# {}", memo.title)));
+            assert!(context.contains(&memo.content));
+        }
+        assert!(context.contains("# Pasted Log"));
+        assert!(context.contains("truncated, 500 KB omitted — fetch with get_memo"));
+        assert!(context.contains(&huge.id.to_string()));
+        assert!(context.contains("## Elided memos\n\n- Pasted Log"));
+        assert!(context.len() < 10_000);
+    }
+
+    #[test]
+    fn test_unlimited_context_includes_everything() {
+        let memo = Memo::new("Big".to_string(), "y".repeat(20_000)).unwrap();
+        let options = ContextOptions {
+            max_memo_context_chars: None,
+            ..Default::default()
+        };
+
+        let context = format_context(&[memo], &options);
+        assert!(context.contains(&"y".repeat(20_000)));
+        assert!(!context.contains("Elided memos"));
+    }
+
+    #[test]
+    fn test_tag_filter_is_case_insensitive() {
+        let mut tagged = Memo::new("Tagged".to_string(), "tagged body".to_string()).unwrap();
+        tagged.tags = vec!["Frontend".to_string()];
+        let untagged = Memo::new("Untagged".to_string(), "untagged body".to_string()).unwrap();
+
+        let options = ContextOptions {
+            tags: vec![" frontend ".to_string()],
+            ..Default::default()
+        };
+        let context = format_context(&[tagged, untagged.clone()], &options);
+        assert!(context.contains("# Tagged"));
+        assert!(!context.contains("# Untagged"));
+
+        assert_eq!(format_context(&[untagged], &options), NO_MATCHING_MEMOS);
+    }
+
+    #[test]
+    fn test_always_included_memos_lead_and_are_never_dropped() {
+        let mut standards = Memo::new("Coding Standards".to_string(), "s".repeat(2_000)).unwrap();
+        standards.tags = vec!["Standards".to_string()];
+        let memos = vec![
+            Memo::new("First".to_string(), "a".repeat(300)).unwrap(),
+            standards,
+            Memo::new("Second".to_string(), "b".repeat(300)).unwrap(),
+        ];
+        let options = ContextOptions {
+            max_bytes: Some(2_700),
+            max_memo_context_chars: Some(1_000),
+            tags: vec!["frontend".to_string()],
+            always_include_tags: vec!["standards".to_string()],
+            ..Default::default()
+        };
+
+        // Included first and in full despite the tag filter, elision and budget
+        let context = format_context(&memos, &options);
+        assert!(context.starts_with("# Coding Standards"));
+        assert!(context.contains(&"s".repeat(2_000)));
+        assert!(!context.contains("# First"));
+
+        // It counts toward the budget, leaving room for only one other memo
+        let options = ContextOptions {
+            tags: Vec::new(),
+            ..options
+        };
+        let context = format_context(&memos, &options);
+        assert!(context.starts_with("# Coding Standards"));
+        assert!(context.contains("# First"));
+        assert!(!context.contains("# Second"));
+        assert!(context.contains("1 more matching memo(s) omitted"));
+
+        // It is never dropped, even when it alone is over the budget
+        let options = ContextOptions {
+            max_bytes: Some(100),
+            ..options
+        };
+        let context = format_context(&memos, &options);
+        assert!(context.contains(&"s".repeat(2_000)));
+        assert!(!context.contains("# First"));
+    }
+
+    #[test]
+    fn test_byte_budget_skips_memos_that_do_not_fit() {
+        let memos = vec![
+            Memo::new("First".to_string(), "a".repeat(300)).unwrap(),
+            Memo::new("Too Big".to_string(), "b".repeat(2_000)).unwrap(),
+            Memo::new("Third".to_string(), "c".repeat(300)).unwrap(),
+        ];
+        let options = ContextOptions {
+            max_bytes: Some(1_000),
+            ..Default::default()
+        };
+
+        let context = format_context(&memos, &options);
+        assert!(context.len() <= 1_000);
+        assert!(context.contains("# First"));
+        assert!(!context.contains("# Too Big"));
+        assert!(context.contains("# Third"));
+        assert!(context.contains("1 more matching memo(s) omitted to stay within 1000 bytes"));
+    }
+}
@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+use tracing::{debug, warn};
+
+use super::cache::MemoMetadata;
+use super::models::{Memo, MemoId};
+
+/// Directory, inside the primary `.memoranda` directory, memos evicted from
+/// the in-memory cache are kept in when the disk tier is enabled.
+pub const DISK_CACHE_DIR_NAME: &str = ".cache";
+
+/// A memo written to the disk tier, with the state of its file at the time,
+/// so it is only served while the file is unchanged.
+#[derive(Debug, Serialize, Deserialize)]
+struct DiskEntry {
+    memo: Memo,
+    file_modified: SystemTime,
+    file_size: u64,
+}
+
+#[derive(Debug, Default)]
+struct DiskIndex {
+    /// Incremented on every access; entries remember when they were last used.
+    clock: u64,
+    /// Size in bytes and last use of each entry on disk.
+    entries: HashMap<MemoId, (u64, u64)>,
+    total_bytes: u64,
+}
+
+/// Second cache tier beneath the in-memory memo cache: memos evicted from
+/// memory are written to `<dir>/<id>.json`, and the least recently used
+/// entries are deleted once together they take more than `max_bytes`.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<DiskIndex>,
+}
+
+impl DiskCache {
+    /// Opens the tier in `dir`, keeping entries written by an earlier run with
+    /// the least recently written treated as least recently used.
+    pub fn open(dir: PathBuf, max_bytes: u64) -> Self {
+        let mut found = Vec::new();
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let Some(id) = entry_id(&entry.path()) else {
+                    continue;
+                };
+                if let Ok(metadata) = entry.metadata() {
+                    let written = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    found.push((written, id, metadata.len()));
+                }
+            }
+        }
+        found.sort();
+
+        let mut index = DiskIndex::default();
+        for (_, id, bytes) in found {
+            index.clock += 1;
+            index.entries.insert(id, (bytes, index.clock));
+            index.total_bytes += bytes;
+        }
+        let cache = Self {
+            dir,
+            max_bytes,
+            index: Mutex::new(index),
+        };
+        cache.evict_over_budget(&mut cache.lock());
+        cache
+    }
+
+    /// Writes `memo` to disk, unless it has no file, its file changed after
+    /// `cached_at` (when the in-memory copy was cached), or it alone exceeds
+    /// the byte budget.
+    pub fn store(&self, memo: &Memo, cached_at: SystemTime) {
+        let Some(file_path) = &memo.file_path else {
+            return;
+        };
+        let Ok(metadata) = fs::metadata(file_path) else {
+            return;
+        };
+        let Ok(file_modified) = metadata.modified() else {
+            return;
+        };
+        if file_modified > cached_at {
+            debug!("Not writing stale memo {} to the disk cache", memo.id);
+            return;
+        }
+
+        let entry = DiskEntry {
+            memo: memo.clone(),
+            file_modified,
+            file_size: metadata.len(),
+        };
+        let bytes = match self.write_entry(&memo.id, &entry) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to write memo {} to the disk cache: {}", memo.id, e);
+                return;
+            }
+        };
+
+        let mut index = self.lock();
+        index.clock += 1;
+        let now = index.clock;
+        if let Some((old_bytes, _)) = index.entries.insert(memo.id, (bytes, now)) {
+            index.total_bytes -= old_bytes;
+        }
+        index.total_bytes += bytes;
+        self.evict_over_budget(&mut index);
+        debug!("Wrote memo {} to the disk cache", memo.id);
+    }
+
+    /// Reads the memo with `id` back, with the metadata of its file, if it is
+    /// on disk and its file has not changed since it was written. Entries
+    /// that no longer match their file are deleted.
+    pub fn load(&self, id: &MemoId) -> Option<(Memo, MemoMetadata)> {
+        {
+            let mut index = self.lock();
+            index.clock += 1;
+            let now = index.clock;
+            index.entries.get_mut(id)?.1 = now;
+        }
+
+        let entry = fs::read(self.entry_path(id))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<DiskEntry>(&bytes).ok());
+        let Some((entry, file_path)) = entry
+            .and_then(|entry| entry.memo.file_path.clone().map(|path| (entry, path)))
+            .filter(|(entry, path)| file_unchanged(path, entry))
+        else {
+            self.remove(id);
+            return None;
+        };
+
+        let metadata = MemoMetadata {
+            id: entry.memo.id,
+            title: entry.memo.title.clone(),
+            file_path,
+            last_modified: entry.file_modified,
+            file_size: entry.file_size,
+        };
+        Some((entry.memo, metadata))
+    }
+
+    /// Deletes the entry for `id`, if there is one.
+    pub fn remove(&self, id: &MemoId) {
+        let mut index = self.lock();
+        if let Some((bytes, _)) = index.entries.remove(id) {
+            index.total_bytes -= bytes;
+            self.delete_entry(id);
+        }
+    }
+
+    /// Deletes every entry.
+    pub fn clear(&self) {
+        let mut index = self.lock();
+        for id in index.entries.keys() {
+            self.delete_entry(id);
+        }
+        *index = DiskIndex {
+            clock: index.clock,
+            ..DiskIndex::default()
+        };
+    }
+
+    /// Total size in bytes of the entries on disk.
+    pub fn total_bytes(&self) -> u64 {
+        self.lock().total_bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DiskIndex> {
+        self.index.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn entry_path(&self, id: &MemoId) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    /// Writes `entry`, returning its size, or `None` when it would not fit
+    /// in the budget on its own.
+    fn write_entry(&self, id: &MemoId, entry: &DiskEntry) -> io::Result<Option<u64>> {
+        let bytes = serde_json::to_vec(entry)?;
+        let size = bytes.len() as u64;
+        if size > self.max_bytes {
+            return Ok(None);
+        }
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.entry_path(id), bytes)?;
+        Ok(Some(size))
+    }
+
+    fn delete_entry(&self, id: &MemoId) {
+        match fs::remove_file(self.entry_path(id)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to delete memo {} from the disk cache: {}", id, e),
+        }
+    }
+
+    /// Deletes least recently used entries until the rest fit in the budget.
+    fn evict_over_budget(&self, index: &mut DiskIndex) {
+        while index.total_bytes > self.max_bytes {
+            let Some((&oldest, &(bytes, _))) = index
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+            else {
+                break;
+            };
+            index.entries.remove(&oldest);
+            index.total_bytes -= bytes;
+            self.delete_entry(&oldest);
+            debug!("Evicted memo {} from the disk cache", oldest);
+        }
+    }
+}
+
+/// The memo id a disk cache file is named after.
+fn entry_id(path: &Path) -> Option<MemoId> {
+    if path.extension()? != "json" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Whether the memo file at `path` still has the modification time and size
+/// recorded in `entry`.
+fn file_unchanged(path: &Path, entry: &DiskEntry) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| {
+        metadata.len() == entry.file_size
+            && metadata
+                .modified()
+                .is_ok_and(|modified| modified == entry.file_modified)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn memo_file(dir: &Path, title: &str) -> Memo {
+        let path = dir.join(format!("{title}.md"));
+        fs::write(&path, format!("# {title}")).unwrap();
+        Memo::with_file_path(title.to_string(), format!("About {title}"), Some(path)).unwrap()
+    }
+
+    #[test]
+    fn test_disk_cache_round_trip_and_staleness() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join(DISK_CACHE_DIR_NAME);
+        let memo = memo_file(temp_dir.path(), "Alpha");
+        let cache = DiskCache::open(cache_dir.clone(), 1 << 20);
+
+        assert!(cache.load(&memo.id).is_none());
+        cache.store(&memo, SystemTime::now());
+        assert_eq!(cache.len(), 1);
+        assert!(cache.total_bytes() > 0);
+        let (loaded, metadata) = cache.load(&memo.id).unwrap();
+        assert_eq!(loaded.title, "Alpha");
+        assert_eq!(loaded.content, "About Alpha");
+        assert_eq!(metadata.file_path, memo.file_path.clone().unwrap());
+
+        // Entries survive a restart
+        let cache = DiskCache::open(cache_dir, 1 << 20);
+        assert!(cache.load(&memo.id).is_some());
+
+        // A changed file makes the entry stale, and it is dropped
+        fs::write(memo.file_path.as_ref().unwrap(), "# Alpha, edited").unwrap();
+        assert!(cache.load(&memo.id).is_none());
+        assert!(cache.is_empty());
+
+        // A memo whose file changed after it was cached is never written
+        cache.store(&memo, SystemTime::UNIX_EPOCH);
+        assert!(cache.is_empty());
+
+        cache.store(&memo, SystemTime::now());
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.total_bytes(), 0);
+    }
+
+    #[test]
+    fn test_disk_cache_evicts_least_recently_used_over_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let [a, b, c] =
+            ["Memo A", "Memo B", "Memo C"].map(|title| memo_file(temp_dir.path(), title));
+        let probe = DiskCache::open(temp_dir.path().join("probe"), 1 << 20);
+        probe.store(&a, SystemTime::now());
+        let entry_bytes = probe.total_bytes();
+
+        let cache = DiskCache::open(temp_dir.path().join("cache"), entry_bytes * 5 / 2);
+        cache.store(&a, SystemTime::now());
+        cache.store(&b, SystemTime::now());
+        assert!(cache.load(&a.id).is_some());
+        cache.store(&c, SystemTime::now());
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.total_bytes() <= entry_bytes * 5 / 2);
+        assert!(cache.load(&b.id).is_none());
+        assert!(cache.load(&a.id).is_some());
+        assert!(cache.load(&c.id).is_some());
+        assert!(!temp_dir
+            .path()
+            .join(format!("cache/{}.json", b.id))
+            .exists());
+
+        // A memo larger than the whole budget is never written
+        let tiny = DiskCache::open(temp_dir.path().join("tiny"), 10);
+        tiny.store(&a, SystemTime::now());
+        assert!(tiny.is_empty());
+    }
+}
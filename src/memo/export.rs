@@ -0,0 +1,206 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use super::models::{Memo, MemoId};
+
+/// Version of the export bundle format, bumped when a field changes meaning
+/// or is removed so readers can tell which layout they were given.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// A memo as written to an export bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedMemo {
+    pub id: MemoId,
+    pub title: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Path of the memo's file relative to the store root.
+    pub path: Option<PathBuf>,
+}
+
+impl ExportedMemo {
+    /// The exported form of `memo`, whose file path is relative to `root`.
+    pub fn new(memo: Memo, root: &std::path::Path) -> Self {
+        let path = memo
+            .file_path
+            .map(|path| path.strip_prefix(root).map(PathBuf::from).unwrap_or(path));
+        Self {
+            id: memo.id,
+            title: memo.title,
+            content: memo.content,
+            tags: memo.tags,
+            created_at: memo.created_at,
+            updated_at: memo.updated_at,
+            path,
+        }
+    }
+
+    /// The memo this entry describes, with its id, tags and timestamps, and
+    /// no file yet. Fails if the title or content is not valid for a memo.
+    pub fn into_memo(self) -> anyhow::Result<Memo> {
+        let mut memo = Memo::new(self.title, self.content)?;
+        memo.id = self.id;
+        memo.created_at = self.created_at;
+        memo.updated_at = self.updated_at;
+        for tag in self.tags {
+            memo.add_tag(tag);
+        }
+        Ok(memo)
+    }
+}
+
+/// A whole export bundle, as read back from disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportBundle {
+    pub format_version: u32,
+    pub memos: Vec<ExportedMemo>,
+}
+
+/// Outcome of exporting the store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportReport {
+    pub path: PathBuf,
+    pub memos: usize,
+}
+
+/// What importing a bundle does with a memo whose id is already in the store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStrategy {
+    /// Leave the memo in the store as it is.
+    #[default]
+    SkipExisting,
+    /// Replace the memo in the store, keeping its file.
+    Overwrite,
+    /// Import the memo as a new one with a fresh id.
+    DuplicateAsNew,
+}
+
+impl FromStr for ImportStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip_existing" => Ok(ImportStrategy::SkipExisting),
+            "overwrite" => Ok(ImportStrategy::Overwrite),
+            "duplicate_as_new" => Ok(ImportStrategy::DuplicateAsNew),
+            other => Err(format!(
+                "Invalid strategy: {other} (expected \"skip_existing\", \"overwrite\", or \"duplicate_as_new\")"
+            )),
+        }
+    }
+}
+
+/// A bundle entry an import could not write, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BundleImportIssue {
+    /// Position of the entry in the bundle's `memos`.
+    pub index: usize,
+    /// The entry's id, when it had a readable one.
+    pub id: Option<MemoId>,
+    pub reason: String,
+}
+
+/// Outcome of importing a bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BundleImportReport {
+    /// Memos written to new files, including those duplicated as new.
+    pub created: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+    pub failed: Vec<BundleImportIssue>,
+}
+
+/// A bundle as read for import: entries are kept as JSON until each is
+/// imported, so one malformed entry fails alone rather than the whole bundle.
+#[derive(Debug, Deserialize)]
+pub struct RawExportBundle {
+    pub format_version: u32,
+    pub memos: Vec<serde_json::Value>,
+}
+
+/// Whether the file at `path` reads as an export bundle.
+pub fn is_export_bundle(path: &std::path::Path) -> bool {
+    std::fs::File::open(path)
+        .ok()
+        .and_then(|file| {
+            serde_json::from_reader::<_, RawExportBundle>(io::BufReader::new(file)).ok()
+        })
+        .is_some()
+}
+
+/// Writes an export bundle one memo at a time, so a large store is never held
+/// in memory as a whole. Each memo takes a line of its own, which keeps diffs
+/// between two exports down to the memos that changed.
+pub struct ExportWriter<W: Write> {
+    writer: W,
+    memos: usize,
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// Starts a bundle, writing everything before the first memo.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        write!(
+            writer,
+            "{{\n  \"format_version\": {EXPORT_FORMAT_VERSION},\n  \"memos\": ["
+        )?;
+        Ok(Self { writer, memos: 0 })
+    }
+
+    pub fn write_memo(&mut self, memo: &ExportedMemo) -> io::Result<()> {
+        let separator = if self.memos == 0 { "" } else { "," };
+        write!(self.writer, "{separator}\n    ")?;
+        serde_json::to_writer(&mut self.writer, memo)?;
+        self.memos += 1;
+        Ok(())
+    }
+
+    /// Closes the bundle, returning the writer and how many memos it holds.
+    pub fn finish(mut self) -> io::Result<(W, usize)> {
+        let indent = if self.memos == 0 { "" } else { "\n  " };
+        write!(self.writer, "{indent}]\n}}\n")?;
+        self.writer.flush()?;
+        Ok((self.writer, self.memos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_export_writer_produces_a_readable_bundle() {
+        let root = Path::new("/repo");
+        let mut memo = Memo::with_file_path(
+            "Alpha".to_string(),
+            "First \"quoted\"\nline".to_string(),
+            Some(root.join(".memoranda/Alpha.md")),
+        )
+        .unwrap();
+        memo.add_tag("work".to_string());
+        let exported = ExportedMemo::new(memo, root);
+        assert_eq!(exported.path, Some(PathBuf::from(".memoranda/Alpha.md")));
+
+        let (bytes, count) = ExportWriter::new(Vec::new()).unwrap().finish().unwrap();
+        assert_eq!(count, 0);
+        let empty: ExportBundle = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(empty.format_version, EXPORT_FORMAT_VERSION);
+        assert!(empty.memos.is_empty());
+
+        let mut writer = ExportWriter::new(Vec::new()).unwrap();
+        writer.write_memo(&exported).unwrap();
+        writer.write_memo(&exported).unwrap();
+        let (bytes, count) = writer.finish().unwrap();
+        assert_eq!(count, 2);
+        let bundle: ExportBundle = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(bundle.memos, vec![exported.clone(), exported]);
+        // One line per memo, between the header and the closing lines
+        assert_eq!(String::from_utf8(bytes).unwrap().lines().count(), 7);
+    }
+}
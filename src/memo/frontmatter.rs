@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use super::models::{Memo, MemoId};
+
+/// Format of the frontmatter block at the top of a memo file.
+///
+/// Reading always detects the format from the file, so directories holding a
+/// mix of formats load fine; the configured format only decides how memos are
+/// written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontmatterFormat {
+    /// A JSON object between `---` lines.
+    #[default]
+    Json,
+    /// YAML between `---` lines.
+    Yaml,
+    /// TOML between `+++` lines, as used by Zola and Hugo.
+    Toml,
+}
+
+/// A frontmatter block that could not be parsed or written.
+#[derive(Error, Debug)]
+pub enum FrontmatterError {
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    TomlParse(#[from] toml::de::Error),
+    #[error("{0}")]
+    TomlWrite(#[from] toml::ser::Error),
+    #[error("line {line}: {message}")]
+    Yaml { line: usize, message: String },
+}
+
+/// A memo file split at its frontmatter fences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrontmatterBlock<'a> {
+    pub format: FrontmatterFormat,
+    pub block: &'a str,
+    /// `None` when the closing fence is missing.
+    pub body: Option<&'a str>,
+}
+
+impl FrontmatterFormat {
+    /// The line that opens and closes a block in this format.
+    pub fn fence(&self) -> &'static str {
+        match self {
+            FrontmatterFormat::Toml => "+++",
+            FrontmatterFormat::Json | FrontmatterFormat::Yaml => "---",
+        }
+    }
+
+    /// Detects the format of a frontmatter block from its opening fence and,
+    /// for `---` blocks, whether the block is a JSON object.
+    pub fn detect(fence: &str, block: &str) -> Option<Self> {
+        match fence {
+            "+++" => Some(FrontmatterFormat::Toml),
+            "---" if block.trim_start().starts_with('{') => Some(FrontmatterFormat::Json),
+            "---" => Some(FrontmatterFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Parses a block in this format into a JSON value.
+    pub fn parse(&self, block: &str) -> Result<Value, FrontmatterError> {
+        match self {
+            FrontmatterFormat::Json => Ok(serde_json::from_str(block)?),
+            FrontmatterFormat::Toml => Ok(toml_to_json(toml::from_str(block)?)),
+            FrontmatterFormat::Yaml => parse_yaml(block),
+        }
+    }
+
+    /// Parses a block in this format into a memo.
+    pub fn parse_memo(&self, block: &str) -> Result<Memo, FrontmatterError> {
+        Ok(serde_json::from_value(self.parse(block)?)?)
+    }
+
+    /// Writes a memo as a block in this format, without fences or a trailing newline.
+    pub fn write_memo(&self, memo: &Memo) -> Result<String, FrontmatterError> {
+        match self {
+            FrontmatterFormat::Json => Ok(serde_json::to_string_pretty(memo)?),
+            FrontmatterFormat::Toml => Ok(toml::to_string(memo)?.trim_end().to_string()),
+            FrontmatterFormat::Yaml => write_yaml(&serde_json::to_value(memo)?),
+        }
+    }
+}
+
+/// Completes a frontmatter object that has no `id`, so its other fields can
+/// still load as a memo: `id` is set to `id`, a missing `title` or `content`
+/// to `title` or `body`, a missing `created_at` to now and a missing
+/// `updated_at` to `created_at`.
+///
+/// Returns `false`, leaving `value` unchanged, if it is not an object or
+/// already has an `id`.
+pub fn complete_partial_frontmatter(
+    value: &mut Value,
+    id: MemoId,
+    title: &str,
+    body: &str,
+) -> bool {
+    let Some(fields) = value.as_object_mut() else {
+        return false;
+    };
+    if fields.contains_key("id") {
+        return false;
+    }
+
+    fields.insert("id".to_string(), Value::String(id.to_string()));
+    fields
+        .entry("title")
+        .or_insert_with(|| Value::String(title.to_string()));
+    fields
+        .entry("content")
+        .or_insert_with(|| Value::String(body.to_string()));
+    let created_at = fields
+        .entry("created_at")
+        .or_insert_with(|| Value::String(chrono::Utc::now().to_rfc3339()))
+        .clone();
+    fields.entry("updated_at").or_insert(created_at);
+    fields
+        .entry("tags")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    true
+}
+
+/// Splits a memo file into its frontmatter block and body.
+///
+/// Returns `None` if the file does not start with a `---` or `+++` fence.
+pub fn split_frontmatter(content: &str) -> Option<FrontmatterBlock<'_>> {
+    let (fence, rest) = ["---", "+++"].into_iter().find_map(|fence| {
+        let rest = content.strip_prefix(fence)?.strip_prefix('\n')?;
+        Some((fence, rest))
+    })?;
+
+    let closing = format!("\n{fence}\n");
+    let (block, body) = match rest.strip_prefix(&closing[1..]) {
+        Some(body) => ("", Some(body)),
+        None => match rest.split_once(&closing) {
+            Some((block, body)) => (block, Some(body)),
+            None => (rest, None),
+        },
+    };
+
+    Some(FrontmatterBlock {
+        format: FrontmatterFormat::detect(fence, block)?,
+        block,
+        body,
+    })
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        // Unquoted TOML datetimes become the RFC 3339 strings memos store
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Writes each field as `key: <JSON value>`. JSON strings and arrays are valid
+/// YAML double-quoted scalars and flow sequences, so no escaping rules beyond
+/// JSON's are needed.
+fn write_yaml(value: &Value) -> Result<String, FrontmatterError> {
+    let fields = value.as_object().ok_or_else(|| FrontmatterError::Yaml {
+        line: 1,
+        message: "frontmatter must be a mapping".to_string(),
+    })?;
+    let lines: Vec<String> = fields
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| format!("{key}: {value}"))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Parses the YAML subset memo frontmatter needs: a top-level mapping of
+/// `key: value` lines whose values are plain, single- or double-quoted
+/// scalars, flow sequences (`[a, b]`), block sequences (`- a` lines), or
+/// literal and folded block scalars (`|` and `>`).
+fn parse_yaml(block: &str) -> Result<Value, FrontmatterError> {
+    let lines: Vec<&str> = block.lines().collect();
+    let mut fields = Map::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let line_number = i + 1;
+        i += 1;
+        if is_blank_or_comment(line) {
+            continue;
+        }
+        let error = |message: &str| FrontmatterError::Yaml {
+            line: line_number,
+            message: message.to_string(),
+        };
+        if line.starts_with([' ', '\t']) {
+            return Err(error("unexpected indentation"));
+        }
+        let (key, rest) = line
+            .split_once(':')
+            .ok_or_else(|| error("expected `key: value`"))?;
+        let key = parse_yaml_scalar(key.trim()).map_err(|message| error(&message))?;
+        let key = match key {
+            Value::String(key) => key,
+            other => other.to_string(),
+        };
+        let rest = rest.trim();
+
+        let value = if rest.is_empty() {
+            let mut items = Vec::new();
+            while i < lines.len() {
+                let item = lines[i].trim_start();
+                if is_blank_or_comment(lines[i]) {
+                    i += 1;
+                } else if let Some(item) = item.strip_prefix('-') {
+                    if !item.is_empty() && !item.starts_with(' ') {
+                        break;
+                    }
+                    items.push(parse_yaml_scalar(item.trim()).map_err(|message| {
+                        FrontmatterError::Yaml {
+                            line: i + 1,
+                            message,
+                        }
+                    })?);
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            if items.is_empty() {
+                Value::Null
+            } else {
+                Value::Array(items)
+            }
+        } else if let Some(indicator) = rest.strip_prefix(['|', '>']) {
+            let folded = rest.starts_with('>');
+            let start = i;
+            while i < lines.len()
+                && (lines[i].trim().is_empty() || lines[i].starts_with([' ', '\t']))
+            {
+                i += 1;
+            }
+            Value::String(block_scalar(&lines[start..i], folded, indicator))
+        } else {
+            parse_yaml_scalar(rest).map_err(|message| error(&message))?
+        };
+        fields.insert(key, value);
+    }
+
+    Ok(Value::Object(fields))
+}
+
+fn is_blank_or_comment(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+/// Joins the indented lines of a block scalar, honoring the `-` (strip) and
+/// `+` (keep) chomping indicators; the default keeps one trailing newline.
+fn block_scalar(lines: &[&str], folded: bool, indicator: &str) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let separator = if folded { " " } else { "\n" };
+    let text = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join(separator);
+
+    match indicator.trim() {
+        "-" => text.trim_end_matches(['\n', ' ']).to_string(),
+        "+" => format!("{text}\n"),
+        _ => format!("{}\n", text.trim_end_matches(['\n', ' '])),
+    }
+}
+
+fn parse_yaml_scalar(text: &str) -> Result<Value, String> {
+    if text.starts_with('"') {
+        return serde_json::from_str::<String>(text)
+            .map(Value::String)
+            .map_err(|e| format!("invalid double-quoted string: {e}"));
+    }
+    if let Some(inner) = text.strip_prefix('\'') {
+        return inner
+            .strip_suffix('\'')
+            .map(|inner| Value::String(inner.replace("''", "'")))
+            .ok_or_else(|| "unterminated single-quoted string".to_string());
+    }
+    if text.starts_with('[') {
+        if let Ok(value) = serde_json::from_str(text) {
+            return Ok(value);
+        }
+        let inner = text
+            .strip_prefix('[')
+            .and_then(|text| text.strip_suffix(']'))
+            .ok_or_else(|| "unterminated flow sequence".to_string())?;
+        return inner
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(parse_yaml_scalar)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array);
+    }
+    if text.starts_with('{') {
+        return serde_json::from_str(text).map_err(|e| format!("invalid flow mapping: {e}"));
+    }
+
+    let plain = match text.find(" #") {
+        Some(comment) => text[..comment].trim_end(),
+        None => text,
+    };
+    Ok(match plain {
+        "" | "~" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => plain
+            .parse::<i64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(plain.to_string())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_memo() -> Memo {
+        let mut memo = Memo::new(
+            "Notes: \"quoted\"".to_string(),
+            "line one\nline two\n".to_string(),
+        )
+        .unwrap();
+        memo.tags = vec!["rust".to_string(), "zola".to_string()];
+        memo
+    }
+
+    #[test]
+    fn test_each_format_round_trips() {
+        let memo = sample_memo();
+        for format in [
+            FrontmatterFormat::Json,
+            FrontmatterFormat::Yaml,
+            FrontmatterFormat::Toml,
+        ] {
+            let block = format.write_memo(&memo).unwrap();
+            let file = format!("{fence}\n{block}\n{fence}\nbody", fence = format.fence());
+
+            let split = split_frontmatter(&file).unwrap();
+            assert_eq!(split.format, format);
+            assert_eq!(split.body, Some("body"));
+            let parsed = format.parse_memo(split.block).unwrap();
+            assert_eq!(
+                (&parsed.id, &parsed.title, &parsed.content, &parsed.tags),
+                (&memo.id, &memo.title, &memo.content, &memo.tags),
+                "{format:?}"
+            );
+            assert_eq!(parsed.created_at, memo.created_at);
+        }
+    }
+
+    #[test]
+    fn test_hand_written_toml_and_yaml() {
+        let toml = "+++\n\
+            id = \"01ARZ3NDEKTSV4RRFFQ69G5FAV\"\n\
+            title = \"Zola style\"\n\
+            content = \"body\"\n\
+            created_at = 2024-03-10T07:00:00Z\n\
+            updated_at = 2024-03-10T07:00:00Z\n\
+            tags = [\"rust\"]\n\
+            +++\nbody";
+        let split = split_frontmatter(toml).unwrap();
+        let memo = split.format.parse_memo(split.block).unwrap();
+        assert_eq!(memo.title, "Zola style");
+        assert_eq!(memo.created_at.to_rfc3339(), "2024-03-10T07:00:00+00:00");
+
+        let yaml = "---\n\
+            # written by hand\n\
+            id: 01ARZ3NDEKTSV4RRFFQ69G5FAV\n\
+            title: 'It''s YAML'\n\
+            content: |\n  first\n  second\n\
+            created_at: 2024-03-10T07:00:00Z\n\
+            updated_at: \"2024-03-10T07:00:00Z\"\n\
+            tags:\n  - rust\n  - notes\n\
+            aliases: [yaml, frontmatter]\n\
+            ---\nbody";
+        let split = split_frontmatter(yaml).unwrap();
+        assert_eq!(split.format, FrontmatterFormat::Yaml);
+        let memo = split.format.parse_memo(split.block).unwrap();
+        assert_eq!(memo.title, "It's YAML");
+        assert_eq!(memo.content, "first\nsecond\n");
+        assert_eq!(memo.tags, ["rust", "notes"]);
+        assert_eq!(memo.aliases, ["yaml", "frontmatter"]);
+
+        assert!(split_frontmatter("no frontmatter").is_none());
+        assert!(FrontmatterFormat::Yaml.parse("  indented: value").is_err());
+    }
+}
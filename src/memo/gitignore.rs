@@ -0,0 +1,258 @@
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the files ignore patterns are read from.
+pub const GITIGNORE_FILE_NAME: &str = ".gitignore";
+
+/// Name of the file, inside a `.memoranda` directory, listing files there that
+/// are not memos, in `.gitignore` syntax.
+pub const MEMORANDA_IGNORE_FILE_NAME: &str = ".memorandaignore";
+
+/// Patterns from the `.gitignore` files met while walking a tree, used to skip
+/// ignored directories such as `target/` or `node_modules/`, or from a
+/// `.memorandaignore`, used to skip files that are not memos.
+///
+/// Supports the common subset of gitignore syntax: comments, `!` negation,
+/// `*`, `?`, `[...]` and `**` wildcards, a trailing `/` for patterns that only
+/// match directories, and patterns anchored to their file by a leading or
+/// inner `/`. Later patterns, and those in deeper directories, take precedence.
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    sets: Vec<RuleSet>,
+    /// Name of the memo directories, which are never ignored.
+    memo_dir_name: Option<String>,
+}
+
+#[derive(Debug)]
+struct RuleSet {
+    /// Directory of the `.gitignore` the rules came from.
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    negated: bool,
+    /// Matched against the path relative to the base, rather than the name.
+    anchored: bool,
+    /// Written with a trailing `/`, so only directories match.
+    dir_only: bool,
+}
+
+impl IgnoreRules {
+    /// Rules for walking a tree for memo directories named `memo_dir_name`.
+    pub fn new(memo_dir_name: &str) -> Self {
+        Self {
+            sets: Vec::new(),
+            memo_dir_name: Some(memo_dir_name.to_string()),
+        }
+    }
+
+    /// Reads `dir`'s `.gitignore`, if it has one, so its patterns apply to
+    /// `dir` and everything below it.
+    pub fn load_dir(&mut self, dir: &Path) {
+        if let Ok(text) = fs::read_to_string(dir.join(GITIGNORE_FILE_NAME)) {
+            self.add_patterns(dir, &text);
+        }
+    }
+
+    /// Reads the `.memorandaignore` in `.memoranda` directory `dir`, if it has one.
+    pub fn load_memoranda_dir(dir: &Path) -> Self {
+        let mut rules = Self::default();
+        if let Ok(text) = fs::read_to_string(dir.join(MEMORANDA_IGNORE_FILE_NAME)) {
+            rules.add_patterns(dir, &text);
+        }
+        rules
+    }
+
+    /// Adds the patterns of an ignore file found in `base`.
+    pub fn add_patterns(&mut self, base: &Path, text: &str) {
+        let rules: Vec<Rule> = text.lines().filter_map(parse_rule).collect();
+        if !rules.is_empty() {
+            self.sets.push(RuleSet {
+                base: base.to_path_buf(),
+                rules,
+            });
+        }
+    }
+
+    /// Whether directory `dir` is ignored: always for `.git`, never for a
+    /// memo directory, since memos kept out of version control are still
+    /// memos, and otherwise by the last matching pattern.
+    pub fn is_ignored(&self, dir: &Path) -> bool {
+        let Some(name) = dir.file_name().and_then(|s| s.to_str()) else {
+            return false;
+        };
+        if self.memo_dir_name.as_deref() == Some(name) {
+            return false;
+        }
+        if name == ".git" {
+            return true;
+        }
+        self.last_match(dir, name, true)
+    }
+
+    /// Whether file `path` is ignored by the last matching pattern.
+    pub fn is_file_ignored(&self, path: &Path) -> bool {
+        match path.file_name().and_then(|s| s.to_str()) {
+            Some(name) => self.last_match(path, name, false),
+            None => false,
+        }
+    }
+
+    /// Whether the last pattern matching `path`, whose final component is
+    /// `name`, ignores it rather than re-including it.
+    fn last_match(&self, path: &Path, name: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for set in &self.sets {
+            let Ok(relative) = path.strip_prefix(&set.base) else {
+                continue;
+            };
+            let relative = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            for rule in set.rules.iter().filter(|rule| is_dir || !rule.dir_only) {
+                let subject = if rule.anchored {
+                    relative.as_str()
+                } else {
+                    name
+                };
+                if rule.regex.is_match(subject) {
+                    ignored = !rule.negated;
+                }
+            }
+        }
+        ignored
+    }
+}
+
+/// Parses one line of a `.gitignore` file; blank lines and comments give `None`.
+fn parse_rule(line: &str) -> Option<Rule> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+    let anchored = pattern.contains('/');
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let regex = Regex::new(&glob_to_regex(pattern)).ok()?;
+    Some(Rule {
+        regex,
+        negated,
+        anchored,
+        dir_only,
+    })
+}
+
+/// Translates a gitignore glob into an anchored regex over `/`-separated paths.
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::from("^");
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_start = i == 0 || chars[i - 1] == '/';
+                if at_start && chars.get(i + 2) == Some(&'/') {
+                    // `**/` matches zero or more leading directories
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                    continue;
+                }
+                regex.push_str(".*");
+                i += 2;
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => match chars[i + 1..].iter().position(|&c| c == ']') {
+                Some(end) => {
+                    let class: String = chars[i + 1..i + 1 + end].iter().collect();
+                    let class = match class.strip_prefix('!') {
+                        Some(rest) => format!("^{rest}"),
+                        None => class,
+                    };
+                    regex.push('[');
+                    regex.push_str(&class.replace('\\', "\\\\"));
+                    regex.push(']');
+                    i += end + 2;
+                    continue;
+                }
+                None => regex.push_str("\\["),
+            },
+            '\\' if i + 1 < chars.len() => {
+                regex.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 2;
+                continue;
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_rules_match_gitignore_patterns() {
+        let root = Path::new("/repo");
+        let mut rules = IgnoreRules::new(".memoranda");
+        rules.add_patterns(
+            root,
+            "# build output\ntarget/\nnode_modules\n/vendor\ndocs/**/generated\n*.tmp\n!keep.tmp\n",
+        );
+        rules.add_patterns(&root.join("app"), "cache\n!target\n");
+
+        assert!(rules.is_ignored(&root.join("target")));
+        assert!(rules.is_ignored(&root.join("web/node_modules")));
+        assert!(rules.is_ignored(&root.join("vendor")));
+        assert!(!rules.is_ignored(&root.join("lib/vendor")));
+        assert!(rules.is_ignored(&root.join("docs/generated")));
+        assert!(rules.is_ignored(&root.join("docs/api/v2/generated")));
+        assert!(rules.is_ignored(&root.join("scratch.tmp")));
+        assert!(!rules.is_ignored(&root.join("keep.tmp")));
+        assert!(!rules.is_ignored(&root.join("src")));
+        // Nested .gitignore files apply below their directory and override
+        assert!(rules.is_ignored(&root.join("app/cache")));
+        assert!(!rules.is_ignored(&root.join("cache")));
+        assert!(!rules.is_ignored(&root.join("app/target")));
+
+        assert!(rules.is_ignored(&root.join(".git")));
+        rules.add_patterns(root, ".memoranda/\n");
+        assert!(!rules.is_ignored(&root.join(".memoranda")));
+    }
+
+    #[test]
+    fn test_ignore_rules_match_files() {
+        let dir = Path::new("/repo/.memoranda");
+        let mut rules = IgnoreRules::default();
+        rules.add_patterns(dir, "drafts-*.md\n/report.md\nscratch/\n!drafts-keep.md\n");
+
+        assert!(rules.is_file_ignored(&dir.join("drafts-monday.md")));
+        assert!(!rules.is_file_ignored(&dir.join("drafts-keep.md")));
+        assert!(rules.is_file_ignored(&dir.join("report.md")));
+        assert!(!rules.is_file_ignored(&dir.join("Notes.md")));
+        // Directory-only patterns never match files
+        assert!(!rules.is_file_ignored(&dir.join("scratch")));
+        assert!(rules.is_ignored(&dir.join("scratch")));
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::models::MemoId;
+
+/// Directory (inside a `.memoranda` directory) that earlier versions of memos are kept in.
+pub const HISTORY_DIR_NAME: &str = ".history";
+
+/// Version files are named `<timestamp>.md` using this format.
+const VERSION_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.6fZ";
+
+/// An earlier version of a memo, saved before an update overwrote it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemoVersion {
+    /// Identifies the version when reading it back; also the file stem.
+    pub timestamp: String,
+    pub saved_at: DateTime<Utc>,
+    pub path: PathBuf,
+}
+
+/// Number and total size of the saved versions in a store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct HistoryUsage {
+    pub versions: usize,
+    pub bytes: u64,
+}
+
+/// Directory holding the saved versions of memo `id` from `memoranda_dir`.
+pub fn history_dir(memoranda_dir: &Path, id: &MemoId) -> PathBuf {
+    memoranda_dir.join(HISTORY_DIR_NAME).join(id.to_string())
+}
+
+/// Copies `file_path` into the history of memo `id`, then deletes the oldest
+/// versions beyond `max_versions`.
+pub fn save_version(
+    memoranda_dir: &Path,
+    id: &MemoId,
+    file_path: &Path,
+    now: DateTime<Utc>,
+    max_versions: usize,
+) -> std::io::Result<MemoVersion> {
+    let dir = history_dir(memoranda_dir, id);
+    fs::create_dir_all(&dir)?;
+    let timestamp = now.format(VERSION_TIMESTAMP_FORMAT).to_string();
+    let path = dir.join(format!("{timestamp}.md"));
+    fs::copy(file_path, &path)?;
+
+    for stale in list_versions(memoranda_dir, id)?
+        .into_iter()
+        .skip(max_versions)
+    {
+        fs::remove_file(&stale.path)?;
+    }
+
+    Ok(MemoVersion {
+        timestamp,
+        saved_at: now,
+        path,
+    })
+}
+
+/// Lists the saved versions of memo `id`, newest first. Files whose names are
+/// not version timestamps are ignored.
+pub fn list_versions(memoranda_dir: &Path, id: &MemoId) -> std::io::Result<Vec<MemoVersion>> {
+    let dir = history_dir(memoranda_dir, id);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(timestamp) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if let Ok(saved_at) = NaiveDateTime::parse_from_str(timestamp, VERSION_TIMESTAMP_FORMAT) {
+            versions.push(MemoVersion {
+                timestamp: timestamp.to_string(),
+                saved_at: saved_at.and_utc(),
+                path,
+            });
+        }
+    }
+    versions.sort_by_key(|version| std::cmp::Reverse(version.saved_at));
+    Ok(versions)
+}
+
+/// Counts the files under `memoranda_dir`'s `.history` directory and their size.
+pub fn history_usage(memoranda_dir: &Path) -> std::io::Result<HistoryUsage> {
+    let mut usage = HistoryUsage::default();
+    let root = memoranda_dir.join(HISTORY_DIR_NAME);
+    if !root.is_dir() {
+        return Ok(usage);
+    }
+
+    for memo_dir in fs::read_dir(&root)? {
+        let memo_dir = memo_dir?.path();
+        if !memo_dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&memo_dir)? {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                usage.versions += 1;
+                usage.bytes += metadata.len();
+            }
+        }
+    }
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_version_keeps_newest_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let memo_path = temp_dir.path().join("Notes.md");
+        let id = MemoId::new();
+        let start = Utc::now();
+
+        for i in 0..4 {
+            fs::write(&memo_path, format!("revision {i}")).unwrap();
+            save_version(
+                temp_dir.path(),
+                &id,
+                &memo_path,
+                start + Duration::seconds(i),
+                2,
+            )
+            .unwrap();
+        }
+
+        let versions = list_versions(temp_dir.path(), &id).unwrap();
+        let contents: Vec<_> = versions
+            .iter()
+            .map(|version| fs::read_to_string(&version.path).unwrap())
+            .collect();
+        assert_eq!(contents, vec!["revision 3", "revision 2"]);
+        assert_eq!(
+            history_usage(temp_dir.path()).unwrap(),
+            HistoryUsage {
+                versions: 2,
+                bytes: 20
+            }
+        );
+        assert!(list_versions(temp_dir.path(), &MemoId::new())
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::frontmatter::{split_frontmatter, FrontmatterFormat};
+use super::models::{Memo, MemoId};
+use super::obsidian::read_obsidian_frontmatter;
+use super::storage::extract_title_from_filename;
+
+/// A markdown file turned into a memo by an import.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportedMemo {
+    pub path: PathBuf,
+    pub id: MemoId,
+    pub title: String,
+}
+
+/// A markdown file an import left out, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportIssue {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Outcome of importing a directory of markdown files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    pub imported: Vec<ImportedMemo>,
+    /// Files that were deliberately not imported, such as empty files or memos
+    /// already in the store.
+    pub skipped: Vec<ImportIssue>,
+    /// Files that could not be read or did not make a valid memo.
+    pub failed: Vec<ImportIssue>,
+}
+
+/// Builds the memo to import from the text of a markdown file.
+///
+/// Memoranda frontmatter is kept whole, so the memo keeps its id, timestamps,
+/// tags and aliases. Other YAML frontmatter contributes its `id`, `title`,
+/// `created`, `updated` and `tags` keys, read as in Obsidian mode, and the
+/// rest of the file becomes the content. Without a title from frontmatter,
+/// the first `# heading` is used, then the file name.
+pub fn memo_from_markdown(text: &str, file_path: &Path) -> Result<Memo> {
+    if let Some(frontmatter) = split_frontmatter(text) {
+        if let Some(body) = frontmatter.body {
+            if let Ok(memo) = frontmatter.format.parse_memo(frontmatter.block) {
+                return Ok(memo);
+            }
+            if frontmatter.format == FrontmatterFormat::Yaml {
+                let fields = read_obsidian_frontmatter(frontmatter.block);
+                let title = fields
+                    .title
+                    .or_else(|| first_heading(body))
+                    .unwrap_or_else(|| extract_title_from_filename(file_path));
+                let mut memo = Memo::new(title, body.to_string())?;
+                if let Some(id) = fields.id {
+                    memo.id = id;
+                }
+                if let Some(created) = fields.created {
+                    memo.created_at = created;
+                }
+                memo.updated_at = fields.updated.unwrap_or(memo.created_at);
+                for tag in fields.tags {
+                    memo.add_tag(tag);
+                }
+                return Ok(memo);
+            }
+        }
+    }
+
+    let title = first_heading(text).unwrap_or_else(|| extract_title_from_filename(file_path));
+    Memo::new(title, text.to_string())
+}
+
+/// Text of the first level-one `# heading`, if any.
+fn first_heading(text: &str) -> Option<String> {
+    text.lines()
+        .filter_map(|line| line.strip_prefix("# "))
+        .map(str::trim)
+        .find(|heading| !heading.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_from_markdown_titles() {
+        let path = Path::new("/notes/meeting_notes.md");
+
+        let memo = memo_from_markdown("intro\n# Weekly Sync\nbody", path).unwrap();
+        assert_eq!(memo.title, "Weekly Sync");
+        assert_eq!(memo.content, "intro\n# Weekly Sync\nbody");
+
+        let memo = memo_from_markdown("## Only a subheading", path).unwrap();
+        assert_eq!(memo.title, "meeting notes");
+
+        let memo = memo_from_markdown(
+            "---\ntitle: From Frontmatter\ntags: [work, planning]\ncreated: 2024-03-10\naliases: [sync]\n---\n# Heading\nbody\n",
+            path,
+        )
+        .unwrap();
+        assert_eq!(memo.title, "From Frontmatter");
+        assert_eq!(memo.tags, vec!["work", "planning"]);
+        assert_eq!(memo.content, "# Heading\nbody\n");
+        assert_eq!(memo.created_at.date_naive().to_string(), "2024-03-10");
+    }
+
+    #[test]
+    fn test_memo_from_markdown_keeps_memoranda_frontmatter() {
+        let mut original = Memo::new("Exported".to_string(), "body".to_string()).unwrap();
+        original.add_tag("kept".to_string());
+        let text = format!(
+            "---\n{}\n---\nbody",
+            serde_json::to_string_pretty(&original).unwrap()
+        );
+
+        let memo = memo_from_markdown(&text, Path::new("renamed.md")).unwrap();
+        assert_eq!(memo.id, original.id);
+        assert_eq!(memo.title, "Exported");
+        assert_eq!(memo.tags, vec!["kept"]);
+        assert_eq!(memo.created_at, original.created_at);
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use super::frontmatter::FrontmatterFormat;
+use super::models::MemoId;
+
+/// A discovered `.memoranda` directory and the memo files directly inside it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayoutDirectory {
+    pub path: PathBuf,
+    pub files: Vec<LayoutEntry>,
+}
+
+/// On-disk facts about a single memo file, gathered without reading its body.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayoutEntry {
+    pub filename: String,
+    pub size: u64,
+    pub mtime: DateTime<Utc>,
+    pub has_valid_frontmatter: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<MemoId>,
+}
+
+impl LayoutEntry {
+    /// Stats a memo file and parses its frontmatter block, if any.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let metadata = path.metadata()?;
+        let frontmatter = read_frontmatter_block(path)?
+            .and_then(|(format, block)| format.parse_memo(&block).ok());
+
+        Ok(Self {
+            filename: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            size: metadata.len(),
+            mtime: metadata.modified()?.into(),
+            has_valid_frontmatter: frontmatter.is_some(),
+            id: frontmatter.map(|memo| memo.id),
+        })
+    }
+}
+
+/// Reads the text between the opening and closing `---` (or `+++`) lines of a
+/// memo file, stopping at the closing line so the body is never read.
+///
+/// Returns `None` if the file does not start with a fence or the block is never closed.
+fn read_frontmatter_block(path: &Path) -> io::Result<Option<(FrontmatterFormat, String)>> {
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    let Some(fence) = lines
+        .next()
+        .transpose()?
+        .filter(|line| line == "---" || line == "+++")
+    else {
+        return Ok(None);
+    };
+
+    let mut block = String::new();
+    for line in lines {
+        let line = line?;
+        if line == fence {
+            return Ok(FrontmatterFormat::detect(&fence, &block).map(|format| (format, block)));
+        }
+        block.push_str(&line);
+        block.push('\n');
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memo::Memo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_layout_entry_frontmatter_validity() {
+        let temp_dir = TempDir::new().unwrap();
+        let memo = Memo::new("Valid".to_string(), "body".to_string()).unwrap();
+        let valid = temp_dir.path().join("valid.md");
+        std::fs::write(
+            &valid,
+            format!("---\n{}\n---\nbody", serde_json::to_string(&memo).unwrap()),
+        )
+        .unwrap();
+        let invalid = temp_dir.path().join("invalid.md");
+        std::fs::write(&invalid, "---\n{ not json\n---\nbody").unwrap();
+        let plain = temp_dir.path().join("plain.md");
+        std::fs::write(&plain, "# Just markdown").unwrap();
+
+        let entry = LayoutEntry::from_file(&valid).unwrap();
+        assert!(entry.has_valid_frontmatter);
+        assert_eq!(entry.id, Some(memo.id));
+        assert_eq!(entry.filename, "valid.md");
+
+        for path in [&invalid, &plain] {
+            let entry = LayoutEntry::from_file(path).unwrap();
+            assert!(!entry.has_valid_frontmatter);
+            assert_eq!(entry.id, None);
+            assert_eq!(entry.size, std::fs::metadata(path).unwrap().len());
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Line endings written to memo bodies.
+///
+/// Frontmatter is always written with LF so it parses the same on every platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Convert CRLF to LF.
+    #[default]
+    Lf,
+    /// Convert LF to CRLF.
+    Crlf,
+    /// Keep whatever line endings the content has.
+    Preserve,
+}
+
+impl LineEnding {
+    /// Normalizes the line endings of `text`, borrowing it when nothing changes.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            LineEnding::Lf if text.contains("\r\n") => Cow::Owned(text.replace("\r\n", "\n")),
+            LineEnding::Crlf if text.contains('\n') => {
+                Cow::Owned(text.replace("\r\n", "\n").replace('\n', "\r\n"))
+            }
+            _ => Cow::Borrowed(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_ending_normalization() {
+        let mixed = "one\r\ntwo\nthree\r\n";
+        assert_eq!(LineEnding::Lf.apply(mixed), "one\ntwo\nthree\n");
+        assert_eq!(LineEnding::Crlf.apply(mixed), "one\r\ntwo\r\nthree\r\n");
+        assert_eq!(LineEnding::Preserve.apply(mixed), mixed);
+        assert!(matches!(LineEnding::Lf.apply("plain\n"), Cow::Borrowed(_)));
+    }
+}
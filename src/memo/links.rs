@@ -0,0 +1,348 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::models::{names_match, Memo, MemoId};
+
+/// A memo ranked by how many other memos link to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HubMemo {
+    pub id: MemoId,
+    pub title: String,
+    pub backlinks: usize,
+}
+
+/// The `[[wiki-link]]` graph between memos, for drawing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Set when nodes were left out to stay within the limit.
+    pub truncated: bool,
+}
+
+/// A memo in the link graph, or a phantom standing for a link target no
+/// memo answers to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// The memo's id, or `phantom:` and the link target for a phantom.
+    pub id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub phantom: bool,
+}
+
+/// A link from one node to another, by node id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Returns the memo name a wiki-link refers to, with or without the surrounding
+/// brackets. `[[Target|label]]` and `[[Target#Heading]]` both name `Target`.
+pub fn wiki_link_target(link: &str) -> &str {
+    let inner = link.trim();
+    let inner = inner
+        .strip_prefix("[[")
+        .and_then(|rest| rest.strip_suffix("]]"))
+        .unwrap_or(inner);
+    let end = inner.find(['|', '#']).unwrap_or(inner.len());
+    inner[..end].trim()
+}
+
+/// Extracts the targets of all `[[wiki-links]]` in memo content, in order of
+/// appearance. Empty links such as `[[]]` are skipped.
+pub fn extract_wiki_links(content: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            break;
+        };
+        let inner = &after[..end];
+        // A nested "[[" means the first opener was never closed
+        if let Some(nested) = inner.rfind("[[") {
+            rest = &after[nested..];
+            continue;
+        }
+        let target = wiki_link_target(inner);
+        if !target.is_empty() {
+            links.push(target.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+
+    links
+}
+
+/// Resolves every `[[wiki-link]]` between `memos` to `(source, target)` id
+/// pairs. Targets resolve like title lookups: titles first, then aliases.
+/// Links to unknown memos and links from a memo to itself are skipped.
+pub fn link_edges(memos: &[Memo]) -> Vec<(MemoId, MemoId)> {
+    let mut edges = Vec::new();
+    for memo in memos {
+        for target in extract_wiki_links(&memo.content) {
+            if let Some(target) = resolve_link(memos, &target).filter(|target| *target != memo.id) {
+                edges.push((memo.id, target));
+            }
+        }
+    }
+    edges
+}
+
+/// The id of the memo a link target names, by title first and then by alias.
+fn resolve_link(memos: &[Memo], target: &str) -> Option<MemoId> {
+    memos
+        .iter()
+        .find(|memo| names_match(&memo.title, target))
+        .or_else(|| memos.iter().find(|memo| memo.answers_to(target)))
+        .map(|memo| memo.id)
+}
+
+/// Builds the link graph of `memos`, with a phantom node for each link
+/// target no memo answers to when `include_unresolved` is set.
+///
+/// Memo nodes come most linked first, ties broken by title, followed by
+/// phantoms. A `limit` above 0 keeps only that many nodes, and the edges
+/// between them. Several links between the same two nodes make one edge.
+pub fn link_graph(memos: &[Memo], include_unresolved: bool, limit: usize) -> LinkGraph {
+    let counts = backlink_counts(memos);
+    let mut ranked: Vec<&Memo> = memos.iter().collect();
+    ranked.sort_by(|a, b| {
+        let backlinks = |memo: &Memo| counts.get(&memo.id).copied().unwrap_or(0);
+        backlinks(b)
+            .cmp(&backlinks(a))
+            .then_with(|| a.title.cmp(&b.title))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let mut nodes: Vec<GraphNode> = ranked
+        .iter()
+        .map(|memo| GraphNode {
+            id: memo.id.to_string(),
+            title: memo.title.clone(),
+            tags: memo.tags.clone(),
+            phantom: false,
+        })
+        .collect();
+    let mut edges = Vec::new();
+    let mut phantoms: Vec<String> = Vec::new();
+    for memo in &ranked {
+        for target in extract_wiki_links(&memo.content) {
+            let to = match resolve_link(memos, &target) {
+                Some(id) if id == memo.id => continue,
+                Some(id) => id.to_string(),
+                None if include_unresolved => {
+                    let known = phantoms.iter().find(|name| names_match(name, &target));
+                    let name = match known {
+                        Some(name) => name.clone(),
+                        None => {
+                            phantoms.push(target.clone());
+                            target
+                        }
+                    };
+                    format!("phantom:{name}")
+                }
+                None => continue,
+            };
+            let edge = GraphEdge {
+                from: memo.id.to_string(),
+                to,
+            };
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+    }
+    nodes.extend(phantoms.into_iter().map(|name| GraphNode {
+        id: format!("phantom:{name}"),
+        title: name,
+        tags: Vec::new(),
+        phantom: true,
+    }));
+
+    let truncated = limit > 0 && nodes.len() > limit;
+    if truncated {
+        nodes.truncate(limit);
+        let kept: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+        edges.retain(|edge| kept.contains(edge.from.as_str()) && kept.contains(edge.to.as_str()));
+    }
+    LinkGraph {
+        nodes,
+        edges,
+        truncated,
+    }
+}
+
+/// Counts, for each memo linked to at least once, how many other memos link to it.
+/// Several links from the same memo count once.
+pub fn backlink_counts(memos: &[Memo]) -> HashMap<MemoId, usize> {
+    let mut edges = link_edges(memos);
+    edges.sort();
+    edges.dedup();
+
+    let mut counts = HashMap::new();
+    for (_, target) in edges {
+        *counts.entry(target).or_default() += 1;
+    }
+    counts
+}
+
+/// Finds the shortest chain of `[[wiki-links]]` connecting two memos.
+///
+/// Links are followed in both directions, so a memo is adjacent to the memos
+/// it links to and to those linking to it. Returns the memo ids from `from` to
+/// `to` inclusive, or `None` if they are not connected within `max_depth` links.
+pub fn shortest_link_path(
+    memos: &[Memo],
+    from: &MemoId,
+    to: &MemoId,
+    max_depth: usize,
+) -> Option<Vec<MemoId>> {
+    if from == to {
+        return Some(vec![*from]);
+    }
+
+    let mut neighbors: HashMap<MemoId, Vec<MemoId>> = HashMap::new();
+    for (source, target) in link_edges(memos) {
+        neighbors.entry(source).or_default().push(target);
+        neighbors.entry(target).or_default().push(source);
+    }
+
+    let mut previous: HashMap<MemoId, MemoId> = HashMap::new();
+    let mut queue = VecDeque::from([(*from, 0)]);
+    while let Some((id, depth)) = queue.pop_front() {
+        if depth == max_depth {
+            continue;
+        }
+        for next in neighbors.get(&id).into_iter().flatten() {
+            if *next == *from || previous.contains_key(next) {
+                continue;
+            }
+            previous.insert(*next, id);
+            if next == to {
+                let mut path = vec![*to];
+                while let Some(step) = previous.get(path.last().unwrap()) {
+                    path.push(*step);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            queue.push_back((*next, depth + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memo(title: &str, content: &str) -> Memo {
+        Memo::new(title.to_string(), content.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_wiki_link_target() {
+        assert_eq!(wiki_link_target("[[Error Handling]]"), "Error Handling");
+        assert_eq!(wiki_link_target("Error Handling"), "Error Handling");
+        assert_eq!(wiki_link_target("[[Errors|see here]]"), "Errors");
+        assert_eq!(wiki_link_target("[[ Errors#Retries ]]"), "Errors");
+    }
+
+    #[test]
+    fn test_extract_wiki_links() {
+        let content =
+            "See [[Error Handling]] and [[Logging|logs]].\n[[]] [[ [[Retries]] and [[unclosed";
+        assert_eq!(
+            extract_wiki_links(content),
+            vec!["Error Handling", "Logging", "Retries"]
+        );
+    }
+
+    #[test]
+    fn test_shortest_link_path() {
+        let a = memo("A", "Start at [[B]] and also [[Missing]].");
+        let b = memo("B", "Continue to [[c|the end]].");
+        let c = memo("C", "The end.");
+        let loner = memo("Loner", "No links here.");
+        let memos = vec![a.clone(), b.clone(), c.clone(), loner.clone()];
+
+        assert_eq!(
+            shortest_link_path(&memos, &a.id, &c.id, 10),
+            Some(vec![a.id, b.id, c.id])
+        );
+        // Links are followed backwards too
+        assert_eq!(
+            shortest_link_path(&memos, &c.id, &a.id, 10),
+            Some(vec![c.id, b.id, a.id])
+        );
+        assert_eq!(shortest_link_path(&memos, &a.id, &loner.id, 10), None);
+        assert_eq!(shortest_link_path(&memos, &a.id, &c.id, 1), None);
+        assert_eq!(
+            shortest_link_path(&memos, &a.id, &a.id, 0),
+            Some(vec![a.id])
+        );
+    }
+
+    #[test]
+    fn test_link_graph() {
+        let mut a = memo("A", "Links to [[B]], [[c]] and [[missing]].");
+        a.tags = vec!["design".to_string()];
+        let b = memo("B", "Back to [[A]], and to [[C|the end]] twice: [[C]].");
+        let c = memo("C", "Mentions [[Missing]] and itself, [[C]].");
+        let memos = vec![a.clone(), b.clone(), c.clone()];
+        let edge = |from: &Memo, to: &str| GraphEdge {
+            from: from.id.to_string(),
+            to: to.to_string(),
+        };
+
+        let graph = link_graph(&memos, true, 0);
+        let titles: Vec<_> = graph.nodes.iter().map(|node| node.title.as_str()).collect();
+        // C has two backlinks, A and B one each; the phantom comes last
+        assert_eq!(titles, vec!["C", "A", "B", "Missing"]);
+        assert_eq!(graph.nodes[1].tags, vec!["design"]);
+        assert!(graph.nodes[3].phantom);
+        assert_eq!(graph.nodes[3].id, "phantom:Missing");
+        assert_eq!(
+            graph.edges,
+            vec![
+                edge(&c, "phantom:Missing"),
+                edge(&a, &b.id.to_string()),
+                edge(&a, &c.id.to_string()),
+                edge(&a, "phantom:Missing"),
+                edge(&b, &a.id.to_string()),
+                edge(&b, &c.id.to_string()),
+            ]
+        );
+        assert!(!graph.truncated);
+
+        // Without phantoms, unresolved links are dropped
+        let graph = link_graph(&memos, false, 0);
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 4);
+
+        // The limit keeps the most linked nodes and the edges between them
+        let graph = link_graph(&memos, true, 2);
+        assert!(graph.truncated);
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges, vec![edge(&a, &c.id.to_string())]);
+    }
+
+    #[test]
+    fn test_backlink_counts() {
+        let hub = memo("Hub", "Central");
+        let a = memo("A", "See [[Hub]] and [[hub|again]]");
+        let b = memo("B", "See [[Hub]] and [[A]]");
+        let c = memo("C", "See [[Hub]] and [[C]]");
+        let counts = backlink_counts(&[hub.clone(), a.clone(), b.clone(), c.clone()]);
+
+        assert_eq!(counts.get(&hub.id), Some(&3));
+        assert_eq!(counts.get(&a.id), Some(&1));
+        assert_eq!(counts.get(&b.id), None);
+        assert_eq!(counts.get(&c.id), None);
+    }
+}
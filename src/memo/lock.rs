@@ -0,0 +1,219 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, warn};
+
+use super::storage::{MemoStoreError, Result};
+
+/// File, inside a memo directory, held while a memo in it is written or deleted.
+pub const STORE_LOCK_FILE_NAME: &str = ".lock";
+
+/// A lock file older than this was left behind by a process that died while
+/// holding it, since no write holds the lock for more than a moment.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Time between attempts to take a lock held by someone else.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Advisory lock on a memo directory, shared by every process using the
+/// store: taken by creating the directory's `.lock` file, which fails while
+/// another holder has it, and released by deleting the file when dropped.
+#[derive(Debug)]
+pub struct StoreLock {
+    path: PathBuf,
+}
+
+impl StoreLock {
+    /// Takes the lock on `dir`, waiting up to `timeout` for another holder to
+    /// release it before failing with [`MemoStoreError::Locked`].
+    pub fn acquire(dir: &Path, timeout: Duration) -> Result<Self> {
+        let started = Instant::now();
+        loop {
+            if let Some(lock) = Self::try_acquire(dir)? {
+                return Ok(lock);
+            }
+            Self::wait(dir, started, timeout)?;
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+
+    /// Takes the lock like [`Self::acquire`], without blocking the runtime
+    /// while it waits.
+    pub async fn acquire_async(dir: &Path, timeout: Duration) -> Result<Self> {
+        let started = Instant::now();
+        loop {
+            if let Some(lock) = Self::try_acquire(dir)? {
+                return Ok(lock);
+            }
+            Self::wait(dir, started, timeout)?;
+            tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+
+    /// Creates the lock file, recording our process id in it, or returns
+    /// `None` while someone else holds it. A stale lock file is removed first.
+    fn try_acquire(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(STORE_LOCK_FILE_NAME);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                // The id only helps whoever finds a stale lock; failing to
+                // write it does not make the lock any less held
+                let _ = writeln!(file, "{}", std::process::id());
+                Ok(Some(Self { path }))
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if is_stale(&path, SystemTime::now()) {
+                    remove_stale_lock(&path, SystemTime::now())?;
+                }
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Fails once `timeout` has passed since `started`.
+    fn wait(dir: &Path, started: Instant, timeout: Duration) -> Result<()> {
+        if started.elapsed() >= timeout {
+            return Err(MemoStoreError::Locked {
+                path: dir.join(STORE_LOCK_FILE_NAME),
+                timeout_ms: timeout.as_millis() as u64,
+            });
+        }
+        debug!("Waiting for memo store lock in {}", dir.display());
+        Ok(())
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!(
+                "Failed to release memo store lock {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Removes the lock file at `path` if it is stale at `now`.
+///
+/// Another waiter may have removed the stale file and a new holder taken the
+/// lock since it was found stale, so the file is first moved to a name of its
+/// own: only one waiter can take it, and that one checks it is still stale
+/// before deleting it. A live lock taken by mistake is put back, unless the
+/// lock has been taken yet again meanwhile.
+fn remove_stale_lock(path: &Path, now: SystemTime) -> Result<()> {
+    static TAKEN: AtomicUsize = AtomicUsize::new(0);
+    let taken = path.with_file_name(format!(
+        "{STORE_LOCK_FILE_NAME}.{}.{}.stale",
+        std::process::id(),
+        TAKEN.fetch_add(1, Ordering::Relaxed)
+    ));
+    match fs::rename(path, &taken) {
+        Ok(()) => {}
+        // Another waiter took it first
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+
+    if is_stale(&taken, now) {
+        warn!("Removing stale memo store lock {}", path.display());
+    } else {
+        match fs::hard_link(&taken, path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => warn!(
+                "Memo store lock {} was replaced while being checked for staleness",
+                path.display()
+            ),
+            Err(e) => {
+                let _ = fs::rename(&taken, path);
+                return Err(e.into());
+            }
+        }
+    }
+    fs::remove_file(&taken)?;
+    Ok(())
+}
+
+/// Whether the lock file at `path` is older than [`STALE_LOCK_AGE`] at `now`.
+fn is_stale(path: &Path, now: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_lock_excludes_other_holders_until_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join(STORE_LOCK_FILE_NAME);
+
+        let lock = StoreLock::acquire(temp_dir.path(), Duration::ZERO).unwrap();
+        assert!(lock_path.exists());
+
+        let err = StoreLock::acquire(temp_dir.path(), Duration::from_millis(50)).unwrap_err();
+        assert!(matches!(
+            err,
+            MemoStoreError::Locked { ref path, timeout_ms: 50 } if *path == lock_path
+        ));
+
+        drop(lock);
+        assert!(!lock_path.exists());
+        let _lock = StoreLock::acquire(temp_dir.path(), Duration::ZERO).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_store_lock_waits_for_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+
+        let lock = StoreLock::acquire(&dir, Duration::ZERO).unwrap();
+        let waiter = tokio::spawn({
+            let dir = dir.clone();
+            async move { StoreLock::acquire_async(&dir, Duration::from_secs(10)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(lock);
+        drop(waiter.await.unwrap().unwrap());
+    }
+
+    #[test]
+    fn test_lock_files_go_stale_once_their_holder_is_gone() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join(STORE_LOCK_FILE_NAME);
+        assert!(!is_stale(&lock_path, SystemTime::now()));
+
+        fs::write(&lock_path, "4242\n").unwrap();
+        assert!(!is_stale(&lock_path, SystemTime::now()));
+        assert!(is_stale(&lock_path, SystemTime::now() + STALE_LOCK_AGE * 2));
+    }
+
+    #[test]
+    fn test_removing_a_stale_lock_spares_one_taken_since() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join(STORE_LOCK_FILE_NAME);
+        let files = || fs::read_dir(temp_dir.path()).unwrap().count();
+
+        // A waiter that found the lock stale runs late, after another waiter
+        // removed it and a new holder took the lock
+        let _lock = StoreLock::acquire(temp_dir.path(), Duration::ZERO).unwrap();
+        remove_stale_lock(&lock_path, SystemTime::now()).unwrap();
+        assert!(lock_path.exists());
+        assert_eq!(files(), 1);
+        assert!(StoreLock::acquire(temp_dir.path(), Duration::ZERO).is_err());
+
+        remove_stale_lock(&lock_path, SystemTime::now() + STALE_LOCK_AGE * 2).unwrap();
+        assert_eq!(files(), 0);
+        // Nothing left to remove
+        remove_stale_lock(&lock_path, SystemTime::now()).unwrap();
+    }
+}
@@ -1,9 +1,57 @@
+pub mod activity;
+pub mod audit;
 pub mod cache;
+pub mod compact;
+pub mod consistency;
+pub mod context;
+pub mod disk_cache;
+pub mod export;
+pub mod frontmatter;
+pub mod gitignore;
+pub mod history;
+pub mod import;
+pub mod layout;
+pub mod line_ending;
+pub mod links;
+pub mod lock;
 pub mod models;
+pub mod obsidian;
+pub mod redaction;
+pub mod retention;
 pub mod search;
+pub mod search_cache;
+pub mod search_index;
+pub mod staging;
+pub mod stemming;
 pub mod storage;
+pub mod tag_meta;
+pub mod trash;
 
+pub use activity::*;
+pub use audit::*;
 pub use cache::*;
+pub use compact::*;
+pub use consistency::*;
+pub use context::*;
+pub use disk_cache::*;
+pub use export::*;
+pub use frontmatter::*;
+pub use gitignore::*;
+pub use history::*;
+pub use import::*;
+pub use layout::*;
+pub use line_ending::*;
+pub use links::*;
+pub use lock::*;
 pub use models::*;
+pub use obsidian::*;
+pub use redaction::*;
+pub use retention::*;
 pub use search::*;
+pub use search_cache::*;
+pub use search_index::*;
+pub use staging::*;
+pub use stemming::*;
 pub use storage::*;
+pub use tag_meta::*;
+pub use trash::*;
@@ -1,15 +1,27 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::path::PathBuf;
 use ulid::Ulid;
 
 // Validation constants
 const MAX_TITLE_LENGTH: usize = 255;
-const MAX_CONTENT_LENGTH: usize = 1024 * 1024; // 1MB
+pub(crate) const MAX_CONTENT_LENGTH: usize = 1024 * 1024; // 1MB
 const MIN_TITLE_LENGTH: usize = 1;
+/// Longest tag, in characters, unless `max_tag_length` is set.
+pub const MAX_TAG_LENGTH: usize = 64;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Object fields checked, in order, when an id arrives wrapped in an object.
+const WRAPPED_ID_FIELDS: &[&str] = &["ulid", "id", "0"];
+/// Longest excerpt of an unparseable id value quoted back in error messages.
+const MAX_ID_ERROR_EXCERPT: usize = 80;
+
+/// A memo identifier, serialized as a plain ULID string.
+///
+/// Deserialization (and [`MemoId::from_json`], used for tool arguments) also
+/// accepts the id wrapped in an object such as `{"ulid": "..."}`, which some
+/// clients produce when round-tripping memos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct MemoId(Ulid);
 
 impl MemoId {
@@ -21,6 +33,61 @@ impl MemoId {
     pub fn from_ulid(ulid: Ulid) -> Self {
         Self(ulid)
     }
+
+    /// Parses an id from a JSON value: a ULID string, or an object holding one
+    /// in a `ulid`, `id`, or `0` field (possibly nested).
+    ///
+    /// # Errors
+    ///
+    /// Returns a message quoting the received value if no ULID can be found.
+    pub fn from_json(value: &serde_json::Value) -> std::result::Result<Self, String> {
+        Self::find_in_json(value).ok_or_else(|| {
+            let mut received = value.to_string();
+            if received.len() > MAX_ID_ERROR_EXCERPT {
+                let mut end = MAX_ID_ERROR_EXCERPT;
+                while !received.is_char_boundary(end) {
+                    end -= 1;
+                }
+                received.truncate(end);
+                received.push_str("...");
+            }
+            format!(
+                "Invalid memo ID format: expected a ULID string or an object with a `ulid` field, got {received}"
+            )
+        })
+    }
+
+    fn find_in_json(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::String(id) => id.trim().parse::<Ulid>().ok().map(Self),
+            serde_json::Value::Object(fields) => WRAPPED_ID_FIELDS
+                .iter()
+                .filter_map(|name| fields.get(*name))
+                .find_map(Self::find_in_json),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for MemoId {
+    type Err = String;
+
+    fn from_str(id: &str) -> std::result::Result<Self, Self::Err> {
+        Self::from_json(&serde_json::Value::String(id.to_string()))
+    }
+}
+
+impl Serialize for MemoId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for MemoId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Self::from_json(&value).map_err(serde::de::Error::custom)
+    }
 }
 
 impl Default for MemoId {
@@ -35,6 +102,13 @@ impl std::fmt::Display for MemoId {
     }
 }
 
+/// A single line of memo content with its 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumberedLine {
+    pub line: usize,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Memo {
     pub id: MemoId,
@@ -44,6 +118,17 @@ pub struct Memo {
     pub updated_at: DateTime<Utc>,
     pub tags: Vec<String>,
     pub file_path: Option<PathBuf>,
+    /// Opts the memo out of filename normalization, keeping its file name even
+    /// when it no longer matches the title.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keep_filename: bool,
+    /// Alternate titles the memo can be found by in title lookups and wiki-links.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Set when the memo file was not valid UTF-8 and was loaded with invalid
+    /// bytes replaced (see `Settings::lenient_encoding`). Never written to disk.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub encoding_warning: bool,
 }
 
 impl Memo {
@@ -68,6 +153,9 @@ impl Memo {
             updated_at: now,
             tags: Vec::new(),
             file_path: None,
+            keep_filename: false,
+            aliases: Vec::new(),
+            encoding_warning: false,
         })
     }
 
@@ -96,6 +184,9 @@ impl Memo {
             updated_at: now,
             tags: Vec::new(),
             file_path,
+            keep_filename: false,
+            aliases: Vec::new(),
+            encoding_warning: false,
         })
     }
 
@@ -105,6 +196,74 @@ impl Memo {
         }
     }
 
+    /// Checks that `tag` is usable as a memo tag: non-empty, at most
+    /// 64 characters, and free of whitespace, commas and control characters.
+    pub fn validate_tag(tag: &str) -> Result<()> {
+        Self::validate_tag_within(tag, MAX_TAG_LENGTH)
+    }
+
+    /// Like [`Self::validate_tag`], allowing tags of up to `max_length` characters.
+    pub fn validate_tag_within(tag: &str, max_length: usize) -> Result<()> {
+        if tag.is_empty() {
+            return Err(anyhow!("Tag cannot be empty"));
+        }
+        if tag.chars().count() > max_length {
+            return Err(anyhow!(
+                "Tag cannot exceed {} characters: {}",
+                max_length,
+                tag
+            ));
+        }
+        if tag
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || c == ',')
+        {
+            return Err(anyhow!(
+                "Tag cannot contain whitespace or commas: {:?}",
+                tag
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the memo carries `tag`, compared case-insensitively after trimming.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| names_match(own, tag))
+    }
+
+    /// Adds an alternate title, returning false if the memo already answers to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the alias is not a valid title.
+    pub fn add_alias(&mut self, alias: String) -> Result<bool> {
+        Self::validate_title(&alias)?;
+        if self.answers_to(&alias) {
+            return Ok(false);
+        }
+        self.aliases.push(alias.trim().to_string());
+        self.updated_at = Utc::now();
+        Ok(true)
+    }
+
+    /// Removes an alias (case-insensitively), returning false if it was not present.
+    pub fn remove_alias(&mut self, alias: &str) -> bool {
+        let before = self.aliases.len();
+        self.aliases
+            .retain(|existing| !names_match(existing, alias));
+        let removed = self.aliases.len() != before;
+        if removed {
+            self.updated_at = Utc::now();
+        }
+        removed
+    }
+
+    /// Returns true if `name` matches the title or any alias, ignoring case and
+    /// surrounding whitespace.
+    pub fn answers_to(&self, name: &str) -> bool {
+        names_match(&self.title, name) || self.aliases.iter().any(|alias| names_match(alias, name))
+    }
+
     /// Updates the memo's content and sets the updated timestamp.
     ///
     /// # Errors
@@ -114,10 +273,34 @@ impl Memo {
         Self::validate_content(&content)?;
         self.content = content;
         self.updated_at = Utc::now();
+        // Replacement content comes in as valid UTF-8, so the lossy load no longer applies
+        self.encoding_warning = false;
+        Ok(())
+    }
+
+    pub fn update_title(&mut self, title: String) -> Result<()> {
+        Self::validate_title(&title)?;
+        self.title = title;
+        self.updated_at = Utc::now();
         Ok(())
     }
 
-    fn validate_title(title: &str) -> Result<()> {
+    /// Splits the content into 1-based numbered lines.
+    ///
+    /// Blank lines are kept, a single trailing newline does not produce an extra
+    /// empty line, and `\r\n` line endings are treated like `\n`.
+    pub fn numbered_lines(&self) -> Vec<NumberedLine> {
+        self.content
+            .lines()
+            .enumerate()
+            .map(|(index, text)| NumberedLine {
+                line: index + 1,
+                text: text.to_string(),
+            })
+            .collect()
+    }
+
+    pub(crate) fn validate_title(title: &str) -> Result<()> {
         if title.is_empty() {
             return Err(anyhow!("Title cannot be empty"));
         }
@@ -150,6 +333,11 @@ impl Memo {
     }
 }
 
+/// Compares memo titles or aliases, ignoring case and surrounding whitespace.
+pub fn names_match(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,6 +356,31 @@ mod tests {
         assert!(!display_str.is_empty());
     }
 
+    #[test]
+    fn test_memo_id_serialization_shapes() {
+        use serde_json::json;
+
+        let id = MemoId::new();
+        let id_str = id.to_string();
+        assert_eq!(serde_json::to_value(id).unwrap(), json!(id_str));
+
+        for shape in [
+            json!(id_str),
+            json!({ "ulid": id_str }),
+            json!({ "id": { "ulid": id_str } }),
+        ] {
+            assert_eq!(MemoId::from_json(&shape), Ok(id));
+            assert_eq!(serde_json::from_value::<MemoId>(shape).unwrap(), id);
+        }
+        assert_eq!(id_str.parse::<MemoId>(), Ok(id));
+
+        let error = MemoId::from_json(&json!({ "uuid": 42 })).unwrap_err();
+        assert!(error.starts_with("Invalid memo ID format"));
+        assert!(error.ends_with(r#"got {"uuid":42}"#), "{error}");
+        let error = MemoId::from_json(&json!("x".repeat(200))).unwrap_err();
+        assert!(error.ends_with("..."));
+    }
+
     #[test]
     fn test_memo_creation() {
         let memo = Memo::new("Test Title".to_string(), "Test content".to_string()).unwrap();
@@ -177,6 +390,16 @@ mod tests {
         assert!(memo.tags.is_empty());
     }
 
+    #[test]
+    fn test_validate_tag() {
+        assert!(Memo::validate_tag("rust").is_ok());
+        assert!(Memo::validate_tag("project/memoranda").is_ok());
+        assert!(Memo::validate_tag("").is_err());
+        assert!(Memo::validate_tag("two words").is_err());
+        assert!(Memo::validate_tag("a,b").is_err());
+        assert!(Memo::validate_tag(&"x".repeat(65)).is_err());
+    }
+
     #[test]
     fn test_memo_add_tag() {
         let mut memo = Memo::new("Test".to_string(), "Content".to_string()).unwrap();
@@ -189,6 +412,24 @@ mod tests {
         assert!(memo.tags.contains(&"tag2".to_string()));
     }
 
+    #[test]
+    fn test_memo_aliases() {
+        let mut memo = Memo::new("Error Handling".to_string(), "Content".to_string()).unwrap();
+        assert!(memo.add_alias(" Errors ".to_string()).unwrap());
+        assert!(!memo.add_alias("errors".to_string()).unwrap());
+        assert!(!memo.add_alias("error handling".to_string()).unwrap());
+        assert!(memo.add_alias("   ".to_string()).is_err());
+        assert_eq!(memo.aliases, vec!["Errors".to_string()]);
+
+        assert!(memo.answers_to("ERRORS"));
+        assert!(memo.answers_to("error handling"));
+        assert!(!memo.answers_to("logging"));
+
+        assert!(memo.remove_alias("errors"));
+        assert!(!memo.remove_alias("errors"));
+        assert!(memo.aliases.is_empty());
+    }
+
     #[test]
     fn test_memo_update_content() {
         let mut memo = Memo::new("Test".to_string(), "Original content".to_string()).unwrap();
@@ -233,4 +474,36 @@ mod tests {
         let result = Memo::new("Valid Title".to_string(), valid_content);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_numbered_lines_blank_and_trailing_newline() {
+        let memo = Memo::new("Title".to_string(), "first\n\nthird\r\n".to_string()).unwrap();
+        let lines = memo.numbered_lines();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            NumberedLine {
+                line: 1,
+                text: "first".to_string()
+            }
+        );
+        assert_eq!(
+            lines[1],
+            NumberedLine {
+                line: 2,
+                text: String::new()
+            }
+        );
+        assert_eq!(
+            lines[2],
+            NumberedLine {
+                line: 3,
+                text: "third".to_string()
+            }
+        );
+
+        let empty = Memo::new("Empty".to_string(), String::new()).unwrap();
+        assert!(empty.numbered_lines().is_empty());
+    }
 }
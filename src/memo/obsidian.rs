@@ -0,0 +1,346 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::Value;
+use std::path::Path;
+use ulid::Ulid;
+
+use super::frontmatter::{split_frontmatter, FrontmatterBlock, FrontmatterFormat};
+use super::models::{Memo, MemoId};
+
+/// Frontmatter keys written in Obsidian mode; every other key is left as found.
+const MANAGED_KEYS: &[&str] = &["id", "title", "created", "updated", "tags"];
+/// Keys of memoranda's full frontmatter that the note body and `created` /
+/// `updated` replace, dropped when a note is rewritten.
+const SUPERSEDED_KEYS: &[&str] = &["content", "created_at", "updated_at"];
+
+/// Memo metadata read from a note's YAML frontmatter in Obsidian mode.
+///
+/// Missing or unreadable values are `None` (or empty) rather than errors, so
+/// any note in a vault loads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObsidianFields {
+    pub id: Option<MemoId>,
+    pub title: Option<String>,
+    pub created: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+}
+
+/// A top-level frontmatter entry: its key line plus any indented, sequence,
+/// comment, or blank lines that follow it.
+struct Entry<'a> {
+    /// `None` for comments and blank lines before the first key.
+    key: Option<&'a str>,
+    lines: Vec<&'a str>,
+}
+
+fn entries(block: &str) -> Vec<Entry<'_>> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for line in block.lines() {
+        let continues = line.trim().is_empty() || line.starts_with([' ', '\t', '-', '#']);
+        match entries.last_mut() {
+            Some(entry) if continues => entry.lines.push(line),
+            _ if continues => entries.push(Entry {
+                key: None,
+                lines: vec![line],
+            }),
+            _ => entries.push(Entry {
+                key: line
+                    .split_once(':')
+                    .map(|(key, _)| key.trim().trim_matches(['"', '\''])),
+                lines: vec![line],
+            }),
+        }
+    }
+    entries
+}
+
+/// Reads the managed keys of a YAML frontmatter block, ignoring all others.
+///
+/// Only the entries for managed keys are parsed, so nested mappings and other
+/// YAML the frontmatter parser does not cover may appear under other keys.
+pub fn read_obsidian_frontmatter(block: &str) -> ObsidianFields {
+    let mut fields = ObsidianFields::default();
+    for entry in entries(block) {
+        let Some(key) = entry.key else { continue };
+        if !MANAGED_KEYS.contains(&key) && !SUPERSEDED_KEYS.contains(&key) {
+            continue;
+        }
+        let Ok(Value::Object(parsed)) = FrontmatterFormat::Yaml.parse(&entry.lines.join("\n"))
+        else {
+            continue;
+        };
+        let Some(value) = parsed.into_iter().next().map(|(_, value)| value) else {
+            continue;
+        };
+        match key {
+            "id" => fields.id = MemoId::from_json(&value).ok(),
+            "title" => {
+                fields.title = value
+                    .as_str()
+                    .map(str::trim)
+                    .filter(|title| !title.is_empty())
+                    .map(String::from)
+            }
+            "created" => fields.created = parse_timestamp(&value),
+            "updated" => fields.updated = parse_timestamp(&value),
+            // The memoranda keys only fill in for missing Obsidian ones
+            "created_at" => fields.created = fields.created.or(parse_timestamp(&value)),
+            "updated_at" => fields.updated = fields.updated.or(parse_timestamp(&value)),
+            "tags" => fields.tags = parse_tags(&value),
+            _ => {}
+        }
+    }
+    fields
+}
+
+/// Splits a note into its Obsidian fields and body.
+///
+/// Notes without frontmatter have no fields and are all body. Returns `None`
+/// for JSON and TOML frontmatter, which memoranda wrote and loads as usual.
+pub fn split_obsidian_note(content: &str) -> Option<(ObsidianFields, &str)> {
+    match split_frontmatter(content) {
+        Some(FrontmatterBlock {
+            format: FrontmatterFormat::Yaml,
+            block,
+            body: Some(body),
+        }) => Some((read_obsidian_frontmatter(block), body)),
+        Some(FrontmatterBlock {
+            format: FrontmatterFormat::Yaml,
+            body: None,
+            ..
+        })
+        | None => Some((ObsidianFields::default(), content)),
+        Some(_) => None,
+    }
+}
+
+/// Rewrites a YAML frontmatter block with the memo's managed keys.
+///
+/// Entries for other keys keep their exact text and position, as do managed
+/// entries whose value is unchanged. Changed managed entries are replaced in
+/// place and missing ones appended. `title` is only added when the memo's
+/// title differs from `file_title`, the title its file name implies.
+pub fn write_obsidian_frontmatter(block: &str, memo: &Memo, file_title: &str) -> String {
+    let wanted = ObsidianFields {
+        id: Some(memo.id),
+        title: Some(memo.title.clone()),
+        created: Some(memo.created_at),
+        updated: Some(memo.updated_at),
+        tags: memo.tags.clone(),
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut written: Vec<&str> = Vec::new();
+    for entry in entries(block) {
+        let key = match entry.key {
+            Some(key) if SUPERSEDED_KEYS.contains(&key) => continue,
+            Some(key) if MANAGED_KEYS.contains(&key) => key,
+            _ => {
+                lines.extend(entry.lines.iter().map(|line| line.to_string()));
+                continue;
+            }
+        };
+        if written.contains(&key) {
+            continue;
+        }
+        written.push(key);
+
+        let text = entry.lines.join("\n");
+        if field_matches(key, &read_obsidian_frontmatter(&text), &wanted) {
+            lines.push(text);
+        } else {
+            lines.push(managed_entry(key, &wanted));
+            // Keep comments and blank lines that trail the replaced value
+            let kept = entry
+                .lines
+                .iter()
+                .rev()
+                .take_while(|line| line.trim().is_empty() || line.trim_start().starts_with('#'))
+                .count();
+            let trailing = &entry.lines[entry.lines.len() - kept..];
+            lines.extend(trailing.iter().map(|line| line.to_string()));
+        }
+    }
+
+    for key in MANAGED_KEYS {
+        let skip = written.contains(key)
+            || (*key == "title" && memo.title == file_title)
+            || (*key == "tags" && memo.tags.is_empty());
+        if !skip {
+            lines.push(managed_entry(key, &wanted));
+        }
+    }
+    lines.join("\n")
+}
+
+fn field_matches(key: &str, found: &ObsidianFields, wanted: &ObsidianFields) -> bool {
+    match key {
+        "id" => found.id == wanted.id,
+        "title" => found.title == wanted.title,
+        "created" => found.created == wanted.created,
+        "updated" => found.updated == wanted.updated,
+        "tags" => found.tags == wanted.tags,
+        _ => false,
+    }
+}
+
+fn managed_entry(key: &str, fields: &ObsidianFields) -> String {
+    let timestamp = |time: Option<DateTime<Utc>>| {
+        time.map(|time| time.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            .unwrap_or_default()
+    };
+    match key {
+        "id" => format!(
+            "id: {}",
+            fields.id.map(|id| id.to_string()).unwrap_or_default()
+        ),
+        "title" => format!(
+            "title: {}",
+            yaml_scalar(fields.title.as_deref().unwrap_or(""))
+        ),
+        "created" => format!("created: {}", timestamp(fields.created)),
+        "updated" => format!("updated: {}", timestamp(fields.updated)),
+        "tags" if fields.tags.is_empty() => "tags: []".to_string(),
+        "tags" => {
+            let items: Vec<String> = fields
+                .tags
+                .iter()
+                .map(|tag| format!("  - {}", yaml_scalar(tag)))
+                .collect();
+            format!("tags:\n{}", items.join("\n"))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Writes text as a plain scalar when it cannot be mistaken for anything
+/// else, and as a double-quoted string otherwise.
+fn yaml_scalar(text: &str) -> String {
+    let plain = text.starts_with(|c: char| c.is_alphabetic())
+        && text
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+        && !matches!(text, "true" | "false" | "null");
+    if plain {
+        text.to_string()
+    } else {
+        Value::String(text.to_string()).to_string()
+    }
+}
+
+/// Accepts RFC 3339 timestamps, Obsidian's zoneless date-time properties
+/// (taken as UTC), and plain dates (midnight UTC).
+fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    let text = value.as_str()?.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|time| time.and_utc())
+}
+
+/// Reads tags from a list or a comma- or space-separated string, dropping the
+/// `#` Obsidian allows in front of each.
+fn parse_tags(value: &Value) -> Vec<String> {
+    let raw: Vec<String> = match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(tag) => tag.clone(),
+                other => other.to_string(),
+            })
+            .collect(),
+        Value::String(tags) => tags.split([',', ' ']).map(String::from).collect(),
+        _ => Vec::new(),
+    };
+    raw.iter()
+        .map(|tag| tag.trim().trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Id for a note without one, derived from its path so every load agrees
+/// until the id is written on the note's first update.
+pub fn derived_memo_id(file_path: &Path) -> MemoId {
+    // 64-bit FNV-1a under two offset bases; stable across runs and builds
+    let fnv = |offset: u64| {
+        file_path
+            .to_string_lossy()
+            .bytes()
+            .fold(offset, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    };
+    let high = fnv(0xcbf2_9ce4_8422_2325);
+    let low = fnv(0x6c62_272e_07bb_0142);
+    MemoId::from_ulid(Ulid::from((u128::from(high) << 64) | u128::from(low)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE_BLOCK: &str = "aliases: [Daily, Journal]\n\
+        cssclass: wide\n\
+        tags:\n  - \"#project\"\n  - notes\n\
+        created: 2024-03-10\n\
+        publish:\n  enabled: true\n  # nested mappings are left alone";
+
+    #[test]
+    fn test_read_managed_fields_only() {
+        let fields = read_obsidian_frontmatter(NOTE_BLOCK);
+        assert_eq!(fields.id, None);
+        assert_eq!(fields.tags, ["project", "notes"]);
+        assert_eq!(
+            fields.created.unwrap().to_rfc3339(),
+            "2024-03-10T00:00:00+00:00"
+        );
+
+        let fields =
+            read_obsidian_frontmatter("tags: \"rust, #api  cli\"\nupdated: 2024-03-10T07:30");
+        assert_eq!(fields.tags, ["rust", "api", "cli"]);
+        assert_eq!(
+            fields.updated.unwrap().to_rfc3339(),
+            "2024-03-10T07:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_write_keeps_unknown_and_unchanged_entries() {
+        let fields = read_obsidian_frontmatter(NOTE_BLOCK);
+        let mut memo = Memo::new("Daily".to_string(), "body".to_string()).unwrap();
+        memo.created_at = fields.created.unwrap();
+        memo.tags = fields.tags;
+
+        let written = write_obsidian_frontmatter(NOTE_BLOCK, &memo, "Daily");
+        assert!(written.starts_with(NOTE_BLOCK), "{written}");
+        assert!(!written.contains("title:"));
+        let fields = read_obsidian_frontmatter(&written);
+        assert_eq!(fields.id, Some(memo.id));
+        assert_eq!(fields.updated, Some(memo.updated_at));
+
+        memo.tags = vec!["notes".to_string()];
+        memo.title = "Daily: March".to_string();
+        let rewritten = write_obsidian_frontmatter(&written, &memo, "Daily_ March");
+        assert!(rewritten.contains("cssclass: wide\ntags:\n  - notes\ncreated: 2024-03-10\n"));
+        assert!(rewritten.ends_with("title: \"Daily: March\""));
+        assert_eq!(
+            read_obsidian_frontmatter(&rewritten).title.as_deref(),
+            Some("Daily: March")
+        );
+    }
+
+    #[test]
+    fn test_derived_ids_are_stable_per_path() {
+        let a = derived_memo_id(Path::new("/vault/.memoranda/a.md"));
+        assert_eq!(a, derived_memo_id(Path::new("/vault/.memoranda/a.md")));
+        assert_ne!(a, derived_memo_id(Path::new("/vault/.memoranda/b.md")));
+    }
+}
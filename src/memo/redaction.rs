@@ -0,0 +1,109 @@
+use regex::Regex;
+use std::borrow::Cow;
+use tracing::warn;
+
+use super::models::Memo;
+use super::search::{PathMention, SearchResult};
+
+/// Text that replaces each redacted match.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Replaces matches of the configured `redaction_patterns` in content served to
+/// clients. Memo files on disk are never changed.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    /// Compiles `patterns`, failing on the first one that is not a valid regex.
+    pub fn new(patterns: &[String]) -> Result<Self, regex::Error> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Compiles `patterns`, skipping (and logging) any that are invalid.
+    ///
+    /// Settings validation rejects invalid patterns, so this only drops
+    /// patterns from settings that were never validated.
+    pub fn lenient(patterns: &[String]) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    warn!(pattern = %pattern, error = %e, "Ignoring invalid redaction pattern");
+                    None
+                }
+            })
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns `text` with every match of every pattern replaced by [`REDACTED`].
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for pattern in &self.patterns {
+            if let Cow::Owned(replaced) = pattern.replace_all(&text, REDACTED) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+
+    /// Redacts a memo's content in place.
+    pub fn redact_memo(&self, memo: &mut Memo) {
+        if let Cow::Owned(content) = self.redact(&memo.content) {
+            memo.content = content;
+        }
+    }
+
+    /// Redacts a path mention's memo content and matching lines in place.
+    pub fn redact_path_mention(&self, mention: &mut PathMention) {
+        self.redact_memo(&mut mention.memo);
+        for line in &mut mention.lines {
+            if let Cow::Owned(redacted) = self.redact(&line.text) {
+                line.text = redacted;
+            }
+        }
+    }
+
+    /// Redacts a search result's memo content and snippets in place.
+    pub fn redact_search_result(&self, result: &mut SearchResult) {
+        self.redact_memo(&mut result.memo);
+        for snippet in &mut result.snippets {
+            if let Cow::Owned(redacted) = self.redact(snippet) {
+                *snippet = redacted;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_replaces_every_match() {
+        let redactor = Redactor::new(&[
+            r"sk-[A-Za-z0-9]{8,}".to_string(),
+            r"password=\S+".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(
+            redactor.redact("key sk-abcdef123456 and sk-zyxwvu987654, password=hunter2"),
+            "key [REDACTED] and [REDACTED], [REDACTED]"
+        );
+        assert!(matches!(redactor.redact("nothing here"), Cow::Borrowed(_)));
+
+        assert!(Redactor::new(&["(".to_string()]).is_err());
+        assert!(Redactor::lenient(&["(".to_string()]).is_empty());
+    }
+}
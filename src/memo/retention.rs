@@ -0,0 +1,192 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::models::{Memo, MemoId};
+use crate::config::Settings;
+
+/// What to do with memos that fall outside the retention window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    /// Move expired memos into the `.archive` directory next to them.
+    #[default]
+    Archive,
+    /// Move expired memos into the `.trash` directory next to them.
+    Trash,
+    /// Only report expired memos, never touch the files.
+    Report,
+}
+
+impl std::fmt::Display for RetentionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetentionAction::Archive => write!(f, "archive"),
+            RetentionAction::Trash => write!(f, "trash"),
+            RetentionAction::Report => write!(f, "report"),
+        }
+    }
+}
+
+/// Time-based retention policy: memos untouched for longer than `max_age`
+/// are handled according to `action`, unless they carry an exempt tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionPolicy {
+    pub max_age: Duration,
+    pub exempt_tags: Vec<String>,
+    pub action: RetentionAction,
+}
+
+impl RetentionPolicy {
+    pub fn new(max_age: Duration, action: RetentionAction) -> Self {
+        Self {
+            max_age,
+            exempt_tags: Vec::new(),
+            action,
+        }
+    }
+
+    pub fn with_exempt_tags(mut self, exempt_tags: Vec<String>) -> Self {
+        self.exempt_tags = exempt_tags;
+        self
+    }
+
+    /// Returns true if the memo has not been updated within `max_age` of `now`.
+    pub fn is_expired(&self, memo: &Memo, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(memo.updated_at) > self.max_age
+    }
+
+    /// Returns true if the memo carries one of the exempt tags (case-insensitive).
+    pub fn is_exempt(&self, memo: &Memo) -> bool {
+        memo.tags.iter().any(|tag| {
+            self.exempt_tags
+                .iter()
+                .any(|exempt| exempt.eq_ignore_ascii_case(tag))
+        })
+    }
+}
+
+impl From<&Settings> for RetentionPolicy {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            // Validated settings are well within range; anything beyond it
+            // saturates so no memo counts as expired
+            max_age: i64::try_from(settings.retention_max_age_days)
+                .ok()
+                .and_then(Duration::try_days)
+                .unwrap_or(Duration::MAX),
+            exempt_tags: settings.retention_exempt_tags.clone(),
+            action: settings.retention_action,
+        }
+    }
+}
+
+/// A memo selected by a retention policy run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RetentionEntry {
+    pub id: MemoId,
+    pub title: String,
+    pub file_path: PathBuf,
+    pub updated_at: DateTime<Utc>,
+    /// Where the memo was (or would be) moved; `None` for report-only runs.
+    pub destination: Option<PathBuf>,
+}
+
+/// Outcome of applying (or previewing) a retention policy.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RetentionReport {
+    pub action: RetentionAction,
+    pub dry_run: bool,
+    /// Number of memos examined.
+    pub scanned: usize,
+    /// Number of expired memos kept because of an exempt tag.
+    pub exempt: usize,
+    /// Expired memos the action applied to (or would apply to on a dry run).
+    pub affected: Vec<RetentionEntry>,
+}
+
+impl RetentionReport {
+    pub fn new(action: RetentionAction, dry_run: bool) -> Self {
+        Self {
+            action,
+            dry_run,
+            scanned: 0,
+            exempt: 0,
+            affected: Vec::new(),
+        }
+    }
+
+    /// Returns true if files were moved by this run.
+    pub fn has_changes(&self) -> bool {
+        !self.dry_run && self.action != RetentionAction::Report && !self.affected.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memo_updated_days_ago(days: i64, tags: &[&str]) -> Memo {
+        let mut memo = Memo::new("Old memo".to_string(), "content".to_string()).unwrap();
+        memo.updated_at = Utc::now() - Duration::days(days);
+        for tag in tags {
+            memo.add_tag(tag.to_string());
+        }
+        memo
+    }
+
+    #[test]
+    fn test_policy_expiry() {
+        let policy = RetentionPolicy::new(Duration::days(180), RetentionAction::Archive);
+        let now = Utc::now();
+
+        assert!(policy.is_expired(&memo_updated_days_ago(200, &[]), now));
+        assert!(!policy.is_expired(&memo_updated_days_ago(10, &[]), now));
+    }
+
+    #[test]
+    fn test_policy_exempt_tags_case_insensitive() {
+        let policy = RetentionPolicy::new(Duration::days(180), RetentionAction::Archive)
+            .with_exempt_tags(vec!["keep".to_string()]);
+
+        assert!(policy.is_exempt(&memo_updated_days_ago(200, &["KEEP"])));
+        assert!(!policy.is_exempt(&memo_updated_days_ago(200, &["other"])));
+    }
+
+    #[test]
+    fn test_policy_from_settings() {
+        let settings = Settings::default();
+        let policy = RetentionPolicy::from(&settings);
+
+        assert_eq!(policy.max_age, Duration::days(180));
+        assert_eq!(policy.exempt_tags, settings.retention_exempt_tags);
+        assert_eq!(policy.action, RetentionAction::Archive);
+
+        let settings = Settings {
+            retention_max_age_days: u64::MAX,
+            ..Default::default()
+        };
+        let policy = RetentionPolicy::from(&settings);
+        assert_eq!(policy.max_age, Duration::MAX);
+        assert!(!policy.is_expired(&memo_updated_days_ago(100_000, &[]), Utc::now()));
+    }
+
+    #[test]
+    fn test_report_has_changes() {
+        let mut report = RetentionReport::new(RetentionAction::Archive, true);
+        report.affected.push(RetentionEntry {
+            id: MemoId::new(),
+            title: "t".to_string(),
+            file_path: PathBuf::from("t.md"),
+            updated_at: Utc::now(),
+            destination: None,
+        });
+        assert!(!report.has_changes());
+
+        report.dry_run = false;
+        assert!(report.has_changes());
+
+        report.action = RetentionAction::Report;
+        assert!(!report.has_changes());
+    }
+}
@@ -1,11 +1,15 @@
-use chrono::{DateTime, Utc};
-use regex::Regex;
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
 use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::fmt::Write;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
 use tracing::warn;
 
+use super::context::{format_context, ContextOptions, NO_MATCHING_MEMOS};
 use super::models::{Memo, MemoId};
+use super::search_cache::LruCache;
+use super::stemming::stem;
 use crate::config::Settings;
 
 // These constants are now configurable - see Settings struct
@@ -13,12 +17,97 @@ use crate::config::Settings;
 const FALLBACK_RECENCY_BOOST_DAYS: f64 = 365.0;
 const FALLBACK_SNIPPET_LENGTH: usize = 100;
 const FALLBACK_SNIPPET_CONTEXT_PADDING: usize = 2;
+const FALLBACK_SNIPPET_MIN_TERM_SCORE: f64 = 1.0;
+const FALLBACK_SNIPPET_RELEVANCE_FLOOR: f64 = 0.0;
+const FALLBACK_REGEX_MAX_LENGTH: usize = 1000;
+const FALLBACK_REGEX_SIZE_LIMIT: usize = 1 << 20;
+const FALLBACK_REGEX_DFA_SIZE_LIMIT: usize = 1 << 20;
+/// Longest excerpt of a rejected pattern quoted back in errors.
+const PATTERN_ERROR_EXCERPT: usize = 80;
+/// Share of a term's score earned by a fuzzy rather than an exact match, so
+/// precise hits rank first.
+const FUZZY_MATCH_WEIGHT: f64 = 0.5;
+/// Score of a memo matched with no terms to score, by lacking the term of a
+/// standalone `NOT` or by falling in the range of a date-only query, so that
+/// such memos still rank by recency.
+const UNSCORED_MATCH_SCORE: f64 = 1.0;
 
 #[derive(Debug, Clone)]
 pub struct SearchConfig {
     pub recency_boost_days: f64,
     pub snippet_length: usize,
     pub snippet_context_padding: usize,
+    /// Minimum score a term must contribute before a snippet is generated for it.
+    pub snippet_min_term_score: f64,
+    /// Results scoring below this floor get no snippets at all.
+    pub snippet_relevance_floor: f64,
+    /// Longest regex or wildcard pattern accepted, in characters.
+    pub regex_max_length: usize,
+    /// Compiled size limit passed to [`RegexBuilder::size_limit`].
+    pub regex_size_limit: usize,
+    /// DFA cache limit passed to [`RegexBuilder::dfa_size_limit`].
+    pub regex_dfa_size_limit: usize,
+    /// Match single-word terms by their [`stem`], so "running" matches "run".
+    pub enable_stemming: bool,
+    /// Lowercase words dropped from queries and left out of the index.
+    pub stop_words: HashSet<String>,
+    /// Most edits (Levenshtein distance) between a word term and a word in a
+    /// memo for a [fuzzy](SearchQuery::fuzzy) query to match; 0 is exact.
+    pub max_edit_distance: usize,
+    /// Multiplier on the score of terms matched in a memo's title.
+    pub title_boost: f64,
+    /// Multiplier on the score of terms matched in a memo's content.
+    pub content_boost: f64,
+    /// Multiplier on the extra score given to recently created memos; 0 ranks
+    /// by relevance alone.
+    pub recency_boost: f64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            recency_boost_days: FALLBACK_RECENCY_BOOST_DAYS,
+            snippet_length: FALLBACK_SNIPPET_LENGTH,
+            snippet_context_padding: FALLBACK_SNIPPET_CONTEXT_PADDING,
+            snippet_min_term_score: FALLBACK_SNIPPET_MIN_TERM_SCORE,
+            snippet_relevance_floor: FALLBACK_SNIPPET_RELEVANCE_FLOOR,
+            regex_max_length: FALLBACK_REGEX_MAX_LENGTH,
+            regex_size_limit: FALLBACK_REGEX_SIZE_LIMIT,
+            regex_dfa_size_limit: FALLBACK_REGEX_DFA_SIZE_LIMIT,
+            enable_stemming: false,
+            stop_words: HashSet::new(),
+            max_edit_distance: 0,
+            title_boost: 1.0,
+            content_boost: 1.0,
+            recency_boost: 1.0,
+        }
+    }
+}
+
+impl SearchConfig {
+    /// This config with the boosts set in `boosts` replacing its own.
+    #[must_use]
+    pub fn with_boosts(mut self, boosts: &SearchBoosts) -> Self {
+        self.title_boost = boosts.title.unwrap_or(self.title_boost);
+        self.content_boost = boosts.content.unwrap_or(self.content_boost);
+        self.recency_boost = boosts.recency.unwrap_or(self.recency_boost);
+        self
+    }
+}
+
+/// Field boosts for a single search, overriding the configured ones that are set.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SearchBoosts {
+    pub title: Option<f64>,
+    pub content: Option<f64>,
+    pub recency: Option<f64>,
+}
+
+impl SearchBoosts {
+    /// The boosts as bit patterns, so searches can be cached by them.
+    pub(crate) fn cache_key(&self) -> [Option<u64>; 3] {
+        [self.title, self.content, self.recency].map(|boost| boost.map(f64::to_bits))
+    }
 }
 
 impl From<&Settings> for SearchConfig {
@@ -27,18 +116,217 @@ impl From<&Settings> for SearchConfig {
             recency_boost_days: settings.search_recency_boost_days,
             snippet_length: settings.search_snippet_length,
             snippet_context_padding: settings.search_snippet_context_padding,
+            snippet_min_term_score: settings.search_snippet_min_term_score,
+            snippet_relevance_floor: settings.search_snippet_relevance_floor,
+            regex_max_length: settings.search_regex_max_length,
+            regex_size_limit: settings.search_regex_size_limit,
+            regex_dfa_size_limit: settings.search_regex_dfa_size_limit,
+            enable_stemming: settings.search_enable_stemming,
+            stop_words: settings
+                .search_stop_words
+                .iter()
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect(),
+            max_edit_distance: settings.search_max_edit_distance,
+            title_boost: settings.search_title_boost,
+            content_boost: settings.search_content_boost,
+            recency_boost: settings.search_recency_boost,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// A regex or wildcard pattern in a query that is too long or fails to compile
+/// within the configured limits.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Invalid search pattern '{pattern}': {reason}")]
+pub struct SearchPatternError {
+    pub pattern: String,
+    pub reason: String,
+}
+
+/// The regexes a query needs, compiled once per search rather than per memo.
+#[derive(Debug, Default)]
+struct CompiledPatterns {
+    regex: Option<Regex>,
+    /// Compiled wildcard terms, keyed by the wildcard pattern.
+    wildcards: HashMap<String, Regex>,
+}
+
+impl CompiledPatterns {
+    fn for_query(
+        query: &SearchQuery,
+        config: &SearchConfig,
+        cache: &RegexCache,
+    ) -> std::result::Result<Self, SearchPatternError> {
+        let mut patterns = Self {
+            regex: query
+                .regex
+                .as_deref()
+                .map(|pattern| compile_pattern(pattern, pattern, config, cache))
+                .transpose()?,
+            wildcards: HashMap::new(),
+        };
+        if let Some(term) = &query.boolean_query {
+            patterns.add_wildcards(term, config, cache)?;
+        }
+        Ok(patterns)
+    }
+
+    fn add_wildcards(
+        &mut self,
+        term: &SearchTerm,
+        config: &SearchConfig,
+        cache: &RegexCache,
+    ) -> std::result::Result<(), SearchPatternError> {
+        match term {
+            SearchTerm::Wildcard(pattern) if !self.wildcards.contains_key(pattern) => {
+                let regex = compile_pattern(pattern, &wildcard_to_regex(pattern), config, cache)?;
+                self.wildcards.insert(pattern.clone(), regex);
+            }
+            SearchTerm::Boolean { left, right, .. } => {
+                self.add_wildcards(left, config, cache)?;
+                self.add_wildcards(right, config, cache)?;
+            }
+            SearchTerm::Not(term) | SearchTerm::Field { term, .. } => {
+                self.add_wildcards(term, config, cache)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Number of compiled patterns a searcher keeps across queries.
+const REGEX_CACHE_CAPACITY: usize = 128;
+
+/// Identifies a compiled pattern: the same source built under different size
+/// limits may succeed under one and fail under another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RegexCacheKey {
+    source: String,
+    size_limit: usize,
+    dfa_size_limit: usize,
+}
+
+type RegexCache = LruCache<RegexCacheKey, Regex>;
+
+/// Compiles `regex_source` (derived from the user's `pattern`) within the
+/// configured length and size limits, reusing a cached compilation if any.
+fn compile_pattern(
+    pattern: &str,
+    regex_source: &str,
+    config: &SearchConfig,
+    cache: &RegexCache,
+) -> std::result::Result<Regex, SearchPatternError> {
+    let invalid = |reason: String| SearchPatternError {
+        pattern: pattern.chars().take(PATTERN_ERROR_EXCERPT).collect(),
+        reason,
+    };
+
+    let length = pattern.chars().count();
+    if length > config.regex_max_length {
+        return Err(invalid(format!(
+            "pattern is {length} characters, the limit is {}",
+            config.regex_max_length
+        )));
+    }
+
+    let key = RegexCacheKey {
+        source: regex_source.to_string(),
+        size_limit: config.regex_size_limit,
+        dfa_size_limit: config.regex_dfa_size_limit,
+    };
+    if let Some(regex) = cache.get(&key) {
+        return Ok(regex);
+    }
+
+    #[cfg(test)]
+    REGEX_COMPILATIONS.with(|count| count.set(count.get() + 1));
+
+    let regex = RegexBuilder::new(regex_source)
+        .size_limit(config.regex_size_limit)
+        .dfa_size_limit(config.regex_dfa_size_limit)
+        .build()
+        .map_err(|e| invalid(e.to_string()))?;
+    cache.insert(key, regex.clone());
+    Ok(regex)
+}
+
+/// Checks that a regex pattern compiles within the configured length and size
+/// limits, without running a search.
+pub fn validate_regex(
+    pattern: &str,
+    config: &SearchConfig,
+) -> std::result::Result<(), SearchPatternError> {
+    compile_pattern(pattern, pattern, config, &RegexCache::new(0)).map(|_| ())
+}
+
+fn wildcard_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    regex.push_str("(?i)"); // Case insensitive
+
+    for ch in pattern.chars() {
+        match ch {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c if c.is_alphanumeric() => regex.push(c),
+            c => {
+                regex.push('\\');
+                regex.push(c);
+            }
+        }
+    }
+
+    regex
+}
+
+/// 64-bit FNV-1a over the bytes written to it. Fingerprints are saved with
+/// the search index, so unlike `DefaultHasher` (and the `Hash` impls of std
+/// types) the result must not change between Rust releases.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Writes a length before its value, so adjacent values cannot run together.
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.write_len(s.len());
+        self.write(s.as_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Counts regex compilations so tests can assert patterns compile once per search.
+    static REGEX_COMPILATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SearchOperator {
     And,
     Or,
     Not,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SearchTerm {
     Word(String),
     Phrase(String),
@@ -48,9 +336,73 @@ pub enum SearchTerm {
         operator: SearchOperator,
         right: Box<SearchTerm>,
     },
+    /// Matches memos the inner term does not, as in `NOT deprecated`.
+    Not(Box<SearchTerm>),
+    /// Matches the inner term against one field only, as in `title:tokio`.
+    Field {
+        field: SearchField,
+        term: Box<SearchTerm>,
+    },
 }
 
-#[derive(Debug, Clone)]
+/// The part of a memo a [`SearchTerm::Field`] term, or a whole title-only or
+/// content-only search, is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchField {
+    Title,
+    Content,
+}
+
+impl SearchField {
+    /// Splits a `title:` or `content:` prefix off `word`, if it has one
+    /// followed by something to search for.
+    fn split_prefix(word: &str) -> Option<(Self, &str)> {
+        let (field, rest) = if let Some(rest) = word.strip_prefix("title:") {
+            (Self::Title, rest)
+        } else {
+            (Self::Content, word.strip_prefix("content:")?)
+        };
+        (!rest.is_empty()).then_some((field, rest))
+    }
+
+    fn includes_title(field: Option<Self>) -> bool {
+        field != Some(Self::Content)
+    }
+
+    fn includes_content(field: Option<Self>) -> bool {
+        field != Some(Self::Title)
+    }
+}
+
+impl SearchTerm {
+    fn without_stop_words(self, stop_words: &HashSet<String>) -> Option<Self> {
+        match self {
+            SearchTerm::Word(word) if stop_words.contains(&word.to_lowercase()) => None,
+            SearchTerm::Boolean {
+                left,
+                operator,
+                right,
+            } => combine_terms(
+                left.without_stop_words(stop_words),
+                operator,
+                right.without_stop_words(stop_words),
+            ),
+            SearchTerm::Not(term) => term
+                .without_stop_words(stop_words)
+                .map(|term| SearchTerm::Not(Box::new(term))),
+            SearchTerm::Field { field, term } => {
+                term.without_stop_words(stop_words)
+                    .map(|term| SearchTerm::Field {
+                        field,
+                        term: Box::new(term),
+                    })
+            }
+            term => Some(term),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SearchQuery {
     pub terms: Vec<String>,
     pub phrase: Option<String>,
@@ -61,6 +413,9 @@ pub struct SearchQuery {
     pub title_only: bool,
     pub content_only: bool,
     pub boolean_query: Option<SearchTerm>,
+    /// Let word terms match words up to [`SearchConfig::max_edit_distance`]
+    /// edits away, for typos. Fuzzy matches score lower than exact ones.
+    pub fuzzy: bool,
 }
 
 impl SearchQuery {
@@ -76,6 +431,7 @@ impl SearchQuery {
             title_only: false,
             content_only: false,
             boolean_query: None,
+            fuzzy: false,
         }
     }
 
@@ -91,6 +447,7 @@ impl SearchQuery {
             title_only: false,
             content_only: false,
             boolean_query: None,
+            fuzzy: false,
         }
     }
 
@@ -106,6 +463,7 @@ impl SearchQuery {
             title_only: false,
             content_only: false,
             boolean_query: None,
+            fuzzy: false,
         }
     }
 
@@ -121,6 +479,7 @@ impl SearchQuery {
             title_only: false,
             content_only: false,
             boolean_query: None,
+            fuzzy: false,
         }
     }
 
@@ -136,17 +495,101 @@ impl SearchQuery {
             title_only: false,
             content_only: false,
             boolean_query: Some(boolean_query),
+            fuzzy: false,
         }
     }
 
+    /// Returns an equivalent query in canonical form, so searches that differ
+    /// only in term case, surrounding whitespace, or tag order compare equal.
+    #[must_use]
+    /// The field `title_only` or `content_only` limits the whole query to;
+    /// `title_only` wins if both are set.
+    fn field(&self) -> Option<SearchField> {
+        if self.title_only {
+            Some(SearchField::Title)
+        } else if self.content_only {
+            Some(SearchField::Content)
+        } else {
+            None
+        }
+    }
+
+    pub fn normalized(&self) -> Self {
+        let mut query = self.clone();
+        query.terms = query
+            .terms
+            .iter()
+            .map(|term| term.trim().to_lowercase())
+            .filter(|term| !term.is_empty())
+            .collect();
+        query.tags.sort();
+        query.tags.dedup();
+        query
+    }
+
+    /// Returns the query without terms that are stop words. A boolean query
+    /// keeps the side of an operator that is not a stop word, and is dropped
+    /// when nothing but stop words remain.
+    #[must_use]
+    pub fn without_stop_words(&self, stop_words: &HashSet<String>) -> Self {
+        let mut query = self.clone();
+        if stop_words.is_empty() {
+            return query;
+        }
+        query
+            .terms
+            .retain(|term| !stop_words.contains(&term.trim().to_lowercase()));
+        query.boolean_query = query
+            .boolean_query
+            .and_then(|term| term.without_stop_words(stop_words));
+        query
+    }
+
+    /// Edits a word term may be away from a memo word and still match.
+    fn max_edits(&self, config: &SearchConfig) -> usize {
+        if self.fuzzy {
+            config.max_edit_distance
+        } else {
+            0
+        }
+    }
+
+    /// Parses a query string: plain terms, a `"quoted phrase"`, a wildcard
+    /// pattern, or terms joined by `AND`, `OR` and `NOT`. A `~` after any
+    /// word term (`rust~`) makes the query [fuzzy](Self::fuzzy).
+    ///
+    /// `after:DATE`, `before:DATE` and `created:DATE..DATE` anywhere in the
+    /// query set [`date_from`](Self::date_from) and [`date_to`](Self::date_to)
+    /// and are not searched for. Dates are `YYYY-MM-DD`, covering the whole
+    /// day, or RFC 3339 timestamps; either end of a range may be left empty.
+    /// Dates that do not parse are ignored with a warning.
+    ///
+    /// `tag:NAME` adds to [`tags`](Self::tags), and `title:TERM` and
+    /// `content:TERM` (`TERM` may be a `"quoted phrase"`) match only that
+    /// field. Field-scoped terms must all match, alongside any of the plain
+    /// terms next to them.
     pub fn parse_query(query: &str) -> Self {
         let mut search_query = SearchQuery::new();
+        let remaining = search_query.take_filters(query);
+        let query = remaining.as_str();
+        search_query.fuzzy = query
+            .split_whitespace()
+            .any(|word| word.len() > 1 && word.ends_with('~') && !word.starts_with('"'));
 
         // Simple parser for basic query formats
-        if query.contains(" AND ") || query.contains(" OR ") || query.contains(" NOT ") {
+        if query.contains(" AND ")
+            || query.contains(" OR ")
+            || query.contains(" NOT ")
+            || query.starts_with("NOT ")
+        {
             if let Some(boolean_query) = Self::parse_boolean_query(query) {
                 search_query.boolean_query = Some(boolean_query);
             }
+        } else if query
+            .split_whitespace()
+            .any(|word| SearchField::split_prefix(word).is_some())
+        {
+            search_query.boolean_query = Self::parse_field_query(query);
         } else if query.starts_with('"') && query.ends_with('"') {
             // Phrase query
             let phrase = query.trim_matches('"').to_string();
@@ -157,49 +600,114 @@ impl SearchQuery {
             search_query.boolean_query = Some(wildcard_term);
         } else {
             // Simple term search
-            search_query.terms = query.split_whitespace().map(|s| s.to_string()).collect();
+            search_query.terms = query
+                .split_whitespace()
+                .map(|s| s.strip_suffix('~').unwrap_or(s).to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
         }
 
         search_query
     }
 
+    /// Parses `AND`, `OR` and `NOT` with the usual precedence (`NOT` binds
+    /// tightest, then `AND`, then `OR`) and parentheses for grouping. `a NOT b`
+    /// matches memos matching `a` but not `b`, and a `NOT` with nothing before
+    /// it, as in `NOT b` or `a AND NOT b`, matches every memo lacking `b`. Words
+    /// between operators form one term, as do `"quoted phrases"`. Stray
+    /// operators and unbalanced parentheses are skipped rather than failing
+    /// the query.
     fn parse_boolean_query(query: &str) -> Option<SearchTerm> {
-        // Simple boolean query parser
-        // This is a basic implementation and could be improved with proper parsing
-        if let Some(and_pos) = query.find(" AND ") {
-            let left = query[..and_pos].trim();
-            let right = query[and_pos + 5..].trim();
-
-            return Some(SearchTerm::Boolean {
-                left: Box::new(Self::parse_term(left)),
-                operator: SearchOperator::And,
-                right: Box::new(Self::parse_term(right)),
-            });
+        let mut parser = BooleanParser {
+            tokens: tokenize_boolean_query(query),
+            position: 0,
+        };
+        let mut term = parser.parse_or();
+        // Whatever follows a stray `)` is still part of the query
+        while parser.position < parser.tokens.len() {
+            parser.position += 1;
+            let rest = parser.parse_or();
+            term = combine_terms(term, SearchOperator::And, rest);
         }
+        term
+    }
 
-        if let Some(or_pos) = query.find(" OR ") {
-            let left = query[..or_pos].trim();
-            let right = query[or_pos + 4..].trim();
-
-            return Some(SearchTerm::Boolean {
-                left: Box::new(Self::parse_term(left)),
-                operator: SearchOperator::Or,
-                right: Box::new(Self::parse_term(right)),
-            });
+    /// Parses plain terms mixed with `title:` and `content:` terms: every
+    /// field-scoped term must match, and at least one plain term, if any.
+    fn parse_field_query(query: &str) -> Option<SearchTerm> {
+        let mut scoped = None;
+        let mut plain = None;
+        for token in tokenize_boolean_query(query) {
+            let QueryToken::Text(word) = token else {
+                continue;
+            };
+            match SearchField::split_prefix(&word) {
+                Some((field, rest)) => {
+                    let term = SearchTerm::Field {
+                        field,
+                        term: Box::new(Self::parse_term(rest)),
+                    };
+                    scoped = combine_terms(scoped, SearchOperator::And, Some(term));
+                }
+                None => {
+                    let term = Self::parse_term(&word);
+                    plain = combine_terms(plain, SearchOperator::Or, Some(term));
+                }
+            }
         }
+        combine_terms(scoped, SearchOperator::And, plain)
+    }
 
-        if let Some(not_pos) = query.find(" NOT ") {
-            let left = query[..not_pos].trim();
-            let right = query[not_pos + 5..].trim();
+    /// Sets the date range and tags from the filter tokens in `query`,
+    /// returning the rest of the query.
+    fn take_filters(&mut self, query: &str) -> String {
+        let is_filter = |token: &str| {
+            ["after:", "before:", "created:", "tag:"]
+                .iter()
+                .any(|prefix| token.starts_with(prefix))
+        };
+        if !query.split_whitespace().any(is_filter) {
+            return query.to_string();
+        }
 
-            return Some(SearchTerm::Boolean {
-                left: Box::new(Self::parse_term(left)),
-                operator: SearchOperator::Not,
-                right: Box::new(Self::parse_term(right)),
-            });
+        let mut rest = Vec::new();
+        for token in query.split_whitespace() {
+            if let Some(tag) = token.strip_prefix("tag:") {
+                if !tag.is_empty() {
+                    self.tags.push(tag.to_string());
+                }
+            } else if let Some(date) = token.strip_prefix("after:") {
+                self.date_from = parse_query_date(date, false).or(self.date_from);
+            } else if let Some(date) = token.strip_prefix("before:") {
+                self.date_to = parse_query_date(date, true).or(self.date_to);
+            } else if let Some(range) = token.strip_prefix("created:") {
+                let (from, to) = range.split_once("..").unwrap_or((range, range));
+                if !from.is_empty() {
+                    self.date_from = parse_query_date(from, false).or(self.date_from);
+                }
+                if !to.is_empty() {
+                    self.date_to = parse_query_date(to, true).or(self.date_to);
+                }
+            } else {
+                rest.push(token);
+            }
         }
+        rest.join(" ")
+    }
 
-        None
+    /// Whether dates are the query's only criteria, so every memo in range matches.
+    fn is_date_only(&self) -> bool {
+        (self.date_from.is_some() || self.date_to.is_some())
+            && self.tags.is_empty()
+            && !self.has_text_criteria()
+    }
+
+    /// Whether the query searches memo text, rather than only tags or dates.
+    fn has_text_criteria(&self) -> bool {
+        !self.terms.is_empty()
+            || self.phrase.is_some()
+            || self.regex.is_some()
+            || self.boolean_query.is_some()
     }
 
     fn parse_term(term: &str) -> SearchTerm {
@@ -210,8 +718,192 @@ impl SearchQuery {
         } else if term.contains('*') || term.contains('?') {
             SearchTerm::Wildcard(term.to_string())
         } else {
-            SearchTerm::Word(term.to_string())
+            SearchTerm::Word(term.strip_suffix('~').unwrap_or(term).to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum QueryToken {
+    Open,
+    Close,
+    Operator(SearchOperator),
+    Text(String),
+}
+
+/// Splits a boolean query into parentheses, operators, and the words and
+/// `"quoted phrases"` between them.
+fn tokenize_boolean_query(query: &str) -> Vec<QueryToken> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(QueryToken::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(QueryToken::Close);
+            }
+            '"' => {
+                let mut phrase = String::from(chars.next().unwrap_or('"'));
+                for c in chars.by_ref() {
+                    phrase.push(c);
+                    if c == '"' {
+                        break;
+                    }
+                }
+                tokens.push(QueryToken::Text(phrase));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                // A field-scoped phrase, as in `title:"async runtime"`
+                if (word == "title:" || word == "content:") && chars.peek() == Some(&'"') {
+                    word.extend(chars.next());
+                    for c in chars.by_ref() {
+                        word.push(c);
+                        if c == '"' {
+                            break;
+                        }
+                    }
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => QueryToken::Operator(SearchOperator::And),
+                    "OR" => QueryToken::Operator(SearchOperator::Or),
+                    "NOT" => QueryToken::Operator(SearchOperator::Not),
+                    _ => QueryToken::Text(word),
+                });
+            }
+        }
+    }
+    tokens
+}
+
+/// Recursive-descent parser over [`QueryToken`]s, one method per precedence level.
+struct BooleanParser {
+    tokens: Vec<QueryToken>,
+    position: usize,
+}
+
+impl BooleanParser {
+    fn peek(&self) -> Option<&QueryToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> Option<SearchTerm> {
+        self.parse_level(SearchOperator::Or, Self::parse_and)
+    }
+
+    fn parse_and(&mut self) -> Option<SearchTerm> {
+        self.parse_level(SearchOperator::And, Self::parse_not)
+    }
+
+    fn parse_not(&mut self) -> Option<SearchTerm> {
+        self.parse_level(SearchOperator::Not, Self::parse_primary)
+    }
+
+    /// Parses operands joined left to right by `operator`.
+    fn parse_level(
+        &mut self,
+        operator: SearchOperator,
+        parse_operand: fn(&mut Self) -> Option<SearchTerm>,
+    ) -> Option<SearchTerm> {
+        let mut term = parse_operand(self);
+        while self.peek() == Some(&QueryToken::Operator(operator.clone())) {
+            self.position += 1;
+            let right = parse_operand(self);
+            term = combine_terms(term, operator.clone(), right);
+        }
+        term
+    }
+
+    /// A parenthesized expression or a run of words; `None`, consuming
+    /// nothing, if an operator or `)` comes first.
+    fn parse_primary(&mut self) -> Option<SearchTerm> {
+        match self.peek()? {
+            QueryToken::Open => {
+                self.position += 1;
+                let term = self.parse_or();
+                if self.peek() == Some(&QueryToken::Close) {
+                    self.position += 1;
+                }
+                term
+            }
+            QueryToken::Text(_) => {
+                // Field-scoped words stand alone; the others form one term
+                let mut words = Vec::new();
+                let mut scoped = None;
+                while let Some(QueryToken::Text(word)) = self.peek() {
+                    match SearchField::split_prefix(word) {
+                        Some((field, rest)) => {
+                            let term = SearchTerm::Field {
+                                field,
+                                term: Box::new(SearchQuery::parse_term(rest)),
+                            };
+                            scoped = combine_terms(scoped, SearchOperator::And, Some(term));
+                        }
+                        None => words.push(word.clone()),
+                    }
+                    self.position += 1;
+                }
+                let plain = (!words.is_empty()).then(|| SearchQuery::parse_term(&words.join(" ")));
+                combine_terms(scoped, SearchOperator::And, plain)
+            }
+            QueryToken::Close | QueryToken::Operator(_) => None,
+        }
+    }
+}
+
+/// Parses a date in a query's date filter: an RFC 3339 timestamp, or a
+/// `YYYY-MM-DD` day, read as its first moment, or its last with `end_of_day`.
+fn parse_query_date(value: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    let day = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(day) => day,
+        Err(e) => {
+            warn!("Ignoring search date filter {:?}: {}", value, e);
+            return None;
+        }
+    };
+    let time = if end_of_day {
+        day.and_hms_nano_opt(23, 59, 59, 999_999_999)
+    } else {
+        day.and_hms_opt(0, 0, 0)
+    };
+    time.map(|time| time.and_utc())
+}
+
+/// Joins two operands, keeping whichever side exists if one is missing;
+/// `NOT` without a left side negates its right side on its own.
+fn combine_terms(
+    left: Option<SearchTerm>,
+    operator: SearchOperator,
+    right: Option<SearchTerm>,
+) -> Option<SearchTerm> {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(SearchTerm::Boolean {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        }),
+        (Some(left), None) => Some(left),
+        (None, Some(right)) if operator == SearchOperator::Not => {
+            Some(SearchTerm::Not(Box::new(right)))
         }
+        (None, right) => right,
     }
 }
 
@@ -221,7 +913,7 @@ impl Default for SearchQuery {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchResult {
     pub memo: Memo,
     pub score: f64,
@@ -242,6 +934,27 @@ impl SearchResult {
     }
 }
 
+/// Renders search results, in the order given, as a Markdown digest: a
+/// `## title (score)` heading per result followed by its snippets. With no
+/// results, [`NO_MATCHING_MEMOS`] is returned.
+pub fn format_search_digest(results: &[SearchResult]) -> String {
+    if results.is_empty() {
+        return NO_MATCHING_MEMOS.to_string();
+    }
+    results
+        .iter()
+        .map(|result| {
+            let mut section = format!("## {} ({:.2})\n", result.memo.title, result.score);
+            for snippet in &result.snippets {
+                section.push_str(snippet);
+                section.push('\n');
+            }
+            section
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl PartialEq for SearchResult {
     fn eq(&self, other: &Self) -> bool {
         self.memo.id == other.memo.id
@@ -267,9 +980,36 @@ impl Ord for SearchResult {
     }
 }
 
+/// A line of memo content that mentions a file path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathMentionLine {
+    /// 1-based line number within the memo content.
+    pub line_number: usize,
+    pub text: String,
+    /// True if the line contains the path exactly as given, before normalization.
+    pub exact: bool,
+}
+
+/// A memo whose content mentions a file path, with the matching lines.
+#[derive(Debug, Clone, Serialize)]
+pub struct PathMention {
+    pub memo: Memo,
+    pub lines: Vec<PathMentionLine>,
+}
+
 #[derive(Debug)]
 pub struct MemoSearcher {
     index: HashMap<String, Vec<MemoId>>,
+    fingerprints: HashMap<MemoId, u64>,
+    /// The index tokens each memo was indexed under, so a memo can be removed
+    /// without scanning the whole index.
+    memo_tokens: HashMap<MemoId, HashSet<String>>,
+    /// Whether indexed tokens are stemmed; see [`Self::with_config`].
+    enable_stemming: bool,
+    /// Words left out of the index.
+    stop_words: HashSet<String>,
+    /// Compiled regex and wildcard patterns, reused across queries.
+    regex_cache: RegexCache,
 }
 
 impl MemoSearcher {
@@ -277,40 +1017,164 @@ impl MemoSearcher {
     pub fn new() -> Self {
         Self {
             index: HashMap::new(),
+            fingerprints: HashMap::new(),
+            memo_tokens: HashMap::new(),
+            enable_stemming: false,
+            stop_words: HashSet::new(),
+            regex_cache: RegexCache::new(REGEX_CACHE_CAPACITY),
         }
     }
 
-    pub fn index_memo(&mut self, memo: &Memo) {
-        let tokens = self.tokenize_text(&format!("{} {}", memo.title, memo.content));
+    /// Indexes memos with the stemming and stop words of `config`. Searches
+    /// should pass the same configuration so query terms line up with the index.
+    #[must_use]
+    pub fn with_config(mut self, config: &SearchConfig) -> Self {
+        self.enable_stemming = config.enable_stemming;
+        self.stop_words = config.stop_words.clone();
+        self
+    }
+
+    /// Identifies the stemming and stop words the index was built with, so a
+    /// saved index built differently is not reused.
+    pub fn tokenizer_fingerprint(&self) -> u64 {
+        let mut stop_words: Vec<&String> = self.stop_words.iter().collect();
+        stop_words.sort();
+        let mut hasher = StableHasher::new();
+        hasher.write(&[u8::from(self.enable_stemming)]);
+        hasher.write_len(stop_words.len());
+        for word in stop_words {
+            hasher.write_str(word);
+        }
+        hasher.finish()
+    }
+
+    /// Creates a searcher from a previously built token index and fingerprints.
+    pub fn from_index_parts(
+        index: HashMap<String, Vec<MemoId>>,
+        fingerprints: HashMap<MemoId, u64>,
+    ) -> Self {
+        let mut memo_tokens: HashMap<MemoId, HashSet<String>> = HashMap::new();
+        for (token, ids) in &index {
+            for id in ids {
+                memo_tokens.entry(*id).or_default().insert(token.clone());
+            }
+        }
+        Self {
+            index,
+            fingerprints,
+            memo_tokens,
+            ..Self::new()
+        }
+    }
+
+    /// Returns the token index and the fingerprint of every indexed memo.
+    pub fn index_parts(&self) -> (&HashMap<String, Vec<MemoId>>, &HashMap<MemoId, u64>) {
+        (&self.index, &self.fingerprints)
+    }
+
+    /// Drops the given memos from the index.
+    pub fn remove_memos(&mut self, ids: &[MemoId]) {
+        for id in ids {
+            self.remove_memo(id);
+        }
+    }
 
+    /// Drops a memo from the index, touching only the tokens it was indexed under.
+    pub fn remove_memo(&mut self, id: &MemoId) {
+        self.fingerprints.remove(id);
+        let Some(tokens) = self.memo_tokens.remove(id) else {
+            return;
+        };
+        for token in tokens {
+            if let Some(memo_ids) = self.index.get_mut(&token) {
+                memo_ids.retain(|memo_id| memo_id != id);
+                if memo_ids.is_empty() {
+                    self.index.remove(&token);
+                }
+            }
+        }
+    }
+
+    /// Drops every indexed memo, keeping compiled patterns for the next queries.
+    pub fn clear_index(&mut self) {
+        self.index.clear();
+        self.fingerprints.clear();
+        self.memo_tokens.clear();
+    }
+
+    /// Indexes a memo, replacing whatever was indexed for it before.
+    pub fn index_memo(&mut self, memo: &Memo) {
+        self.remove_memo(&memo.id);
+        self.fingerprints.insert(memo.id, Self::fingerprint(memo));
+        let tokens = self.tokenize_text(&format!(
+            "{} {} {}",
+            memo.title,
+            memo.content,
+            memo.tags.join(" ")
+        ));
+
+        let memo_tokens = self.memo_tokens.entry(memo.id).or_default();
         for token in tokens {
-            self.index
-                .entry(token.to_lowercase())
-                .or_default()
-                .push(memo.id);
+            let token = token.to_lowercase();
+            self.index.entry(token.clone()).or_default().push(memo.id);
+            memo_tokens.insert(token);
+        }
+    }
+
+    /// Hash of the indexed text, used to detect memos changed since indexing.
+    pub fn fingerprint(memo: &Memo) -> u64 {
+        let mut hasher = StableHasher::new();
+        hasher.write_str(&memo.title);
+        hasher.write_str(&memo.content);
+        hasher.write_len(memo.tags.len());
+        for tag in &memo.tags {
+            hasher.write_str(tag);
         }
+        hasher.finish()
+    }
+
+    /// Returns the fingerprint recorded when the memo was indexed, if it was.
+    pub fn indexed_fingerprint(&self, id: &MemoId) -> Option<u64> {
+        self.fingerprints.get(id).copied()
     }
 
+    /// Returns the IDs of all indexed memos.
+    pub fn indexed_ids(&self) -> impl Iterator<Item = &MemoId> {
+        self.fingerprints.keys()
+    }
+
+    /// Searches with the fallback configuration.
+    ///
+    /// A query with an invalid regex or wildcard pattern matches nothing; use
+    /// [`Self::search_with_config`] to get the error instead.
     pub fn search(&self, query: &SearchQuery, memos: &[Memo]) -> Vec<SearchResult> {
         // Use fallback constants for backward compatibility
-        let config = SearchConfig {
-            recency_boost_days: FALLBACK_RECENCY_BOOST_DAYS,
-            snippet_length: FALLBACK_SNIPPET_LENGTH,
-            snippet_context_padding: FALLBACK_SNIPPET_CONTEXT_PADDING,
-        };
+        let config = SearchConfig::default();
         self.search_with_config(query, memos, &config)
+            .unwrap_or_else(|e| {
+                warn!("{}", e);
+                Vec::new()
+            })
     }
 
+    /// Searches `memos`, compiling the query's regex and wildcard patterns once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern exceeds `config.regex_max_length` or does
+    /// not compile within the configured size limits.
     pub fn search_with_config(
         &self,
         query: &SearchQuery,
         memos: &[Memo],
         config: &SearchConfig,
-    ) -> Vec<SearchResult> {
+    ) -> std::result::Result<Vec<SearchResult>, SearchPatternError> {
+        let query = &query.without_stop_words(&config.stop_words);
+        let patterns = CompiledPatterns::for_query(query, config, &self.regex_cache)?;
         let mut results = Vec::new();
 
         for memo in memos {
-            if let Some(score) = self.score_memo_with_config(memo, query, config) {
+            if let Some(score) = self.score_memo_with_config(memo, query, config, &patterns) {
                 let mut result = SearchResult::new(memo.clone(), score);
                 self.add_snippets_with_config(&mut result, query, config);
                 results.push(result);
@@ -318,43 +1182,33 @@ impl MemoSearcher {
         }
 
         results.sort();
-        results
+        Ok(results)
     }
 
     pub fn get_all_context(&self, memos: &[Memo]) -> String {
-        let mut context = String::new();
-
-        for memo in memos {
-            let _ = write!(
-                context,
-                "# {}\n\n**Created:** {}\n**Updated:** {}\n**Tags:** {}\n\n{}\n\n---\n\n",
-                memo.title,
-                memo.created_at.format("%Y-%m-%d %H:%M:%S"),
-                memo.updated_at.format("%Y-%m-%d %H:%M:%S"),
-                memo.tags.join(", "),
-                memo.content
-            );
-        }
-
-        context
+        format_context(memos, &ContextOptions::default())
     }
 
     fn tokenize_text(&self, text: &str) -> Vec<String> {
         text.split_whitespace()
             .map(|s| s.trim_matches(|c: char| !c.is_alphanumeric()))
             .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
+            .filter(|s| self.stop_words.is_empty() || !self.stop_words.contains(&s.to_lowercase()))
+            .map(|s| {
+                if self.enable_stemming {
+                    stem(&s.to_lowercase())
+                } else {
+                    s.to_string()
+                }
+            })
             .collect()
     }
 
     #[allow(dead_code)]
     fn score_memo(&self, memo: &Memo, query: &SearchQuery) -> Option<f64> {
-        let config = SearchConfig {
-            recency_boost_days: FALLBACK_RECENCY_BOOST_DAYS,
-            snippet_length: FALLBACK_SNIPPET_LENGTH,
-            snippet_context_padding: FALLBACK_SNIPPET_CONTEXT_PADDING,
-        };
-        self.score_memo_with_config(memo, query, &config)
+        let config = SearchConfig::default();
+        let patterns = CompiledPatterns::for_query(query, &config, &self.regex_cache).ok()?;
+        self.score_memo_with_config(memo, query, &config, &patterns)
     }
 
     fn score_memo_with_config(
@@ -362,14 +1216,19 @@ impl MemoSearcher {
         memo: &Memo,
         query: &SearchQuery,
         config: &SearchConfig,
+        patterns: &CompiledPatterns,
     ) -> Option<f64> {
-        let mut score = 0.0;
-        let mut matches = false;
+        // A query of dates alone matches every memo in range
+        let mut matches = query.is_date_only();
+        let mut score = if matches { UNSCORED_MATCH_SCORE } else { 0.0 };
+        let max_edits = query.max_edits(config);
+        let field = query.field();
 
         // Term matching
         if !query.terms.is_empty() {
             for term in &query.terms {
-                let (term_score, term_matches) = self.score_term_match(memo, term, 2.0, 1.0);
+                let (term_score, term_matches) =
+                    self.score_term_match(memo, term, 2.0, 1.0, config, max_edits, field);
                 score += term_score;
                 if term_matches {
                     matches = true;
@@ -379,21 +1238,30 @@ impl MemoSearcher {
 
         // Phrase matching
         if let Some(phrase) = &query.phrase {
-            let (phrase_score, phrase_matches) = self.score_term_match(memo, phrase, 3.0, 1.5);
+            let (phrase_score, phrase_matches) =
+                self.score_term_match(memo, phrase, 3.0, 1.5, config, 0, field);
             score += phrase_score;
             if phrase_matches {
                 matches = true;
             }
         }
 
-        // Tag matching
+        // Tag matching: a memo must carry one of the tags, and matches on
+        // them alone only when the query searches no text
         if !query.tags.is_empty() {
+            let mut tag_matches = false;
             for tag in &query.tags {
-                if memo.tags.contains(tag) {
+                if memo.has_tag(tag) {
                     score += 1.0;
-                    matches = true;
+                    tag_matches = true;
                 }
             }
+            if !tag_matches {
+                return None;
+            }
+            if !query.has_text_criteria() {
+                matches = true;
+            }
         }
 
         // Date filtering
@@ -410,24 +1278,23 @@ impl MemoSearcher {
         }
 
         // Regex matching
-        if let Some(regex_pattern) = &query.regex {
-            match Regex::new(regex_pattern) {
-                Ok(regex) => {
-                    let search_text = format!("{} {}", memo.title, memo.content);
-                    if regex.is_match(&search_text) {
-                        score += 1.0;
-                        matches = true;
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to compile regex pattern '{}': {}", regex_pattern, e);
-                }
+        if let Some(regex) = &patterns.regex {
+            let search_text = match field {
+                Some(SearchField::Title) => memo.title.clone(),
+                Some(SearchField::Content) => memo.content.clone(),
+                None => format!("{} {}", memo.title, memo.content),
+            };
+            if regex.is_match(&search_text) {
+                score += 1.0;
+                matches = true;
             }
         }
 
         // Boolean query matching
         if let Some(boolean_query) = &query.boolean_query {
-            if let Some(boolean_score) = self.evaluate_boolean_term(memo, boolean_query) {
+            if let Some(boolean_score) =
+                self.evaluate_compiled_term(memo, boolean_query, config, patterns, max_edits, field)
+            {
                 score += boolean_score;
                 matches = true;
             }
@@ -438,7 +1305,7 @@ impl MemoSearcher {
             let days_since_creation = (Utc::now() - memo.created_at).num_days();
             let recency_boost =
                 1.0 / (1.0 + days_since_creation as f64 / config.recency_boost_days);
-            score *= 1.0 + recency_boost;
+            score *= 1.0 + recency_boost * config.recency_boost;
 
             Some(score)
         } else {
@@ -448,11 +1315,7 @@ impl MemoSearcher {
 
     #[allow(dead_code)]
     fn add_snippets(&self, result: &mut SearchResult, query: &SearchQuery) {
-        let config = SearchConfig {
-            recency_boost_days: FALLBACK_RECENCY_BOOST_DAYS,
-            snippet_length: FALLBACK_SNIPPET_LENGTH,
-            snippet_context_padding: FALLBACK_SNIPPET_CONTEXT_PADDING,
-        };
+        let config = SearchConfig::default();
         self.add_snippets_with_config(result, query, &config)
     }
 
@@ -462,8 +1325,28 @@ impl MemoSearcher {
         query: &SearchQuery,
         config: &SearchConfig,
     ) {
+        // Snippets come from the content, which title-only searches skip
+        if result.score < config.snippet_relevance_floor
+            || !SearchField::includes_content(query.field())
+        {
+            return;
+        }
+
         if !query.terms.is_empty() {
             for term in &query.terms {
+                // Only terms that contributed meaningfully to the score get snippets
+                let (term_score, _) = self.score_term_match(
+                    &result.memo,
+                    term,
+                    2.0,
+                    1.0,
+                    config,
+                    query.max_edits(config),
+                    query.field(),
+                );
+                if term_score <= 0.0 || term_score < config.snippet_min_term_score {
+                    continue;
+                }
                 if let Some(snippet) = self.extract_snippet_with_config(
                     &result.memo.content,
                     term,
@@ -525,37 +1408,48 @@ impl MemoSearcher {
         }
     }
 
+    #[allow(dead_code)]
     fn evaluate_boolean_term(&self, memo: &Memo, term: &SearchTerm) -> Option<f64> {
+        let mut patterns = CompiledPatterns::default();
+        let config = SearchConfig::default();
+        patterns
+            .add_wildcards(term, &config, &self.regex_cache)
+            .ok()?;
+        self.evaluate_compiled_term(memo, term, &config, &patterns, 0, None)
+    }
+
+    /// Scores `term` against `memo`, looking only in `field` when given.
+    fn evaluate_compiled_term(
+        &self,
+        memo: &Memo,
+        term: &SearchTerm,
+        config: &SearchConfig,
+        patterns: &CompiledPatterns,
+        max_edits: usize,
+        field: Option<SearchField>,
+    ) -> Option<f64> {
         match term {
-            SearchTerm::Word(word) => self.score_term_match_optional(memo, word, 2.0, 1.0),
-            SearchTerm::Phrase(phrase) => self.score_term_match_optional(memo, phrase, 3.0, 1.5),
+            SearchTerm::Word(word) => {
+                self.score_term_match_optional(memo, word, 2.0, 1.0, config, max_edits, field)
+            }
+            SearchTerm::Phrase(phrase) => {
+                self.score_term_match_optional(memo, phrase, 3.0, 1.5, config, 0, field)
+            }
             SearchTerm::Wildcard(pattern) => {
-                let regex_pattern = self.wildcard_to_regex(pattern);
-                match Regex::new(&regex_pattern) {
-                    Ok(regex) => {
-                        let search_text = format!("{} {}", memo.title, memo.content);
-                        if regex.is_match(&search_text) {
-                            Some(1.0)
-                        } else {
-                            None
-                        }
-                    }
-                    Err(e) => {
-                        warn!(
-                            "Failed to compile wildcard pattern '{}' as regex '{}': {}",
-                            pattern, regex_pattern, e
-                        );
-                        None
-                    }
-                }
+                let regex = patterns.wildcards.get(pattern)?;
+                let matched = (SearchField::includes_title(field) && regex.is_match(&memo.title))
+                    || (SearchField::includes_content(field) && regex.is_match(&memo.content));
+                matched.then_some(1.0)
             }
             SearchTerm::Boolean {
                 left,
                 operator,
                 right,
             } => {
-                let left_score = self.evaluate_boolean_term(memo, left);
-                let right_score = self.evaluate_boolean_term(memo, right);
+                let left_score =
+                    self.evaluate_compiled_term(memo, left, config, patterns, max_edits, field);
+                let right_score =
+                    self.evaluate_compiled_term(memo, right, config, patterns, max_edits, field);
 
                 match operator {
                     SearchOperator::And => match (left_score, right_score) {
@@ -577,75 +1471,191 @@ impl MemoSearcher {
                     }
                 }
             }
+            SearchTerm::Not(term) => self
+                .evaluate_compiled_term(memo, term, config, patterns, max_edits, field)
+                .is_none()
+                .then_some(UNSCORED_MATCH_SCORE),
+            SearchTerm::Field { field, term } => {
+                self.evaluate_compiled_term(memo, term, config, patterns, max_edits, Some(*field))
+            }
         }
     }
 
+    #[allow(dead_code)]
     fn wildcard_to_regex(&self, pattern: &str) -> String {
-        let mut regex = String::new();
-        regex.push_str("(?i)"); // Case insensitive
-
-        for ch in pattern.chars() {
-            match ch {
-                '*' => regex.push_str(".*"),
-                '?' => regex.push('.'),
-                c if c.is_alphanumeric() => regex.push(c),
-                c => {
-                    regex.push('\\');
-                    regex.push(c);
-                }
-            }
-        }
-
-        regex
+        wildcard_to_regex(pattern)
     }
 
-    /// Helper method to score a term match against a memo
+    /// Helper method to score a term match against a memo, looking only in
+    /// `field` when given
+    #[allow(clippy::too_many_arguments)]
     fn score_term_match(
         &self,
         memo: &Memo,
         term: &str,
         title_score: f64,
         content_score: f64,
+        config: &SearchConfig,
+        max_edits: usize,
+        field: Option<SearchField>,
     ) -> (f64, bool) {
         let term_lower = term.to_lowercase();
-        let title_lower = memo.title.to_lowercase();
-        let content_lower = memo.content.to_lowercase();
 
         let mut score = 0.0;
         let mut matches = false;
 
-        if title_lower.contains(&term_lower) {
-            score += title_score;
-            matches = true;
+        if SearchField::includes_title(field) {
+            let title_lower = memo.title.to_lowercase();
+            if let Some(weight) = match_weight(&title_lower, &term_lower, config, max_edits) {
+                score += title_score * config.title_boost * weight;
+                matches = true;
+            }
         }
 
-        if content_lower.contains(&term_lower) {
-            score += content_score;
-            matches = true;
+        if SearchField::includes_content(field) {
+            let content_lower = memo.content.to_lowercase();
+            if let Some(weight) = match_weight(&content_lower, &term_lower, config, max_edits) {
+                score += content_score * config.content_boost * weight;
+                matches = true;
+            }
         }
 
         (score, matches)
     }
 
-    /// Helper method to score a term match and return Option<f64> for boolean evaluation
+    /// Helper method to score a term match and return Option<f64> for boolean
+    /// evaluation, looking only in `field` when given
+    #[allow(clippy::too_many_arguments)]
     fn score_term_match_optional(
         &self,
         memo: &Memo,
         term: &str,
         title_score: f64,
         content_score: f64,
+        config: &SearchConfig,
+        max_edits: usize,
+        field: Option<SearchField>,
     ) -> Option<f64> {
         let term_lower = term.to_lowercase();
-        let title_lower = memo.title.to_lowercase();
-        let content_lower = memo.content.to_lowercase();
+        let title_match = || {
+            SearchField::includes_title(field)
+                .then(|| match_weight(&memo.title.to_lowercase(), &term_lower, config, max_edits))
+                .flatten()
+                .map(|weight| title_score * config.title_boost * weight)
+        };
+        let content_match = || {
+            SearchField::includes_content(field)
+                .then(|| match_weight(&memo.content.to_lowercase(), &term_lower, config, max_edits))
+                .flatten()
+                .map(|weight| content_score * config.content_boost * weight)
+        };
 
-        if title_lower.contains(&term_lower) {
-            Some(title_score)
-        } else if content_lower.contains(&term_lower) {
-            Some(content_score)
-        } else {
-            None
+        title_match().or_else(content_match)
+    }
+}
+
+/// How much of a term's score lowercase `text` earns: all of it for a match
+/// per [`text_matches`], [`FUZZY_MATCH_WEIGHT`] of it for a word within
+/// `max_edits` edits of the term, and none otherwise.
+fn match_weight(text: &str, term: &str, config: &SearchConfig, max_edits: usize) -> Option<f64> {
+    if text_matches(text, term, config.enable_stemming) {
+        Some(1.0)
+    } else if fuzzy_matches(text, term, max_edits) {
+        Some(FUZZY_MATCH_WEIGHT)
+    } else {
+        None
+    }
+}
+
+/// Whether lowercase `text` has a word within `max_edits` edits of a
+/// single-word `term`. Terms of `max_edits` characters or fewer never match
+/// fuzzily, since nearly any short word would.
+fn fuzzy_matches(text: &str, term: &str, max_edits: usize) -> bool {
+    if max_edits == 0 || term.contains(char::is_whitespace) {
+        return false;
+    }
+    let term = term.trim_matches(|c: char| !c.is_alphanumeric());
+    term.chars().count() > max_edits
+        && text
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| !word.is_empty() && within_edit_distance(word, term, max_edits))
+}
+
+/// Whether the Levenshtein distance between `a` and `b` is at most `max`.
+fn within_edit_distance(a: &str, b: &str, max: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, &a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        // Distances never shrink from one row to the next
+        if current.iter().min().is_some_and(|&min| min > max) {
+            return false;
+        }
+        previous = current;
+    }
+    previous[b.len()] <= max
+}
+
+/// Whether lowercase `text` contains lowercase `term`, or, with `stemming`,
+/// a word with the same stem as a single-word `term`.
+fn text_matches(text: &str, term: &str, stemming: bool) -> bool {
+    if text.contains(term) {
+        return true;
+    }
+    if !stemming || term.contains(char::is_whitespace) {
+        return false;
+    }
+    let term_stem = stem(term.trim_matches(|c: char| !c.is_alphanumeric()));
+    !term_stem.is_empty()
+        && text
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| !word.is_empty() && stem(word) == term_stem)
+}
+
+impl MemoSearcher {
+    /// Finds memos whose content mentions `path`.
+    ///
+    /// Both the path and memo lines are normalized with [`normalize_path_text`], so
+    /// `src\foo.rs`, `./src/foo.rs` and `src/foo.rs` all match each other. A match must
+    /// not be embedded in a longer identifier (`src/foo.rsx` does not mention `src/foo.rs`).
+    /// Nor may it be the tail of a longer path (`lib/src/foo.rs` does not either).
+    pub fn find_path_mentions(&self, path: &str, memos: &[Memo]) -> Vec<PathMention> {
+        let needle = normalize_path_text(path.trim());
+        let needle = needle.strip_prefix("./").unwrap_or(&needle);
+        if needle.is_empty() {
+            return Vec::new();
         }
+
+        memos
+            .iter()
+            .filter_map(|memo| {
+                let lines: Vec<PathMentionLine> = memo
+                    .content
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| contains_path(&normalize_path_text(line), needle))
+                    .map(|(index, line)| PathMentionLine {
+                        line_number: index + 1,
+                        text: line.to_string(),
+                        exact: line.contains(path.trim()),
+                    })
+                    .collect();
+
+                (!lines.is_empty()).then(|| PathMention {
+                    memo: memo.clone(),
+                    lines,
+                })
+            })
+            .collect()
     }
 }
 
@@ -655,6 +1665,52 @@ impl Default for MemoSearcher {
     }
 }
 
+/// Copies the index; the copy compiles its own patterns as queries need them.
+impl Clone for MemoSearcher {
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index.clone(),
+            fingerprints: self.fingerprints.clone(),
+            memo_tokens: self.memo_tokens.clone(),
+            enable_stemming: self.enable_stemming,
+            stop_words: self.stop_words.clone(),
+            ..Self::new()
+        }
+    }
+}
+
+/// Normalizes path separators for matching: backslashes become forward slashes
+/// and repeated separators collapse to one.
+pub fn normalize_path_text(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        let c = if c == '\\' { '/' } else { c };
+        if c == '/' && normalized.ends_with('/') {
+            continue;
+        }
+        normalized.push(c);
+    }
+    normalized
+}
+
+/// Returns true if `needle` occurs in `haystack` without being part of a longer
+/// word or the tail of a longer path, optionally after a leading `./`.
+fn contains_path(haystack: &str, needle: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let is_path_char = |c: char| is_word_char(c) || matches!(c, '/' | '.' | '-');
+
+    haystack.match_indices(needle).any(|(start, _)| {
+        let prefix = &haystack[..start];
+        let before = prefix
+            .strip_suffix("./")
+            .unwrap_or(prefix)
+            .chars()
+            .next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(is_path_char) && !after.is_some_and(is_word_char)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -671,6 +1727,26 @@ mod tests {
         memo
     }
 
+    #[test]
+    fn test_fingerprints_are_stable() {
+        // Published FNV-1a test vectors
+        let mut hasher = StableHasher::new();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        // Saved indexes depend on these exact values
+        let memo = create_test_memo_with_tags("Title", "Body", vec!["tag".to_string()]);
+        assert_eq!(MemoSearcher::fingerprint(&memo), 0xb4f2_069d_f7d5_643c);
+
+        // Text moving between fields changes the fingerprint
+        let moved = create_test_memo_with_tags("TitleB", "ody", vec!["tag".to_string()]);
+        assert_ne!(
+            MemoSearcher::fingerprint(&moved),
+            MemoSearcher::fingerprint(&memo)
+        );
+    }
+
     #[test]
     fn test_search_query_creation() {
         let query = SearchQuery::new();
@@ -722,7 +1798,7 @@ mod tests {
         let result1 = SearchResult::new(memo1, 1.0);
         let result2 = SearchResult::new(memo2, 2.0);
 
-        let mut results = vec![result1, result2];
+        let mut results = [result1, result2];
         results.sort();
 
         assert_eq!(results[0].score, 2.0);
@@ -747,6 +1823,32 @@ mod tests {
         assert!(searcher.index.contains_key("content"));
     }
 
+    #[test]
+    fn test_memo_searcher_remove_and_reindex_memo() {
+        let mut searcher = MemoSearcher::new();
+        let kept = create_test_memo("Shared", "apple");
+        let mut changed = create_test_memo("Shared", "banana");
+        searcher.index_memo(&kept);
+        searcher.index_memo(&changed);
+
+        changed.content = "cherry".to_string();
+        searcher.index_memo(&changed);
+        assert_eq!(searcher.index["shared"], vec![kept.id, changed.id]);
+        assert!(!searcher.index.contains_key("banana"));
+        assert_eq!(searcher.index["cherry"], vec![changed.id]);
+
+        searcher.remove_memo(&changed.id);
+        assert_eq!(searcher.index["shared"], vec![kept.id]);
+        assert!(!searcher.index.contains_key("cherry"));
+        assert_eq!(searcher.indexed_fingerprint(&changed.id), None);
+
+        // A searcher rebuilt from saved parts can still remove memos
+        let (index, fingerprints) = searcher.index_parts();
+        let mut restored = MemoSearcher::from_index_parts(index.clone(), fingerprints.clone());
+        restored.remove_memo(&kept.id);
+        assert!(restored.index.is_empty());
+    }
+
     #[test]
     fn test_memo_searcher_search_terms() {
         let mut searcher = MemoSearcher::new();
@@ -848,6 +1950,56 @@ mod tests {
         assert!(snippet.ends_with("..."));
     }
 
+    #[test]
+    fn test_snippets_only_for_strongly_matching_terms() {
+        let searcher = MemoSearcher::new();
+        // "rust" matches title and content (3.0); "cargo" only content (1.0)
+        let content = format!(
+            "Use rust daily. {} Builds run through cargo.",
+            "x".repeat(80)
+        );
+        let memos = [create_test_memo("Rust Notes", &content)];
+        let query = SearchQuery::with_terms(vec!["rust".to_string(), "cargo".to_string()]);
+        let lenient = SearchConfig {
+            snippet_length: 20,
+            ..Default::default()
+        };
+        let strict = SearchConfig {
+            snippet_min_term_score: 2.0,
+            ..lenient.clone()
+        };
+
+        let results = searcher
+            .search_with_config(&query, &memos, &lenient)
+            .unwrap();
+        assert_eq!(results[0].snippets.len(), 2);
+
+        let results = searcher
+            .search_with_config(&query, &memos, &strict)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippets.len(), 1);
+        assert!(results[0].snippets[0].contains("rust"));
+        assert!(!results[0].snippets[0].contains("cargo"));
+    }
+
+    #[test]
+    fn test_no_snippets_below_relevance_floor() {
+        let searcher = MemoSearcher::new();
+        let memo = create_test_memo("Notes", "A passing mention of rust.");
+        let query = SearchQuery::with_terms(vec!["rust".to_string()]);
+        let config = SearchConfig {
+            snippet_relevance_floor: 100.0,
+            ..Default::default()
+        };
+
+        let results = searcher
+            .search_with_config(&query, &[memo], &config)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].snippets.is_empty());
+    }
+
     #[test]
     fn test_score_memo_with_title_match() {
         let searcher = MemoSearcher::new();
@@ -960,6 +2112,338 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_search_query_parse_boolean_precedence_and_groups() {
+        fn word(w: &str) -> SearchTerm {
+            SearchTerm::Word(w.to_string())
+        }
+        fn boolean(left: SearchTerm, operator: SearchOperator, right: SearchTerm) -> SearchTerm {
+            SearchTerm::Boolean {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            }
+        }
+
+        // NOT binds tighter than AND, which binds tighter than OR
+        let query = SearchQuery::parse_query("rust AND (async OR tokio) NOT deprecated");
+        assert_eq!(
+            query.boolean_query,
+            Some(boolean(
+                word("rust"),
+                SearchOperator::And,
+                boolean(
+                    boolean(word("async"), SearchOperator::Or, word("tokio")),
+                    SearchOperator::Not,
+                    word("deprecated"),
+                ),
+            ))
+        );
+        assert_eq!(
+            SearchQuery::parse_query("a AND b OR c").boolean_query,
+            Some(boolean(
+                boolean(word("a"), SearchOperator::And, word("b")),
+                SearchOperator::Or,
+                word("c"),
+            ))
+        );
+        assert_eq!(
+            SearchQuery::parse_query("a OR b AND c").boolean_query,
+            Some(boolean(
+                word("a"),
+                SearchOperator::Or,
+                boolean(word("b"), SearchOperator::And, word("c")),
+            ))
+        );
+        assert_eq!(
+            SearchQuery::parse_query("(a OR b) AND c").boolean_query,
+            Some(boolean(
+                boolean(word("a"), SearchOperator::Or, word("b")),
+                SearchOperator::And,
+                word("c"),
+            ))
+        );
+
+        // Operators inside quotes are part of the phrase
+        assert_eq!(
+            SearchQuery::parse_query("\"salt AND pepper\" OR spice").boolean_query,
+            Some(boolean(
+                SearchTerm::Phrase("salt AND pepper".to_string()),
+                SearchOperator::Or,
+                word("spice"),
+            ))
+        );
+
+        // Unbalanced parentheses are tolerated rather than rejected
+        assert_eq!(
+            SearchQuery::parse_query("(a OR b AND c").boolean_query,
+            SearchQuery::parse_query("a OR b AND c").boolean_query
+        );
+        assert_eq!(
+            SearchQuery::parse_query("a OR b) AND c").boolean_query,
+            Some(boolean(
+                boolean(word("a"), SearchOperator::Or, word("b")),
+                SearchOperator::And,
+                word("c"),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_standalone_not_excludes_term() {
+        let not = |term: SearchTerm| SearchTerm::Not(Box::new(term));
+        let word = |w: &str| SearchTerm::Word(w.to_string());
+        assert_eq!(
+            SearchQuery::parse_query("NOT experimental").boolean_query,
+            Some(not(word("experimental")))
+        );
+        assert_eq!(
+            SearchQuery::parse_query("rust AND NOT experimental").boolean_query,
+            Some(SearchTerm::Boolean {
+                left: Box::new(word("rust")),
+                operator: SearchOperator::And,
+                right: Box::new(not(word("experimental"))),
+            })
+        );
+
+        let searcher = MemoSearcher::new();
+        let mut stable = create_test_memo("Stable API", "Supported for years");
+        stable.created_at = Utc::now() - chrono::Duration::days(400);
+        let recent = create_test_memo("Release plan", "Ship the stable build");
+        let flagged = create_test_memo("New parser", "Still experimental, do not use");
+        let memos = vec![stable.clone(), flagged, recent.clone()];
+
+        let results = searcher.search(&SearchQuery::parse_query("NOT experimental"), &memos);
+        let ids: Vec<_> = results.iter().map(|result| result.memo.id).collect();
+        // Every memo without the term, newest first
+        assert_eq!(ids, vec![recent.id, stable.id]);
+        assert!(results[0].score > results[1].score);
+        assert!(results[1].score > 0.0);
+
+        let results = searcher.search(
+            &SearchQuery::parse_query("stable AND NOT experimental"),
+            &memos,
+        );
+        assert_eq!(results.len(), 2);
+        let results = searcher.search(&SearchQuery::parse_query("stable NOT ship"), &memos);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, stable.id);
+    }
+
+    #[test]
+    fn test_search_query_parse_date_filters() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let start = |y, m, d| Some(day(y, m, d).and_hms_opt(0, 0, 0).unwrap().and_utc());
+        let end = |y, m, d| {
+            Some(
+                day(y, m, d)
+                    .and_hms_nano_opt(23, 59, 59, 999_999_999)
+                    .unwrap()
+                    .and_utc(),
+            )
+        };
+
+        let query = SearchQuery::parse_query("rust after:2024-01-01 async");
+        assert_eq!(query.terms, vec!["rust", "async"]);
+        assert_eq!(query.date_from, start(2024, 1, 1));
+        assert_eq!(query.date_to, None);
+
+        let query = SearchQuery::parse_query("before:2024-06-30 rust OR go");
+        assert_eq!(query.date_to, end(2024, 6, 30));
+        assert!(query.boolean_query.is_some());
+
+        let query = SearchQuery::parse_query("created:2024-01-01..2024-06-30");
+        assert_eq!(query.date_from, start(2024, 1, 1));
+        assert_eq!(query.date_to, end(2024, 6, 30));
+        let query = SearchQuery::parse_query("created:2024-03-05");
+        assert_eq!(query.date_from, start(2024, 3, 5));
+        assert_eq!(query.date_to, end(2024, 3, 5));
+        let query = SearchQuery::parse_query("created:..2024-06-30T12:00:00Z");
+        assert_eq!(query.date_from, None);
+        assert_eq!(
+            query.date_to,
+            Some("2024-06-30T12:00:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+
+        // Unparseable dates are dropped without failing the query
+        let query = SearchQuery::parse_query("rust after:yesterday");
+        assert_eq!(query.terms, vec!["rust"]);
+        assert_eq!(query.date_from, None);
+    }
+
+    #[test]
+    fn test_search_with_date_filters() {
+        let at = |y, m, d| {
+            NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut old = create_test_memo("Rust 2023", "Old rust notes");
+        old.created_at = at(2023, 11, 2);
+        let mut new = create_test_memo("Rust 2024", "New rust notes");
+        new.created_at = at(2024, 2, 14);
+        let mut other = create_test_memo("Go 2024", "Go notes");
+        other.created_at = at(2024, 3, 1);
+        let memos = vec![old.clone(), new.clone(), other.clone()];
+        let searcher = MemoSearcher::new();
+
+        let results = searcher.search(&SearchQuery::parse_query("rust after:2024-01-01"), &memos);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, new.id);
+        assert_eq!(
+            searcher
+                .search(&SearchQuery::parse_query("rust"), &memos)
+                .len(),
+            2
+        );
+
+        // Dates alone match every memo in range, newest first
+        let results = searcher.search(
+            &SearchQuery::parse_query("created:2024-01-01..2024-12-31"),
+            &memos,
+        );
+        let ids: Vec<_> = results.iter().map(|result| result.memo.id).collect();
+        assert_eq!(ids, vec![other.id, new.id]);
+        let results = searcher.search(&SearchQuery::parse_query("before:2024-02-14"), &memos);
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_search_query_parse_field_operators() {
+        let field = |field, term| SearchTerm::Field {
+            field,
+            term: Box::new(term),
+        };
+
+        let query = SearchQuery::parse_query("tag:rust title:tokio");
+        assert_eq!(query.tags, vec!["rust"]);
+        assert!(query.terms.is_empty());
+        assert_eq!(
+            query.boolean_query,
+            Some(field(
+                SearchField::Title,
+                SearchTerm::Word("tokio".to_string())
+            ))
+        );
+
+        let query = SearchQuery::parse_query(r#"content:"worker pool" runtime"#);
+        assert_eq!(
+            query.boolean_query,
+            Some(SearchTerm::Boolean {
+                left: Box::new(field(
+                    SearchField::Content,
+                    SearchTerm::Phrase("worker pool".to_string())
+                )),
+                operator: SearchOperator::And,
+                right: Box::new(SearchTerm::Word("runtime".to_string())),
+            })
+        );
+
+        let query = SearchQuery::parse_query("title:tokio OR title:async-std");
+        assert!(matches!(
+            query.boolean_query,
+            Some(SearchTerm::Boolean {
+                operator: SearchOperator::Or,
+                ..
+            })
+        ));
+
+        // An empty `tag:` is dropped, and an empty `title:` searched as a word
+        let query = SearchQuery::parse_query("tag: title:");
+        assert!(query.tags.is_empty());
+        assert_eq!(query.terms, vec!["title:"]);
+    }
+
+    #[test]
+    fn test_search_with_field_operators() {
+        let tagged = |title: &str, content: &str, tag: &str| {
+            create_test_memo_with_tags(title, content, vec![tag.to_string()])
+        };
+        let tokio = tagged("Tokio runtime", "Spawning tasks", "rust");
+        let body_only = tagged("Async notes", "We use tokio here", "rust");
+        let untagged = tagged("Tokio in Python?", "Asyncio comparison", "python");
+        let memos = vec![tokio.clone(), body_only.clone(), untagged];
+        let searcher = MemoSearcher::new();
+        let ids = |query: &str| -> Vec<_> {
+            searcher
+                .search(&SearchQuery::parse_query(query), &memos)
+                .iter()
+                .map(|result| result.memo.id)
+                .collect()
+        };
+
+        assert_eq!(ids("tag:rust title:tokio"), vec![tokio.id]);
+        assert_eq!(ids("tag:rust content:tokio"), vec![body_only.id]);
+        assert_eq!(ids("tag:rust tokio").len(), 2);
+        assert_eq!(ids("title:tokio").len(), 2);
+        assert_eq!(ids("tag:rust").len(), 2);
+        assert_eq!(ids("title:tokio NOT content:asyncio"), vec![tokio.id]);
+    }
+
+    #[test]
+    fn test_search_title_only_and_content_only() {
+        let searcher = MemoSearcher::new();
+        let in_title = create_test_memo("Tokio runtime", "Spawning tasks");
+        let in_content = create_test_memo("Async notes", "We use tokio here");
+        let memos = vec![in_title.clone(), in_content.clone()];
+        let ids = |query: &str, title_only: bool, content_only: bool| -> Vec<_> {
+            let mut query = SearchQuery::parse_query(query);
+            query.title_only = title_only;
+            query.content_only = content_only;
+            searcher
+                .search(&query, &memos)
+                .iter()
+                .map(|result| result.memo.id)
+                .collect()
+        };
+
+        assert_eq!(ids("tokio", false, false).len(), 2);
+        assert_eq!(ids("tokio", true, false), vec![in_title.id]);
+        assert_eq!(ids("tokio", false, true), vec![in_content.id]);
+        assert_eq!(ids("\"use tokio\"", true, false), Vec::<MemoId>::new());
+        assert_eq!(ids("tokio OR nothing", false, true), vec![in_content.id]);
+        assert_eq!(ids("tok*", true, false), vec![in_title.id]);
+        // An explicit field operator still applies
+        assert_eq!(ids("content:tasks", true, false), vec![in_title.id]);
+        // Title wins when both are set
+        assert_eq!(ids("tokio", true, true), vec![in_title.id]);
+
+        let mut query = SearchQuery::parse_query("tokio");
+        query.title_only = true;
+        let results = searcher.search(&query, &memos);
+        assert!(results[0].snippets.is_empty());
+    }
+
+    #[test]
+    fn test_format_search_digest() {
+        let mut first = SearchResult::new(create_test_memo("Deploy", "Steps"), 4.5);
+        first.snippets = vec!["...deploy on Fridays...".to_string()];
+        let second = SearchResult::new(create_test_memo("Release notes", "Notes"), 1.25);
+
+        assert_eq!(
+            format_search_digest(&[first, second]),
+            "## Deploy (4.50)\n...deploy on Fridays...\n\n## Release notes (1.25)\n"
+        );
+        assert_eq!(format_search_digest(&[]), NO_MATCHING_MEMOS);
+    }
+
+    #[test]
+    fn test_memo_searcher_boolean_grouped_search() {
+        let searcher = MemoSearcher::new();
+        let tokio = create_test_memo("Tokio runtime", "Rust async with tokio");
+        let threads = create_test_memo("Threads", "Rust concurrency with threads");
+        let old = create_test_memo("Old futures", "Rust async, deprecated API");
+        let python = create_test_memo("Asyncio", "Python async tokio-like loop");
+        let memos = vec![tokio.clone(), threads, old, python];
+
+        let query = SearchQuery::parse_query("rust AND (async OR tokio) NOT deprecated");
+        let results = searcher.search(&query, &memos);
+        let ids: Vec<_> = results.iter().map(|result| result.memo.id).collect();
+        assert_eq!(ids, vec![tokio.id]);
+    }
+
     #[test]
     fn test_memo_searcher_boolean_and_search() {
         let searcher = MemoSearcher::new();
@@ -974,6 +2458,105 @@ mod tests {
         assert_eq!(results[0].memo.id, memo1.id);
     }
 
+    #[test]
+    fn test_stemming_and_stop_words() {
+        let runner = create_test_memo("Morning", "She runs along the river");
+        let plan = create_test_memo("The plan", "Nothing about exercise");
+        let memos = vec![runner.clone(), plan.clone()];
+        let searcher = MemoSearcher::new();
+        let config = SearchConfig {
+            enable_stemming: true,
+            stop_words: HashSet::from(["the".to_string()]),
+            ..SearchConfig::default()
+        };
+
+        // Stemming lines "running" up with "runs"; the default does not
+        let running = SearchQuery::parse_query("running");
+        assert!(searcher.search(&running, &memos).is_empty());
+        let results = searcher
+            .search_with_config(&running, &memos, &config)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, runner.id);
+
+        // Stop words neither match nor score
+        let query = SearchQuery::parse_query("the plan");
+        assert_eq!(searcher.search(&query, &memos).len(), 2);
+        let results = searcher
+            .search_with_config(&query, &memos, &config)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, plan.id);
+        let boolean = SearchQuery::parse_query("the AND river");
+        let results = searcher
+            .search_with_config(&boolean, &memos, &config)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, runner.id);
+
+        // The index is tokenized the same way
+        let mut indexed = MemoSearcher::new().with_config(&config);
+        indexed.index_memo(&runner);
+        assert!(indexed.index.contains_key("run"));
+        assert!(indexed.index.contains_key("river"));
+        assert!(!indexed.index.contains_key("the"));
+        assert_ne!(
+            indexed.tokenizer_fingerprint(),
+            MemoSearcher::new().tokenizer_fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_search_tolerates_typos() {
+        let exact = create_test_memo("Progamming notes", "Typo in the title");
+        let typo = create_test_memo("Rust", "Systems programming with ownership");
+        let memos = vec![typo.clone(), exact.clone()];
+        let searcher = MemoSearcher::new();
+        let config = SearchConfig {
+            max_edit_distance: 1,
+            ..SearchConfig::default()
+        };
+
+        let query = SearchQuery::parse_query("progamming~");
+        assert!(query.fuzzy);
+        assert_eq!(query.terms, vec!["progamming"]);
+
+        // The exact hit ranks above the one found only by fuzzy matching
+        let results = searcher
+            .search_with_config(&query, &memos, &config)
+            .unwrap();
+        let ids: Vec<_> = results.iter().map(|result| result.memo.id).collect();
+        assert_eq!(ids, vec![exact.id, typo.id]);
+
+        // Without `~`, or with the default distance of 0, matching is exact
+        let exact_query = SearchQuery::parse_query("progamming");
+        assert!(!exact_query.fuzzy);
+        assert_eq!(
+            searcher
+                .search_with_config(&exact_query, &memos, &config)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(searcher.search(&query, &memos).len(), 1);
+
+        // Boolean terms take `~` too
+        let boolean = SearchQuery::parse_query("ownership AND progamming~");
+        assert!(boolean.fuzzy);
+        let results = searcher
+            .search_with_config(&boolean, &memos, &config)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, typo.id);
+
+        assert!(within_edit_distance("progamming", "programming", 1));
+        assert!(!within_edit_distance("progamming", "programing", 1));
+        assert!(within_edit_distance("kitten", "sitting", 3));
+        assert!(!within_edit_distance("kitten", "sitting", 2));
+        // Terms no longer than the distance only match exactly
+        assert!(!fuzzy_matches("rust is fun", "x", 1));
+    }
+
     #[test]
     fn test_memo_searcher_boolean_or_search() {
         let searcher = MemoSearcher::new();
@@ -1006,6 +2589,107 @@ mod tests {
         assert_eq!(results[0].memo.id, memo1.id);
     }
 
+    #[test]
+    fn test_patterns_compile_once_per_search() {
+        let searcher = MemoSearcher::new();
+        let memos: Vec<Memo> = (0..100)
+            .map(|i| create_test_memo(&format!("Memo {i}"), &format!("rust note {i}")))
+            .collect();
+        let config = SearchConfig::default();
+
+        let mut query = SearchQuery::new();
+        query.regex = Some(r"note \d+".to_string());
+        query.boolean_query = SearchQuery::parse_boolean_query("rus* OR pyth?n");
+        REGEX_COMPILATIONS.with(|count| count.set(0));
+
+        let results = searcher
+            .search_with_config(&query, &memos, &config)
+            .unwrap();
+        assert_eq!(results.len(), 100);
+        // One regex plus two wildcard terms, regardless of the number of memos
+        assert_eq!(REGEX_COMPILATIONS.with(|count| count.get()), 3);
+
+        // Repeating the query reuses the compiled patterns
+        searcher
+            .search_with_config(&query, &memos, &config)
+            .unwrap();
+        assert_eq!(REGEX_COMPILATIONS.with(|count| count.get()), 3);
+
+        // Different size limits compile the regex again
+        let tighter = SearchConfig {
+            regex_size_limit: config.regex_size_limit / 2,
+            ..config.clone()
+        };
+        searcher
+            .search_with_config(&query, &memos, &tighter)
+            .unwrap();
+        assert_eq!(REGEX_COMPILATIONS.with(|count| count.get()), 6);
+    }
+
+    #[test]
+    fn test_oversized_and_invalid_patterns_are_rejected() {
+        let searcher = MemoSearcher::new();
+        let memos = vec![create_test_memo("Rust", "rust")];
+        let config = SearchConfig::default();
+
+        let mut query = SearchQuery::new();
+        query.regex = Some("a".repeat(10_000));
+        REGEX_COMPILATIONS.with(|count| count.set(0));
+        let error = searcher
+            .search_with_config(&query, &memos, &config)
+            .unwrap_err();
+        assert!(error.reason.contains("10000 characters"), "{error}");
+        assert_eq!(error.pattern.len(), PATTERN_ERROR_EXCERPT);
+        assert_eq!(REGEX_COMPILATIONS.with(|count| count.get()), 0);
+
+        query.regex = Some("(unclosed".to_string());
+        assert!(searcher
+            .search_with_config(&query, &memos, &config)
+            .is_err());
+
+        // A pattern within the length cap can still blow the compiled size limit
+        query.regex = Some(r"\w{1000}".to_string());
+        let tight = SearchConfig {
+            regex_size_limit: 1024,
+            ..SearchConfig::default()
+        };
+        let error = searcher
+            .search_with_config(&query, &memos, &tight)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("Invalid search pattern"));
+
+        let query = SearchQuery::parse_query(&format!("{}*", "x".repeat(2000)));
+        assert!(searcher
+            .search_with_config(&query, &memos, &config)
+            .is_err());
+        assert!(searcher.search(&query, &memos).is_empty());
+    }
+
+    #[test]
+    fn test_pathological_patterns_are_rejected_quickly() {
+        let searcher = MemoSearcher::new();
+        let memos: Vec<_> = (0..200)
+            .map(|i| create_test_memo(&format!("Memo {i}"), &"word ".repeat(200)))
+            .collect();
+        let config = SearchConfig::default();
+
+        // Short enough to pass the length check, but its compiled program would
+        // be hundreds of megabytes
+        let mut query = SearchQuery::new();
+        query.regex = Some(r"(?:\w{100}){100}".to_string());
+        let started = std::time::Instant::now();
+        let error = searcher
+            .search_with_config(&query, &memos, &config)
+            .unwrap_err();
+        assert!(error.reason.contains("size limit"), "{error}");
+
+        let query = SearchQuery::parse_query(&format!("{}*", "ab?".repeat(500)));
+        assert!(searcher
+            .search_with_config(&query, &memos, &config)
+            .is_err());
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    }
+
     #[test]
     fn test_wildcard_to_regex() {
         let searcher = MemoSearcher::new();
@@ -1048,4 +2732,54 @@ mod tests {
         assert!(score.is_some());
         assert_eq!(score.unwrap(), 1.0); // Wildcard match
     }
+
+    #[test]
+    fn test_normalize_path_text() {
+        assert_eq!(
+            normalize_path_text("src\\memo\\storage.rs"),
+            "src/memo/storage.rs"
+        );
+        assert_eq!(
+            normalize_path_text("src//memo///storage.rs"),
+            "src/memo/storage.rs"
+        );
+        assert_eq!(normalize_path_text("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_find_path_mentions_across_slash_styles() {
+        let searcher = MemoSearcher::new();
+        let memos = vec![
+            create_test_memo("Unix", "Intro\nSee src/memo/storage.rs for details"),
+            create_test_memo("Windows", "Edited src\\memo\\storage.rs today"),
+            create_test_memo("Relative", "Look at ./src/memo/storage.rs"),
+            create_test_memo("Other", "Nothing about src/memo/storage.rsx here"),
+            create_test_memo("Nested", "Vendored in lib/src/memo/storage.rs"),
+            create_test_memo(
+                "Parent",
+                "Or ../src/memo/storage.rs and my-src/memo/storage.rs",
+            ),
+        ];
+
+        let mentions = searcher.find_path_mentions("src/memo/storage.rs", &memos);
+        let titles: Vec<&str> = mentions.iter().map(|m| m.memo.title.as_str()).collect();
+        assert_eq!(titles, vec!["Unix", "Windows", "Relative"]);
+
+        assert_eq!(mentions[0].lines.len(), 1);
+        assert_eq!(mentions[0].lines[0].line_number, 2);
+        assert!(mentions[0].lines[0].exact);
+        assert!(!mentions[1].lines[0].exact);
+
+        // A backslash query matches forward-slash mentions too
+        let mentions = searcher.find_path_mentions("src\\memo\\storage.rs", &memos);
+        assert_eq!(mentions.len(), 3);
+        assert!(mentions[1].lines[0].exact);
+    }
+
+    #[test]
+    fn test_find_path_mentions_empty_path() {
+        let searcher = MemoSearcher::new();
+        let memos = vec![create_test_memo("Memo", "src/lib.rs")];
+        assert!(searcher.find_path_mentions("  ", &memos).is_empty());
+    }
 }
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+
+use super::search::{SearchQuery, SearchResult};
+
+/// A search as seen by the cache: the normalized query and boosts plus the
+/// index version it ran against and the state of the memo files, so results
+/// from before a mutation, in this process or on disk, can never be returned.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchCacheKey {
+    pub index_version: u64,
+    /// Hash of every memo file's path, size and modification time.
+    pub files: u64,
+    pub query: SearchQuery,
+    /// The per-search boosts, as bit patterns.
+    pub boosts: [Option<u64>; 3],
+}
+
+#[derive(Debug)]
+struct CacheEntries<K, V> {
+    /// Incremented on every access; entries remember when they were last used.
+    clock: u64,
+    entries: HashMap<K, (u64, V)>,
+}
+
+/// Least-recently-used cache shared behind `&self`, used for search results
+/// and for compiled search patterns.
+#[derive(Debug)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    inner: Mutex<CacheEntries<K, V>>,
+}
+
+/// Search results by normalized query and index version.
+pub type SearchResultCache = LruCache<SearchCacheKey, Vec<SearchResult>>;
+
+impl<K: Eq + Hash + Clone, V: Clone> LruCache<K, V> {
+    /// A zero capacity caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(CacheEntries {
+                clock: 0,
+                entries: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let now = inner.clock;
+        inner.entries.get_mut(key).map(|(last_used, value)| {
+            *last_used = now;
+            value.clone()
+        })
+    }
+
+    /// Stores a value, evicting the least recently used entry when full.
+    pub fn insert(&self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if inner.entries.len() >= self.capacity && !inner.entries.contains_key(&key) {
+            let oldest = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                inner.entries.remove(&oldest);
+            }
+        }
+        inner.clock += 1;
+        let now = inner.clock;
+        inner.entries.insert(key, (now, value));
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(term: &str) -> SearchCacheKey {
+        SearchCacheKey {
+            index_version: 0,
+            files: 0,
+            query: SearchQuery::with_terms(vec![term.to_string()]),
+            boosts: [None; 3],
+        }
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let cache = SearchResultCache::new(2);
+        cache.insert(key("a"), Vec::new());
+        cache.insert(key("b"), Vec::new());
+        assert!(cache.get(&key("a")).is_some());
+
+        cache.insert(key("c"), Vec::new());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&key("b")).is_none());
+        assert!(cache.get(&key("a")).is_some());
+        assert!(cache.get(&key("c")).is_some());
+
+        let disabled = SearchResultCache::new(0);
+        disabled.insert(key("a"), Vec::new());
+        assert!(disabled.is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tracing::{debug, warn};
+
+use super::models::{Memo, MemoId};
+use super::search::{MemoSearcher, SearchConfig};
+use super::storage::write_temp_path;
+
+/// File (inside the primary `.memoranda` directory) the search index is saved to.
+pub const SEARCH_INDEX_FILE_NAME: &str = ".search-index.json";
+
+/// Bumped whenever the saved layout or tokenization changes, so older files
+/// are rebuilt instead of trusted.
+const SEARCH_INDEX_FORMAT: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct SavedIndex<'a> {
+    format: u32,
+    index: Cow<'a, HashMap<String, Vec<MemoId>>>,
+    fingerprints: Cow<'a, HashMap<MemoId, u64>>,
+    /// [`MemoSearcher::tokenizer_fingerprint`] of the saved index.
+    #[serde(default)]
+    tokenizer: u64,
+    /// Modification time of each memo's file when it was indexed, in
+    /// milliseconds since the epoch.
+    modified: HashMap<MemoId, u64>,
+}
+
+/// Modification time of a memo's file in milliseconds since the epoch.
+fn modified_millis(memo: &Memo) -> Option<u64> {
+    let modified = fs::metadata(memo.file_path.as_ref()?)
+        .ok()?
+        .modified()
+        .ok()?;
+    u64::try_from(modified.duration_since(UNIX_EPOCH).ok()?.as_millis()).ok()
+}
+
+/// Writes `searcher`'s index of `memos` to `.search-index.json`.
+pub fn save_search_index(
+    searcher: &MemoSearcher,
+    memos: &[Memo],
+    memoranda_dir: &Path,
+) -> std::io::Result<()> {
+    let (index, fingerprints) = searcher.index_parts();
+    let saved = SavedIndex {
+        format: SEARCH_INDEX_FORMAT,
+        index: Cow::Borrowed(index),
+        fingerprints: Cow::Borrowed(fingerprints),
+        tokenizer: searcher.tokenizer_fingerprint(),
+        modified: memos
+            .iter()
+            .filter_map(|memo| Some((memo.id, modified_millis(memo)?)))
+            .collect(),
+    };
+    // Written beside the index and renamed over it, so a crash mid-write
+    // never leaves a truncated index behind
+    let path = memoranda_dir.join(SEARCH_INDEX_FILE_NAME);
+    let temp_path = write_temp_path(&path);
+    fs::write(&temp_path, serde_json::to_vec(&saved)?)
+        .and_then(|()| fs::rename(&temp_path, &path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            e
+        })
+}
+
+/// Loads `.search-index.json` as an index of `memos`, tokenized as `config` says.
+///
+/// Entries for memos that are gone, or whose file changed since it was
+/// indexed, are dropped and those memos indexed afresh. Returns `None` when
+/// there is no usable saved index, including one built with different
+/// stemming or stop words, in which case the caller rebuilds.
+pub fn load_search_index(
+    memoranda_dir: &Path,
+    memos: &[Memo],
+    config: &SearchConfig,
+) -> Option<MemoSearcher> {
+    let path = memoranda_dir.join(SEARCH_INDEX_FILE_NAME);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!(
+                "Failed to read saved search index {}: {}",
+                path.display(),
+                e
+            );
+            return None;
+        }
+    };
+    let saved = match serde_json::from_str::<SavedIndex>(&text) {
+        Ok(saved) if saved.format == SEARCH_INDEX_FORMAT => saved,
+        Ok(_) => {
+            debug!("Ignoring saved search index in an older format");
+            return None;
+        }
+        Err(e) => {
+            warn!("Ignoring unreadable search index {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    let mut searcher =
+        MemoSearcher::from_index_parts(saved.index.into_owned(), saved.fingerprints.into_owned())
+            .with_config(config);
+    if saved.tokenizer != searcher.tokenizer_fingerprint() {
+        debug!("Ignoring saved search index built with other stemming or stop words");
+        return None;
+    }
+    let current: HashMap<MemoId, &Memo> = memos.iter().map(|memo| (memo.id, memo)).collect();
+    let stale: Vec<MemoId> = searcher
+        .indexed_ids()
+        .filter(|id| match current.get(id) {
+            Some(memo) => {
+                saved.modified.get(id).copied() != modified_millis(memo)
+                    || searcher.indexed_fingerprint(id) != Some(MemoSearcher::fingerprint(memo))
+            }
+            None => true,
+        })
+        .copied()
+        .collect();
+    searcher.remove_memos(&stale);
+
+    for memo in memos {
+        if searcher.indexed_fingerprint(&memo.id).is_none() {
+            searcher.index_memo(memo);
+        }
+    }
+    debug!(
+        reindexed = stale.len(),
+        "Loaded saved search index from {}",
+        path.display()
+    );
+    Some(searcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn memo_file(dir: &Path, title: &str, content: &str) -> Memo {
+        let mut memo = Memo::new(title.to_string(), content.to_string()).unwrap();
+        let path = dir.join(format!("{title}.md"));
+        fs::write(&path, content).unwrap();
+        memo.file_path = Some(path);
+        memo
+    }
+
+    #[test]
+    fn test_load_drops_changed_and_missing_memos() {
+        let temp_dir = TempDir::new().unwrap();
+        let kept = memo_file(temp_dir.path(), "Kept", "apple");
+        let mut changed = memo_file(temp_dir.path(), "Changed", "banana");
+        let removed = memo_file(temp_dir.path(), "Removed", "cherry");
+
+        let config = SearchConfig::default();
+        let mut searcher = MemoSearcher::new();
+        for memo in [&kept, &changed, &removed] {
+            searcher.index_memo(memo);
+        }
+        save_search_index(
+            &searcher,
+            &[kept.clone(), changed.clone(), removed.clone()],
+            temp_dir.path(),
+        )
+        .unwrap();
+        // The temporary file the index was written through is gone
+        assert!(!fs::read_dir(temp_dir.path()).unwrap().any(|entry| entry
+            .unwrap()
+            .path()
+            .extension()
+            .is_some_and(|extension| extension == "tmp")));
+
+        changed.content = "durian".to_string();
+        let loaded =
+            load_search_index(temp_dir.path(), &[kept.clone(), changed.clone()], &config).unwrap();
+        let mut ids: Vec<_> = loaded.indexed_ids().copied().collect();
+        ids.sort();
+        let mut expected = vec![kept.id, changed.id];
+        expected.sort();
+        assert_eq!(ids, expected);
+        assert_eq!(
+            loaded.indexed_fingerprint(&changed.id),
+            Some(MemoSearcher::fingerprint(&changed))
+        );
+        let (index, _) = loaded.index_parts();
+        assert!(!index.contains_key("banana"));
+        assert!(!index.contains_key("cherry"));
+        assert_eq!(index["durian"], vec![changed.id]);
+
+        let stemming = SearchConfig {
+            enable_stemming: true,
+            ..SearchConfig::default()
+        };
+        assert!(
+            load_search_index(temp_dir.path(), std::slice::from_ref(&kept), &stemming).is_none()
+        );
+
+        fs::write(temp_dir.path().join(SEARCH_INDEX_FILE_NAME), "not json").unwrap();
+        assert!(load_search_index(temp_dir.path(), &[kept], &config).is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use ulid::Ulid;
+
+use super::models::MAX_CONTENT_LENGTH;
+use super::storage::{MemoStoreError, Result};
+
+/// Directory (inside a `.memoranda` directory) holding content of in-progress chunked writes.
+pub const STAGING_DIR_NAME: &str = ".staging";
+
+/// A memo being assembled from chunks before it is committed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedWrite {
+    pub title: String,
+    pub tags: Vec<String>,
+    /// Temporary file the chunks are appended to.
+    pub path: PathBuf,
+    pub bytes_written: usize,
+    /// Line breaks in the content staged so far.
+    pub newlines: usize,
+}
+
+/// In-progress chunked writes, keyed by the token handed out when each began.
+#[derive(Debug, Default)]
+pub struct StagedWrites {
+    writes: Mutex<HashMap<String, StagedWrite>>,
+}
+
+impl StagedWrites {
+    /// Creates an empty staging file under `memoranda_dir` and returns its token.
+    pub fn begin(&self, memoranda_dir: &Path, title: String, tags: Vec<String>) -> Result<String> {
+        let staging_dir = memoranda_dir.join(STAGING_DIR_NAME);
+        fs::create_dir_all(&staging_dir)?;
+
+        let token = Ulid::new().to_string();
+        let path = staging_dir.join(format!("{token}.partial"));
+        fs::File::create(&path)?;
+
+        self.writes.lock().unwrap().insert(
+            token.clone(),
+            StagedWrite {
+                title,
+                tags,
+                path,
+                bytes_written: 0,
+                newlines: 0,
+            },
+        );
+        Ok(token)
+    }
+
+    /// Appends a chunk, returning the total bytes staged so far. A chunk that
+    /// would take the content past the maximum size, or past `max_lines`
+    /// lines when set, is rejected without being appended.
+    pub fn append(&self, token: &str, data: &str, max_lines: Option<usize>) -> Result<usize> {
+        let mut writes = self.writes.lock().unwrap();
+        let write = writes.get_mut(token).ok_or_else(|| unknown_token(token))?;
+
+        let total = write.bytes_written + data.len();
+        if total > MAX_CONTENT_LENGTH {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "Chunked write would exceed the maximum content size of {MAX_CONTENT_LENGTH} bytes"
+                ),
+            });
+        }
+        let newlines = write.newlines + data.bytes().filter(|&b| b == b'\n').count();
+        if let Some(max_lines) = max_lines.filter(|&max_lines| newlines + 1 > max_lines) {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "Chunked write would exceed the limit of {max_lines} lines; consider splitting it into several memos"
+                ),
+            });
+        }
+
+        OpenOptions::new()
+            .append(true)
+            .open(&write.path)?
+            .write_all(data.as_bytes())?;
+        write.bytes_written = total;
+        write.newlines = newlines;
+        Ok(total)
+    }
+
+    /// Returns a write with its staged content, leaving it registered so a
+    /// commit that fails can be retried; [`StagedWrites::finish`] removes it
+    /// once the memo is written.
+    pub fn read(&self, token: &str) -> Result<(StagedWrite, String)> {
+        let write = self
+            .writes
+            .lock()
+            .unwrap()
+            .get(token)
+            .cloned()
+            .ok_or_else(|| unknown_token(token))?;
+        let content = fs::read_to_string(&write.path)?;
+        Ok((write, content))
+    }
+
+    /// Drops a committed write and deletes its staging file.
+    pub fn finish(&self, token: &str) -> Result<()> {
+        self.abort(token)
+    }
+
+    /// Drops a write and deletes its staging file.
+    pub fn abort(&self, token: &str) -> Result<()> {
+        let write = self
+            .writes
+            .lock()
+            .unwrap()
+            .remove(token)
+            .ok_or_else(|| unknown_token(token))?;
+        Self::discard(&write)
+    }
+
+    pub fn discard(write: &StagedWrite) -> Result<()> {
+        match fs::remove_file(&write.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn unknown_token(token: &str) -> MemoStoreError {
+    MemoStoreError::Validation {
+        message: format!("Unknown or finished memo write token: {token}"),
+    }
+}
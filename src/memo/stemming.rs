@@ -0,0 +1,280 @@
+/// Reduces an English word to its stem with the Porter algorithm
+/// (M. F. Porter, "An algorithm for suffix stripping", 1980), so that
+/// "running", "runs" and "run" all become "run".
+///
+/// `word` should already be lowercase. Words of two letters or fewer, and
+/// words containing anything other than ASCII letters, are returned as is.
+pub fn stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+
+    let mut stemmer = Stemmer {
+        word: word.as_bytes().to_vec(),
+    };
+    stemmer.step1ab();
+    stemmer.step1c();
+    stemmer.step2();
+    stemmer.step3();
+    stemmer.step4();
+    stemmer.step5();
+    // Only ASCII letters were ever written
+    String::from_utf8(stemmer.word).unwrap_or_else(|_| word.to_string())
+}
+
+/// Suffix replacements for step 2, applied when the remaining stem has a
+/// measure above zero.
+const STEP2_SUFFIXES: &[(&str, &str)] = &[
+    ("ational", "ate"),
+    ("tional", "tion"),
+    ("enci", "ence"),
+    ("anci", "ance"),
+    ("izer", "ize"),
+    ("bli", "ble"),
+    ("alli", "al"),
+    ("entli", "ent"),
+    ("eli", "e"),
+    ("ousli", "ous"),
+    ("ization", "ize"),
+    ("ation", "ate"),
+    ("ator", "ate"),
+    ("alism", "al"),
+    ("iveness", "ive"),
+    ("fulness", "ful"),
+    ("ousness", "ous"),
+    ("aliti", "al"),
+    ("iviti", "ive"),
+    ("biliti", "ble"),
+    ("logi", "log"),
+];
+
+/// Suffix replacements for step 3, applied when the remaining stem has a
+/// measure above zero.
+const STEP3_SUFFIXES: &[(&str, &str)] = &[
+    ("icate", "ic"),
+    ("ative", ""),
+    ("alize", "al"),
+    ("iciti", "ic"),
+    ("ical", "ic"),
+    ("ful", ""),
+    ("ness", ""),
+];
+
+/// Suffixes step 4 removes when the remaining stem has a measure above one.
+/// `ion` is handled separately, since it also needs an `s` or `t` before it.
+const STEP4_SUFFIXES: &[&str] = &[
+    "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ou", "ism",
+    "ate", "iti", "ous", "ive", "ize",
+];
+
+struct Stemmer {
+    word: Vec<u8>,
+}
+
+impl Stemmer {
+    fn is_consonant(&self, i: usize) -> bool {
+        match self.word[i] {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.is_consonant(i - 1),
+            _ => true,
+        }
+    }
+
+    /// The number of vowel-consonant sequences in the first `len` letters.
+    fn measure(&self, len: usize) -> usize {
+        let mut count = 0;
+        let mut i = 0;
+        while i < len && self.is_consonant(i) {
+            i += 1;
+        }
+        loop {
+            while i < len && !self.is_consonant(i) {
+                i += 1;
+            }
+            if i >= len {
+                return count;
+            }
+            while i < len && self.is_consonant(i) {
+                i += 1;
+            }
+            count += 1;
+            if i >= len {
+                return count;
+            }
+        }
+    }
+
+    fn has_vowel(&self, len: usize) -> bool {
+        (0..len).any(|i| !self.is_consonant(i))
+    }
+
+    /// Whether the first `len` letters end in a double consonant.
+    fn ends_double_consonant(&self, len: usize) -> bool {
+        len >= 2 && self.word[len - 1] == self.word[len - 2] && self.is_consonant(len - 1)
+    }
+
+    /// Whether the first `len` letters end consonant-vowel-consonant, with the
+    /// last consonant not `w`, `x` or `y`, as in "hop" but not "snow".
+    fn ends_cvc(&self, len: usize) -> bool {
+        len >= 3
+            && self.is_consonant(len - 3)
+            && !self.is_consonant(len - 2)
+            && self.is_consonant(len - 1)
+            && !matches!(self.word[len - 1], b'w' | b'x' | b'y')
+    }
+
+    /// The length of the stem before `suffix`, if the word ends with it.
+    fn stem_before(&self, suffix: &str) -> Option<usize> {
+        self.word
+            .ends_with(suffix.as_bytes())
+            .then(|| self.word.len() - suffix.len())
+    }
+
+    fn replace_suffix(&mut self, stem_len: usize, replacement: &str) {
+        self.word.truncate(stem_len);
+        self.word.extend_from_slice(replacement.as_bytes());
+    }
+
+    /// Replaces the first suffix in `suffixes` the word ends with, if the stem
+    /// before it has a measure above `min_measure`.
+    fn replace_first(&mut self, suffixes: &[(&str, &str)], min_measure: usize) {
+        for (suffix, replacement) in suffixes {
+            if let Some(stem_len) = self.stem_before(suffix) {
+                if self.measure(stem_len) > min_measure {
+                    self.replace_suffix(stem_len, replacement);
+                }
+                return;
+            }
+        }
+    }
+
+    /// Plurals and `-ed` or `-ing` endings.
+    fn step1ab(&mut self) {
+        if self.word.ends_with(b"s") {
+            if let Some(stem_len) = self.stem_before("sses") {
+                self.replace_suffix(stem_len, "ss");
+            } else if let Some(stem_len) = self.stem_before("ies") {
+                self.replace_suffix(stem_len, "i");
+            } else if !self.word.ends_with(b"ss") {
+                self.word.pop();
+            }
+        }
+
+        if let Some(stem_len) = self.stem_before("eed") {
+            if self.measure(stem_len) > 0 {
+                self.word.pop();
+            }
+            return;
+        }
+        let Some(stem_len) = self
+            .stem_before("ed")
+            .or_else(|| self.stem_before("ing"))
+            .filter(|&stem_len| self.has_vowel(stem_len))
+        else {
+            return;
+        };
+        self.word.truncate(stem_len);
+        if self.word.ends_with(b"at") || self.word.ends_with(b"bl") || self.word.ends_with(b"iz") {
+            self.word.push(b'e');
+        } else if self.ends_double_consonant(stem_len) {
+            if !matches!(self.word[stem_len - 1], b'l' | b's' | b'z') {
+                self.word.pop();
+            }
+        } else if self.measure(stem_len) == 1 && self.ends_cvc(stem_len) {
+            self.word.push(b'e');
+        }
+    }
+
+    /// A final `y` after a vowel in the stem becomes `i`.
+    fn step1c(&mut self) {
+        if let Some(stem_len) = self.stem_before("y") {
+            if self.has_vowel(stem_len) {
+                self.word[stem_len] = b'i';
+            }
+        }
+    }
+
+    fn step2(&mut self) {
+        self.replace_first(STEP2_SUFFIXES, 0);
+    }
+
+    fn step3(&mut self) {
+        self.replace_first(STEP3_SUFFIXES, 0);
+    }
+
+    fn step4(&mut self) {
+        if let Some(stem_len) = self.stem_before("ion") {
+            if stem_len > 0 && matches!(self.word[stem_len - 1], b's' | b't') {
+                if self.measure(stem_len) > 1 {
+                    self.word.truncate(stem_len);
+                }
+                return;
+            }
+        }
+        if let Some(stem_len) = STEP4_SUFFIXES
+            .iter()
+            .find_map(|suffix| self.stem_before(suffix))
+        {
+            if self.measure(stem_len) > 1 {
+                self.word.truncate(stem_len);
+            }
+        }
+    }
+
+    /// A final `e`, and the second `l` of a final `ll`, on long enough stems.
+    fn step5(&mut self) {
+        if let Some(stem_len) = self.stem_before("e") {
+            let measure = self.measure(stem_len);
+            if measure > 1 || (measure == 1 && !self.ends_cvc(stem_len)) {
+                self.word.pop();
+            }
+        }
+        let len = self.word.len();
+        if self.word.ends_with(b"l") && self.ends_double_consonant(len) && self.measure(len) > 1 {
+            self.word.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem_matches_reference_outputs() {
+        let cases = [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("cats", "cat"),
+            ("feed", "feed"),
+            ("agreed", "agre"),
+            ("plastered", "plaster"),
+            ("motoring", "motor"),
+            ("sing", "sing"),
+            ("conflated", "conflat"),
+            ("troubled", "troubl"),
+            ("sized", "size"),
+            ("hopping", "hop"),
+            ("falling", "fall"),
+            ("hissing", "hiss"),
+            ("filing", "file"),
+            ("happy", "happi"),
+            ("relational", "relat"),
+            ("conditional", "condit"),
+            ("rational", "ration"),
+            ("generalization", "gener"),
+            ("adoption", "adopt"),
+            ("controll", "control"),
+            ("running", "run"),
+            ("runs", "run"),
+            ("run", "run"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(stem(word), expected, "stem of {word}");
+        }
+
+        assert_eq!(stem("is"), "is");
+        assert_eq!(stem("café"), "café");
+        assert_eq!(stem("v2"), "v2");
+    }
+}
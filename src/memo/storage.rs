@@ -1,18 +1,63 @@
 // use anyhow::anyhow;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
-use std::sync::RwLock;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, TryLockError};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::fs as async_fs;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 
-use super::cache::{MemoCache, MemoMetadata};
-use super::models::{Memo, MemoId};
-use super::search::{MemoSearcher, SearchQuery, SearchResult};
+use super::activity::{activity, ActivityBucket, ActivityGranularity};
+use super::audit::{AuditLog, AuditRecord};
+use super::cache::{CacheConfig, MemoCache, MemoMetadata};
+use super::compact::{trashed_at, CompactEntry, CompactOptions, CompactReport};
+use super::consistency::{CacheDivergence, ConsistencyReport};
+use super::context::{format_context, ContextOptions};
+use super::disk_cache::DISK_CACHE_DIR_NAME;
+use super::export::{
+    is_export_bundle, BundleImportIssue, BundleImportReport, ExportReport, ExportWriter,
+    ExportedMemo, ImportStrategy, RawExportBundle, EXPORT_FORMAT_VERSION,
+};
+use super::frontmatter::{
+    complete_partial_frontmatter, split_frontmatter, FrontmatterError, FrontmatterFormat,
+};
+use super::gitignore::{IgnoreRules, GITIGNORE_FILE_NAME, MEMORANDA_IGNORE_FILE_NAME};
+use super::history::{history_usage, list_versions, save_version, HistoryUsage, MemoVersion};
+use super::import::{memo_from_markdown, ImportIssue, ImportReport, ImportedMemo};
+use super::layout::{LayoutDirectory, LayoutEntry};
+use super::links::{
+    backlink_counts, link_graph, shortest_link_path, wiki_link_target, HubMemo, LinkGraph,
+};
+use super::lock::StoreLock;
+use super::models::{names_match, Memo, MemoId};
+use super::obsidian::{derived_memo_id, split_obsidian_note, write_obsidian_frontmatter};
+use super::redaction::Redactor;
+use super::retention::{RetentionAction, RetentionEntry, RetentionPolicy, RetentionReport};
+use super::search::{
+    normalize_path_text, MemoSearcher, PathMention, SearchBoosts, SearchConfig, SearchField,
+    SearchQuery, SearchResult,
+};
+use super::search_cache::{SearchCacheKey, SearchResultCache};
+use super::search_index::{load_search_index, save_search_index};
+use super::staging::StagedWrites;
+use super::tag_meta::{TagAliasMap, TagMeta, TagMetaMap, TagRules};
+use super::trash::{original_path, TrashedMemo};
+use crate::config::Settings;
+use crate::observer::EventObserver;
 use crate::utils::{retry_with_backoff_sync, RetryConfig};
 
+/// Directory (inside a `.memoranda` directory) that archived memos are moved into.
+pub const ARCHIVE_DIR_NAME: &str = ".archive";
+/// Directory (inside a `.memoranda` directory) that trashed memos are moved into.
+pub const TRASH_DIR_NAME: &str = ".trash";
+/// How many levels of subfolders below a `.memoranda` directory memos are loaded from.
+pub const MAX_MEMO_SUBFOLDER_DEPTH: usize = 8;
+
 #[derive(Error, Debug)]
 pub enum MemoStoreError {
     #[error("Memo not found: {id}")]
@@ -24,7 +69,7 @@ pub enum MemoStoreError {
     #[error("Invalid frontmatter in file {file}: {source}")]
     InvalidFrontmatter {
         file: String,
-        source: serde_json::Error,
+        source: FrontmatterError,
     },
 
     #[error("Missing frontmatter section in file {file}")]
@@ -44,6 +89,29 @@ pub enum MemoStoreError {
 
     #[error("Git repository not found")]
     GitNotFound,
+
+    #[error("Alias '{alias}' is already used by memo {id}")]
+    AliasConflict { alias: String, id: String },
+
+    #[error("Memo id {id} is used by multiple files: {}", display_paths(.paths))]
+    DuplicateId { id: String, paths: Vec<PathBuf> },
+
+    #[error("{} already exists; choose a different title", path.display())]
+    FileAlreadyExists { path: PathBuf },
+
+    #[error("{} is held by another process; gave up after {timeout_ms} ms", path.display())]
+    Locked { path: PathBuf, timeout_ms: u64 },
+
+    #[error("{} was changed by another process while being updated; try again", path.display())]
+    WriteConflict { path: PathBuf },
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 pub type Result<T> = std::result::Result<T, MemoStoreError>;
@@ -107,23 +175,251 @@ impl MemoStorage {
     }
 }
 
+/// A memo whose file name no longer matches its sanitized title.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FilenameDrift {
+    pub id: MemoId,
+    pub title: String,
+    pub file_path: PathBuf,
+    /// The name the file is (or would be) renamed to, suffixed on collision.
+    pub expected_path: PathBuf,
+}
+
+/// Which title wins when a memo's frontmatter title and file name disagree,
+/// as they do after a file is renamed by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleAuthority {
+    /// Keep the frontmatter title; the file can be renamed to match it.
+    #[default]
+    Frontmatter,
+    /// Take the title from the file name, overriding the frontmatter.
+    Filename,
+}
+
+/// A memo file whose frontmatter title does not match its file name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TitleMismatch {
+    pub id: MemoId,
+    pub file_path: PathBuf,
+    pub frontmatter_title: String,
+    /// The title the file name gives.
+    pub filename_title: String,
+}
+
+/// Several memo files claiming the same id, e.g. after a file was copied.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateId {
+    pub id: MemoId,
+    /// Conflicting files, sorted; the first keeps the id when duplicates are fixed.
+    pub paths: Vec<PathBuf>,
+}
+
+/// A duplicate memo file that was given a fresh id.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IdReassignment {
+    pub file_path: PathBuf,
+    pub old_id: MemoId,
+    pub new_id: MemoId,
+}
+
+/// Per-id result of a batch delete.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeleteOutcome {
+    pub id: String,
+    pub deleted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DeleteOutcome {
+    pub fn deleted(id: impl ToString) -> Self {
+        Self {
+            id: id.to_string(),
+            deleted: true,
+            error: None,
+        }
+    }
+
+    pub fn failed(id: impl ToString, error: impl ToString) -> Self {
+        Self {
+            id: id.to_string(),
+            deleted: false,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// Search index work waiting for the next search.
+#[derive(Debug, Clone, Default)]
+struct PendingIndexChanges {
+    /// The whole index must be rebuilt: at startup, and after changes that
+    /// are not tracked memo by memo.
+    full_rebuild: bool,
+    /// Memos created, changed or removed since the index was last updated.
+    changed: HashSet<MemoId>,
+}
+
+impl PendingIndexChanges {
+    fn rebuild() -> Self {
+        Self {
+            full_rebuild: true,
+            ..Self::default()
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.full_rebuild && self.changed.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub struct MemoStore {
     root_path: PathBuf,
-    searcher: RwLock<MemoSearcher>,
-    index_dirty: RwLock<bool>,
+    /// The live search index. Rebuilds index into a fresh searcher and swap it
+    /// in, so searches only ever hold this lock long enough to clone the `Arc`.
+    searcher: RwLock<Arc<MemoSearcher>>,
+    pending_index_changes: RwLock<PendingIndexChanges>,
+    /// Incremented whenever the index falls behind; part of every search cache key.
+    index_version: AtomicU64,
+    search_cache: SearchResultCache,
+    last_index_rebuild: RwLock<Option<Instant>>,
     cache: MemoCache,
+    observer: Option<Arc<dyn EventObserver>>,
+    settings: Settings,
+    redactor: Redactor,
+    tag_rules: TagRules,
+    staged_writes: StagedWrites,
+    audit_log: AuditLog,
+    audit_actor: Option<String>,
 }
 
 impl MemoStore {
-    // Helper function to check if a file is a markdown file
-    fn is_markdown_file(path: &Path) -> bool {
-        path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md")
+    /// Where a new memo titled `title` goes in `dir`, named after its title
+    /// with the first of the `memo_file_extensions`.
+    fn new_memo_path(&self, dir: &Path, title: &str) -> PathBuf {
+        dir.join(format!(
+            "{}.{}",
+            sanitize_filename(title),
+            self.settings.new_memo_file_extension()
+        ))
+    }
+
+    /// Whether `path` is a file with one of the `memo_file_extensions`.
+    fn is_memo_file(&self, path: &Path) -> bool {
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|s| s.to_str())
+                .is_some_and(|extension| self.settings.is_memo_file_extension(extension))
+    }
+
+    /// Whether `path` is a temporary file of an atomic memo write, either
+    /// named by [`write_temp_path`], `<file name>.<pid>-<ulid>.tmp`, or the
+    /// older `<file name>.tmp` of a file with one of the `memo_file_extensions`.
+    fn is_write_temp_file(&self, path: &Path) -> bool {
+        let Some(name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".tmp"))
+        else {
+            return false;
+        };
+        let Some((file_name, suffix)) = name.rsplit_once('.') else {
+            return false;
+        };
+        if let Some((pid, ulid)) = suffix.split_once('-') {
+            return !file_name.is_empty()
+                && !pid.is_empty()
+                && pid.bytes().all(|byte| byte.is_ascii_digit())
+                && ulid.parse::<ulid::Ulid>().is_ok();
+        }
+        !file_name.is_empty() && self.settings.is_memo_file_extension(suffix)
+    }
+
+    /// Whether `path` names a subfolder memos may be kept in: not hidden, so
+    /// `.trash`, `.history` and the like are left alone.
+    fn is_memo_subfolder_name(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|s| s.to_str())
+            .is_some_and(|name| !name.starts_with('.'))
+    }
+
+    /// `.memoranda` directory `dir` and the subfolders below it, up to
+    /// [`MAX_MEMO_SUBFOLDER_DEPTH`] levels deep, leaving out hidden folders
+    /// and those matched by `ignore_rules`. Symlinked folders are not followed.
+    fn memo_folders(dir: &Path, ignore_rules: &IgnoreRules) -> Result<Vec<PathBuf>> {
+        let mut folders = vec![dir.to_path_buf()];
+        let mut level = vec![dir.to_path_buf()];
+        for _ in 0..MAX_MEMO_SUBFOLDER_DEPTH {
+            let mut next = Vec::new();
+            for folder in &level {
+                for entry in fs::read_dir(folder)? {
+                    let entry = entry?;
+                    let path = entry.path();
+                    if entry.file_type()?.is_dir()
+                        && Self::is_memo_subfolder_name(&path)
+                        && !ignore_rules.is_ignored(&path)
+                    {
+                        next.push(path);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            folders.extend(next.iter().cloned());
+            level = next;
+        }
+        Ok(folders)
+    }
+
+    /// The memo files in `.memoranda` directory `dir` and its subfolders,
+    /// leaving out those matched by its `.memorandaignore`.
+    fn memo_files(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let ignore_rules = IgnoreRules::load_memoranda_dir(dir);
+        let mut paths = Vec::new();
+        for folder in Self::memo_folders(dir, &ignore_rules)? {
+            for entry in fs::read_dir(folder)? {
+                let path = entry?.path();
+                if self.is_memo_file(&path) && !ignore_rules.is_file_ignored(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Async version of [`Self::memo_files`].
+    async fn memo_files_async(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut ignore_rules = IgnoreRules::default();
+        if let Ok(text) = async_fs::read_to_string(dir.join(MEMORANDA_IGNORE_FILE_NAME)).await {
+            ignore_rules.add_patterns(dir, &text);
+        }
+        let mut paths = Vec::new();
+        let mut stack = vec![(dir.to_path_buf(), 0)];
+        while let Some((folder, depth)) = stack.pop() {
+            let mut dir_entries = async_fs::read_dir(&folder).await?;
+            while let Some(entry) = dir_entries.next_entry().await? {
+                let path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    if depth < MAX_MEMO_SUBFOLDER_DEPTH
+                        && Self::is_memo_subfolder_name(&path)
+                        && !ignore_rules.is_ignored(&path)
+                    {
+                        stack.push((path, depth + 1));
+                    }
+                } else if self.is_memo_file(&path) && !ignore_rules.is_file_ignored(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        Ok(paths)
     }
 
     // Helper function to create and cache memo metadata
     async fn create_and_cache_metadata(&self, memo: &Memo, file_path: &Path) -> Result<()> {
-        if let Ok(file_metadata) = async_fs::metadata(file_path).await {
+        if let Ok(file_metadata) = stat_file_async(file_path).await {
             if let Ok(last_modified) = file_metadata.modified() {
                 let metadata = MemoMetadata {
                     id: memo.id,
@@ -142,25 +438,111 @@ impl MemoStore {
 
     /// Prepares file content for memo serialization with frontmatter.
     /// This helper reduces duplication between sync and async save methods.
-    fn prepare_memo_file_content(&self, memo: &Memo) -> Result<String> {
+    ///
+    /// `existing` is the current file content, read only in Obsidian mode so
+    /// the note's other frontmatter keys can be carried over.
+    ///
+    /// A memo loaded with invalid UTF-8 replaced is refused until its content
+    /// is replaced, so the original bytes in its file are never overwritten
+    /// with the replacement characters.
+    /// Applies the configured line endings to the body of a memo about to be
+    /// written, so the memo returned and cached matches the file. The
+    /// frontmatter keeps its own LF endings.
+    fn normalize_line_endings(&self, memo: &mut Memo) {
+        if let Cow::Owned(content) = self.settings.line_ending.apply(&memo.content) {
+            memo.content = content;
+        }
+    }
+
+    fn prepare_memo_file_content(
+        &self,
+        memo: &Memo,
+        file_path: &Path,
+        existing: Option<&str>,
+    ) -> Result<String> {
+        if memo.encoding_warning {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "{} is not valid UTF-8 and was loaded with invalid bytes replaced; update its content to rewrite it",
+                    file_path.display()
+                ),
+            });
+        }
         // Create memo without file_path for serialization
         let mut memo_for_serialization = memo.clone();
         memo_for_serialization.file_path = None;
+        memo_for_serialization.encoding_warning = false;
+
+        if self.settings.obsidian_compat {
+            let block = existing
+                .and_then(split_frontmatter)
+                .filter(|frontmatter| {
+                    frontmatter.format == FrontmatterFormat::Yaml && frontmatter.body.is_some()
+                })
+                .map_or("", |frontmatter| frontmatter.block);
+            let frontmatter = write_obsidian_frontmatter(
+                block,
+                &memo_for_serialization,
+                &extract_title_from_filename(file_path),
+            );
+            return Ok(format!(
+                "---\n{frontmatter}\n---\n{}",
+                memo_for_serialization.content
+            ));
+        }
 
-        let frontmatter = serde_json::to_string_pretty(&memo_for_serialization)?;
-        Ok(format!("---\n{}\n---\n{}", frontmatter, memo.content))
+        let format = self.settings.frontmatter_format;
+        let frontmatter = format.write_memo(&memo_for_serialization).map_err(|e| {
+            MemoStoreError::InvalidFrontmatter {
+                file: memo.title.clone(),
+                source: e,
+            }
+        })?;
+        Ok(format!(
+            "{fence}\n{}\n{fence}\n{}",
+            frontmatter,
+            memo_for_serialization.content,
+            fence = format.fence()
+        ))
     }
 
     /// Helper function to create a memo from content with frontmatter parsing fallback.
     /// This reduces duplication between sync and async loading methods.
+    ///
+    /// `lossy` is whether invalid UTF-8 was replaced in `content`, in which
+    /// case incomplete frontmatter is not written back to the file.
     fn create_memo_from_content_with_fallback(
         &self,
         content: String,
         file_path: &Path,
+        lossy: bool,
     ) -> Result<Memo> {
-        match self.parse_frontmatter(&content) {
-            Ok(Some(mut memo)) => {
+        if self.settings.obsidian_compat {
+            if let Some(memo) = Self::create_obsidian_memo(&content, file_path)? {
+                return Ok(memo);
+            }
+        }
+
+        match self.parse_frontmatter(&content, file_path) {
+            Ok(Some((mut memo, completed))) => {
                 memo.file_path = Some(file_path.to_path_buf());
+                if completed {
+                    debug!(
+                        "Frontmatter in {} has no id; using {}",
+                        file_path.display(),
+                        memo.id
+                    );
+                    if self.settings.complete_partial_frontmatter && !lossy {
+                        match self.save_memo_to_file(&mut memo, file_path) {
+                            Ok(()) => info!("Completed frontmatter in {}", file_path.display()),
+                            Err(e) => warn!(
+                                "Failed to complete frontmatter in {}: {}",
+                                file_path.display(),
+                                e
+                            ),
+                        }
+                    }
+                }
                 Ok(memo)
             }
             Ok(None) => {
@@ -183,66 +565,248 @@ impl MemoStore {
         }
     }
 
-    // Helper function to parse frontmatter and extract memo ID from content
-    fn extract_memo_id_from_content(content: &str, file_path: &Path) -> Result<Option<MemoId>> {
-        if !content.starts_with("---\n") {
+    /// Builds a memo from an Obsidian note: the body is the content, the title
+    /// comes from a `title` key or the file name, and a note without an `id`
+    /// gets one derived from its path. Returns `None` for JSON and TOML
+    /// frontmatter, which load as regular memos.
+    fn create_obsidian_memo(content: &str, file_path: &Path) -> Result<Option<Memo>> {
+        let Some((fields, body)) = split_obsidian_note(content) else {
             return Ok(None);
+        };
+        let title = fields
+            .title
+            .unwrap_or_else(|| extract_title_from_filename(file_path));
+        let mut memo =
+            Memo::with_file_path(title, body.to_string(), Some(file_path.to_path_buf()))?;
+        memo.id = fields.id.unwrap_or_else(|| derived_memo_id(file_path));
+        if let Some(created) = fields.created {
+            memo.created_at = created;
         }
+        memo.updated_at = fields.updated.unwrap_or(memo.created_at);
+        memo.tags = fields.tags;
+        Ok(Some(memo))
+    }
 
-        let mut parts = content.splitn(3, "---\n");
-        parts.next(); // skip the first empty part
+    // Helper function to parse frontmatter and extract memo ID from content
+    fn extract_memo_id_from_content(
+        &self,
+        content: &str,
+        file_path: &Path,
+    ) -> Result<Option<MemoId>> {
+        if self.settings.obsidian_compat {
+            if let Some((fields, _)) = split_obsidian_note(content) {
+                return Ok(Some(
+                    fields.id.unwrap_or_else(|| derived_memo_id(file_path)),
+                ));
+            }
+        }
 
-        let frontmatter = parts.next().ok_or(MemoStoreError::MissingFrontmatter {
-            file: file_path.display().to_string(),
-        })?;
+        let Some(frontmatter) = split_frontmatter(content) else {
+            return Ok(None);
+        };
 
         // Parse just the id field from the frontmatter
-        let value: serde_json::Value =
-            serde_json::from_str(frontmatter).map_err(|e| MemoStoreError::InvalidFrontmatter {
+        let value = frontmatter.format.parse(frontmatter.block).map_err(|e| {
+            MemoStoreError::InvalidFrontmatter {
                 file: file_path.display().to_string(),
                 source: e,
-            })?;
-
-        if let Some(id_str) = value.get("id").and_then(|v| v.as_str()) {
-            if let Ok(ulid) = id_str.parse::<ulid::Ulid>() {
-                return Ok(Some(MemoId::from_ulid(ulid)));
             }
-        }
+        })?;
 
-        Ok(None)
+        if value.is_object() && value.get("id").is_none() {
+            return Ok(Some(derived_memo_id(file_path)));
+        }
+        Ok(value.get("id").and_then(|id| MemoId::from_json(id).ok()))
     }
     pub fn new(root_path: PathBuf) -> Self {
         Self {
             root_path,
-            searcher: RwLock::new(MemoSearcher::new()),
-            index_dirty: RwLock::new(true),
+            searcher: RwLock::new(Arc::new(MemoSearcher::new())),
+            pending_index_changes: RwLock::new(PendingIndexChanges::rebuild()),
+            index_version: AtomicU64::new(0),
+            search_cache: SearchResultCache::new(Settings::default().search_cache_size),
+            last_index_rebuild: RwLock::new(None),
             cache: MemoCache::new(),
+            observer: None,
+            settings: Settings::default(),
+            redactor: Redactor::default(),
+            tag_rules: TagRules::default(),
+            staged_writes: StagedWrites::default(),
+            audit_log: AuditLog::default(),
+            audit_actor: None,
         }
     }
 
     pub fn new_with_cache_config(root_path: PathBuf, cache_size: u64, ttl_seconds: u64) -> Self {
         Self {
             root_path,
-            searcher: RwLock::new(MemoSearcher::new()),
-            index_dirty: RwLock::new(true),
+            searcher: RwLock::new(Arc::new(MemoSearcher::new())),
+            pending_index_changes: RwLock::new(PendingIndexChanges::rebuild()),
+            index_version: AtomicU64::new(0),
+            search_cache: SearchResultCache::new(Settings::default().search_cache_size),
+            last_index_rebuild: RwLock::new(None),
             cache: MemoCache::with_config(cache_size, ttl_seconds),
+            observer: None,
+            settings: Settings::default(),
+            redactor: Redactor::default(),
+            tag_rules: TagRules::default(),
+            staged_writes: StagedWrites::default(),
+            audit_log: AuditLog::default(),
+            audit_actor: None,
         }
     }
 
+    /// Creates a store whose memo cache and behavior are configured by `settings`.
+    pub fn new_with_settings(root_path: PathBuf, settings: &Settings) -> Self {
+        let mut store = Self::new(root_path);
+        store.cache = MemoCache::with_cache_config(CacheConfig::from(settings));
+        store.with_settings(settings.clone())
+    }
+
     pub fn from_git_root() -> Result<Self> {
         let git_root = find_git_root()?;
         Ok(Self::new(git_root))
     }
 
+    /// Applies the given settings to store behavior such as context formatting.
+    pub fn with_settings(mut self, settings: Settings) -> Self {
+        self.search_cache = SearchResultCache::new(settings.search_cache_size);
+        self.redactor = Redactor::lenient(&settings.redaction_patterns);
+        self.tag_rules = TagRules::from(&settings);
+        self.settings = settings;
+        if self.settings.disk_cache_max_bytes > 0 {
+            self.cache = MemoCache::with_cache_config(CacheConfig {
+                disk_cache_dir: self.disk_cache_dir(),
+                disk_cache_max_bytes: self.settings.disk_cache_max_bytes,
+                ..self.cache.get_config().clone()
+            });
+        }
+        self
+    }
+
+    /// Directory of the disk cache tier, inside the primary memo directory, or
+    /// `None` while there is no memo directory to put it in.
+    fn disk_cache_dir(&self) -> Option<PathBuf> {
+        match self.get_primary_memoranda_dir() {
+            Ok(primary) => Some(
+                self.memo_dir_of(&primary.join(DISK_CACHE_DIR_NAME))
+                    .join(DISK_CACHE_DIR_NAME),
+            ),
+            Err(e) => {
+                debug!("Disk cache disabled, no memo directory found: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Returns the redactor built from the `redaction_patterns` setting, for
+    /// content served to clients.
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    /// Returns the settings this store was configured with.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Attaches an observer that is notified of memo lifecycle events and searches.
+    pub fn with_observer(mut self, observer: Arc<dyn EventObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Sets who later changes are attributed to in the audit log, such as the
+    /// name of the connected MCP client.
+    pub fn set_audit_actor(&mut self, actor: Option<String>) {
+        self.audit_actor = actor;
+    }
+
+    /// Replaces (or removes) the attached event observer.
+    pub fn set_observer(&mut self, observer: Option<Arc<dyn EventObserver>>) {
+        self.observer = observer;
+    }
+
+    /// Reports the outcome of a store operation to the observer, if any.
+    fn observe<T>(
+        &self,
+        operation: &str,
+        result: &Result<T>,
+        on_success: impl FnOnce(&dyn EventObserver, &T),
+    ) {
+        if let Some(observer) = &self.observer {
+            match result {
+                Ok(value) => on_success(observer.as_ref(), value),
+                Err(e) => observer.on_error(operation, &e.to_string()),
+            }
+        }
+    }
+
+    /// Records a successful change to a memo in the audit log.
+    fn audit<T>(&self, operation: &str, result: &Result<T>, id: impl FnOnce(&T) -> MemoId) {
+        if let Ok(value) = result {
+            self.audit_memo(operation, id(value));
+        }
+    }
+
+    fn audit_memo(&self, operation: &str, id: MemoId) {
+        self.append_audit(AuditRecord::new(operation, self.audit_actor.clone()).with_id(id));
+    }
+
+    /// Appends to the audit log when `audit_log` is set. The change already
+    /// happened, so a failed append is logged rather than returned.
+    fn append_audit(&self, record: AuditRecord) {
+        if !self.settings.audit_log {
+            return;
+        }
+        let memoranda_dir = || {
+            self.get_primary_memoranda_dir()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()))
+        };
+        if let Err(e) = self.audit_log.append(memoranda_dir, &record) {
+            warn!("Failed to append {} to the audit log: {}", record.op, e);
+        }
+    }
+
+    /// Finds every memo directory (named `memo_dir_name`, `.memoranda` by
+    /// default) under the store root.
+    ///
+    /// With `respect_gitignore` set, directories ignored by `.gitignore`
+    /// files (and `.git` itself) are not descended into.
     pub fn find_memoranda_dirs(&self) -> Result<Vec<PathBuf>> {
+        let follow_symlinks = self.settings.follow_symlinks;
+        let respect_gitignore = self.settings.respect_gitignore;
+        let memo_dir_name = self.settings.memo_dir_name.as_str();
         let mut memoranda_dirs = Vec::new();
+        let mut seen = HashSet::new();
+        let mut ignore_rules = IgnoreRules::new(memo_dir_name);
 
+        // With follow_links enabled, WalkDir reports a loop error for any symlink
+        // pointing back at an ancestor instead of descending into it again.
         for entry in WalkDir::new(&self.root_path)
+            .follow_links(follow_symlinks)
             .into_iter()
+            .filter_entry(|entry| {
+                if !respect_gitignore || !entry.file_type().is_dir() {
+                    return true;
+                }
+                if entry.depth() > 0 && ignore_rules.is_ignored(entry.path()) {
+                    return false;
+                }
+                #[cfg(test)]
+                DIRECTORIES_SCANNED.with(|count| count.set(count.get() + 1));
+                ignore_rules.load_dir(entry.path());
+                true
+            })
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
-            if path.is_dir() && path.file_name().and_then(|s| s.to_str()) == Some(".memoranda") {
+            if entry.file_type().is_dir()
+                && path.file_name().and_then(|s| s.to_str()) == Some(memo_dir_name)
+            {
+                if follow_symlinks && !seen.insert(fs::canonicalize(path)?) {
+                    continue;
+                }
                 memoranda_dirs.push(path.to_path_buf());
             }
         }
@@ -250,20 +814,66 @@ impl MemoStore {
         Ok(memoranda_dirs)
     }
 
+    /// Lists each `.memoranda` directory with the memo files directly inside it,
+    /// reading only file metadata and frontmatter.
+    pub fn store_layout(&self) -> Result<Vec<LayoutDirectory>> {
+        let mut layout = Vec::new();
+        for dir in self.find_memoranda_dirs()? {
+            let mut files = Vec::new();
+            for path in self.memo_files(&dir)? {
+                files.push(LayoutEntry::from_file(&path)?);
+            }
+            files.sort_by(|a, b| a.filename.cmp(&b.filename));
+            layout.push(LayoutDirectory { path: dir, files });
+        }
+        Ok(layout)
+    }
+
+    /// Async version of [`Self::find_memoranda_dirs`].
     pub async fn find_memoranda_dirs_async(&self) -> Result<Vec<PathBuf>> {
+        let follow_symlinks = self.settings.follow_symlinks;
+        let respect_gitignore = self.settings.respect_gitignore;
+        let memo_dir_name = self.settings.memo_dir_name.as_str();
         let mut memoranda_dirs = Vec::new();
         let mut stack = vec![self.root_path.clone()];
+        // Canonical paths of directories already visited, used to break symlink cycles
+        let mut visited = HashSet::new();
+        let mut ignore_rules = IgnoreRules::new(memo_dir_name);
 
         while let Some(current_dir) = stack.pop() {
+            if follow_symlinks && !visited.insert(async_fs::canonicalize(&current_dir).await?) {
+                continue;
+            }
+            if respect_gitignore {
+                #[cfg(test)]
+                DIRECTORIES_SCANNED.with(|count| count.set(count.get() + 1));
+                if let Ok(text) =
+                    async_fs::read_to_string(current_dir.join(GITIGNORE_FILE_NAME)).await
+                {
+                    ignore_rules.add_patterns(&current_dir, &text);
+                }
+            }
+
             let mut entries = async_fs::read_dir(&current_dir).await?;
             while let Some(entry) = entries.next_entry().await? {
                 let path = entry.path();
-                let metadata = entry.metadata().await?;
+                let mut metadata = entry.metadata().await?;
+                if follow_symlinks && metadata.file_type().is_symlink() {
+                    match stat_file_async(&path).await {
+                        Ok(target_metadata) => metadata = target_metadata,
+                        // Dangling symlink
+                        Err(_) => continue,
+                    }
+                }
 
                 if metadata.is_dir() {
-                    if path.file_name().and_then(|s| s.to_str()) == Some(".memoranda") {
+                    if path.file_name().and_then(|s| s.to_str()) == Some(memo_dir_name) {
+                        if follow_symlinks && !visited.insert(async_fs::canonicalize(&path).await?)
+                        {
+                            continue;
+                        }
                         memoranda_dirs.push(path);
-                    } else {
+                    } else if !respect_gitignore || !ignore_rules.is_ignored(&path) {
                         // Add subdirectory to stack for recursive traversal
                         stack.push(path);
                     }
@@ -274,42 +884,132 @@ impl MemoStore {
         Ok(memoranda_dirs)
     }
 
-    /// Gets the first available memoranda directory (sync version).
-    /// This helper reduces duplication of the common pattern:
-    /// find_memoranda_dirs()?.first().ok_or(NoMemorandaDirectories)
+    /// Gets the memo directory new memos are written to (sync version).
+    ///
+    /// That is the `default_memo_dir` setting when set, and otherwise the
+    /// memo directory closest to the working directory: the deepest one
+    /// beside the working directory or one of its ancestors, then the one at
+    /// the git root, then the shallowest, ties broken by path.
     pub fn get_primary_memoranda_dir(&self) -> Result<PathBuf> {
         let memoranda_dirs = self.find_memoranda_dirs()?;
-        memoranda_dirs
-            .first()
-            .cloned()
-            .ok_or(MemoStoreError::NoMemorandaDirectories)
+        self.select_primary_dir(memoranda_dirs)
     }
 
-    /// Gets the first available memoranda directory (async version).
-    /// This helper reduces duplication of the common pattern:
-    /// find_memoranda_dirs_async().await?.first().ok_or(NoMemorandaDirectories)
+    /// Gets the memo directory new memos are written to (async version).
     pub async fn get_primary_memoranda_dir_async(&self) -> Result<PathBuf> {
         let memoranda_dirs = self.find_memoranda_dirs_async().await?;
-        memoranda_dirs
-            .first()
-            .cloned()
+        self.select_primary_dir(memoranda_dirs)
+    }
+
+    fn select_primary_dir(&self, memoranda_dirs: Vec<PathBuf>) -> Result<PathBuf> {
+        if memoranda_dirs.is_empty() {
+            return Err(MemoStoreError::NoMemorandaDirectories);
+        }
+        if let Some(directory) = &self.settings.default_memo_dir {
+            return self.find_memo_dir(memoranda_dirs, directory);
+        }
+        let current_dir = std::env::current_dir().ok();
+        let git_root = find_git_root().ok();
+        select_closest_memo_dir(memoranda_dirs, current_dir.as_deref(), git_root.as_deref())
             .ok_or(MemoStoreError::NoMemorandaDirectories)
     }
 
+    /// Finds the memo directory `directory` names, relative to the store root:
+    /// the memo directory itself, the directory containing it, or a subfolder
+    /// inside it, which need not exist yet.
+    fn find_memo_dir(&self, memoranda_dirs: Vec<PathBuf>, directory: &str) -> Result<PathBuf> {
+        if memoranda_dirs.is_empty() {
+            return Err(MemoStoreError::NoMemorandaDirectories);
+        }
+        let resolved = self.resolve_context_directory(directory);
+        let wanted = canonical_or_same(&resolved);
+        let found = memoranda_dirs.iter().find(|dir| {
+            let dir = canonical_or_same(dir);
+            dir == wanted || dir.parent() == Some(wanted.as_path())
+        });
+        let subfolder = || {
+            memoranda_dirs.iter().find_map(|dir| {
+                let rest = resolved.strip_prefix(dir).ok()?;
+                let valid = rest.components().count() <= MAX_MEMO_SUBFOLDER_DEPTH
+                    && rest.components().all(|component| {
+                        matches!(component, std::path::Component::Normal(_))
+                            && Self::is_memo_subfolder_name(Path::new(component.as_os_str()))
+                    });
+                valid.then(|| resolved.clone())
+            })
+        };
+        match found.cloned().or_else(subfolder) {
+            Some(dir) => Ok(dir),
+            None => Err(MemoStoreError::Validation {
+                message: format!(
+                    "{directory} is not a memo directory; choose one of: {}",
+                    memoranda_dirs
+                        .iter()
+                        .map(|dir| dir.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }),
+        }
+    }
+
+    /// Finds the memo directory a new memo in `directory` goes to, like
+    /// [`Self::find_memo_dir`]. When `directory` has no memo directory but
+    /// could, one is created there with `auto_create_scope_dir` set, and the
+    /// error mentions the setting otherwise.
+    fn memo_dir_for_new_memo(&self, directory: &str) -> Result<PathBuf> {
+        let error = match self.find_memo_dir(self.find_memoranda_dirs()?, directory) {
+            Ok(dir) => return Ok(dir),
+            Err(error) => error,
+        };
+        let Some(scope_dir) = self.scope_memo_dir(directory) else {
+            return Err(error);
+        };
+        if !self.settings.auto_create_scope_dir {
+            let message = match error {
+                MemoStoreError::Validation { message } => message,
+                _ => format!("{directory} is not a memo directory"),
+            };
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "{message}; or set auto_create_scope_dir to have one created there"
+                ),
+            });
+        }
+        info!("Creating memo directory {}", scope_dir.display());
+        fs::create_dir_all(&scope_dir)?;
+        Ok(scope_dir)
+    }
+
+    /// The memo directory that would be created for `directory`: `directory`
+    /// itself when it is named like a memo directory, or one inside it. Only
+    /// directories under the store root, and not inside another memo
+    /// directory, get one.
+    fn scope_memo_dir(&self, directory: &str) -> Option<PathBuf> {
+        let resolved = self.resolve_context_directory(directory);
+        let relative = resolved.strip_prefix(&self.root_path).ok()?;
+        let memo_dir_name = self.settings.memo_dir_name.as_str();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            let std::path::Component::Normal(name) = component else {
+                return None;
+            };
+            if name == memo_dir_name {
+                return components.peek().is_none().then_some(resolved);
+            }
+        }
+        Some(resolved.join(memo_dir_name))
+    }
+
     pub fn list_memos(&self) -> Result<Vec<Memo>> {
         let mut memos = Vec::new();
         let memoranda_dirs = self.find_memoranda_dirs()?;
 
         for dir in memoranda_dirs {
-            for entry in fs::read_dir(&dir)? {
-                let entry = entry?;
-                let path = entry.path();
-
-                if Self::is_markdown_file(&path) {
-                    match self.load_memo_from_file(&path) {
-                        Ok(memo) => memos.push(memo),
-                        Err(e) => warn!("Failed to load memo from {}: {}", path.display(), e),
-                    }
+            for path in self.memo_files(&dir)? {
+                match self.load_memo_from_file(&path) {
+                    Ok(memo) => memos.push(memo),
+                    Err(e) => warn!("Failed to load memo from {}: {}", path.display(), e),
                 }
             }
         }
@@ -317,20 +1017,24 @@ impl MemoStore {
         Ok(memos)
     }
 
+    /// Loads every memo, like [`Self::list_memos`]. An id found in more than
+    /// one file is evicted from the cache, so the next
+    /// [`Self::get_memo_async`] rescans and reports the conflict.
     pub async fn list_memos_async(&self) -> Result<Vec<Memo>> {
         let mut memos = Vec::new();
+        let mut seen = HashSet::new();
         let memoranda_dirs = self.find_memoranda_dirs_async().await?;
 
         for dir in memoranda_dirs {
-            let mut dir_entries = async_fs::read_dir(&dir).await?;
-            while let Some(entry) = dir_entries.next_entry().await? {
-                let path = entry.path();
-
-                if Self::is_markdown_file(&path) {
-                    match self.load_memo_from_file_async(&path).await {
-                        Ok(memo) => memos.push(memo),
-                        Err(e) => warn!("Failed to load memo from {}: {}", path.display(), e),
+            for path in self.memo_files_async(&dir).await? {
+                match self.load_memo_from_file_async(&path).await {
+                    Ok(memo) => {
+                        if !seen.insert(memo.id) {
+                            self.cache.remove_memo(&memo.id).await;
+                        }
+                        memos.push(memo);
                     }
+                    Err(e) => warn!("Failed to load memo from {}: {}", path.display(), e),
                 }
             }
         }
@@ -338,682 +1042,4731 @@ impl MemoStore {
         Ok(memos)
     }
 
+    /// Loads the memo with the given id.
+    ///
+    /// Every memo file is checked so that an id claimed by more than one file is
+    /// reported as [`MemoStoreError::DuplicateId`] instead of resolving to
+    /// whichever file happened to be scanned first.
     pub fn get_memo(&self, id: &MemoId) -> Result<Option<Memo>> {
+        self.find_memo_file(id)?
+            .map(|path| self.load_memo_from_file(&path))
+            .transpose()
+    }
+
+    /// Finds the file of the memo with `id` by reading each file's frontmatter.
+    fn find_memo_file(&self, id: &MemoId) -> Result<Option<PathBuf>> {
         let memoranda_dirs = self.find_memoranda_dirs()?;
+        let mut matches = Vec::new();
 
         for dir in memoranda_dirs {
-            for entry in fs::read_dir(&dir)? {
-                let entry = entry?;
-                let path = entry.path();
-
-                if Self::is_markdown_file(&path) {
-                    // Quick check: read just the frontmatter to check ID
-                    if let Ok(Some(memo_id)) = self.extract_memo_id_from_file(&path) {
-                        if memo_id == *id {
-                            // Found the memo, load it fully
-                            return Ok(Some(self.load_memo_from_file(&path)?));
-                        }
+            for path in self.memo_files(&dir)? {
+                // Quick check: read just the frontmatter to check ID
+                if let Ok(Some(memo_id)) = self.extract_memo_id_from_file(&path) {
+                    if memo_id == *id {
+                        matches.push(path);
                     }
                 }
             }
         }
 
-        Ok(None)
+        Self::single_match(id, matches)
     }
 
-    pub async fn get_memo_async(&self, id: &MemoId) -> Result<Option<Memo>> {
-        // Check cache first
-        if let Some(cached_memo) = self.cache.get_memo(id).await {
-            // Verify cache validity if we have the file path
-            if let Some(file_path) = &cached_memo.file_path {
-                if self
-                    .cache
-                    .is_memo_valid(id, file_path)
-                    .await
-                    .unwrap_or(false)
-                {
-                    return Ok(Some((*cached_memo).clone()));
-                }
-            } else {
-                return Ok(Some((*cached_memo).clone()));
+    fn single_match(id: &MemoId, mut paths: Vec<PathBuf>) -> Result<Option<PathBuf>> {
+        if paths.len() > 1 {
+            paths.sort();
+            return Err(MemoStoreError::DuplicateId {
+                id: id.to_string(),
+                paths,
+            });
+        }
+        Ok(paths.pop())
+    }
+
+    /// Returns the cached copy of a memo if its file has not changed since it
+    /// was cached.
+    async fn cached_memo(&self, id: &MemoId) -> Option<Memo> {
+        let cached_memo = self.cache.get_memo(id).await?;
+        // Verify cache validity if we have the file path
+        if let Some(file_path) = &cached_memo.file_path {
+            if !self
+                .cache
+                .is_memo_valid(id, file_path)
+                .await
+                .unwrap_or(false)
+            {
+                return None;
             }
         }
+        Some((*cached_memo).clone())
+    }
 
-        // Cache miss or invalid, load from file
+    /// Async version of [`Self::find_memo_file`].
+    async fn find_memo_file_async(&self, id: &MemoId) -> Result<Option<PathBuf>> {
         let memoranda_dirs = self.find_memoranda_dirs_async().await?;
+        let mut matches = Vec::new();
 
         for dir in memoranda_dirs {
-            let mut dir_entries = async_fs::read_dir(&dir).await?;
-            while let Some(entry) = dir_entries.next_entry().await? {
-                let path = entry.path();
-
-                if Self::is_markdown_file(&path) {
-                    // Quick check: read just the frontmatter to check ID
-                    if let Ok(Some(memo_id)) = self.extract_memo_id_from_file_async(&path).await {
-                        if memo_id == *id {
-                            // Found the memo, load it fully and cache it
-                            let memo = self.load_memo_from_file_async(&path).await?;
-
-                            // Cache the memo
-                            self.cache.put_memo(memo.clone()).await;
-
-                            // Cache metadata for validation
-                            if let Some(file_path) = &memo.file_path {
-                                let _ = self.create_and_cache_metadata(&memo, file_path).await;
-                            }
-
-                            return Ok(Some(memo));
-                        }
+            for path in self.memo_files_async(&dir).await? {
+                // Quick check: read just the frontmatter to check ID
+                if let Ok(Some(memo_id)) = self.extract_memo_id_from_file_async(&path).await {
+                    if memo_id == *id {
+                        matches.push(path);
                     }
                 }
             }
         }
 
-        Ok(None)
+        Self::single_match(id, matches)
     }
 
-    fn extract_memo_id_from_file(&self, file_path: &Path) -> Result<Option<MemoId>> {
-        let file_path_clone = file_path.to_path_buf();
-
-        // Retry file read operation with exponential backoff
-        let content = retry_with_backoff_sync(
-            || fs::read_to_string(&file_path_clone).map_err(anyhow::Error::from),
-            RetryConfig::for_file_io(),
-            "read_memo_file",
-        )?;
-
-        Self::extract_memo_id_from_content(&content, file_path)
+    /// Reads the memo with `id` for a read-modify-write, as
+    /// [`Self::load_memo_for_update`] does.
+    fn memo_for_update(&self, id: &MemoId) -> Result<(Memo, FileStamp)> {
+        let path = self
+            .find_memo_file(id)?
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+        self.load_memo_for_update(&path)
     }
 
-    async fn extract_memo_id_from_file_async(&self, file_path: &Path) -> Result<Option<MemoId>> {
-        let content = async_fs::read_to_string(file_path).await?;
-        Self::extract_memo_id_from_content(&content, file_path)
+    /// Async version of [`Self::memo_for_update`]. The file is looked up in
+    /// the cache first, but the memo is always read from it.
+    async fn memo_for_update_async(&self, id: &MemoId) -> Result<(Memo, FileStamp)> {
+        let cached_path = self.cached_memo(id).await.and_then(|memo| memo.file_path);
+        let path = match cached_path {
+            Some(path) => path,
+            None => self
+                .find_memo_file_async(id)
+                .await?
+                .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?,
+        };
+        self.load_memo_for_update_async(&path).await
     }
 
-    pub fn create_memo(&self, title: String, content: String) -> Result<Memo> {
-        let target_dir = self.get_primary_memoranda_dir()?;
-
-        let filename = sanitize_filename(&title);
-        let file_path = target_dir.join(format!("{filename}.md"));
-
-        let memo = Memo::with_file_path(title, content.clone(), Some(file_path.clone()))?;
-
-        self.save_memo_to_file(&memo, &file_path)?;
-        self.mark_index_dirty();
-
-        Ok(memo)
+    /// Reads the memo in `file_path` for a read-modify-write, along with the
+    /// stamp its file had. Saving it with [`Self::save_updated_memo`] fails
+    /// if another process has written the file since, instead of silently
+    /// dropping that write.
+    ///
+    /// The file is read again if it changes while being read, as it does when
+    /// loading completes its frontmatter.
+    fn load_memo_for_update(&self, file_path: &Path) -> Result<(Memo, FileStamp)> {
+        let mut stamp = FileStamp::read(file_path)?;
+        for _ in 0..UPDATE_READ_ATTEMPTS {
+            let memo = self.load_memo_from_file(file_path)?;
+            let after = FileStamp::read(file_path)?;
+            if after == stamp {
+                return Ok((memo, stamp));
+            }
+            stamp = after;
+        }
+        Err(MemoStoreError::WriteConflict {
+            path: file_path.to_path_buf(),
+        })
     }
 
-    pub async fn create_memo_async(&self, title: String, content: String) -> Result<Memo> {
-        let target_dir = self.get_primary_memoranda_dir_async().await?;
-
-        let filename = sanitize_filename(&title);
-        let file_path = target_dir.join(format!("{filename}.md"));
+    async fn load_memo_for_update_async(&self, file_path: &Path) -> Result<(Memo, FileStamp)> {
+        let mut stamp = FileStamp::read_async(file_path).await?;
+        for _ in 0..UPDATE_READ_ATTEMPTS {
+            let memo = self.load_memo_from_file_async(file_path).await?;
+            let after = FileStamp::read_async(file_path).await?;
+            if after == stamp {
+                return Ok((memo, stamp));
+            }
+            stamp = after;
+        }
+        Err(MemoStoreError::WriteConflict {
+            path: file_path.to_path_buf(),
+        })
+    }
 
-        let memo = Memo::with_file_path(title, content.clone(), Some(file_path.clone()))?;
+    pub async fn get_memo_async(&self, id: &MemoId) -> Result<Option<Memo>> {
+        // Check cache first
+        if let Some(memo) = self.cached_memo(id).await {
+            return Ok(Some(memo));
+        }
 
-        self.save_memo_to_file_async(&memo, &file_path).await?;
+        // Cache miss or invalid, load from file
+        let Some(path) = self.find_memo_file_async(id).await? else {
+            return Ok(None);
+        };
 
-        // Cache the newly created memo
+        // Found the memo, load it fully and cache it
+        let memo = self.load_memo_from_file_async(&path).await?;
         self.cache.put_memo(memo.clone()).await;
 
-        // Cache metadata
-        let _ = self.create_and_cache_metadata(&memo, &file_path).await;
-
-        self.mark_index_dirty();
+        // Cache metadata for validation
+        if let Some(file_path) = &memo.file_path {
+            let _ = self.create_and_cache_metadata(&memo, file_path).await;
+        }
 
-        Ok(memo)
+        Ok(Some(memo))
     }
 
-    pub fn update_memo(&self, id: &MemoId, content: String) -> Result<Memo> {
-        let mut memo = self
-            .get_memo(id)?
-            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
-
-        memo.update_content(content)?;
+    /// Loads several memos with a single scan of the memo directories.
+    ///
+    /// The result lines up with `ids`: each entry is the memo with that id, or
+    /// `None` if no file claims it. As with [`MemoStore::get_memo`], an id
+    /// claimed by more than one file is reported as [`MemoStoreError::DuplicateId`].
+    pub fn get_memos_batch(&self, ids: &[MemoId]) -> Result<Vec<Option<Memo>>> {
+        let wanted: HashSet<MemoId> = ids.iter().copied().collect();
+        let mut paths_by_id: HashMap<MemoId, Vec<PathBuf>> = HashMap::new();
+        if !wanted.is_empty() {
+            for dir in self.find_memoranda_dirs()? {
+                for path in self.memo_files(&dir)? {
+                    if let Ok(Some(memo_id)) = self.extract_memo_id_from_file(&path) {
+                        if wanted.contains(&memo_id) {
+                            paths_by_id.entry(memo_id).or_default().push(path);
+                        }
+                    }
+                }
+            }
+        }
 
-        if let Some(file_path) = &memo.file_path {
-            self.save_memo_to_file(&memo, file_path)?;
+        let mut found = HashMap::new();
+        for (id, paths) in paths_by_id {
+            if let Some(path) = Self::single_match(&id, paths)? {
+                found.insert(id, self.load_memo_from_file(&path)?);
+            }
         }
-        self.mark_index_dirty();
 
-        Ok(memo)
+        Ok(ids.iter().map(|id| found.get(id).cloned()).collect())
     }
 
-    pub async fn update_memo_async(&self, id: &MemoId, content: String) -> Result<Memo> {
-        let mut memo = self
-            .get_memo_async(id)
-            .await?
-            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+    /// Async version of [`MemoStore::get_memos_batch`]. Memos already in the
+    /// cache are served from it, and the directories are only scanned when
+    /// some id is not cached.
+    pub async fn get_memos_batch_async(&self, ids: &[MemoId]) -> Result<Vec<Option<Memo>>> {
+        let mut found = HashMap::new();
+        let mut missing = HashSet::new();
+        for id in ids {
+            if found.contains_key(id) || missing.contains(id) {
+                continue;
+            }
+            match self.cached_memo(id).await {
+                Some(memo) => {
+                    found.insert(*id, memo);
+                }
+                None => {
+                    missing.insert(*id);
+                }
+            }
+        }
 
-        memo.update_content(content)?;
+        if !missing.is_empty() {
+            let mut paths_by_id: HashMap<MemoId, Vec<PathBuf>> = HashMap::new();
+            for dir in self.find_memoranda_dirs_async().await? {
+                for path in self.memo_files_async(&dir).await? {
+                    if let Ok(Some(memo_id)) = self.extract_memo_id_from_file_async(&path).await {
+                        if missing.contains(&memo_id) {
+                            paths_by_id.entry(memo_id).or_default().push(path);
+                        }
+                    }
+                }
+            }
 
-        if let Some(file_path) = &memo.file_path {
-            self.save_memo_to_file_async(&memo, file_path).await?;
+            for (id, paths) in paths_by_id {
+                let Some(path) = Self::single_match(&id, paths)? else {
+                    continue;
+                };
+                let memo = self.load_memo_from_file_async(&path).await?;
+                self.cache.put_memo(memo.clone()).await;
+                let _ = self.create_and_cache_metadata(&memo, &path).await;
+                found.insert(id, memo);
+            }
+        }
 
-            // Update cache with new memo version
-            self.cache.put_memo(memo.clone()).await;
+        Ok(ids.iter().map(|id| found.get(id).cloned()).collect())
+    }
 
-            // Update metadata cache
-            let _ = self.create_and_cache_metadata(&memo, file_path).await;
+    /// Lists the markdown files in the `.memoranda` directories that their
+    /// `.memorandaignore` files keep from being loaded as memos.
+    pub fn ignored_memo_files(&self) -> Result<Vec<PathBuf>> {
+        let mut ignored = Vec::new();
+        for dir in self.find_memoranda_dirs()? {
+            let ignore_rules = IgnoreRules::load_memoranda_dir(&dir);
+            for folder in Self::memo_folders(&dir, &ignore_rules)? {
+                for entry in fs::read_dir(folder)? {
+                    let path = entry?.path();
+                    if self.is_memo_file(&path) && ignore_rules.is_file_ignored(&path) {
+                        ignored.push(path);
+                    }
+                }
+            }
         }
-        self.mark_index_dirty();
-
-        Ok(memo)
+        ignored.sort();
+        Ok(ignored)
     }
 
-    pub fn delete_memo(&self, id: &MemoId) -> Result<()> {
-        let memo = self
-            .get_memo(id)?
-            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+    /// Finds ids claimed by more than one memo file. Each one found is evicted
+    /// from the cache, so [`Self::get_memo_async`] rescans and reports the
+    /// conflict instead of serving the copy cached before it appeared.
+    pub fn find_duplicate_ids(&self) -> Result<Vec<DuplicateId>> {
+        let mut paths_by_id: HashMap<MemoId, Vec<PathBuf>> = HashMap::new();
+        for dir in self.find_memoranda_dirs()? {
+            for path in self.memo_files(&dir)? {
+                if let Ok(Some(memo_id)) = self.extract_memo_id_from_file(&path) {
+                    paths_by_id.entry(memo_id).or_default().push(path);
+                }
+            }
+        }
 
-        if let Some(file_path) = &memo.file_path {
-            let file_path_clone = file_path.clone();
-            retry_with_backoff_sync(
-                || fs::remove_file(&file_path_clone).map_err(anyhow::Error::from),
-                RetryConfig::for_file_io(),
-                "delete_memo_file",
-            )?;
+        let mut duplicates: Vec<DuplicateId> = paths_by_id
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(id, mut paths)| {
+                paths.sort();
+                for path in &paths {
+                    self.cache.remove_memo_sync(&id, path);
+                }
+                DuplicateId { id, paths }
+            })
+            .collect();
+        duplicates.sort_by(|a, b| a.paths.cmp(&b.paths));
+        Ok(duplicates)
+    }
+
+    /// Lists memo files whose frontmatter title does not match their file
+    /// name, whichever `title_authority` is set. Memos with `keep_filename`
+    /// set are never reported.
+    pub fn find_title_mismatches(&self) -> Result<Vec<TitleMismatch>> {
+        let mut mismatches = Vec::new();
+        for dir in self.find_memoranda_dirs()? {
+            for path in self.memo_files(&dir)? {
+                let Ok(bytes) = fs::read(&path) else {
+                    continue;
+                };
+                let Ok((content, lossy)) = self.decode_memo_bytes(bytes, &path) else {
+                    continue;
+                };
+                let Ok(memo) = self.create_memo_from_content_with_fallback(content, &path, lossy)
+                else {
+                    continue;
+                };
+                if memo.keep_filename || title_fits_filename(&memo.title, &path) {
+                    continue;
+                }
+                mismatches.push(TitleMismatch {
+                    id: memo.id,
+                    filename_title: extract_title_from_filename(&path),
+                    frontmatter_title: memo.title,
+                    file_path: path,
+                });
+            }
+        }
+        mismatches.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        Ok(mismatches)
+    }
 
-            // TODO: Remove from cache - need to handle async cache operations from sync context
+    /// Gives every duplicate but the first of each group a freshly generated id.
+    pub fn fix_duplicate_ids(&self) -> Result<Vec<IdReassignment>> {
+        let mut reassignments = Vec::new();
+        for duplicate in self.find_duplicate_ids()? {
+            for path in duplicate.paths.iter().skip(1) {
+                let mut memo = self.load_memo_from_file(path)?;
+                memo.id = MemoId::new();
+                self.save_memo_to_file(&mut memo, path)?;
+                self.cache.remove_memo_sync(&duplicate.id, path);
+                reassignments.push(IdReassignment {
+                    file_path: path.clone(),
+                    old_id: duplicate.id,
+                    new_id: memo.id,
+                });
+            }
         }
-        self.mark_index_dirty();
 
-        Ok(())
+        if !reassignments.is_empty() {
+            self.mark_index_dirty();
+        }
+        Ok(reassignments)
     }
 
-    pub async fn delete_memo_async(&self, id: &MemoId) -> Result<()> {
-        let memo = self
-            .get_memo_async(id)
-            .await?
-            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+    fn extract_memo_id_from_file(&self, file_path: &Path) -> Result<Option<MemoId>> {
+        let file_path_clone = file_path.to_path_buf();
 
-        if let Some(file_path) = &memo.file_path {
-            async_fs::remove_file(file_path).await?;
+        // Retry file read operation with exponential backoff
+        let bytes = retry_with_backoff_sync(
+            || fs::read(&file_path_clone).map_err(anyhow::Error::from),
+            RetryConfig::for_file_io(),
+            "read_memo_file",
+        )?;
 
-            // Remove from cache
-            self.cache.remove_memo(id).await;
-            self.cache.remove_metadata(file_path).await;
+        let (content, _) = self.decode_memo_bytes(bytes, file_path)?;
+        self.extract_memo_id_from_content(&content, file_path)
+    }
+
+    async fn extract_memo_id_from_file_async(&self, file_path: &Path) -> Result<Option<MemoId>> {
+        let bytes = async_fs::read(file_path).await?;
+        let (content, _) = self.decode_memo_bytes(bytes, file_path)?;
+        self.extract_memo_id_from_content(&content, file_path)
+    }
+
+    /// Decodes a memo file's bytes, returning the text and whether invalid UTF-8
+    /// had to be replaced.
+    ///
+    /// Invalid UTF-8 is an error unless `lenient_encoding` is enabled, in which
+    /// case it is replaced with U+FFFD so the memo is still listed.
+    fn decode_memo_bytes(&self, bytes: Vec<u8>, file_path: &Path) -> Result<(String, bool)> {
+        match String::from_utf8(bytes) {
+            Ok(content) => Ok((content, false)),
+            Err(e) if self.settings.lenient_encoding => {
+                warn!(
+                    "Memo file {} is not valid UTF-8; loading it with invalid bytes replaced",
+                    file_path.display()
+                );
+                Ok((String::from_utf8_lossy(e.as_bytes()).into_owned(), true))
+            }
+            Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e).into()),
         }
-        self.mark_index_dirty();
+    }
+
+    /// Applies [`TagRules::check`] to each tag, rejecting the list if any fails.
+    fn checked_tags(&self, tags: Vec<String>) -> Result<Vec<String>> {
+        tags.iter().map(|tag| self.tag_rules.check(tag)).collect()
+    }
 
+    /// Rejects content with more lines than `max_memo_lines`, if set.
+    fn check_line_limit(&self, content: &str) -> Result<()> {
+        let Some(max_lines) = self.settings.max_memo_lines else {
+            return Ok(());
+        };
+        let lines = content.bytes().filter(|&b| b == b'\n').count() + 1;
+        if lines > max_lines {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "Memo content has {lines} lines, the limit is {max_lines}; consider splitting it into several memos"
+                ),
+            });
+        }
         Ok(())
     }
 
-    fn load_memo_from_file(&self, file_path: &Path) -> Result<Memo> {
-        let content = fs::read_to_string(file_path)?;
-        self.create_memo_from_content_with_fallback(content, file_path)
+    pub fn create_memo(&self, title: String, content: String) -> Result<Memo> {
+        self.create_memo_with_tags(title, content, Vec::new())
     }
 
-    async fn load_memo_from_file_async(&self, file_path: &Path) -> Result<Memo> {
-        let content = async_fs::read_to_string(file_path).await?;
-        self.create_memo_from_content_with_fallback(content, file_path)
+    /// Creates a memo carrying `tags`, which are trimmed, checked against the
+    /// tag settings as in [`TagRules::check`] and persisted in the memo's
+    /// frontmatter.
+    ///
+    /// The memo is written to `<title>.md` in the primary `.memoranda`
+    /// directory. If that file already exists, whether another memo or a
+    /// hand-written note, nothing is written and
+    /// [`MemoStoreError::FileAlreadyExists`] is returned.
+    pub fn create_memo_with_tags(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+    ) -> Result<Memo> {
+        let result = self.write_new_memo(title, content, tags, None);
+        self.observe("create_memo", &result, |observer, memo| {
+            observer.on_memo_created(memo)
+        });
+        self.audit("create_memo", &result, |memo| memo.id);
+        result
     }
 
-    fn parse_frontmatter(&self, content: &str) -> Result<Option<Memo>> {
-        if !content.starts_with("---\n") {
-            return Ok(None);
+    /// Like [`Self::create_memo_with_tags`], but writes the memo to the memo
+    /// directory `directory` names, relative to the store root: the memo
+    /// directory itself or the directory containing it.
+    pub fn create_memo_in_directory(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        directory: &str,
+    ) -> Result<Memo> {
+        let result = self.write_new_memo(title, content, tags, Some(directory));
+        self.observe("create_memo", &result, |observer, memo| {
+            observer.on_memo_created(memo)
+        });
+        self.audit("create_memo", &result, |memo| memo.id);
+        result
+    }
+
+    fn write_new_memo(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        directory: Option<&str>,
+    ) -> Result<Memo> {
+        let tags = self.checked_tags(tags)?;
+        self.check_line_limit(&content)?;
+        let target_dir = match directory {
+            Some(directory) => self.memo_dir_for_new_memo(directory)?,
+            None => self.get_primary_memoranda_dir()?,
+        };
+        fs::create_dir_all(&target_dir)?;
+        info!("Creating memo '{}' in {}", title, target_dir.display());
+
+        let file_path = self.new_memo_path(&target_dir, &title);
+        if file_path.exists() {
+            return Err(MemoStoreError::FileAlreadyExists { path: file_path });
+        }
+
+        let mut memo = Memo::with_file_path(title, content.clone(), Some(file_path.clone()))?;
+        for tag in tags {
+            memo.add_tag(tag);
         }
 
-        let mut parts = content.splitn(3, "---\n");
-        parts.next(); // skip the first empty part
+        self.save_memo_to_file(&mut memo, &file_path)?;
+        self.mark_memo_changed(&memo.id);
 
-        let frontmatter = parts.next().ok_or(MemoStoreError::MissingFrontmatter {
-            file: "unknown".to_string(),
-        })?;
+        Ok(memo)
+    }
 
-        let _body = parts.next().ok_or(MemoStoreError::MissingFrontmatter {
-            file: "unknown".to_string(),
-        })?;
+    /// Imports every `.md` file under `dir` as a memo in the primary
+    /// `.memoranda` directory, adding `tag` to each when given.
+    ///
+    /// Titles, ids and tags come from the file as described in
+    /// [`memo_from_markdown`]. Hidden directories such as `.git` or another
+    /// store's `.memoranda` are not searched. Empty files, and files whose id
+    /// or title and content match a memo already in the store, are skipped, so
+    /// importing a directory again adds only new files. A file that cannot be
+    /// read or does not make a valid memo is reported as failed without
+    /// stopping the import. A relative `dir` is taken relative to the store root.
+    pub fn import_directory(&self, dir: &Path, tag: Option<&str>) -> Result<ImportReport> {
+        let dir = self.root_path.join(dir);
+        let dir = dir.as_path();
+        if !dir.is_dir() {
+            return Err(MemoStoreError::Validation {
+                message: format!("{} is not a directory", dir.display()),
+            });
+        }
+        let tags = self.checked_tags(tag.map(String::from).into_iter().collect())?;
+        let target_dir = self.get_primary_memoranda_dir()?;
+        let existing = self.list_memos()?;
+        let mut known_ids: HashSet<MemoId> = existing.iter().map(|memo| memo.id).collect();
+        // Files without an id get a new one each import, so re-imports are
+        // recognized by title and content instead
+        let mut known_memos: HashSet<(String, String)> = existing
+            .into_iter()
+            .map(|memo| (memo.title, memo.content))
+            .collect();
+        let mut report = ImportReport::default();
 
-        // Parse frontmatter as JSON
-        let memo = serde_json::from_str::<Memo>(frontmatter).map_err(|e| {
-            MemoStoreError::InvalidFrontmatter {
-                file: "unknown".to_string(),
-                source: e,
+        let mut paths = Vec::new();
+        for entry in WalkDir::new(dir)
+            .follow_links(self.settings.follow_symlinks)
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+            })
+        {
+            match entry {
+                Ok(entry) if self.is_memo_file(entry.path()) => paths.push(entry.into_path()),
+                Ok(_) => {}
+                Err(e) => report.failed.push(ImportIssue {
+                    path: e.path().map(Path::to_path_buf).unwrap_or_default(),
+                    reason: e.to_string(),
+                }),
             }
-        })?;
+        }
+        paths.sort();
+
+        for path in paths {
+            let text = match fs::read_to_string(&path) {
+                Ok(text) if text.trim().is_empty() => {
+                    report.skipped.push(ImportIssue {
+                        path,
+                        reason: "empty file".to_string(),
+                    });
+                    continue;
+                }
+                Ok(text) => text,
+                Err(e) => {
+                    report.failed.push(ImportIssue {
+                        path,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
 
-        Ok(Some(memo))
-    }
+            let result = memo_from_markdown(&text, &path)
+                .map_err(MemoStoreError::from)
+                .and_then(|mut memo| {
+                    if known_ids.contains(&memo.id)
+                        || known_memos.contains(&(memo.title.clone(), memo.content.clone()))
+                    {
+                        return Ok(None);
+                    }
+                    for tag in &tags {
+                        memo.add_tag(tag.clone());
+                    }
+                    self.write_imported_memo(memo, &target_dir).map(Some)
+                });
+            self.observe("create_memo", &result, |observer, memo| {
+                if let Some(memo) = memo {
+                    observer.on_memo_created(memo);
+                }
+            });
+            if let Ok(Some(memo)) = &result {
+                self.audit_memo("create_memo", memo.id);
+            }
 
-    fn save_memo_to_file(&self, memo: &Memo, file_path: &Path) -> Result<()> {
-        if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent)?;
+            match result {
+                Ok(Some(memo)) => {
+                    known_ids.insert(memo.id);
+                    known_memos.insert((memo.title.clone(), memo.content.clone()));
+                    report.imported.push(ImportedMemo {
+                        path,
+                        id: memo.id,
+                        title: memo.title,
+                    });
+                }
+                Ok(None) => report.skipped.push(ImportIssue {
+                    path,
+                    reason: "already in the store".to_string(),
+                }),
+                Err(e) => report.failed.push(ImportIssue {
+                    path,
+                    reason: e.to_string(),
+                }),
+            }
         }
 
-        let file_content = self.prepare_memo_file_content(memo)?;
+        if !report.imported.is_empty() {
+            self.mark_memos_changed(report.imported.iter().map(|memo| memo.id));
+        }
+        Ok(report)
+    }
 
-        // Atomic write: write to temporary file first, then rename
-        let temp_file_path = file_path.with_extension("md.tmp");
-        let file_path_clone = file_path.to_path_buf();
-        let temp_file_path_clone = temp_file_path.clone();
-        let file_content_clone = file_content.clone();
+    /// Writes an imported memo to a new file in `target_dir`, suffixing the
+    /// file name rather than replacing a memo with the same title.
+    fn write_imported_memo(&self, mut memo: Memo, target_dir: &Path) -> Result<Memo> {
+        self.check_imported_memo(&mut memo)?;
+        let file_path = unique_memo_path(
+            target_dir,
+            &sanitize_filename(&memo.title),
+            self.settings.new_memo_file_extension(),
+            |path| path.exists(),
+        );
+        memo.file_path = Some(file_path.clone());
+        self.save_memo_to_file(&mut memo, &file_path)?;
+        Ok(memo)
+    }
 
-        // Write to temporary file with retry logic
-        retry_with_backoff_sync(
-            || fs::write(&temp_file_path_clone, &file_content_clone).map_err(anyhow::Error::from),
-            RetryConfig::for_file_io(),
-            "write_memo_temp_file",
-        )?;
+    /// Applies the tag settings and line limit to a memo about to be imported.
+    fn check_imported_memo(&self, memo: &mut Memo) -> Result<()> {
+        for tag in self.checked_tags(std::mem::take(&mut memo.tags))? {
+            memo.add_tag(tag);
+        }
+        self.check_line_limit(&memo.content)
+    }
 
-        // Atomically rename temporary file to final destination with retry
-        retry_with_backoff_sync(
-            || fs::rename(&temp_file_path, &file_path_clone).map_err(anyhow::Error::from),
-            RetryConfig::for_file_io(),
-            "rename_memo_file",
-        )
-        .map_err(|e| {
-            // Clean up temporary file on failure
-            let _ = fs::remove_file(&temp_file_path);
-            e
-        })?;
+    /// Resolves a path given by a client against the store root, failing
+    /// unless it stays inside the root: absolute paths, `..` segments and
+    /// symlinks leading out of the root are rejected.
+    pub fn confined_path(&self, path: &Path) -> Result<PathBuf> {
+        let outside = || MemoStoreError::Validation {
+            message: format!("{} is not a path inside the store root", path.display()),
+        };
+        if path.as_os_str().is_empty()
+            || !path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(outside());
+        }
 
-        Ok(())
+        let resolved = self.root_path.join(path);
+        let root = self.root_path.canonicalize()?;
+        let existing = resolved
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or(&self.root_path)
+            .canonicalize()?;
+        if !existing.starts_with(&root) {
+            return Err(outside());
+        }
+        Ok(resolved)
     }
 
-    async fn save_memo_to_file_async(&self, memo: &Memo, file_path: &Path) -> Result<()> {
-        if let Some(parent) = file_path.parent() {
-            async_fs::create_dir_all(parent).await?;
+    /// Writes every memo to the JSON bundle at `path`, described in
+    /// [`ExportWriter`], sorted by id so exporting an unchanged store gives
+    /// the same file. A relative `path` is taken relative to the store root.
+    ///
+    /// Memo content goes through [`Self::redactor`], so a bundle never holds
+    /// text that clients are not served; importing it back replaces those
+    /// matches in the store's memos with the redaction marker.
+    ///
+    /// Memos are read and written one at a time, and the bundle goes through
+    /// a temporary file, so an export that fails part way leaves any earlier
+    /// bundle at `path` as it was. An existing file at `path` is only replaced
+    /// if it is an export bundle itself. Memo files that cannot be read are
+    /// left out with a warning, as in [`Self::list_memos`].
+    pub fn export_all(&self, path: &Path) -> Result<ExportReport> {
+        let path = self.root_path.join(path);
+        if fs::symlink_metadata(&path).is_ok() && !is_export_bundle(&path) {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "{} already exists and is not a memo export bundle; choose a different path",
+                    path.display()
+                ),
+            });
         }
+        let mut files = Vec::new();
+        for dir in self.find_memoranda_dirs()? {
+            for file in self.memo_files(&dir)? {
+                let id = match self.extract_memo_id_from_file(&file) {
+                    Ok(Some(id)) => Some(id),
+                    Ok(None) => None,
+                    Err(e) => {
+                        warn!("Failed to load memo from {}: {}", file.display(), e);
+                        continue;
+                    }
+                };
+                // A memo without frontmatter gets a new id and timestamps
+                // each time it is loaded, so it is exported with an id
+                // derived from its path and its file's modification time
+                files.push((
+                    id.unwrap_or_else(|| derived_memo_id(&file)),
+                    file,
+                    id.is_none(),
+                ));
+            }
+        }
+        files.sort();
 
-        let file_content = self.prepare_memo_file_content(memo)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = write_temp_path(&path);
+        let result = (|| {
+            let file = fs::File::create(&temp_path)?;
+            let mut writer = ExportWriter::new(std::io::BufWriter::new(file))?;
+            for (id, file, derived) in &files {
+                match self.load_memo_from_file(file) {
+                    Ok(mut memo) => {
+                        memo.id = *id;
+                        self.redactor.redact_memo(&mut memo);
+                        if *derived {
+                            if let Ok(modified) = fs::metadata(file).and_then(|m| m.modified()) {
+                                memo.created_at = modified.into();
+                                memo.updated_at = memo.created_at;
+                            }
+                        }
+                        writer.write_memo(&ExportedMemo::new(memo, &self.root_path))?;
+                    }
+                    Err(e) => warn!("Failed to load memo from {}: {}", file.display(), e),
+                }
+            }
+            let (file, memos) = writer.finish()?;
+            file.into_inner()
+                .map_err(std::io::IntoInnerError::into_error)?
+                .sync_all()?;
+            fs::rename(&temp_path, &path)?;
+            Ok(memos)
+        })();
+        let memos = result.map_err(|e: std::io::Error| {
+            let _ = fs::remove_file(&temp_path);
+            MemoStoreError::from(e)
+        })?;
 
-        // Atomic write: write to temporary file first, then rename
-        let temp_file_path = file_path.with_extension("md.tmp");
+        info!("Exported {} memos to {}", memos, path.display());
+        Ok(ExportReport { path, memos })
+    }
 
-        // Write to temporary file
-        async_fs::write(&temp_file_path, &file_content).await?;
+    /// Imports the memos in a JSON bundle written by [`Self::export_all`]
+    /// into the primary `.memoranda` directory. A relative `path` is taken
+    /// relative to the store root.
+    ///
+    /// Memos keep the ids, tags and timestamps recorded in the bundle, and
+    /// new ones get a file named after their title, suffixed rather than
+    /// replacing another file. `strategy` decides what happens to a memo
+    /// whose id is already in the store; an id repeated within the bundle
+    /// counts as being in the store from its second entry on. An entry that
+    /// is malformed or not a valid memo is reported as failed without
+    /// stopping the import.
+    pub fn import_all(&self, path: &Path, strategy: ImportStrategy) -> Result<BundleImportReport> {
+        let path = self.root_path.join(path);
+        let file = fs::File::open(&path)?;
+        let bundle: RawExportBundle = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| MemoStoreError::Validation {
+                message: format!("{} is not a memo export bundle: {e}", path.display()),
+            })?;
+        if bundle.format_version > EXPORT_FORMAT_VERSION {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "{} has bundle format version {}; only versions up to {} can be imported",
+                    path.display(),
+                    bundle.format_version,
+                    EXPORT_FORMAT_VERSION
+                ),
+            });
+        }
 
-        // Atomically rename temporary file to final destination
-        match async_fs::rename(&temp_file_path, file_path).await {
-            Ok(()) => Ok(()),
-            Err(e) => {
-                // Clean up temporary file on failure
-                let _ = async_fs::remove_file(&temp_file_path).await;
-                Err(e.into())
+        let target_dir = self.get_primary_memoranda_dir()?;
+        // Keyed the way export_all names memos, so a memo without
+        // frontmatter is recognized by the id derived from its path
+        let mut existing: HashMap<MemoId, PathBuf> = HashMap::new();
+        for dir in self.find_memoranda_dirs()? {
+            for file in self.memo_files(&dir)? {
+                if let Ok(id) = self.extract_memo_id_from_file(&file) {
+                    existing.insert(id.unwrap_or_else(|| derived_memo_id(&file)), file);
+                }
             }
         }
-    }
 
-    pub fn search_memos(&self, query: &str) -> Result<Vec<SearchResult>> {
-        let memos = self.list_memos()?;
-        self.ensure_index_updated(&memos)?;
+        let mut report = BundleImportReport::default();
+        let mut changed = Vec::new();
+        for (index, entry) in bundle.memos.into_iter().enumerate() {
+            let id = entry.get("id").and_then(|id| MemoId::from_json(id).ok());
+            let memo = serde_json::from_value::<ExportedMemo>(entry)
+                .map_err(|e| MemoStoreError::Validation {
+                    message: format!("Invalid bundle entry: {e}"),
+                })
+                .and_then(|exported| Ok(exported.into_memo()?));
+            let result = memo.and_then(|mut memo| match existing.get(&memo.id) {
+                Some(_) if strategy == ImportStrategy::SkipExisting => Ok(None),
+                Some(file_path) if strategy == ImportStrategy::Overwrite => {
+                    self.check_imported_memo(&mut memo)?;
+                    memo.file_path = Some(file_path.clone());
+                    self.save_memo_to_file(&mut memo, file_path)?;
+                    self.cache.remove_memo_sync(&memo.id, file_path);
+                    Ok(Some((memo, true)))
+                }
+                existing_memo => {
+                    if existing_memo.is_some() {
+                        memo.id = MemoId::new();
+                    }
+                    let memo = self.write_imported_memo(memo, &target_dir)?;
+                    Ok(Some((memo, false)))
+                }
+            });
+
+            self.observe(
+                "import_memos",
+                &result,
+                |observer, imported| match imported {
+                    Some((memo, true)) => observer.on_memo_updated(memo),
+                    Some((memo, false)) => observer.on_memo_created(memo),
+                    None => {}
+                },
+            );
+            match &result {
+                Ok(Some((memo, true))) => self.audit_memo("update_memo", memo.id),
+                Ok(Some((memo, false))) => self.audit_memo("create_memo", memo.id),
+                _ => {}
+            }
 
-        let searcher = self.searcher.read().unwrap();
-        let search_query = SearchQuery::parse_query(query);
-        let results = searcher.search(&search_query, &memos);
+            match result {
+                Ok(Some((memo, overwritten))) => {
+                    if overwritten {
+                        report.overwritten += 1;
+                    } else {
+                        report.created += 1;
+                        if let Some(file_path) = &memo.file_path {
+                            existing.insert(memo.id, file_path.clone());
+                        }
+                    }
+                    changed.push(memo.id);
+                }
+                Ok(None) => report.skipped += 1,
+                Err(e) => report.failed.push(BundleImportIssue {
+                    index,
+                    id,
+                    reason: e.to_string(),
+                }),
+            }
+        }
 
-        Ok(results)
+        if !changed.is_empty() {
+            self.mark_memos_changed(changed);
+        }
+        info!(
+            "Imported {} ({:?}): {} created, {} overwritten, {} skipped, {} failed",
+            path.display(),
+            strategy,
+            report.created,
+            report.overwritten,
+            report.skipped,
+            report.failed.len()
+        );
+        Ok(report)
     }
 
-    pub fn search_memos_with_query(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
-        let memos = self.list_memos()?;
-        self.ensure_index_updated(&memos)?;
+    /// Starts a memo whose content arrives in chunks, returning the token that
+    /// identifies the write in later calls.
+    ///
+    /// Chunks are staged in a temporary file under the primary `.memoranda`
+    /// directory; nothing is visible as a memo until [`Self::commit_memo_write`].
+    pub fn begin_memo_write(&self, title: String, tags: Vec<String>) -> Result<String> {
+        // Reject a bad title now rather than after all the chunks were sent
+        Memo::new(title.clone(), String::new())?;
+        let tags = self.checked_tags(tags)?;
+        let target_dir = self.get_primary_memoranda_dir()?;
+        let file_path = self.new_memo_path(&target_dir, &title);
+        if file_path.exists() {
+            return Err(MemoStoreError::FileAlreadyExists { path: file_path });
+        }
+        self.staged_writes.begin(&target_dir, title, tags)
+    }
 
-        let searcher = self.searcher.read().unwrap();
-        let results = searcher.search(query, &memos);
+    /// Appends a chunk to a staged write, returning the total bytes staged.
+    /// A chunk that would take the memo past `max_memo_lines` is rejected.
+    pub fn write_memo_chunk(&self, token: &str, data: &str) -> Result<usize> {
+        self.staged_writes
+            .append(token, data, self.settings.max_memo_lines)
+    }
 
-        Ok(results)
+    /// Turns a staged write into a memo, written atomically like any other.
+    /// If the memo cannot be written, the staged content is kept so the
+    /// commit can be retried, or the write aborted.
+    pub fn commit_memo_write(&self, token: &str) -> Result<Memo> {
+        let (write, content) = self.staged_writes.read(token)?;
+        let result = self.write_new_memo(write.title, content, write.tags, None);
+        if result.is_ok() {
+            self.staged_writes.finish(token)?;
+        }
+        self.observe("create_memo", &result, |observer, memo| {
+            observer.on_memo_created(memo)
+        });
+        self.audit("create_memo", &result, |memo| memo.id);
+        result
     }
 
-    pub fn get_all_context(&self) -> Result<String> {
-        let memos = self.list_memos()?;
-        let searcher = MemoSearcher::new();
+    /// Discards a staged write without creating a memo.
+    pub fn abort_memo_write(&self, token: &str) -> Result<()> {
+        self.staged_writes.abort(token)
+    }
 
-        Ok(searcher.get_all_context(&memos))
+    pub async fn create_memo_async(&self, title: String, content: String) -> Result<Memo> {
+        self.create_memo_with_tags_async(title, content, Vec::new())
+            .await
     }
 
-    /// Ensures the search index is up-to-date with the current memos
-    fn ensure_index_updated(&self, memos: &[Memo]) -> Result<()> {
-        let is_dirty = *self.index_dirty.read().unwrap();
+    /// Async counterpart of [`Self::create_memo_with_tags`].
+    pub async fn create_memo_with_tags_async(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+    ) -> Result<Memo> {
+        let result = self.write_new_memo_async(title, content, tags, None).await;
+        self.observe("create_memo", &result, |observer, memo| {
+            observer.on_memo_created(memo)
+        });
+        self.audit("create_memo", &result, |memo| memo.id);
+        result
+    }
 
-        if is_dirty {
-            let mut searcher = self.searcher.write().unwrap();
-            *searcher = MemoSearcher::new();
+    /// Async counterpart of [`Self::create_memo_in_directory`].
+    pub async fn create_memo_in_directory_async(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        directory: &str,
+    ) -> Result<Memo> {
+        let result = self
+            .write_new_memo_async(title, content, tags, Some(directory))
+            .await;
+        self.observe("create_memo", &result, |observer, memo| {
+            observer.on_memo_created(memo)
+        });
+        self.audit("create_memo", &result, |memo| memo.id);
+        result
+    }
 
-            // Re-index all memos
-            for memo in memos {
-                searcher.index_memo(memo);
-            }
+    async fn write_new_memo_async(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        directory: Option<&str>,
+    ) -> Result<Memo> {
+        let tags = self.checked_tags(tags)?;
+        self.check_line_limit(&content)?;
+        let target_dir = match directory {
+            Some(directory) => self.memo_dir_for_new_memo(directory)?,
+            None => self.get_primary_memoranda_dir_async().await?,
+        };
+        async_fs::create_dir_all(&target_dir).await?;
+        info!("Creating memo '{}' in {}", title, target_dir.display());
+
+        let file_path = self.new_memo_path(&target_dir, &title);
+        if async_fs::try_exists(&file_path).await? {
+            return Err(MemoStoreError::FileAlreadyExists { path: file_path });
+        }
 
-            *self.index_dirty.write().unwrap() = false;
+        let mut memo = Memo::with_file_path(title, content.clone(), Some(file_path.clone()))?;
+        for tag in tags {
+            memo.add_tag(tag);
         }
 
-        Ok(())
-    }
+        let metadata = self.save_memo_to_file_async(&mut memo, &file_path).await?;
 
-    /// Marks the search index as dirty, requiring re-indexing
-    fn mark_index_dirty(&self) {
-        *self.index_dirty.write().unwrap() = true;
-    }
+        // Cache the newly created memo along with the metadata from the write
+        self.cache
+            .put_memo_with_metadata(memo.clone(), metadata)
+            .await;
 
-    /// Get cache statistics for monitoring
-    pub async fn get_cache_stats(&self) -> super::cache::CacheStats {
-        self.cache.get_stats().await
-    }
+        self.mark_memo_changed(&memo.id);
 
-    /// Get cache hit ratio for monitoring
-    pub fn get_cache_hit_ratio(&self) -> f64 {
-        self.cache.cache_hit_ratio()
+        Ok(memo)
     }
 
-    /// Clear all cached memos (useful for testing or memory management)
-    pub async fn clear_cache(&self) {
-        self.cache.invalidate_all().await;
+    pub fn update_memo(&self, id: &MemoId, content: String) -> Result<Memo> {
+        let result = self.rewrite_memo(id, content);
+        self.observe("update_memo", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("update_memo", &result, |memo| memo.id);
+        result
     }
 
-    /// Preload frequently accessed memos into cache
-    pub async fn warm_cache(&self) -> Result<usize> {
-        let memos = self.list_memos_async().await?;
-        let count = memos.len();
+    fn rewrite_memo(&self, id: &MemoId, content: String) -> Result<Memo> {
+        self.check_line_limit(&content)?;
+        let (mut memo, stamp) = self.memo_for_update(id)?;
 
-        for memo in memos {
-            self.cache.put_memo(memo).await;
+        memo.update_content(content)?;
+
+        if let Some(file_path) = memo.file_path.clone() {
+            self.save_history_version(&memo.id, &file_path)?;
+            self.save_updated_memo(&mut memo, &file_path, stamp)?;
         }
+        self.mark_memo_changed(id);
 
-        info!("Warmed cache with {} memos", count);
-        Ok(count)
+        Ok(memo)
     }
-}
 
-pub fn sanitize_filename(title: &str) -> String {
-    title
-        .chars()
-        .map(|c| match c {
-            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
-            '\0'..='\x1f' => '_',
-            c => c,
+    pub async fn update_memo_async(&self, id: &MemoId, content: String) -> Result<Memo> {
+        let result = self.rewrite_memo_async(id, content).await;
+        self.observe("update_memo", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("update_memo", &result, |memo| memo.id);
+        result
+    }
+
+    async fn rewrite_memo_async(&self, id: &MemoId, content: String) -> Result<Memo> {
+        self.check_line_limit(&content)?;
+        let (mut memo, stamp) = self.memo_for_update_async(id).await?;
+
+        memo.update_content(content)?;
+
+        if let Some(file_path) = memo.file_path.clone() {
+            self.save_history_version(&memo.id, &file_path)?;
+            let metadata = self
+                .save_updated_memo_async(&mut memo, &file_path, stamp)
+                .await?;
+
+            // Update cache with new memo version and its metadata in one step
+            self.cache
+                .put_memo_with_metadata(memo.clone(), metadata)
+                .await;
+        }
+        self.mark_memo_changed(id);
+
+        Ok(memo)
+    }
+
+    /// Copies a memo's file into its history before an update overwrites it,
+    /// if `memo_history_max_versions` is set. Memos in subfolders keep their
+    /// history in their memo directory's `.history`, with the others.
+    fn save_history_version(&self, id: &MemoId, file_path: &Path) -> Result<()> {
+        let max_versions = self.settings.memo_history_max_versions;
+        if max_versions == 0 {
+            return Ok(());
+        }
+        save_version(
+            &self.memo_dir_of(file_path),
+            id,
+            file_path,
+            chrono::Utc::now(),
+            max_versions,
+        )?;
+        Ok(())
+    }
+
+    /// Lists the saved earlier versions of a memo, newest first.
+    pub fn list_versions(&self, id: &MemoId) -> Result<Vec<MemoVersion>> {
+        let mut versions = Vec::new();
+        for memoranda_dir in self.find_memoranda_dirs()? {
+            versions.extend(list_versions(&memoranda_dir, id)?);
+        }
+        versions.sort_by_key(|version| std::cmp::Reverse(version.saved_at));
+        Ok(versions)
+    }
+
+    /// Loads the version of a memo saved at `timestamp`, as reported by
+    /// [`Self::list_versions`].
+    pub fn get_version(&self, id: &MemoId, timestamp: &str) -> Result<Option<Memo>> {
+        self.list_versions(id)?
+            .into_iter()
+            .find(|version| version.timestamp == timestamp)
+            .map(|version| self.load_memo_from_file(&version.path))
+            .transpose()
+    }
+
+    /// Counts the saved versions across every `.history` directory and their size.
+    pub fn history_usage(&self) -> Result<HistoryUsage> {
+        let mut total = HistoryUsage::default();
+        for memoranda_dir in self.find_memoranda_dirs()? {
+            let usage = history_usage(&memoranda_dir)?;
+            total.versions += usage.versions;
+            total.bytes += usage.bytes;
+        }
+        Ok(total)
+    }
+
+    /// Changes a memo's title, keeping its ID and creation time.
+    ///
+    /// The file keeps its name, so a retitled memo is reported by
+    /// [`Self::find_filename_drift`] until [`Self::normalize_filenames`]
+    /// renames it (unless `keep_filename` is set). Use [`Self::rename_memo`]
+    /// to move the file along with the title.
+    pub fn update_memo_title(&self, id: &MemoId, title: String) -> Result<Memo> {
+        let result = self.retitle_memo(id, title);
+        self.observe("update_memo_title", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("update_memo_title", &result, |memo| memo.id);
+        result
+    }
+
+    fn retitle_memo(&self, id: &MemoId, title: String) -> Result<Memo> {
+        let (mut memo, stamp) = self.memo_for_update(id)?;
+
+        memo.update_title(title)?;
+
+        if let Some(file_path) = memo.file_path.clone() {
+            self.save_updated_memo(&mut memo, &file_path, stamp)?;
+            self.cache.remove_memo_sync(id, &file_path);
+        }
+        self.mark_memo_changed(id);
+
+        Ok(memo)
+    }
+
+    pub async fn update_memo_title_async(&self, id: &MemoId, title: String) -> Result<Memo> {
+        let result = self.retitle_memo_async(id, title).await;
+        self.observe("update_memo_title", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("update_memo_title", &result, |memo| memo.id);
+        result
+    }
+
+    async fn retitle_memo_async(&self, id: &MemoId, title: String) -> Result<Memo> {
+        let (mut memo, stamp) = self.memo_for_update_async(id).await?;
+
+        memo.update_title(title)?;
+
+        if let Some(file_path) = memo.file_path.clone() {
+            let metadata = self
+                .save_updated_memo_async(&mut memo, &file_path, stamp)
+                .await?;
+            self.cache
+                .put_memo_with_metadata(memo.clone(), metadata)
+                .await;
+        }
+        self.mark_memo_changed(id);
+
+        Ok(memo)
+    }
+
+    /// Changes a memo's title and moves its file to match, keeping its ID and
+    /// creation time.
+    ///
+    /// The file stays in its directory under the sanitized new title. If another
+    /// file already has that name, a ` (N)` suffix is added, as
+    /// [`Self::normalize_filenames`] does.
+    pub fn rename_memo(&self, id: &MemoId, new_title: String) -> Result<Memo> {
+        let result = self.move_and_retitle_memo(id, new_title);
+        self.observe("rename_memo", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("rename_memo", &result, |memo| memo.id);
+        result
+    }
+
+    fn move_and_retitle_memo(&self, id: &MemoId, new_title: String) -> Result<Memo> {
+        let (mut memo, stamp) = self.memo_for_update(id)?;
+        memo.update_title(new_title)?;
+
+        let Some(old_path) = memo.file_path.clone() else {
+            self.mark_memo_changed(id);
+            return Ok(memo);
+        };
+        let stem = sanitize_filename(&memo.title);
+        let unchanged =
+            stem.is_empty() || old_path.file_stem().and_then(|s| s.to_str()) == Some(stem.as_str());
+        if unchanged {
+            self.save_updated_memo(&mut memo, &old_path, stamp)?;
+            self.mark_memo_changed(id);
+            return Ok(memo);
+        }
+
+        let dir = old_path.parent().unwrap_or_else(|| Path::new("."));
+        let new_path = unique_memo_path(dir, &stem, memo_file_extension(&old_path), |path| {
+            path.exists()
+        });
+
+        // Move first so the rewrite below keeps anything else in the file;
+        // the moved file keeps its stamp
+        Self::move_memo_file(&old_path, &new_path)?;
+        memo.file_path = Some(new_path.clone());
+        if let Err(e) = self.save_updated_memo(&mut memo, &new_path, stamp) {
+            let _ = Self::move_memo_file(&new_path, &old_path);
+            return Err(e);
+        }
+        info!("Renamed {} to {}", old_path.display(), new_path.display());
+        self.cache.remove_memo_sync(id, &old_path);
+        self.mark_memo_changed(id);
+
+        Ok(memo)
+    }
+
+    /// Finds the memo titled `title`, ignoring case. If no title matches, a memo
+    /// with a matching alias is returned instead.
+    pub fn get_memo_by_title(&self, title: &str) -> Result<Option<Memo>> {
+        let mut memos = self.list_memos()?;
+        let index = memos
+            .iter()
+            .position(|memo| names_match(&memo.title, title))
+            .or_else(|| memos.iter().position(|memo| memo.answers_to(title)));
+
+        Ok(index.map(|index| memos.swap_remove(index)))
+    }
+
+    /// Resolves a `[[wiki-link]]` (brackets optional) to the memo it names.
+    pub fn resolve_wiki_link(&self, link: &str) -> Result<Option<Memo>> {
+        self.get_memo_by_title(wiki_link_target(link))
+    }
+
+    /// Finds the shortest chain of wiki-links between two memos, following
+    /// links in either direction, up to `link_path_max_depth` links long.
+    ///
+    /// Returns `None` if the memos are not connected within that bound.
+    pub fn link_path(&self, from: &MemoId, to: &MemoId) -> Result<Option<Vec<Memo>>> {
+        let memos = self.list_memos()?;
+        for id in [from, to] {
+            if !memos.iter().any(|memo| memo.id == *id) {
+                return Err(MemoStoreError::MemoNotFound { id: id.to_string() });
+            }
+        }
+
+        let path = shortest_link_path(&memos, from, to, self.settings.link_path_max_depth);
+        Ok(path.map(|ids| {
+            ids.iter()
+                .filter_map(|id| memos.iter().find(|memo| memo.id == *id).cloned())
+                .collect()
+        }))
+    }
+
+    /// Ranks memos by how many other memos link to them, most linked first,
+    /// with ties broken by title. Memos nothing links to are left out; a
+    /// `limit` of 0 returns every linked memo.
+    pub fn hub_memos(&self, limit: usize) -> Result<Vec<HubMemo>> {
+        let memos = self.list_memos()?;
+        let counts = backlink_counts(&memos);
+
+        let mut hubs: Vec<_> = memos
+            .into_iter()
+            .filter_map(|memo| {
+                counts.get(&memo.id).map(|&backlinks| HubMemo {
+                    id: memo.id,
+                    title: memo.title,
+                    backlinks,
+                })
+            })
+            .collect();
+        hubs.sort_by(|a, b| {
+            b.backlinks
+                .cmp(&a.backlinks)
+                .then_with(|| a.title.cmp(&b.title))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        if limit > 0 {
+            hubs.truncate(limit);
+        }
+        Ok(hubs)
+    }
+
+    /// Builds the `[[wiki-link]]` graph of every memo, as described in
+    /// [`link_graph`], keeping at most `limit` nodes unless it is 0.
+    pub fn link_graph(&self, include_unresolved: bool, limit: usize) -> Result<LinkGraph> {
+        let memos = self.list_memos()?;
+        Ok(link_graph(&memos, include_unresolved, limit))
+    }
+
+    /// Adds an alternate title to a memo.
+    ///
+    /// Aliases are unique across the store: an alias matching another memo's
+    /// title or alias is rejected so title lookups stay deterministic.
+    pub fn add_alias(&self, id: &MemoId, alias: String) -> Result<Memo> {
+        let result = self.write_alias(id, |memo, memos| {
+            if let Some(owner) = memos
+                .iter()
+                .find(|other| other.id != memo.id && other.answers_to(&alias))
+            {
+                return Err(MemoStoreError::AliasConflict {
+                    alias: alias.trim().to_string(),
+                    id: owner.id.to_string(),
+                });
+            }
+            Ok(memo.add_alias(alias)?)
+        });
+        self.observe("add_alias", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("add_alias", &result, |memo| memo.id);
+        result
+    }
+
+    /// Removes an alternate title from a memo.
+    pub fn remove_alias(&self, id: &MemoId, alias: &str) -> Result<Memo> {
+        let result = self.write_alias(id, |memo, _| {
+            if memo.remove_alias(alias) {
+                Ok(true)
+            } else {
+                Err(MemoStoreError::Validation {
+                    message: format!("Memo {} has no alias '{}'", memo.id, alias.trim()),
+                })
+            }
+        });
+        self.observe("remove_alias", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("remove_alias", &result, |memo| memo.id);
+        result
+    }
+
+    /// Applies an alias change to a memo, saving it if `change` reports a modification.
+    fn write_alias(
+        &self,
+        id: &MemoId,
+        change: impl FnOnce(&mut Memo, &[Memo]) -> Result<bool>,
+    ) -> Result<Memo> {
+        let memos = self.list_memos()?;
+        let file_path = memos
+            .iter()
+            .find(|memo| memo.id == *id)
+            .and_then(|memo| memo.file_path.clone())
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+        let (mut memo, stamp) = self.load_memo_for_update(&file_path)?;
+
+        if change(&mut memo, &memos)? {
+            if let Some(file_path) = memo.file_path.clone() {
+                self.save_updated_memo(&mut memo, &file_path, stamp)?;
+                self.cache.remove_memo_sync(id, &file_path);
+            }
+            self.mark_memo_changed(id);
+        }
+
+        Ok(memo)
+    }
+
+    /// Adds `tags` to a memo, in the order given, after checking them as in
+    /// [`TagRules::check`]. Tags the memo already carries, compared
+    /// case-insensitively, and repeats within `tags` are skipped.
+    pub async fn add_tags_to_memo(&self, id: &MemoId, tags: Vec<String>) -> Result<Memo> {
+        let result = match self.checked_tags(tags) {
+            Ok(tags) => {
+                self.write_tags_async(id, |memo| {
+                    let before = memo.tags.len();
+                    for tag in tags {
+                        if !memo.has_tag(&tag) {
+                            memo.add_tag(tag);
+                        }
+                    }
+                    memo.tags.len() != before
+                })
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        self.observe("add_tags", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("add_tags", &result, |memo| memo.id);
+        result
+    }
+
+    /// Removes `tags` from a memo, compared case-insensitively, keeping the
+    /// order of the rest. Tags the memo does not carry are ignored.
+    pub async fn remove_tags_from_memo(&self, id: &MemoId, tags: &[String]) -> Result<Memo> {
+        let result = self
+            .write_tags_async(id, |memo| {
+                let before = memo.tags.len();
+                memo.tags
+                    .retain(|own| !tags.iter().any(|tag| names_match(own, tag)));
+                memo.tags.len() != before
+            })
+            .await;
+        self.observe("remove_tags", &result, |observer, memo| {
+            observer.on_memo_updated(memo)
+        });
+        self.audit("remove_tags", &result, |memo| memo.id);
+        result
+    }
+
+    /// Applies a tag change to a memo, saving it and updating the cache if
+    /// `change` reports a modification.
+    async fn write_tags_async(
+        &self,
+        id: &MemoId,
+        change: impl FnOnce(&mut Memo) -> bool,
+    ) -> Result<Memo> {
+        let (mut memo, stamp) = self.memo_for_update_async(id).await?;
+
+        if change(&mut memo) {
+            memo.updated_at = chrono::Utc::now();
+            if let Some(file_path) = memo.file_path.clone() {
+                let metadata = self
+                    .save_updated_memo_async(&mut memo, &file_path, stamp)
+                    .await?;
+                self.cache
+                    .put_memo_with_metadata(memo.clone(), metadata)
+                    .await;
+            }
+            self.mark_memo_changed(id);
+        }
+
+        Ok(memo)
+    }
+
+    pub fn delete_memo(&self, id: &MemoId) -> Result<()> {
+        let result = self.remove_memo_file(id);
+        self.observe("delete_memo", &result, |observer, _| {
+            observer.on_memo_deleted(id)
+        });
+        self.audit("delete_memo", &result, |_| *id);
+        result
+    }
+
+    fn remove_memo_file(&self, id: &MemoId) -> Result<()> {
+        let memo = self
+            .get_memo(id)?
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+
+        if let Some(file_path) = &memo.file_path {
+            let _lock = self.lock_memo_dir(file_path)?;
+            let file_path_clone = file_path.clone();
+            retry_with_backoff_sync(
+                || fs::remove_file(&file_path_clone).map_err(anyhow::Error::from),
+                RetryConfig::for_file_io(),
+                "delete_memo_file",
+            )?;
+
+            self.cache.remove_memo_sync(id, file_path);
+        }
+        self.mark_memo_changed(id);
+
+        Ok(())
+    }
+
+    pub async fn delete_memo_async(&self, id: &MemoId) -> Result<()> {
+        let result = self.remove_memo_file_async(id).await;
+        self.observe("delete_memo", &result, |observer, _| {
+            observer.on_memo_deleted(id)
+        });
+        self.audit("delete_memo", &result, |_| *id);
+        result
+    }
+
+    async fn remove_memo_file_async(&self, id: &MemoId) -> Result<()> {
+        let memo = self
+            .get_memo_async(id)
+            .await?
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+
+        if let Some(file_path) = &memo.file_path {
+            self.remove_file_locked_async(file_path).await?;
+
+            // Remove from cache
+            self.cache.remove_memo(id).await;
+            self.cache.remove_metadata(file_path).await;
+        }
+        self.mark_memo_changed(id);
+
+        Ok(())
+    }
+
+    /// Deletes a memo file while holding the lock on its memo directory.
+    async fn remove_file_locked_async(&self, file_path: &Path) -> Result<()> {
+        let _lock = self.lock_memo_dir_async(file_path).await?;
+        async_fs::remove_file(file_path).await?;
+        Ok(())
+    }
+
+    /// Takes the lock on the memo directory holding `file_path`, so no other
+    /// process writes or deletes a memo there until it is dropped. Returns
+    /// `None` when `file_locking` is off.
+    fn lock_memo_dir(&self, file_path: &Path) -> Result<Option<StoreLock>> {
+        if !self.settings.file_locking {
+            return Ok(None);
+        }
+        StoreLock::acquire(&self.memo_dir_of(file_path), self.file_lock_timeout()).map(Some)
+    }
+
+    async fn lock_memo_dir_async(&self, file_path: &Path) -> Result<Option<StoreLock>> {
+        if !self.settings.file_locking {
+            return Ok(None);
+        }
+        StoreLock::acquire_async(&self.memo_dir_of(file_path), self.file_lock_timeout())
+            .await
+            .map(Some)
+    }
+
+    fn file_lock_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.settings.file_lock_timeout_ms)
+    }
+
+    /// The memo directory `file_path` is in, looking past memo subfolders,
+    /// or the directory containing it when it is not in one.
+    fn memo_dir_of(&self, file_path: &Path) -> PathBuf {
+        let parent = file_path.parent().unwrap_or_else(|| Path::new("."));
+        parent
+            .ancestors()
+            .find(|dir| {
+                dir.file_name().and_then(|name| name.to_str())
+                    == Some(self.settings.memo_dir_name.as_str())
+            })
+            .unwrap_or(parent)
+            .to_path_buf()
+    }
+
+    /// Deletes several memos in one pass, returning an outcome per id so partial
+    /// failures are visible. With `soft` set, memos are moved into `.trash`
+    /// instead of being removed. The store is scanned once and the search index
+    /// is invalidated once at the end.
+    pub async fn delete_memos_async(
+        &self,
+        ids: &[MemoId],
+        soft: bool,
+    ) -> Result<Vec<DeleteOutcome>> {
+        let mut paths: HashMap<MemoId, PathBuf> = self
+            .list_memos_async()
+            .await?
+            .into_iter()
+            .filter_map(|memo| memo.file_path.map(|path| (memo.id, path)))
+            .collect();
+        let now = chrono::Utc::now();
+        let mut outcomes = Vec::with_capacity(ids.len());
+        let mut removed = Vec::new();
+
+        for id in ids {
+            let result = match paths.remove(id) {
+                Some(file_path) if soft => {
+                    Self::move_memo_file_async(&file_path, &trash_destination(&file_path, now))
+                        .await
+                        .map(|()| file_path)
+                }
+                Some(file_path) => self
+                    .remove_file_locked_async(&file_path)
+                    .await
+                    .map(|()| file_path),
+                None => Err(MemoStoreError::MemoNotFound { id: id.to_string() }),
+            };
+
+            self.observe("delete_memo", &result, |observer, _| {
+                observer.on_memo_deleted(id)
+            });
+            self.audit("delete_memo", &result, |_| *id);
+            match result {
+                Ok(file_path) => {
+                    outcomes.push(DeleteOutcome::deleted(id));
+                    removed.push((*id, file_path));
+                }
+                Err(e) => outcomes.push(DeleteOutcome::failed(id, e)),
+            }
+        }
+
+        for (id, file_path) in &removed {
+            self.cache.remove_memo(id).await;
+            self.cache.remove_metadata(file_path).await;
+        }
+        if !removed.is_empty() {
+            self.mark_memos_changed(removed.iter().map(|(id, _)| *id));
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Moves a memo into the `.trash` directory next to it, from where it can
+    /// be restored with [`Self::untrash_memo`] until compaction purges it.
+    pub fn trash_memo(&self, id: &MemoId) -> Result<TrashedMemo> {
+        let result = self.move_memo_to_trash(id);
+        self.observe("trash_memo", &result, |observer, _| {
+            observer.on_memo_deleted(id)
+        });
+        self.audit("trash_memo", &result, |_| *id);
+        result
+    }
+
+    fn move_memo_to_trash(&self, id: &MemoId) -> Result<TrashedMemo> {
+        let memo = self
+            .get_memo(id)?
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+        let file_path = memo
+            .file_path
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+
+        let trashed_path = trash_destination(&file_path, chrono::Utc::now());
+        Self::move_memo_file(&file_path, &trashed_path)?;
+        self.cache.remove_memo_sync(id, &file_path);
+        self.mark_memo_changed(id);
+
+        Ok(TrashedMemo {
+            id: memo.id,
+            title: memo.title,
+            trashed_at: trashed_at(&trashed_path),
+            trashed_path,
+            original_path: file_path,
         })
-        .collect::<String>()
-        .trim_matches('.')
-        .to_string()
-}
+    }
+
+    /// Lists memos in every `.trash` directory, most recently trashed first.
+    pub fn list_trash(&self) -> Result<Vec<TrashedMemo>> {
+        let mut trashed = Vec::new();
+        for trash_dir in self.trash_dirs()? {
+            for entry in fs::read_dir(&trash_dir)? {
+                let path = entry?.path();
+                if !self.is_memo_file(&path) {
+                    continue;
+                }
+                match self.load_memo_from_file(&path) {
+                    Ok(memo) => trashed.push(TrashedMemo {
+                        id: memo.id,
+                        title: memo.title,
+                        original_path: original_path(&path),
+                        trashed_at: trashed_at(&path),
+                        trashed_path: path,
+                    }),
+                    Err(e) => warn!("Skipping unreadable trashed memo {}: {}", path.display(), e),
+                }
+            }
+        }
+        trashed.sort_by(|a, b| {
+            b.trashed_at
+                .cmp(&a.trashed_at)
+                .then_with(|| a.trashed_path.cmp(&b.trashed_path))
+        });
+        Ok(trashed)
+    }
+
+    /// The `.trash` directories of every `.memoranda` directory and memo
+    /// subfolder, since memos are trashed next to where they were.
+    fn trash_dirs(&self) -> Result<Vec<PathBuf>> {
+        let mut trash_dirs = Vec::new();
+        for memoranda_dir in self.find_memoranda_dirs()? {
+            let ignore_rules = IgnoreRules::load_memoranda_dir(&memoranda_dir);
+            for folder in Self::memo_folders(&memoranda_dir, &ignore_rules)? {
+                let trash_dir = folder.join(TRASH_DIR_NAME);
+                if trash_dir.is_dir() {
+                    trash_dirs.push(trash_dir);
+                }
+            }
+        }
+        Ok(trash_dirs)
+    }
+
+    /// Moves a trashed memo back to where it was trashed from.
+    ///
+    /// Fails without moving anything if no trashed memo has this id or if a
+    /// file now occupies the original path.
+    pub fn untrash_memo(&self, id: &MemoId) -> Result<Memo> {
+        let result = self.restore_from_trash(id);
+        self.observe("untrash_memo", &result, |observer, memo| {
+            observer.on_memo_created(memo)
+        });
+        self.audit("untrash_memo", &result, |memo| memo.id);
+        result
+    }
+
+    fn restore_from_trash(&self, id: &MemoId) -> Result<Memo> {
+        // Most recently trashed first, so a memo trashed twice restores its latest copy
+        let trashed = self
+            .list_trash()?
+            .into_iter()
+            .find(|trashed| trashed.id == *id)
+            .ok_or_else(|| MemoStoreError::MemoNotFound {
+                id: format!("{id} (not in trash)"),
+            })?;
+
+        if trashed.original_path.exists() {
+            return Err(MemoStoreError::Validation {
+                message: format!(
+                    "Cannot untrash memo {id}: {} already exists",
+                    trashed.original_path.display()
+                ),
+            });
+        }
+        Self::move_memo_file(&trashed.trashed_path, &trashed.original_path)?;
+        self.mark_memo_changed(id);
+
+        self.load_memo_from_file(&trashed.original_path)
+    }
+
+    /// Applies a time-based retention policy to every memo in the store.
+    ///
+    /// Memos whose `updated_at` is older than the policy's `max_age` and that carry
+    /// none of its exempt tags are archived, trashed, or only reported.
+    pub fn apply_retention_policy(&self, policy: &RetentionPolicy) -> Result<RetentionReport> {
+        self.run_retention_policy(policy, false)
+    }
+
+    /// Reports exactly what `apply_retention_policy` would change, without touching any files.
+    pub fn preview_retention_policy(&self, policy: &RetentionPolicy) -> Result<RetentionReport> {
+        self.run_retention_policy(policy, true)
+    }
+
+    fn run_retention_policy(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<RetentionReport> {
+        let now = chrono::Utc::now();
+        let mut report = RetentionReport::new(policy.action, dry_run);
+
+        for memo in self.list_memos()? {
+            report.scanned += 1;
+
+            if !policy.is_expired(&memo, now) {
+                continue;
+            }
+            if policy.is_exempt(&memo) {
+                report.exempt += 1;
+                continue;
+            }
+            let Some(file_path) = memo.file_path.clone() else {
+                continue;
+            };
+
+            let destination = match policy.action {
+                RetentionAction::Archive => Some(archive_destination(&file_path)),
+                RetentionAction::Trash => Some(trash_destination(&file_path, now)),
+                RetentionAction::Report => None,
+            };
+
+            if !dry_run {
+                if let Some(destination) = &destination {
+                    Self::move_memo_file(&file_path, destination)?;
+                    self.cache.remove_memo_sync(&memo.id, &file_path);
+                    info!(
+                        "Retention {} moved {} to {}",
+                        policy.action,
+                        file_path.display(),
+                        destination.display()
+                    );
+                }
+            }
+
+            report.affected.push(RetentionEntry {
+                id: memo.id,
+                title: memo.title,
+                file_path,
+                updated_at: memo.updated_at,
+                destination,
+            });
+        }
+
+        if report.has_changes() {
+            self.mark_index_dirty();
+        }
+
+        Ok(report)
+    }
+
+    /// Prunes internal artifacts: trashed memos older than `trash_max_age` and
+    /// write temporaries older than `temp_file_max_age` are permanently
+    /// deleted. Saved versions in `.history` are left alone, since
+    /// `memo_history_max_versions` already bounds them, as is the audit log,
+    /// which is only ever appended to.
+    pub fn compact(&self, options: &CompactOptions) -> Result<CompactReport> {
+        let now = chrono::Utc::now();
+        let mut report = CompactReport::new(options.dry_run);
+
+        for trash_dir in self.trash_dirs()? {
+            for entry in fs::read_dir(&trash_dir)? {
+                let entry = entry?;
+                let metadata = entry.metadata()?;
+                if !metadata.is_file() {
+                    continue;
+                }
+                report.trash_scanned += 1;
+
+                let path = entry.path();
+                let created_at = match trashed_at(&path) {
+                    Some(created_at) => created_at,
+                    None => metadata.modified()?.into(),
+                };
+                if now.signed_duration_since(created_at) <= options.trash_max_age {
+                    continue;
+                }
+
+                if !options.dry_run {
+                    // Read before the file is gone, to record which memo was purged
+                    let id = (self.settings.audit_log && self.is_memo_file(&path))
+                        .then(|| self.load_memo_from_file(&path).ok())
+                        .flatten()
+                        .map(|memo| memo.id);
+                    fs::remove_file(&path)?;
+                    info!("Compaction purged {}", path.display());
+                    if let Some(id) = id {
+                        self.audit_memo("purge_memo", id);
+                    }
+                }
+                report.trash_purged.push(CompactEntry {
+                    path,
+                    bytes: metadata.len(),
+                    created_at,
+                });
+            }
+        }
+
+        for path in self.write_temp_files()? {
+            let metadata = fs::metadata(&path)?;
+            let created_at: chrono::DateTime<chrono::Utc> = metadata.modified()?.into();
+            if now.signed_duration_since(created_at) <= options.temp_file_max_age {
+                continue;
+            }
+
+            if !options.dry_run {
+                fs::remove_file(&path)?;
+                info!(
+                    "Compaction purged orphaned temporary file {}",
+                    path.display()
+                );
+            }
+            report.temp_files_purged.push(CompactEntry {
+                path,
+                bytes: metadata.len(),
+                created_at,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Temporary files of atomic memo writes in every memo folder, which are
+    /// only left behind once their write was interrupted.
+    fn write_temp_files(&self) -> Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        for memoranda_dir in self.find_memoranda_dirs()? {
+            let ignore_rules = IgnoreRules::load_memoranda_dir(&memoranda_dir);
+            for folder in Self::memo_folders(&memoranda_dir, &ignore_rules)? {
+                for entry in fs::read_dir(folder)? {
+                    let entry = entry?;
+                    if entry.file_type()?.is_file() && self.is_write_temp_file(&entry.path()) {
+                        paths.push(entry.path());
+                    }
+                }
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Lists memos whose file name does not match their sanitized title.
+    /// Memos with `keep_filename` set are never reported.
+    pub fn find_filename_drift(&self) -> Result<Vec<FilenameDrift>> {
+        Ok(plan_filename_fixes(&self.list_memos()?))
+    }
+
+    /// Renames memo files to match their titles, suffixing ` (2)`, ` (3)`, ... on
+    /// collision. With `dry_run` set, only reports the planned renames.
+    pub fn normalize_filenames(&self, dry_run: bool) -> Result<Vec<FilenameDrift>> {
+        let drift = self.find_filename_drift()?;
+
+        if !dry_run && !drift.is_empty() {
+            for entry in &drift {
+                Self::move_memo_file(&entry.file_path, &entry.expected_path)?;
+                info!(
+                    "Renamed {} to {}",
+                    entry.file_path.display(),
+                    entry.expected_path.display()
+                );
+                self.cache.remove_memo_sync(&entry.id, &entry.file_path);
+                self.audit_memo("sync_filename", entry.id);
+            }
+            self.mark_index_dirty();
+        }
+
+        Ok(drift)
+    }
+
+    /// Renames one memo's file to match its current title, as
+    /// [`Self::normalize_filenames`] does for the whole store. Returns the
+    /// rename made, or `None` if the file name already fits the title or the
+    /// memo has `keep_filename` set. The memo keeps its id.
+    pub fn sync_filename(&self, id: &MemoId) -> Result<Option<FilenameDrift>> {
+        let memo = self
+            .get_memo(id)?
+            .ok_or(MemoStoreError::MemoNotFound { id: id.to_string() })?;
+        let Some(drift) = filename_drift(&memo, |path| path.exists()) else {
+            return Ok(None);
+        };
+
+        Self::move_memo_file(&drift.file_path, &drift.expected_path)?;
+        info!(
+            "Renamed {} to {}",
+            drift.file_path.display(),
+            drift.expected_path.display()
+        );
+        self.cache.remove_memo_sync(id, &drift.file_path);
+        self.mark_memo_changed(id);
+        self.audit_memo("sync_filename", *id);
+
+        Ok(Some(drift))
+    }
+
+    /// Async version of [`MemoStore::normalize_filenames`] that also moves the cached
+    /// memo and file metadata to the new paths.
+    pub async fn normalize_filenames_async(&self, dry_run: bool) -> Result<Vec<FilenameDrift>> {
+        let memos = self.list_memos_async().await?;
+        let drift = plan_filename_fixes(&memos);
+
+        if !dry_run && !drift.is_empty() {
+            for entry in &drift {
+                Self::move_memo_file_async(&entry.file_path, &entry.expected_path).await?;
+                info!(
+                    "Renamed {} to {}",
+                    entry.file_path.display(),
+                    entry.expected_path.display()
+                );
+
+                self.cache.remove_metadata(&entry.file_path).await;
+                if let Some(memo) = memos.iter().find(|memo| memo.id == entry.id) {
+                    let mut memo = memo.clone();
+                    memo.file_path = Some(entry.expected_path.clone());
+                    self.create_and_cache_metadata(&memo, &entry.expected_path)
+                        .await?;
+                    self.cache.put_memo(memo).await;
+                }
+                self.audit_memo("sync_filename", entry.id);
+            }
+            self.mark_index_dirty();
+        }
+
+        Ok(drift)
+    }
+
+    async fn move_memo_file_async(from: &Path, to: &Path) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        async_fs::rename(from, to).await?;
+        Ok(())
+    }
+
+    fn move_memo_file(from: &Path, to: &Path) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        retry_with_backoff_sync(
+            || fs::rename(from, to).map_err(anyhow::Error::from),
+            RetryConfig::for_file_io(),
+            "move_memo_file",
+        )?;
+        Ok(())
+    }
+
+    fn load_memo_from_file(&self, file_path: &Path) -> Result<Memo> {
+        let bytes = fs::read(file_path)?;
+        self.create_memo_from_bytes(bytes, file_path)
+    }
+
+    async fn load_memo_from_file_async(&self, file_path: &Path) -> Result<Memo> {
+        let bytes = async_fs::read(file_path).await?;
+        self.create_memo_from_bytes(bytes, file_path)
+    }
+
+    fn create_memo_from_bytes(&self, bytes: Vec<u8>, file_path: &Path) -> Result<Memo> {
+        let (content, lossy) = self.decode_memo_bytes(bytes, file_path)?;
+        let mut memo = self.create_memo_from_content_with_fallback(content, file_path, lossy)?;
+        memo.encoding_warning = lossy;
+        if self.settings.title_authority == TitleAuthority::Filename {
+            self.take_title_from_filename(&mut memo, file_path);
+        }
+        Ok(memo)
+    }
+
+    /// Replaces a frontmatter title that does not match the file name with the
+    /// title the file name gives, rewriting the file when
+    /// `complete_partial_frontmatter` is set. Memos with `keep_filename` set
+    /// keep their frontmatter title.
+    fn take_title_from_filename(&self, memo: &mut Memo, file_path: &Path) {
+        if memo.keep_filename || title_fits_filename(&memo.title, file_path) {
+            return;
+        }
+        let title = extract_title_from_filename(file_path);
+        if Memo::validate_title(&title).is_err() {
+            return;
+        }
+        debug!(
+            "Title '{}' in {} differs from the file name; using '{}'",
+            memo.title,
+            file_path.display(),
+            title
+        );
+        memo.title = title;
+        if self.settings.complete_partial_frontmatter && !memo.encoding_warning {
+            match self.save_memo_to_file(memo, file_path) {
+                Ok(()) => info!("Rewrote title in {}", file_path.display()),
+                Err(e) => warn!("Failed to rewrite title in {}: {}", file_path.display(), e),
+            }
+        }
+    }
+
+    /// Parses a memo from its file's frontmatter.
+    ///
+    /// Frontmatter without an `id` is completed with one derived from the
+    /// file path, so the id stays the same across loads, and defaults for any
+    /// other missing fields; the returned flag is `true` in that case.
+    fn parse_frontmatter(&self, content: &str, file_path: &Path) -> Result<Option<(Memo, bool)>> {
+        let Some(frontmatter) = split_frontmatter(content) else {
+            return Ok(None);
+        };
+
+        let Some(body) = frontmatter.body else {
+            return Err(MemoStoreError::MissingFrontmatter {
+                file: "unknown".to_string(),
+            });
+        };
+
+        let invalid = |e: FrontmatterError| MemoStoreError::InvalidFrontmatter {
+            file: "unknown".to_string(),
+            source: e,
+        };
+        // The format is detected from the fences, so stores may mix formats
+        let mut value = frontmatter
+            .format
+            .parse(frontmatter.block)
+            .map_err(invalid)?;
+        let completed = complete_partial_frontmatter(
+            &mut value,
+            derived_memo_id(file_path),
+            &extract_title_from_filename(file_path),
+            body,
+        );
+        let memo = serde_json::from_value(value).map_err(|e| invalid(e.into()))?;
+
+        Ok(Some((memo, completed)))
+    }
+
+    fn save_memo_to_file(&self, memo: &mut Memo, file_path: &Path) -> Result<()> {
+        self.write_memo_file(memo, file_path, None)
+    }
+
+    /// Saves a memo read by [`Self::load_memo_for_update`], failing with
+    /// [`MemoStoreError::WriteConflict`] if its file no longer has `stamp`.
+    /// The check and the write are made under the memo directory's lock.
+    fn save_updated_memo(&self, memo: &mut Memo, file_path: &Path, stamp: FileStamp) -> Result<()> {
+        self.write_memo_file(memo, file_path, Some(stamp))
+    }
+
+    fn write_memo_file(
+        &self,
+        memo: &mut Memo,
+        file_path: &Path,
+        expected: Option<FileStamp>,
+    ) -> Result<()> {
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _lock = self.lock_memo_dir(file_path)?;
+        if let Some(expected) = expected {
+            expected.check(FileStamp::read(file_path), file_path)?;
+        }
+
+        let existing = if self.settings.obsidian_compat {
+            fs::read_to_string(file_path).ok()
+        } else {
+            None
+        };
+        self.normalize_line_endings(memo);
+        let file_content = self.prepare_memo_file_content(memo, file_path, existing.as_deref())?;
+
+        // Atomic write: write to temporary file first, then rename
+        let temp_file_path = write_temp_path(file_path);
+        let file_path_clone = file_path.to_path_buf();
+        let temp_file_path_clone = temp_file_path.clone();
+        let file_content_clone = file_content.clone();
+
+        // Write to temporary file with retry logic
+        retry_with_backoff_sync(
+            || fs::write(&temp_file_path_clone, &file_content_clone).map_err(anyhow::Error::from),
+            RetryConfig::for_file_io(),
+            "write_memo_temp_file",
+        )
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_file_path);
+            e
+        })?;
+
+        // Atomically rename temporary file to final destination with retry
+        retry_with_backoff_sync(
+            || fs::rename(&temp_file_path, &file_path_clone).map_err(anyhow::Error::from),
+            RetryConfig::for_file_io(),
+            "rename_memo_file",
+        )
+        .map_err(|e| {
+            // Clean up temporary file on failure
+            let _ = fs::remove_file(&temp_file_path);
+            e
+        })?;
+
+        Ok(())
+    }
+
+    /// Writes a memo atomically and returns the metadata of the written file.
+    ///
+    /// The size and modification time are read from the handle used for the write,
+    /// so callers can cache them without a second lookup of the final path.
+    async fn save_memo_to_file_async(
+        &self,
+        memo: &mut Memo,
+        file_path: &Path,
+    ) -> Result<MemoMetadata> {
+        self.write_memo_file_async(memo, file_path, None).await
+    }
+
+    /// Async version of [`Self::save_updated_memo`].
+    async fn save_updated_memo_async(
+        &self,
+        memo: &mut Memo,
+        file_path: &Path,
+        stamp: FileStamp,
+    ) -> Result<MemoMetadata> {
+        self.write_memo_file_async(memo, file_path, Some(stamp))
+            .await
+    }
+
+    async fn write_memo_file_async(
+        &self,
+        memo: &mut Memo,
+        file_path: &Path,
+        expected: Option<FileStamp>,
+    ) -> Result<MemoMetadata> {
+        use tokio::io::AsyncWriteExt;
+
+        if let Some(parent) = file_path.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+        let _lock = self.lock_memo_dir_async(file_path).await?;
+        if let Some(expected) = expected {
+            expected.check(FileStamp::read_async(file_path).await, file_path)?;
+        }
+
+        let existing = if self.settings.obsidian_compat {
+            async_fs::read_to_string(file_path).await.ok()
+        } else {
+            None
+        };
+        self.normalize_line_endings(memo);
+        let file_content = self.prepare_memo_file_content(memo, file_path, existing.as_deref())?;
+
+        // Atomic write: write to temporary file first, then rename
+        let temp_file_path = write_temp_path(file_path);
+
+        // Write to temporary file, keeping the handle to read back its metadata
+        let written = async {
+            let mut temp_file = async_fs::File::create(&temp_file_path).await?;
+            temp_file.write_all(file_content.as_bytes()).await?;
+            temp_file.flush().await?;
+            temp_file.metadata().await
+        }
+        .await;
+        let file_metadata = match written {
+            Ok(file_metadata) => file_metadata,
+            Err(e) => {
+                let _ = async_fs::remove_file(&temp_file_path).await;
+                return Err(e.into());
+            }
+        };
+
+        // Atomically rename temporary file to final destination
+        if let Err(e) = async_fs::rename(&temp_file_path, file_path).await {
+            // Clean up temporary file on failure
+            let _ = async_fs::remove_file(&temp_file_path).await;
+            return Err(e.into());
+        }
+
+        Ok(MemoMetadata {
+            id: memo.id,
+            title: memo.title.clone(),
+            file_path: file_path.to_path_buf(),
+            last_modified: file_metadata.modified()?,
+            file_size: file_metadata.len(),
+        })
+    }
+
+    pub fn search_memos(&self, query: &str) -> Result<Vec<SearchResult>> {
+        self.search_memos_with_boosts(query, &SearchBoosts::default())
+    }
+
+    /// Searches like [`Self::search_memos`], with `boosts` replacing the
+    /// configured field boosts for this search only.
+    pub fn search_memos_with_boosts(
+        &self,
+        query: &str,
+        boosts: &SearchBoosts,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_memos_in_field(query, boosts, None)
+    }
+
+    /// Searches like [`Self::search_memos_with_boosts`], matching only memo
+    /// titles or only memo content when `field` is given.
+    pub fn search_memos_in_field(
+        &self,
+        query: &str,
+        boosts: &SearchBoosts,
+        field: Option<SearchField>,
+    ) -> Result<Vec<SearchResult>> {
+        let mut parsed = SearchQuery::parse_query(query);
+        parsed.title_only = field == Some(SearchField::Title);
+        parsed.content_only = field == Some(SearchField::Content);
+        let result = self.run_search(&parsed, boosts);
+        self.observe("search_memos", &result, |observer, results| {
+            observer.on_search(query, results.len())
+        });
+        result
+    }
+
+    pub fn search_memos_with_query(&self, query: &SearchQuery) -> Result<Vec<SearchResult>> {
+        let result = self.run_search(query, &SearchBoosts::default());
+        self.observe("search_memos", &result, |observer, results| {
+            observer.on_search(&query.terms.join(" "), results.len())
+        });
+        result
+    }
+
+    fn run_search(&self, query: &SearchQuery, boosts: &SearchBoosts) -> Result<Vec<SearchResult>> {
+        let with_aliases = self.with_tag_aliases(query)?;
+        let query = with_aliases.as_ref().unwrap_or(query);
+
+        let key = SearchCacheKey {
+            index_version: self.index_version.load(Ordering::Acquire),
+            files: self.memo_files_stamp()?,
+            query: query.normalized(),
+            boosts: boosts.cache_key(),
+        };
+        if let Some(results) = self.search_cache.get(&key) {
+            #[cfg(test)]
+            SEARCH_CACHE_HITS.with(|count| count.set(count.get() + 1));
+            return Ok(results);
+        }
+
+        let memos = self.list_memos()?;
+        self.ensure_index_updated(&memos, key.index_version);
+
+        let results = self
+            .searcher()
+            .search_with_config(
+                query,
+                &memos,
+                &SearchConfig::from(&self.settings).with_boosts(boosts),
+            )
+            .map_err(|e| MemoStoreError::Validation {
+                message: e.to_string(),
+            })?;
+
+        // Results from a deferred rebuild are about to go stale, so only cache
+        // searches that ran against an up-to-date index
+        if self.pending_index_changes.read().unwrap().is_empty() {
+            self.search_cache.insert(key, results.clone());
+        }
+        Ok(results)
+    }
+
+    /// Hashes the path and [`FileStamp`] of every memo file, so cached search
+    /// results are only reused while the files on disk are unchanged, however
+    /// they were changed.
+    fn memo_files_stamp(&self) -> Result<u64> {
+        use std::hash::{Hash, Hasher};
+
+        let mut files = Vec::new();
+        for dir in self.find_memoranda_dirs()? {
+            for path in self.memo_files(&dir)? {
+                let stamp = FileStamp::read(&path).ok();
+                files.push((path, stamp));
+            }
+        }
+        files.sort();
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        files.hash(&mut hasher);
+        Ok(hasher.finish())
+    }
+
+    /// `query` with its tags widened to their aliases and canonical tags, or
+    /// `None` when it has no tags or no aliases are recorded.
+    fn with_tag_aliases(&self, query: &SearchQuery) -> Result<Option<SearchQuery>> {
+        if query.tags.is_empty() {
+            return Ok(None);
+        }
+        let aliases = self.tag_aliases()?;
+        if aliases.is_empty() {
+            return Ok(None);
+        }
+        let mut widened = query.clone();
+        widened.tags = query
+            .tags
+            .iter()
+            .flat_map(|tag| aliases.equivalents(tag))
+            .collect();
+        Ok(Some(widened))
+    }
+
+    /// Counts memo creations and updates per bucket over the last `days` days.
+    pub fn activity(
+        &self,
+        days: u32,
+        granularity: ActivityGranularity,
+    ) -> Result<Vec<ActivityBucket>> {
+        let memos = self.list_memos()?;
+        Ok(activity(
+            &memos,
+            days,
+            granularity,
+            chrono::Utc::now().date_naive(),
+        ))
+    }
+
+    pub fn get_all_context(&self) -> Result<String> {
+        self.get_context(&[], None)
+    }
+
+    /// Aggregates context from memos carrying any of `tags` and stored under
+    /// `directory`, a path relative to the store root in either slash style.
+    pub fn get_context(&self, tags: &[String], directory: Option<&str>) -> Result<String> {
+        let memos = self.list_memos()?;
+        let options = ContextOptions {
+            tags: tags.to_vec(),
+            directory: directory.map(|dir| self.resolve_context_directory(dir)),
+            ..ContextOptions::from(&self.settings)
+        };
+
+        Ok(format_context(&memos, &options))
+    }
+
+    /// Aggregates context from the memos carrying `tag`, keeping the document
+    /// within `max_bytes` when given.
+    pub fn context_for_tag(&self, tag: &str, max_bytes: Option<usize>) -> Result<String> {
+        let memos = self.list_memos()?;
+        let options = ContextOptions {
+            tags: vec![tag.to_string()],
+            max_bytes,
+            ..ContextOptions::from(&self.settings)
+        };
+
+        Ok(format_context(&memos, &options))
+    }
+
+    /// Returns the tag metadata recorded in the primary directory's `.tags.json`.
+    pub fn tag_meta(&self) -> Result<TagMetaMap> {
+        TagMetaMap::load(&self.get_primary_memoranda_dir()?)
+    }
+
+    /// Returns the metadata recorded for `tag`, compared case-insensitively.
+    pub fn get_tag_meta(&self, tag: &str) -> Result<Option<TagMeta>> {
+        Ok(self.tag_meta()?.get(tag).cloned())
+    }
+
+    /// Replaces the metadata recorded for `tag`. Empty metadata removes the
+    /// tag's entry from `.tags.json`.
+    pub fn set_tag_meta(&self, tag: &str, meta: TagMeta) -> Result<TagMeta> {
+        let tag = self.tag_rules.check(tag)?;
+        let tag = tag.as_str();
+        let memoranda_dir = self.get_primary_memoranda_dir()?;
+        let mut map = TagMetaMap::load(&memoranda_dir)?;
+        map.set(tag, meta.clone());
+        map.save(&memoranda_dir)?;
+        self.append_audit(AuditRecord::new("set_tag_meta", self.audit_actor.clone()).with_tag(tag));
+        Ok(meta)
+    }
+
+    /// Returns the tag aliases recorded in the primary directory's
+    /// `.tag-aliases.json`.
+    pub fn tag_aliases(&self) -> Result<TagAliasMap> {
+        TagAliasMap::load(&self.get_primary_memoranda_dir()?)
+    }
+
+    /// Makes `alias` an alias of `canonical`, so searching or filtering by
+    /// either matches memos carrying the other, or removes the alias when
+    /// `canonical` is `None`. Memo files are left as they are.
+    pub fn set_tag_alias(&self, alias: &str, canonical: Option<&str>) -> Result<TagAliasMap> {
+        let alias = self.tag_rules.check(alias)?;
+        let alias = alias.as_str();
+        let canonical = canonical
+            .map(|canonical| self.tag_rules.check(canonical))
+            .transpose()?;
+        let canonical = canonical.as_deref();
+        if let Some(canonical) = canonical {
+            if names_match(alias, canonical) {
+                return Err(MemoStoreError::Validation {
+                    message: format!("Tag '{alias}' cannot be an alias of itself"),
+                });
+            }
+        }
+        let memoranda_dir = self.get_primary_memoranda_dir()?;
+        let mut map = TagAliasMap::load(&memoranda_dir)?;
+        map.set(alias, canonical);
+        map.save(&memoranda_dir)?;
+        self.append_audit(
+            AuditRecord::new("set_tag_alias", self.audit_actor.clone()).with_tag(alias),
+        );
+        Ok(map)
+    }
+
+    fn resolve_context_directory(&self, directory: &str) -> PathBuf {
+        let normalized = normalize_path_text(directory);
+        if Path::new(&normalized).is_absolute() {
+            return PathBuf::from(normalized);
+        }
+        let relative = normalized.trim_start_matches("./").trim_matches('/');
+        self.root_path.join(relative)
+    }
+
+    /// Finds memos whose content mentions the given file path, in either slash style.
+    pub fn memos_mentioning_path(&self, path: &str) -> Result<Vec<PathMention>> {
+        let memos = self.list_memos()?;
+        let searcher = MemoSearcher::new();
+
+        Ok(searcher.find_path_mentions(path, &memos))
+    }
+
+    /// Ensures the search index is up-to-date with the current memos.
+    ///
+    /// Memos created, changed or removed since the last search are reindexed
+    /// one by one, so a search after a write costs O(changed memos). The
+    /// whole index is rebuilt only at startup and after changes not tracked
+    /// memo by memo.
+    ///
+    /// Full rebuilds are debounced: within `search_index_debounce_ms` of the
+    /// last rebuild, they are left for a later search so a burst of such
+    /// changes interleaved with searches triggers at most one rebuild. The new
+    /// index is built off to the side and swapped in, so concurrent searches
+    /// keep using the previous index instead of waiting. A search that finds
+    /// another update already running does the same.
+    ///
+    /// `index_version` is the version `memos` was listed at; pending changes
+    /// are only cleared if no write has bumped it since.
+    fn ensure_index_updated(&self, memos: &[Memo], index_version: u64) {
+        let pending = self.pending_index_changes.read().unwrap().clone();
+        if pending.is_empty() {
+            return;
+        }
+
+        let mut last_rebuild = match self.last_index_rebuild.try_write() {
+            Ok(last_rebuild) => last_rebuild,
+            Err(TryLockError::WouldBlock) => {
+                debug!("Search index update already running; searching the previous index");
+                return;
+            }
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        };
+        if pending.full_rebuild {
+            let debounce = Duration::from_millis(self.settings.search_index_debounce_ms);
+            if last_rebuild.is_some_and(|rebuilt_at| rebuilt_at.elapsed() < debounce) {
+                debug!("Deferring search index rebuild until changes settle");
+                return;
+            }
+            self.rebuild_index(memos, last_rebuild.is_none());
+            *last_rebuild = Some(Instant::now());
+        } else {
+            self.update_index(memos, &pending.changed);
+        }
+
+        let mut pending_changes = self.pending_index_changes.write().unwrap();
+        if self.index_version.load(Ordering::Acquire) == index_version {
+            *pending_changes = PendingIndexChanges::default();
+        }
+    }
+
+    /// Replaces the search index with one built from `memos`, and saves it.
+    ///
+    /// The first rebuild since startup starts from the index saved by the
+    /// previous run, reindexing only memos that changed in between.
+    fn rebuild_index(&self, memos: &[Memo], first_rebuild: bool) {
+        #[cfg(test)]
+        INDEX_REBUILDS.with(|count| count.set(count.get() + 1));
+
+        let memoranda_dir = self.get_primary_memoranda_dir().ok();
+        let config = SearchConfig::from(&self.settings);
+        let saved = match (&memoranda_dir, first_rebuild) {
+            (Some(dir), true) => load_search_index(dir, memos, &config),
+            _ => None,
+        };
+        #[cfg(test)]
+        if saved.is_some() {
+            SAVED_INDEX_LOADS.with(|count| count.set(count.get() + 1));
+        }
+        let searcher = saved.unwrap_or_else(|| {
+            let mut searcher = MemoSearcher::new().with_config(&config);
+            for memo in memos {
+                searcher.index_memo(memo);
+            }
+            searcher
+        });
+        if let Some(dir) = &memoranda_dir {
+            if let Err(e) = save_search_index(&searcher, memos, dir) {
+                warn!("Failed to save search index to {}: {}", dir.display(), e);
+            }
+        }
+        *self.searcher.write().unwrap() = Arc::new(searcher);
+    }
+
+    /// Reindexes the `changed` memos in place, dropping those no longer in `memos`.
+    ///
+    /// The saved index is left as is; the next startup reindexes whatever
+    /// changed since it was written.
+    fn update_index(&self, memos: &[Memo], changed: &HashSet<MemoId>) {
+        #[cfg(test)]
+        INDEX_UPDATES.with(|count| count.set(count.get() + 1));
+
+        // Updates run one at a time under `last_index_rebuild`, so the copy
+        // cannot miss another update; searches keep using the current index
+        // until the updated one is swapped in.
+        let mut searcher = MemoSearcher::clone(&self.searcher());
+        for id in changed {
+            searcher.remove_memo(id);
+        }
+        for memo in memos.iter().filter(|memo| changed.contains(&memo.id)) {
+            searcher.index_memo(memo);
+        }
+        *self.searcher.write().unwrap() = Arc::new(searcher);
+    }
+
+    /// Returns the current search index; later rebuilds do not affect it.
+    fn searcher(&self) -> Arc<MemoSearcher> {
+        Arc::clone(&self.searcher.read().unwrap())
+    }
+
+    /// Marks the whole search index as stale, for changes not tracked memo by memo.
+    fn mark_index_dirty(&self) {
+        self.record_index_change(|pending| pending.full_rebuild = true);
+    }
+
+    /// Marks one memo as needing reindexing on the next search.
+    fn mark_memo_changed(&self, id: &MemoId) {
+        self.mark_memos_changed([*id]);
+    }
+
+    /// Marks memos as needing reindexing on the next search.
+    fn mark_memos_changed(&self, ids: impl IntoIterator<Item = MemoId>) {
+        self.record_index_change(|pending| pending.changed.extend(ids));
+    }
+
+    fn record_index_change(&self, record: impl FnOnce(&mut PendingIndexChanges)) {
+        // Bumped under the pending lock so an update cannot clear the changes
+        // between the two
+        let mut pending = self.pending_index_changes.write().unwrap();
+        record(&mut pending);
+        self.index_version.fetch_add(1, Ordering::AcqRel);
+        drop(pending);
+        self.search_cache.clear();
+    }
+
+    /// Get cache statistics for monitoring
+    pub async fn get_cache_stats(&self) -> super::cache::CacheStats {
+        self.cache.get_stats().await
+    }
+
+    /// Get cache hit ratio for monitoring
+    pub fn get_cache_hit_ratio(&self) -> f64 {
+        self.cache.cache_hit_ratio()
+    }
+
+    /// Clear all cached memos (useful for testing or memory management)
+    pub async fn clear_cache(&self) {
+        self.cache.invalidate_all().await;
+    }
+
+    /// Preload frequently accessed memos into cache
+    pub async fn warm_cache(&self) -> Result<usize> {
+        let memos = self.list_memos_async().await?;
+        let count = memos.len();
+
+        for memo in memos {
+            self.cache.put_memo(memo).await;
+        }
+
+        info!("Warmed cache with {} memos", count);
+        Ok(count)
+    }
+
+    /// Compares cached memos, cached file metadata, and the search index against
+    /// the memo files on disk.
+    ///
+    /// Cache entries are stale when their file is gone, has a different size or
+    /// modification time, or now holds a different memo. The index is only
+    /// compared when it is not already scheduled for a rebuild. With `fix`, stale
+    /// cache entries are evicted and a drifted index is marked for rebuild.
+    pub async fn verify_consistency(&self, fix: bool) -> Result<ConsistencyReport> {
+        let memos = self.list_memos_async().await?;
+        let on_disk: HashMap<&Path, &Memo> = memos
+            .iter()
+            .filter_map(|memo| memo.file_path.as_deref().map(|path| (path, memo)))
+            .collect();
+        let mut report = ConsistencyReport::new(fix);
+        report.files_scanned = memos.len();
+
+        let mut stale_paths = HashSet::new();
+        for metadata in self.cache.cached_metadata() {
+            let unchanged = match stat_file_async(&metadata.file_path).await {
+                Ok(file_metadata) => {
+                    file_metadata.len() == metadata.file_size
+                        && file_metadata.modified().ok() == Some(metadata.last_modified)
+                        && on_disk
+                            .get(metadata.file_path.as_path())
+                            .is_some_and(|memo| memo.id == metadata.id)
+                }
+                Err(_) => false,
+            };
+            if !unchanged {
+                if fix {
+                    self.cache.remove_metadata(&metadata.file_path).await;
+                    self.cache.remove_memo(&metadata.id).await;
+                }
+                stale_paths.insert(metadata.file_path.clone());
+            }
+        }
+
+        for cached in self.cache.cached_memos() {
+            let Some(path) = &cached.file_path else {
+                continue;
+            };
+            let matches_disk = on_disk
+                .get(path.as_path())
+                .is_some_and(|memo| memo.id == cached.id);
+            if !matches_disk && !stale_paths.contains(path) {
+                if fix {
+                    self.cache.remove_memo(&cached.id).await;
+                }
+                stale_paths.insert(path.clone());
+            }
+        }
+        report.stale_cache_entries = stale_paths.into_iter().collect();
+        report.stale_cache_entries.sort();
+
+        if self.pending_index_changes.read().unwrap().is_empty() {
+            let searcher = self.searcher();
+            let disk_ids: HashSet<MemoId> = memos.iter().map(|memo| memo.id).collect();
+            report.missing_indexed = searcher
+                .indexed_ids()
+                .filter(|id| !disk_ids.contains(id))
+                .copied()
+                .collect();
+            report.missing_indexed.sort();
+
+            for memo in &memos {
+                let Some(path) = memo.file_path.clone() else {
+                    continue;
+                };
+                match searcher.indexed_fingerprint(&memo.id) {
+                    None => report.unindexed_files.push(path),
+                    Some(fingerprint) if fingerprint != MemoSearcher::fingerprint(memo) => {
+                        report.stale_index_entries.push(path)
+                    }
+                    Some(_) => {}
+                }
+            }
+            report.unindexed_files.sort();
+            report.stale_index_entries.sort();
+        }
+
+        if fix && report.index_drifted() {
+            self.mark_index_dirty();
+        }
+
+        if !report.is_consistent() {
+            warn!(
+                stale_cache_entries = report.stale_cache_entries.len(),
+                missing_indexed = report.missing_indexed.len(),
+                unindexed_files = report.unindexed_files.len(),
+                stale_index_entries = report.stale_index_entries.len(),
+                fixed = fix,
+                "Memo store is out of sync with the filesystem"
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Reads the file of every cached memo afresh and reports, per memo,
+    /// whether the cached copy still matches it. Unlike
+    /// [`Self::verify_consistency`], which trusts file sizes and modification
+    /// times, this compares the memos themselves, to catch cache updates that
+    /// were missed. Nothing is evicted.
+    pub async fn cache_consistency(&self) -> Result<Vec<CacheDivergence>> {
+        let mut entries = Vec::new();
+        for cached in self.cache.cached_memos() {
+            let Some(file_path) = cached.file_path.clone() else {
+                continue;
+            };
+            let (diverged, file_missing) = if async_fs::try_exists(&file_path).await? {
+                let on_disk = self.load_memo_from_file_async(&file_path).await;
+                let diverged = on_disk.map_or(true, |memo| {
+                    memo.title != cached.title
+                        || memo.content != cached.content
+                        || memo.tags != cached.tags
+                });
+                (diverged, false)
+            } else {
+                (true, true)
+            };
+            entries.push(CacheDivergence {
+                id: cached.id,
+                file_path,
+                diverged,
+                file_missing,
+            });
+        }
+        entries.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+        let diverged = entries.iter().filter(|entry| entry.diverged).count();
+        if diverged > 0 {
+            warn!(
+                diverged,
+                cached = entries.len(),
+                "Cached memos differ from their files"
+            );
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Counts path-based metadata lookups so tests can assert on filesystem churn.
+    static METADATA_LOOKUPS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Counts full search index rebuilds so tests can assert on debouncing.
+    static INDEX_REBUILDS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Counts incremental search index updates.
+    static INDEX_UPDATES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Counts rebuilds that started from a saved search index.
+    static SAVED_INDEX_LOADS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Counts searches answered from the result cache.
+    static SEARCH_CACHE_HITS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    /// Counts directories read while discovering `.memoranda` directories
+    /// with `respect_gitignore` set.
+    static DIRECTORIES_SCANNED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Looks up file metadata by path. All async path lookups in the store go
+/// through here so tests can count them; metadata read from a directory entry
+/// or an open file needs no lookup and is not counted.
+async fn stat_file_async(path: &Path) -> std::io::Result<std::fs::Metadata> {
+    #[cfg(test)]
+    METADATA_LOOKUPS.with(|count| count.set(count.get() + 1));
+    async_fs::metadata(path).await
+}
+
+pub fn sanitize_filename(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            '\0'..='\x1f' => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim_matches('.')
+        .to_string()
+}
+
+pub fn extract_title_from_filename(file_path: &Path) -> String {
+    file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Untitled")
+        .replace('_', " ")
+}
+
+/// Plans renames for memos whose file stem doesn't match `sanitize_filename(title)`.
+///
+/// Files already named `<title> (N).md` (from an earlier collision) and memos whose
+/// title was derived from the file name are left alone.
+fn plan_filename_fixes(memos: &[Memo]) -> Vec<FilenameDrift> {
+    let mut taken: HashSet<PathBuf> = memos.iter().filter_map(|m| m.file_path.clone()).collect();
+    let mut drift = Vec::new();
+
+    for memo in memos {
+        if let Some(entry) = filename_drift(memo, |path| taken.contains(path) || path.exists()) {
+            taken.insert(entry.expected_path.clone());
+            drift.push(entry);
+        }
+    }
+
+    drift
+}
+
+/// Returns where `memo`'s file should be renamed to match its title, skipping
+/// names for which `is_taken` is true, or `None` if the name already fits.
+fn filename_drift(memo: &Memo, is_taken: impl Fn(&Path) -> bool) -> Option<FilenameDrift> {
+    let file_path = memo.file_path.as_ref()?;
+    if memo.keep_filename {
+        return None;
+    }
+    if title_fits_filename(&memo.title, file_path) {
+        return None;
+    }
+
+    let expected = sanitize_filename(&memo.title);
+    let dir = file_path.parent().unwrap_or_else(|| Path::new("."));
+    Some(FilenameDrift {
+        id: memo.id,
+        title: memo.title.clone(),
+        file_path: file_path.clone(),
+        expected_path: unique_memo_path(dir, &expected, memo_file_extension(file_path), is_taken),
+    })
+}
+
+/// Whether `file_path` is named after `title`: its stem is the sanitized
+/// title, possibly suffixed ` (N)` after a collision, or the title is the one
+/// the file name gives.
+fn title_fits_filename(title: &str, file_path: &Path) -> bool {
+    let expected = sanitize_filename(title);
+    let stem = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    expected.is_empty()
+        || stem == expected
+        || title == extract_title_from_filename(file_path)
+        || is_collision_suffixed(stem, &expected)
+}
+
+/// How many times a read-modify-write reads a memo file that keeps changing
+/// under it before giving up with [`MemoStoreError::WriteConflict`].
+const UPDATE_READ_ATTEMPTS: usize = 3;
+
+/// What a memo file looked like when it was read, to tell whether another
+/// process has written it since. Saves replace the file by renaming, so on
+/// Unix a new inode shows a write even within the modification time's
+/// resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct FileStamp {
+    modified: std::time::SystemTime,
+    len: u64,
+    #[cfg(unix)]
+    inode: u64,
+}
+
+impl FileStamp {
+    fn of(metadata: &fs::Metadata) -> std::io::Result<Self> {
+        Ok(Self {
+            modified: metadata.modified()?,
+            len: metadata.len(),
+            #[cfg(unix)]
+            inode: std::os::unix::fs::MetadataExt::ino(metadata),
+        })
+    }
+
+    fn read(file_path: &Path) -> std::io::Result<Self> {
+        Self::of(&fs::metadata(file_path)?)
+    }
+
+    async fn read_async(file_path: &Path) -> std::io::Result<Self> {
+        Self::of(&async_fs::metadata(file_path).await?)
+    }
+
+    /// Fails with [`MemoStoreError::WriteConflict`] unless `current`, the
+    /// file's stamp now, is this one.
+    fn check(self, current: std::io::Result<Self>, file_path: &Path) -> Result<()> {
+        match current {
+            Ok(current) if current == self => Ok(()),
+            Ok(_) => Err(MemoStoreError::WriteConflict {
+                path: file_path.to_path_buf(),
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(MemoStoreError::WriteConflict {
+                    path: file_path.to_path_buf(),
+                })
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// The temporary file an atomic write of `file_path` goes through before it is
+/// renamed into place: `<file name>.<pid>-<ulid>.tmp` beside it, unique to
+/// the write so concurrent saves of one memo never share a temporary file.
+pub(crate) fn write_temp_path(file_path: &Path) -> PathBuf {
+    let name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    file_path.with_file_name(format!(
+        "{name}.{}-{}.tmp",
+        std::process::id(),
+        ulid::Ulid::new()
+    ))
+}
+
+/// Returns `<dir>/<stem>.<extension>`, or the first
+/// `<dir>/<stem> (N).<extension>` from N = 2 that is not taken.
+fn unique_memo_path(
+    dir: &Path,
+    stem: &str,
+    extension: &str,
+    is_taken: impl Fn(&Path) -> bool,
+) -> PathBuf {
+    let mut path = dir.join(format!("{stem}.{extension}"));
+    let mut suffix = 2;
+    while is_taken(&path) {
+        path = dir.join(format!("{stem} ({suffix}).{extension}"));
+        suffix += 1;
+    }
+    path
+}
+
+/// The extension of memo file `path`, which renames keep.
+fn memo_file_extension(path: &Path) -> &str {
+    path.extension().and_then(|s| s.to_str()).unwrap_or("md")
+}
+
+/// Returns true if `stem` is `expected` followed by a ` (N)` collision suffix.
+fn is_collision_suffixed(stem: &str, expected: &str) -> bool {
+    stem.strip_prefix(expected)
+        .and_then(|rest| rest.strip_prefix(" ("))
+        .and_then(|rest| rest.strip_suffix(')'))
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Returns where a memo file is moved when archived: `<dir>/.archive/<file name>`,
+/// falling back to a timestamped name if that file already exists.
+fn archive_destination(file_path: &Path) -> PathBuf {
+    let parent = file_path.parent().unwrap_or_else(|| Path::new("."));
+    let archive_dir = parent.join(ARCHIVE_DIR_NAME);
+    let destination = archive_dir.join(file_path.file_name().unwrap_or_default());
+
+    if destination.exists() {
+        archive_dir.join(timestamped_file_name(file_path, chrono::Utc::now()))
+    } else {
+        destination
+    }
+}
+
+/// Returns where a memo file is moved when trashed: `<dir>/.trash/<stem>.<timestamp>.<ext>`,
+/// or `<stem>.<timestamp> (N).<ext>` when a memo of the same name was trashed
+/// within the same second.
+fn trash_destination(file_path: &Path, now: chrono::DateTime<chrono::Utc>) -> PathBuf {
+    let parent = file_path.parent().unwrap_or_else(|| Path::new("."));
+    let name = timestamped_file_name(file_path, now);
+    let name = Path::new(&name);
+    unique_memo_path(
+        &parent.join(TRASH_DIR_NAME),
+        &name.file_stem().unwrap_or_default().to_string_lossy(),
+        memo_file_extension(name),
+        |path| path.exists(),
+    )
+}
+
+fn timestamped_file_name(file_path: &Path, now: chrono::DateTime<chrono::Utc>) -> String {
+    let stem = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("memo");
+    let extension = file_path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("md");
+
+    format!("{stem}.{}.{extension}", now.format("%Y%m%dT%H%M%SZ"))
+}
+
+/// Picks the memo directory closest to `current_dir` from `memoranda_dirs`:
+/// the deepest one whose parent is `current_dir` or one of its ancestors, then
+/// the one directly under `git_root`, then the shallowest, ties broken by path.
+fn select_closest_memo_dir(
+    memoranda_dirs: Vec<PathBuf>,
+    current_dir: Option<&Path>,
+    git_root: Option<&Path>,
+) -> Option<PathBuf> {
+    let current_dir = current_dir.map(canonical_or_same);
+    let git_root = git_root.map(canonical_or_same);
+    let canonical: Vec<PathBuf> = memoranda_dirs
+        .iter()
+        .map(|dir| canonical_or_same(dir))
+        .collect();
+
+    let enclosing = canonical
+        .iter()
+        .enumerate()
+        .filter_map(|(i, dir)| {
+            let parent = dir.parent()?;
+            let current_dir = current_dir.as_ref()?;
+            current_dir
+                .starts_with(parent)
+                .then(|| (parent.components().count(), i))
+        })
+        .max();
+    if let Some((_, i)) = enclosing {
+        return memoranda_dirs.into_iter().nth(i);
+    }
+
+    if let Some(i) = canonical
+        .iter()
+        .position(|dir| git_root.is_some() && dir.parent() == git_root.as_deref())
+    {
+        return memoranda_dirs.into_iter().nth(i);
+    }
+
+    memoranda_dirs
+        .into_iter()
+        .min_by(|a, b| (a.components().count(), a).cmp(&(b.components().count(), b)))
+}
+
+/// The canonical form of `path`, or `path` itself when it cannot be resolved.
+fn canonical_or_same(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+pub fn find_git_root() -> Result<PathBuf> {
+    let current_dir = std::env::current_dir()?;
+    let mut dir = current_dir.as_path();
+
+    loop {
+        if dir.join(".git").exists() {
+            return Ok(dir.to_path_buf());
+        }
+
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => return Err(MemoStoreError::GitNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_storage_creation() {
+        let storage = MemoStorage::new();
+        assert_eq!(storage.list_memos().len(), 0);
+    }
+
+    #[test]
+    fn test_store_and_retrieve_memo() {
+        let mut storage = MemoStorage::new();
+        let memo = Memo::new("Test".to_string(), "Content".to_string()).unwrap();
+        let memo_id = memo.id;
+
+        storage.store_memo(memo).unwrap();
+
+        let retrieved = storage.get_memo(&memo_id);
+        assert!(retrieved.is_some());
+        assert_eq!(retrieved.unwrap().title, "Test");
+    }
+
+    #[test]
+    fn test_list_memos() {
+        let mut storage = MemoStorage::new();
+        let memo1 = Memo::new("Test1".to_string(), "Content1".to_string()).unwrap();
+        let memo2 = Memo::new("Test2".to_string(), "Content2".to_string()).unwrap();
+
+        storage.store_memo(memo1).unwrap();
+        storage.store_memo(memo2).unwrap();
+
+        let memos = storage.list_memos();
+        assert_eq!(memos.len(), 2);
+    }
+
+    #[test]
+    fn test_remove_memo() {
+        let mut storage = MemoStorage::new();
+        let memo = Memo::new("Test".to_string(), "Content".to_string()).unwrap();
+        let memo_id = memo.id;
+
+        storage.store_memo(memo).unwrap();
+        assert!(storage.get_memo(&memo_id).is_some());
+
+        let removed = storage.remove_memo(&memo_id);
+        assert!(removed.is_some());
+        assert_eq!(removed.unwrap().title, "Test");
+        assert!(storage.get_memo(&memo_id).is_none());
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("Hello World"), "Hello World");
+        assert_eq!(sanitize_filename("test/file"), "test_file");
+        assert_eq!(sanitize_filename("test\\file"), "test_file");
+        assert_eq!(sanitize_filename("test:file"), "test_file");
+        assert_eq!(sanitize_filename("test*file"), "test_file");
+        assert_eq!(sanitize_filename("test?file"), "test_file");
+        assert_eq!(sanitize_filename("test\"file"), "test_file");
+        assert_eq!(sanitize_filename("test<file"), "test_file");
+        assert_eq!(sanitize_filename("test>file"), "test_file");
+        assert_eq!(sanitize_filename("test|file"), "test_file");
+        assert_eq!(sanitize_filename("...test..."), "test");
+        assert_eq!(sanitize_filename("test\x00file"), "test_file");
+    }
+
+    #[test]
+    fn test_extract_title_from_filename() {
+        use std::path::Path;
+
+        let path = Path::new("test_file.md");
+        assert_eq!(extract_title_from_filename(path), "test file");
+
+        let path = Path::new("hello_world.md");
+        assert_eq!(extract_title_from_filename(path), "hello world");
+
+        let path = Path::new("single.md");
+        assert_eq!(extract_title_from_filename(path), "single");
+
+        let path = Path::new("no_extension");
+        assert_eq!(extract_title_from_filename(path), "no extension");
+    }
+
+    #[test]
+    fn test_find_git_root() {
+        let result = find_git_root();
+        assert!(result.is_ok());
+        let git_root = result.unwrap();
+        assert!(git_root.join(".git").exists());
+    }
+
+    #[test]
+    fn test_memo_store_creation() {
+        use std::env;
+        let temp_dir = env::temp_dir();
+        let store = MemoStore::new(temp_dir.clone());
+        assert_eq!(store.root_path, temp_dir);
+    }
+
+    #[test]
+    fn test_memo_store_find_memoranda_dirs() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create a .memoranda directory
+        let memoranda_dir = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        // Create a nested .memoranda directory
+        let nested_dir = temp_path.join("nested");
+        fs::create_dir(&nested_dir).unwrap();
+        let nested_memoranda = nested_dir.join(".memoranda");
+        fs::create_dir(&nested_memoranda).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+        let dirs = store.find_memoranda_dirs().unwrap();
+
+        assert_eq!(dirs.len(), 2);
+        assert!(dirs.contains(&memoranda_dir));
+        assert!(dirs.contains(&nested_memoranda));
+    }
+
+    #[test]
+    fn test_memo_store_with_file_operations() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create a .memoranda directory
+        let memoranda_dir = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+
+        // Create a memo using the store (which will use proper format)
+        let _memo = store
+            .create_memo(
+                "Test Memo".to_string(),
+                "This is a test memo content.".to_string(),
+            )
+            .unwrap();
+
+        // Test listing memos
+        let memos = store.list_memos().unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].title, "Test Memo");
+        assert_eq!(memos[0].content, "This is a test memo content.");
+
+        // Test getting a specific memo
+        let memo_id = memos[0].id;
+        let retrieved_memo = store.get_memo(&memo_id).unwrap();
+        assert!(retrieved_memo.is_some());
+        let retrieved_memo = retrieved_memo.unwrap();
+        assert_eq!(retrieved_memo.title, "Test Memo");
+        assert_eq!(retrieved_memo.id, memo_id);
+    }
+
+    #[test]
+    fn test_memo_store_create_memo() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create a .memoranda directory
+        let memoranda_dir = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+
+        // Test creating a new memo
+        let memo = store
+            .create_memo("New Memo".to_string(), "This is new content".to_string())
+            .unwrap();
+        assert_eq!(memo.title, "New Memo");
+        assert_eq!(memo.content, "This is new content");
+        assert!(memo.file_path.is_some());
+
+        // Verify the file was created
+        let file_path = memo.file_path.unwrap();
+        assert!(file_path.exists());
+        let file_content = fs::read_to_string(&file_path).unwrap();
+        assert!(file_content.contains("This is new content"));
+        assert!(file_content.starts_with("---\n"));
+    }
+
+    #[tokio::test]
+    async fn test_create_memo_refuses_to_overwrite_existing_file() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let todo_path = memoranda_dir.join("TODO.md");
+        fs::write(&todo_path, "- water the plants\n").unwrap();
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        match store.create_memo("TODO".to_string(), "new".to_string()) {
+            Err(MemoStoreError::FileAlreadyExists { path }) => assert_eq!(path, todo_path),
+            other => panic!("expected FileAlreadyExists, got {other:?}"),
+        }
+        assert!(matches!(
+            store
+                .create_memo_async("TODO".to_string(), "new".to_string())
+                .await,
+            Err(MemoStoreError::FileAlreadyExists { .. })
+        ));
+        assert_eq!(
+            fs::read_to_string(&todo_path).unwrap(),
+            "- water the plants\n"
+        );
+
+        // A second memo with the same title must not replace the first either
+        let first = store
+            .create_memo("Plan".to_string(), "first".to_string())
+            .unwrap();
+        assert!(store
+            .create_memo("Plan".to_string(), "second".to_string())
+            .is_err());
+        assert_eq!(store.get_memo(&first.id).unwrap().unwrap().content, "first");
+    }
+
+    #[tokio::test]
+    async fn test_create_memo_with_tags_is_searchable_by_tag() {
+        use crate::memo::search::SearchQuery;
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+        fs::create_dir(temp_path.join(".memoranda")).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+        let tagged = store
+            .create_memo_with_tags(
+                "Tagged".to_string(),
+                "Body".to_string(),
+                vec![" rust ".to_string(), "notes".to_string()],
+            )
+            .unwrap();
+        assert_eq!(tagged.tags, vec!["rust", "notes"]);
+        let async_tagged = store
+            .create_memo_with_tags_async(
+                "Async Tagged".to_string(),
+                "Body".to_string(),
+                vec!["rust".to_string()],
+            )
+            .await
+            .unwrap();
+        store
+            .create_memo("Untagged".to_string(), "Body".to_string())
+            .unwrap();
+
+        let file_content = fs::read_to_string(tagged.file_path.as_ref().unwrap()).unwrap();
+        assert!(file_content.contains("rust"));
+
+        // A fresh store has to read the tags back from the files
+        let reopened = MemoStore::new(temp_path.to_path_buf());
+        let query = SearchQuery::with_tags(vec!["rust".to_string()]);
+        let mut ids: Vec<_> = reopened
+            .search_memos_with_query(&query)
+            .unwrap()
+            .into_iter()
+            .map(|result| result.memo.id)
+            .collect();
+        ids.sort();
+        let mut expected = vec![tagged.id, async_tagged.id];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        let err = store
+            .create_memo_with_tags(
+                "Bad Tags".to_string(),
+                "Body".to_string(),
+                vec!["two words".to_string()],
+            )
+            .unwrap_err();
+        assert!(matches!(err, MemoStoreError::Validation { .. }));
+        assert!(!temp_path.join(".memoranda/Bad Tags.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_update_memo_title_keeps_id_and_file() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+        fs::create_dir(temp_path.join(".memoranda")).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+        let memo = store
+            .create_memo("Draft".to_string(), "Deploy steps".to_string())
+            .unwrap();
+        let file_path = memo.file_path.clone().unwrap();
+
+        let renamed = store
+            .update_memo_title(&memo.id, "Deploy Runbook".to_string())
+            .unwrap();
+        assert_eq!(renamed.id, memo.id);
+        assert_eq!(renamed.created_at, memo.created_at);
+        assert!(renamed.updated_at >= memo.updated_at);
+        assert_eq!(renamed.file_path.as_ref(), Some(&file_path));
+
+        let reloaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(reloaded.title, "Deploy Runbook");
+        assert_eq!(reloaded.content, "Deploy steps");
+        let results = store.search_memos("runbook").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(store.find_filename_drift().unwrap().len(), 1);
+
+        // The async path refreshes the cached copy too
+        store.get_memo_async(&memo.id).await.unwrap();
+        store
+            .update_memo_title_async(&memo.id, "Release Runbook".to_string())
+            .await
+            .unwrap();
+        let cached = store.get_memo_async(&memo.id).await.unwrap().unwrap();
+        assert_eq!(cached.title, "Release Runbook");
+
+        assert!(store.update_memo_title(&memo.id, String::new()).is_err());
+        assert!(matches!(
+            store.update_memo_title(&MemoId::new(), "Missing".to_string()),
+            Err(MemoStoreError::MemoNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rename_memo_moves_file_and_keeps_id() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo("Draft".to_string(), "Deploy steps".to_string())
+            .unwrap();
+        store
+            .create_memo("Runbook".to_string(), "Existing".to_string())
+            .unwrap();
+
+        let renamed = store.rename_memo(&memo.id, "Runbook".to_string()).unwrap();
+        assert_eq!(renamed.id, memo.id);
+        assert_eq!(renamed.created_at, memo.created_at);
+        assert_eq!(
+            renamed.file_path,
+            Some(memoranda_dir.join("Runbook (2).md"))
+        );
+        assert!(!memoranda_dir.join("Draft.md").exists());
+        assert!(!fs::read_dir(&memoranda_dir)
+            .unwrap()
+            .any(|entry| store.is_write_temp_file(&entry.unwrap().path())));
+
+        let reloaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(reloaded.title, "Runbook");
+        assert_eq!(reloaded.content, "Deploy steps");
+        assert_eq!(reloaded.file_path, renamed.file_path);
+        assert!(store.find_filename_drift().unwrap().is_empty());
+
+        let renamed = store
+            .rename_memo(&memo.id, "Release: Steps".to_string())
+            .unwrap();
+        assert_eq!(
+            renamed.file_path,
+            Some(memoranda_dir.join("Release_ Steps.md"))
+        );
+        assert_eq!(store.list_memos().unwrap().len(), 2);
 
-pub fn extract_title_from_filename(file_path: &Path) -> String {
-    file_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("Untitled")
-        .replace('_', " ")
-}
+        assert!(store.rename_memo(&memo.id, String::new()).is_err());
+        assert!(memoranda_dir.join("Release_ Steps.md").exists());
+    }
 
-pub fn find_git_root() -> Result<PathBuf> {
-    let current_dir = std::env::current_dir()?;
-    let mut dir = current_dir.as_path();
+    #[test]
+    fn test_memo_store_update_memo() {
+        use std::fs;
+        use tempfile::TempDir;
 
-    loop {
-        if dir.join(".git").exists() {
-            return Ok(dir.to_path_buf());
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create a .memoranda directory
+        let memoranda_dir = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+
+        // Create a memo
+        let memo = store
+            .create_memo("Update Test".to_string(), "Original content".to_string())
+            .unwrap();
+        let memo_id = memo.id;
+
+        // Update the memo
+        let updated_memo = store
+            .update_memo(&memo_id, "Updated content".to_string())
+            .unwrap();
+        assert_eq!(updated_memo.content, "Updated content");
+        assert!(updated_memo.updated_at > updated_memo.created_at);
+
+        // Verify the file was updated
+        let file_path = updated_memo.file_path.unwrap();
+        let file_content = fs::read_to_string(&file_path).unwrap();
+        assert!(file_content.contains("Updated content"));
+        assert!(file_content.starts_with("---\n"));
+    }
+
+    #[test]
+    fn test_memo_store_delete_memo() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create a .memoranda directory
+        let memoranda_dir = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+
+        // Create a memo
+        let memo = store
+            .create_memo("Delete Test".to_string(), "To be deleted".to_string())
+            .unwrap();
+        let memo_id = memo.id;
+        let file_path = memo.file_path.clone().unwrap();
+
+        // Verify the file exists
+        assert!(file_path.exists());
+
+        // Delete the memo
+        store.delete_memo(&memo_id).unwrap();
+
+        // Verify the file was deleted
+        assert!(!file_path.exists());
+
+        // Verify the memo is no longer retrievable
+        let retrieved = store.get_memo(&memo_id).unwrap();
+        assert!(retrieved.is_none());
+    }
+
+    fn seed_memo_updated_days_ago(
+        store: &MemoStore,
+        title: &str,
+        days: i64,
+        tags: &[&str],
+    ) -> Memo {
+        let mut memo = store
+            .create_memo(title.to_string(), format!("{title} content"))
+            .unwrap();
+        memo.updated_at = chrono::Utc::now() - chrono::Duration::days(days);
+        for tag in tags {
+            memo.add_tag(tag.to_string());
         }
+        let file_path = memo.file_path.clone().unwrap();
+        store.save_memo_to_file(&mut memo, &file_path).unwrap();
+        memo
+    }
 
-        match dir.parent() {
-            Some(parent) => dir = parent,
-            None => return Err(MemoStoreError::GitNotFound),
+    #[test]
+    fn test_retention_policy_dry_run_and_archive() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let stale = seed_memo_updated_days_ago(&store, "Stale", 200, &[]);
+        let _kept = seed_memo_updated_days_ago(&store, "Kept", 200, &["keep"]);
+        let _fresh = seed_memo_updated_days_ago(&store, "Fresh", 10, &[]);
+
+        let policy = RetentionPolicy::new(chrono::Duration::days(180), RetentionAction::Archive)
+            .with_exempt_tags(vec!["keep".to_string(), "pinned".to_string()]);
+
+        // Dry run lists exactly the stale memo and changes nothing
+        let preview = store.preview_retention_policy(&policy).unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.scanned, 3);
+        assert_eq!(preview.exempt, 1);
+        assert_eq!(preview.affected.len(), 1);
+        assert_eq!(preview.affected[0].id, stale.id);
+        let destination = preview.affected[0].destination.clone().unwrap();
+        assert_eq!(
+            destination,
+            memoranda_dir.join(ARCHIVE_DIR_NAME).join("Stale.md")
+        );
+        assert!(stale.file_path.as_ref().unwrap().exists());
+        assert_eq!(store.list_memos().unwrap().len(), 3);
+
+        // Applying moves the stale memo into the archive
+        let report = store.apply_retention_policy(&policy).unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.affected, preview.affected);
+        assert!(!stale.file_path.as_ref().unwrap().exists());
+        assert!(destination.exists());
+
+        let remaining = store.list_memos().unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|memo| memo.id != stale.id));
+    }
+
+    #[test]
+    fn test_retention_policy_trash_and_report_actions() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let stale = seed_memo_updated_days_ago(&store, "Stale", 400, &[]);
+        let _pinned = seed_memo_updated_days_ago(&store, "Pinned", 400, &["Pinned"]);
+
+        // Report never touches files
+        let report_policy =
+            RetentionPolicy::new(chrono::Duration::days(180), RetentionAction::Report)
+                .with_exempt_tags(vec!["pinned".to_string()]);
+        let report = store.apply_retention_policy(&report_policy).unwrap();
+        assert_eq!(report.affected.len(), 1);
+        assert_eq!(report.exempt, 1);
+        assert!(report.affected[0].destination.is_none());
+        assert!(stale.file_path.as_ref().unwrap().exists());
+
+        // Trash moves the file under .trash with a timestamped name
+        let trash_policy = RetentionPolicy {
+            action: RetentionAction::Trash,
+            ..report_policy
+        };
+        let report = store.apply_retention_policy(&trash_policy).unwrap();
+        assert_eq!(report.affected.len(), 1);
+        let destination = report.affected[0].destination.clone().unwrap();
+        assert_eq!(
+            destination.parent().unwrap(),
+            memoranda_dir.join(TRASH_DIR_NAME)
+        );
+        assert!(destination.exists());
+        assert!(!stale.file_path.as_ref().unwrap().exists());
+        assert_eq!(store.list_memos().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_memos_of_one_name_trashed_in_one_second_are_all_kept() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir_all(memoranda_dir.join(TRASH_DIR_NAME)).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let now = chrono::Utc::now();
+        let file_path = memoranda_dir.join("Notes.md");
+        let first = trash_destination(&file_path, now);
+        fs::write(&first, "trashed").unwrap();
+        let second = trash_destination(&file_path, now);
+        assert_ne!(first, second);
+        assert_eq!(trashed_at(&second), trashed_at(&first));
+        assert_eq!(original_path(&second), file_path);
+        fs::remove_file(&first).unwrap();
+
+        let mut ids = Vec::new();
+        for content in ["first", "second"] {
+            let memo = store
+                .create_memo("Notes".to_string(), content.to_string())
+                .unwrap();
+            store.trash_memo(&memo.id).unwrap();
+            ids.push(memo.id);
         }
+        let mut trashed: Vec<_> = store.list_trash().unwrap().iter().map(|t| t.id).collect();
+        trashed.sort();
+        ids.sort();
+        assert_eq!(trashed, ids);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_compact_purges_only_expired_trash() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        let trash_dir = memoranda_dir.join(TRASH_DIR_NAME);
+        fs::create_dir_all(&trash_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let active = store
+            .create_memo("Active".to_string(), "keep me".to_string())
+            .unwrap();
+        let now = chrono::Utc::now();
+        let expired = trash_destination(
+            &memoranda_dir.join("Old.md"),
+            now - chrono::Duration::days(90),
+        );
+        let recent = trash_destination(
+            &memoranda_dir.join("New.md"),
+            now - chrono::Duration::days(1),
+        );
+        fs::write(&expired, "x".repeat(2048)).unwrap();
+        fs::write(&recent, "recent").unwrap();
+
+        let options = CompactOptions::new(chrono::Duration::days(30));
+
+        let preview = store.compact(&options.clone().with_dry_run(true)).unwrap();
+        assert_eq!(preview.trash_scanned, 2);
+        assert_eq!(preview.trash_purged.len(), 1);
+        assert!(expired.exists());
+
+        let report = store.compact(&options).unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.trash_purged.len(), 1);
+        assert_eq!(report.trash_purged[0].path, expired);
+        assert_eq!(report.bytes_reclaimed(), 2048);
+        assert!(!expired.exists());
+        assert!(recent.exists());
+        assert!(active.file_path.unwrap().exists());
+        assert!(report.temp_files_purged.is_empty());
+        assert_eq!(store.list_memos().unwrap().len(), 1);
+    }
 
     #[test]
-    fn test_memo_storage_creation() {
-        let storage = MemoStorage::new();
-        assert_eq!(storage.list_memos().len(), 0);
+    fn test_compact_purges_orphaned_write_temp_files() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir_all(memoranda_dir.join("ops")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo("Active".to_string(), "keep me".to_string())
+            .unwrap();
+
+        let legacy = memoranda_dir.join("Active.md.tmp");
+        let orphan = write_temp_path(&memoranda_dir.join("ops/Runbook.md"));
+        fs::write(&legacy, "partial").unwrap();
+        fs::write(&orphan, "partial write").unwrap();
+        let not_temp = ["scratch.tmp", "notes.backup.tmp", "Active.md.42-draft.tmp"]
+            .map(|name| memoranda_dir.join(name));
+        for path in &not_temp {
+            fs::write(path, "not ours").unwrap();
+        }
+
+        // Young temporary files may belong to a write still in progress
+        let options = CompactOptions::new(chrono::Duration::days(30));
+        let report = store.compact(&options).unwrap();
+        assert!(report.temp_files_purged.is_empty());
+
+        let options = options.with_temp_file_max_age(chrono::Duration::seconds(-1));
+        let preview = store.compact(&options.clone().with_dry_run(true)).unwrap();
+        assert_eq!(preview.temp_files_purged.len(), 2);
+        assert!(orphan.exists());
+
+        let report = store.compact(&options).unwrap();
+        let mut purged: Vec<_> = report
+            .temp_files_purged
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect();
+        purged.sort();
+        let mut expected = vec![legacy.clone(), orphan.clone()];
+        expected.sort();
+        assert_eq!(purged, expected);
+        assert_eq!(report.bytes_reclaimed(), 20);
+        assert!(!legacy.exists());
+        assert!(!orphan.exists());
+        assert!(not_temp.iter().all(|path| path.exists()));
+        assert!(memo.file_path.unwrap().exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_saves_of_one_memo_leave_valid_file() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir_all(&memoranda_dir).unwrap();
+        let store = Arc::new(MemoStore::new(temp_dir.path().to_path_buf()));
+        let memo = store
+            .create_memo("Shared".to_string(), "initial".to_string())
+            .unwrap();
+        let file_path = memo.file_path.clone().unwrap();
+
+        let mut tasks = Vec::new();
+        for task in 0..8 {
+            let store = Arc::clone(&store);
+            let file_path = file_path.clone();
+            let mut memo = memo.clone();
+            tasks.push(tokio::spawn(async move {
+                for round in 0..10 {
+                    memo.content = format!("task {task} round {round}\n{}", "x".repeat(4096));
+                    if task % 2 == 0 {
+                        store.save_memo_to_file(&mut memo, &file_path).unwrap();
+                    } else {
+                        store
+                            .save_memo_to_file_async(&mut memo, &file_path)
+                            .await
+                            .unwrap();
+                    }
+                }
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let text = fs::read_to_string(&file_path).unwrap();
+        assert!(text.starts_with("---\n"));
+        let reloaded = store.load_memo_from_file(&file_path).unwrap();
+        assert_eq!(reloaded.id, memo.id);
+        assert!(reloaded.content.starts_with("task "));
+        assert!(!fs::read_dir(&memoranda_dir)
+            .unwrap()
+            .any(|entry| store.is_write_temp_file(&entry.unwrap().path())));
+    }
+
+    fn write_memo_file(path: &Path, memo: &Memo) {
+        let mut memo = memo.clone();
+        memo.file_path = None;
+        let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+        fs::write(path, format!("---\n{frontmatter}\n---\n{}", memo.content)).unwrap();
     }
 
     #[test]
-    fn test_store_and_retrieve_memo() {
-        let mut storage = MemoStorage::new();
-        let memo = Memo::new("Test".to_string(), "Content".to_string()).unwrap();
-        let memo_id = memo.id;
+    fn test_normalize_filenames_dry_run_collision_and_opt_out() {
+        use tempfile::TempDir;
 
-        storage.store_memo(memo).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
 
-        let retrieved = storage.get_memo(&memo_id);
-        assert!(retrieved.is_some());
-        assert_eq!(retrieved.unwrap().title, "Test");
+        // Already correctly named; its name collides with the drifted memo's title
+        let existing = store
+            .create_memo("Plan".to_string(), "current".to_string())
+            .unwrap();
+        let drifted = Memo::new("Plan".to_string(), "older".to_string()).unwrap();
+        write_memo_file(&memoranda_dir.join("Old plan.md"), &drifted);
+        let mut pinned = Memo::new("Pinned title".to_string(), "keep".to_string()).unwrap();
+        pinned.keep_filename = true;
+        write_memo_file(&memoranda_dir.join("Custom name.md"), &pinned);
+
+        let preview = store.normalize_filenames(true).unwrap();
+        assert_eq!(preview.len(), 1);
+        assert_eq!(preview[0].id, drifted.id);
+        assert_eq!(preview[0].file_path, memoranda_dir.join("Old plan.md"));
+        assert_eq!(preview[0].expected_path, memoranda_dir.join("Plan (2).md"));
+        assert!(memoranda_dir.join("Old plan.md").exists());
+
+        let renamed = store.normalize_filenames(false).unwrap();
+        assert_eq!(renamed, preview);
+        assert!(!memoranda_dir.join("Old plan.md").exists());
+        assert!(memoranda_dir.join("Plan (2).md").exists());
+        assert!(memoranda_dir.join("Custom name.md").exists());
+        assert!(existing.file_path.unwrap().exists());
+
+        let moved = store.get_memo(&drifted.id).unwrap().unwrap();
+        assert_eq!(moved.file_path, Some(memoranda_dir.join("Plan (2).md")));
+        assert!(store.find_filename_drift().unwrap().is_empty());
     }
 
     #[test]
-    fn test_list_memos() {
-        let mut storage = MemoStorage::new();
-        let memo1 = Memo::new("Test1".to_string(), "Content1".to_string()).unwrap();
-        let memo2 = Memo::new("Test2".to_string(), "Content2".to_string()).unwrap();
+    fn test_sync_filename_renames_one_memo_to_its_title() {
+        use tempfile::TempDir;
 
-        storage.store_memo(memo1).unwrap();
-        storage.store_memo(memo2).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
 
-        let memos = storage.list_memos();
-        assert_eq!(memos.len(), 2);
+        let existing = store
+            .create_memo("Plan".to_string(), "current".to_string())
+            .unwrap();
+        let drifted = store
+            .create_memo("Draft".to_string(), "older".to_string())
+            .unwrap();
+        store
+            .update_memo_title(&drifted.id, "Plan".to_string())
+            .unwrap();
+        let other = Memo::new("Elsewhere".to_string(), "stale".to_string()).unwrap();
+        write_memo_file(&memoranda_dir.join("Old name.md"), &other);
+
+        let renamed = store.sync_filename(&drifted.id).unwrap().unwrap();
+        assert_eq!(renamed.file_path, memoranda_dir.join("Draft.md"));
+        assert_eq!(renamed.expected_path, memoranda_dir.join("Plan (2).md"));
+        assert!(!memoranda_dir.join("Draft.md").exists());
+        // Only the requested memo is renamed
+        assert!(memoranda_dir.join("Old name.md").exists());
+
+        let moved = store.get_memo(&drifted.id).unwrap().unwrap();
+        assert_eq!(moved.title, "Plan");
+        assert_eq!(moved.content, "older");
+        assert_eq!(moved.file_path, Some(memoranda_dir.join("Plan (2).md")));
+        assert!(store.sync_filename(&drifted.id).unwrap().is_none());
+        assert!(store.sync_filename(&existing.id).unwrap().is_none());
+        assert!(matches!(
+            store.sync_filename(&MemoId::new()),
+            Err(MemoStoreError::MemoNotFound { .. })
+        ));
     }
 
     #[test]
-    fn test_remove_memo() {
-        let mut storage = MemoStorage::new();
-        let memo = Memo::new("Test".to_string(), "Content".to_string()).unwrap();
-        let memo_id = memo.id;
+    fn test_title_authority_frontmatter_keeps_frontmatter_title() {
+        use tempfile::TempDir;
 
-        storage.store_memo(memo).unwrap();
-        assert!(storage.get_memo(&memo_id).is_some());
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let memo = Memo::new("Release plan".to_string(), "ship it".to_string()).unwrap();
+        write_memo_file(&memoranda_dir.join("Launch checklist.md"), &memo);
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let loaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.title, "Release plan");
+
+        let mismatches = store.find_title_mismatches().unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].id, memo.id);
+        assert_eq!(mismatches[0].frontmatter_title, "Release plan");
+        assert_eq!(mismatches[0].filename_title, "Launch checklist");
+        // The file is the one to change
+        assert_eq!(store.find_filename_drift().unwrap().len(), 1);
+    }
 
-        let removed = storage.remove_memo(&memo_id);
-        assert!(removed.is_some());
-        assert_eq!(removed.unwrap().title, "Test");
-        assert!(storage.get_memo(&memo_id).is_none());
+    #[test]
+    fn test_title_authority_filename_overrides_frontmatter_title() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let path = memoranda_dir.join("Launch checklist.md");
+        let memo = Memo::new("Release plan".to_string(), "ship it".to_string()).unwrap();
+        write_memo_file(&path, &memo);
+        let mut pinned = Memo::new("Pinned title".to_string(), "keep".to_string()).unwrap();
+        pinned.keep_filename = true;
+        write_memo_file(&memoranda_dir.join("Custom name.md"), &pinned);
+        let settings = Settings {
+            title_authority: TitleAuthority::Filename,
+            ..Default::default()
+        };
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings.clone());
+
+        let loaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.title, "Launch checklist");
+        assert_eq!(loaded.content, "ship it");
+        let pinned_loaded = store.get_memo(&pinned.id).unwrap().unwrap();
+        assert_eq!(pinned_loaded.title, "Pinned title");
+        assert_eq!(store.search_memos("launch").unwrap()[0].memo.id, memo.id);
+        assert!(store.find_filename_drift().unwrap().is_empty());
+        // The file is left alone unless rewriting is enabled
+        assert!(fs::read_to_string(&path).unwrap().contains("Release plan"));
+        assert_eq!(store.find_title_mismatches().unwrap().len(), 1);
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            complete_partial_frontmatter: true,
+            ..settings
+        });
+        assert_eq!(
+            store.get_memo(&memo.id).unwrap().unwrap().title,
+            "Launch checklist"
+        );
+        assert!(!fs::read_to_string(&path).unwrap().contains("Release plan"));
+        assert!(store.find_title_mismatches().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_normalize_filenames_async_updates_cache() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let memo = Memo::new("New architecture plan".to_string(), "plan".to_string()).unwrap();
+        write_memo_file(&memoranda_dir.join("Old plan.md"), &memo);
+        // Warm the cache with the old path
+        store.get_memo_async(&memo.id).await.unwrap().unwrap();
+
+        let renamed = store.normalize_filenames_async(false).await.unwrap();
+        assert_eq!(renamed.len(), 1);
+
+        let expected_path = memoranda_dir.join("New architecture plan.md");
+        let cached = store.get_memo_async(&memo.id).await.unwrap().unwrap();
+        assert_eq!(cached.file_path, Some(expected_path.clone()));
+        assert!(store.cache.get_metadata(&expected_path).await.is_some());
+        assert!(store
+            .cache
+            .get_metadata(&memoranda_dir.join("Old plan.md"))
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_chunked_write_enforces_content_limit() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        assert!(store
+            .begin_memo_write("  ".to_string(), Vec::new())
+            .is_err());
+
+        let token = store
+            .begin_memo_write("Big".to_string(), Vec::new())
+            .unwrap();
+        let chunk = "x".repeat(super::super::models::MAX_CONTENT_LENGTH / 2);
+        store.write_memo_chunk(&token, &chunk).unwrap();
+        store.write_memo_chunk(&token, &chunk).unwrap();
+        assert!(matches!(
+            store.write_memo_chunk(&token, "one byte too many"),
+            Err(MemoStoreError::Validation { .. })
+        ));
+
+        // The rejected chunk was not appended, so the write still commits
+        let memo = store.commit_memo_write(&token).unwrap();
+        assert_eq!(memo.content.len(), chunk.len() * 2);
+        assert!(store.abort_memo_write(&token).is_err());
+    }
+
+    #[test]
+    fn test_chunked_write_survives_a_failed_commit() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            max_memo_lines: Some(3),
+            ..Default::default()
+        });
+
+        let token = store
+            .begin_memo_write("Streamed".to_string(), Vec::new())
+            .unwrap();
+        store.write_memo_chunk(&token, "one\ntwo").unwrap();
+        // The line limit is checked as chunks arrive, not only at commit
+        assert!(matches!(
+            store.write_memo_chunk(&token, "\nthree\nfour"),
+            Err(MemoStoreError::Validation { .. })
+        ));
+        store.write_memo_chunk(&token, "\nthree").unwrap();
+
+        // A file taking the memo's name makes the commit fail, keeping the
+        // chunks for a retry
+        let blocker = memoranda_dir.join("Streamed.md");
+        fs::write(&blocker, "hand-written").unwrap();
+        assert!(matches!(
+            store.commit_memo_write(&token),
+            Err(MemoStoreError::FileAlreadyExists { .. })
+        ));
+        fs::remove_file(&blocker).unwrap();
+        let memo = store.commit_memo_write(&token).unwrap();
+        assert_eq!(memo.content, "one\ntwo\nthree");
+        assert!(store.commit_memo_write(&token).is_err());
+        assert_eq!(
+            fs::read_dir(memoranda_dir.join(".staging"))
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_frontmatter_without_id_keeps_its_fields() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let json_path = memoranda_dir.join("partial.md");
+        fs::write(
+            &json_path,
+            "---\n{\"title\": \"Partial\", \"tags\": [\"keep\"], \"created_at\": \"2024-01-02T03:04:05Z\"}\n---\nBody text",
+        )
+        .unwrap();
+        let yaml_path = memoranda_dir.join("untitled.md");
+        fs::write(&yaml_path, "---\ntags: [draft]\n---\nYAML body").unwrap();
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let mut memos = store.list_memos().unwrap();
+        memos.sort_by(|a, b| a.title.cmp(&b.title));
+        assert_eq!(memos.len(), 2);
+        let partial = &memos[0];
+        assert_eq!(partial.title, "Partial");
+        assert_eq!(partial.content, "Body text");
+        assert_eq!(partial.tags, vec!["keep"]);
+        assert_eq!(partial.created_at.to_rfc3339(), "2024-01-02T03:04:05+00:00");
+        assert_eq!(partial.updated_at, partial.created_at);
+        assert_eq!(partial.id, derived_memo_id(&json_path));
+        assert_eq!(memos[1].title, "untitled");
+        assert_eq!(memos[1].tags, vec!["draft"]);
+
+        // The generated id is stable, so the memo can be fetched by it
+        assert_eq!(
+            store.get_memo(&partial.id).unwrap().unwrap().title,
+            "Partial"
+        );
+        assert!(!fs::read_to_string(&json_path).unwrap().contains("\"id\""));
+
+        let completing = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            complete_partial_frontmatter: true,
+            ..Default::default()
+        });
+        let reloaded = completing.get_memo(&partial.id).unwrap().unwrap();
+        assert_eq!(reloaded.tags, vec!["keep"]);
+        let rewritten = fs::read_to_string(&json_path).unwrap();
+        assert!(rewritten.contains(&partial.id.to_string()));
+        assert!(rewritten.ends_with("Body text"));
+        assert_eq!(store.list_memos().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_encoding_surfaces_invalid_utf8_memos() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let memo = Memo::new("Latin1".to_string(), "body".to_string()).unwrap();
+        let mut bytes = format!(
+            "---\n{}\n---\n",
+            serde_json::to_string_pretty(&memo).unwrap()
+        )
+        .into_bytes();
+        bytes.extend_from_slice(b"caf\xe9 notes");
+        let path = memoranda_dir.join("Latin1.md");
+        fs::write(&path, bytes).unwrap();
+
+        let strict = MemoStore::new(temp_dir.path().to_path_buf());
+        assert!(strict.list_memos().unwrap().is_empty());
+
+        let lenient = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            lenient_encoding: true,
+            ..Default::default()
+        });
+        let memos = lenient.list_memos().unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].id, memo.id);
+        assert!(memos[0].encoding_warning);
+        let found = lenient.get_memo_async(&memo.id).await.unwrap().unwrap();
+        assert!(found.encoding_warning);
+        let json = serde_json::to_value(&found).unwrap();
+        assert_eq!(json["encoding_warning"], true);
+
+        // Changes that would write the replaced body back are refused, leaving
+        // the original bytes alone
+        let original = fs::read(&path).unwrap();
+        assert!(lenient.add_alias(&memo.id, "Cafe".to_string()).is_err());
+        assert!(lenient
+            .add_tags_to_memo(&memo.id, vec!["notes".to_string()])
+            .await
+            .is_err());
+        assert!(lenient
+            .update_memo_title(&memo.id, "Cafe".to_string())
+            .is_err());
+        assert!(lenient.rename_memo(&memo.id, "Cafe".to_string()).is_err());
+        assert_eq!(fs::read(&path).unwrap(), original);
+
+        // Nor is incomplete frontmatter filled in
+        let partial_path = memoranda_dir.join("Partial.md");
+        let partial = b"---\n{\"title\": \"Partial\"}\n---\nna\xefve".to_vec();
+        fs::write(&partial_path, &partial).unwrap();
+        let completing = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            lenient_encoding: true,
+            complete_partial_frontmatter: true,
+            ..Default::default()
+        });
+        assert_eq!(completing.list_memos().unwrap().len(), 2);
+        assert_eq!(fs::read(&partial_path).unwrap(), partial);
+        fs::remove_file(&partial_path).unwrap();
+
+        // Rewriting the memo stores valid UTF-8 and drops the flag
+        let updated = lenient
+            .update_memo(&memo.id, "café notes".to_string())
+            .unwrap();
+        assert!(!updated.encoding_warning);
+        assert!(!fs::read_to_string(&path)
+            .unwrap()
+            .contains("encoding_warning"));
+        assert!(
+            !lenient
+                .get_memo(&updated.id)
+                .unwrap()
+                .unwrap()
+                .encoding_warning
+        );
     }
 
-    #[test]
-    fn test_sanitize_filename() {
-        assert_eq!(sanitize_filename("Hello World"), "Hello World");
-        assert_eq!(sanitize_filename("test/file"), "test_file");
-        assert_eq!(sanitize_filename("test\\file"), "test_file");
-        assert_eq!(sanitize_filename("test:file"), "test_file");
-        assert_eq!(sanitize_filename("test*file"), "test_file");
-        assert_eq!(sanitize_filename("test?file"), "test_file");
-        assert_eq!(sanitize_filename("test\"file"), "test_file");
-        assert_eq!(sanitize_filename("test<file"), "test_file");
-        assert_eq!(sanitize_filename("test>file"), "test_file");
-        assert_eq!(sanitize_filename("test|file"), "test_file");
-        assert_eq!(sanitize_filename("...test..."), "test");
-        assert_eq!(sanitize_filename("test\x00file"), "test_file");
+    #[tokio::test]
+    async fn test_max_memo_lines_enforced_on_create_and_update() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let limited = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            max_memo_lines: Some(3),
+            ..Default::default()
+        });
+        let at_limit = "one\ntwo\nthree".to_string();
+        let over_limit = "one\ntwo\nthree\n".to_string();
+
+        let memo = limited
+            .create_memo("At Limit".to_string(), at_limit.clone())
+            .unwrap();
+        let error = limited
+            .create_memo("Over Limit".to_string(), over_limit.clone())
+            .unwrap_err();
+        assert!(matches!(error, MemoStoreError::Validation { .. }));
+        assert!(error.to_string().contains("4 lines"), "{error}");
+        assert!(limited
+            .create_memo_async("Over Async".to_string(), over_limit.clone())
+            .await
+            .is_err());
+        assert!(limited.update_memo(&memo.id, over_limit.clone()).is_err());
+        assert!(limited
+            .update_memo_async(&memo.id, over_limit.clone())
+            .await
+            .is_err());
+        assert_eq!(
+            limited.get_memo(&memo.id).unwrap().unwrap().content,
+            at_limit
+        );
+
+        let unlimited = MemoStore::new(temp_dir.path().to_path_buf());
+        let long = "line\n".repeat(10_000);
+        unlimited.update_memo(&memo.id, long.clone()).unwrap();
+        unlimited.create_memo("Long".to_string(), long).unwrap();
     }
 
     #[test]
-    fn test_extract_title_from_filename() {
-        use std::path::Path;
-
-        let path = Path::new("test_file.md");
-        assert_eq!(extract_title_from_filename(path), "test file");
+    fn test_frontmatter_formats_mix_in_one_store() {
+        use crate::memo::FrontmatterFormat;
+        use tempfile::TempDir;
 
-        let path = Path::new("hello_world.md");
-        assert_eq!(extract_title_from_filename(path), "hello world");
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let json_store = MemoStore::new(temp_dir.path().to_path_buf());
+        let json_memo = json_store
+            .create_memo("Json".to_string(), "json body".to_string())
+            .unwrap();
 
-        let path = Path::new("single.md");
-        assert_eq!(extract_title_from_filename(path), "single");
+        let mut memos = vec![json_memo];
+        for format in [FrontmatterFormat::Toml, FrontmatterFormat::Yaml] {
+            let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+                frontmatter_format: format,
+                ..Default::default()
+            });
+            let memo = store
+                .create_memo(format!("{format:?}"), format!("{format:?} body"))
+                .unwrap();
+            let on_disk = fs::read_to_string(memo.file_path.as_ref().unwrap()).unwrap();
+            assert!(on_disk.starts_with(&format!("{}\n", format.fence())));
+            assert!(!on_disk.starts_with(&format!("{}\n{{", format.fence())));
+            memos.push(memo);
+        }
 
-        let path = Path::new("no_extension");
-        assert_eq!(extract_title_from_filename(path), "no extension");
+        // Every store reads every format, whatever it writes
+        let layout = json_store.store_layout().unwrap();
+        assert!(layout[0]
+            .files
+            .iter()
+            .all(|file| file.has_valid_frontmatter));
+        let listed = json_store.list_memos().unwrap();
+        assert_eq!(listed.len(), 3);
+        for memo in &memos {
+            let loaded = json_store.get_memo(&memo.id).unwrap().unwrap();
+            assert_eq!(loaded.title, memo.title);
+            assert_eq!(loaded.content, memo.content);
+        }
     }
 
     #[test]
-    fn test_find_git_root() {
-        let result = find_git_root();
-        assert!(result.is_ok());
-        let git_root = result.unwrap();
-        assert!(git_root.join(".git").exists());
+    fn test_obsidian_notes_keep_unknown_frontmatter() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let note_path = memoranda_dir.join("Weekly Review.md");
+        fs::write(
+            &note_path,
+            "---\naliases:\n  - Review\ncssclass: wide\ntags: [\"#planning\"]\n---\n# Week 12\n",
+        )
+        .unwrap();
+        fs::write(memoranda_dir.join("Plain.md"), "no frontmatter").unwrap();
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            obsidian_compat: true,
+            ..Default::default()
+        });
+        let memo = store
+            .list_memos()
+            .unwrap()
+            .into_iter()
+            .find(|memo| memo.title == "Weekly Review")
+            .unwrap();
+        assert_eq!(memo.content, "# Week 12\n");
+        assert_eq!(memo.tags, ["planning"]);
+        // Without the setting the note loads from its partial frontmatter,
+        // under the same derived id
+        let default_store = MemoStore::new(temp_dir.path().to_path_buf());
+        let partial = default_store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(partial.aliases, ["Review"]);
+
+        // Notes without an id keep a stable one until their first update writes it
+        let updated = store
+            .update_memo(&memo.id, "# Week 13\n".to_string())
+            .unwrap();
+        let on_disk = fs::read_to_string(&note_path).unwrap();
+        assert!(
+            on_disk.starts_with(
+                "---\naliases:\n  - Review\ncssclass: wide\ntags: [\"#planning\"]\nid: "
+            ),
+            "{on_disk}"
+        );
+        assert!(on_disk.ends_with("\n---\n# Week 13\n"));
+        assert!(!on_disk.contains("content"));
+        let loaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.content, "# Week 13\n");
+        assert_eq!(loaded.updated_at, updated.updated_at);
+
+        let created = store
+            .create_memo("Ideas: Q3".to_string(), "body".to_string())
+            .unwrap();
+        let on_disk = fs::read_to_string(created.file_path.as_ref().unwrap()).unwrap();
+        assert!(on_disk.contains("\ntitle: \"Ideas: Q3\"\n"), "{on_disk}");
+        assert_eq!(
+            store.get_memo(&created.id).unwrap().unwrap().title,
+            "Ideas: Q3"
+        );
     }
 
     #[test]
-    fn test_memo_store_creation() {
-        use std::env;
-        let temp_dir = env::temp_dir();
-        let store = MemoStore::new(temp_dir.clone());
-        assert_eq!(store.root_path, temp_dir);
+    fn test_line_ending_normalized_on_write() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let crlf_content = "first line\r\nsecond line\r\n".to_string();
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo("Windows".to_string(), crlf_content.clone())
+            .unwrap();
+        let on_disk = fs::read_to_string(memo.file_path.as_ref().unwrap()).unwrap();
+        assert!(!on_disk.contains('\r'));
+        assert!(on_disk.ends_with("---\nfirst line\nsecond line\n"));
+        // The memo handed back matches its file, not the content passed in
+        assert_eq!(memo.content, "first line\nsecond line\n");
+        let loaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.content, "first line\nsecond line\n");
+        let updated = store
+            .update_memo(&memo.id, "third line\r\n".to_string())
+            .unwrap();
+        assert_eq!(updated.content, "third line\n");
+
+        let preserving = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            line_ending: crate::memo::LineEnding::Preserve,
+            ..Default::default()
+        });
+        let memo = preserving
+            .create_memo("Preserved".to_string(), crlf_content.clone())
+            .unwrap();
+        let on_disk = fs::read_to_string(memo.file_path.as_ref().unwrap()).unwrap();
+        // Frontmatter lines stay LF; only the body keeps its CRLF endings
+        assert!(on_disk.starts_with("---\n{\n"));
+        assert!(on_disk.ends_with(&format!("}}\n---\n{crlf_content}")));
+        let loaded = preserving.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.content, crlf_content);
     }
 
     #[test]
-    fn test_memo_store_find_memoranda_dirs() {
-        use std::fs;
+    fn test_wrapped_ids_in_frontmatter_are_read() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
-
-        // Create a .memoranda directory
-        let memoranda_dir = temp_path.join(".memoranda");
+        let memoranda_dir = temp_dir.path().join(".memoranda");
         fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
 
-        // Create a nested .memoranda directory
-        let nested_dir = temp_path.join("nested");
-        fs::create_dir(&nested_dir).unwrap();
-        let nested_memoranda = nested_dir.join(".memoranda");
-        fs::create_dir(&nested_memoranda).unwrap();
+        let memo = Memo::new("Old Format".to_string(), "wrapped id".to_string()).unwrap();
+        let mut frontmatter = serde_json::to_value(&memo).unwrap();
+        frontmatter["id"] = serde_json::json!({ "ulid": memo.id.to_string() });
+        let path = memoranda_dir.join("Old Format.md");
+        fs::write(&path, format!("---\n{frontmatter:#}\n---\nwrapped id")).unwrap();
 
-        let store = MemoStore::new(temp_path.to_path_buf());
-        let dirs = store.find_memoranda_dirs().unwrap();
+        let loaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.id, memo.id);
+        assert_eq!(store.list_memos().unwrap()[0].id, memo.id);
 
-        assert_eq!(dirs.len(), 2);
-        assert!(dirs.contains(&memoranda_dir));
-        assert!(dirs.contains(&nested_memoranda));
+        // Rewriting the memo stores the id as a plain string
+        store
+            .update_memo(&memo.id, "rewritten".to_string())
+            .unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains(&format!("\"id\": \"{}\"", memo.id)));
     }
 
-    #[test]
-    fn test_memo_store_with_file_operations() {
-        use std::fs;
+    #[tokio::test]
+    async fn test_duplicate_ids_are_detected_and_fixed() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
-
-        // Create a .memoranda directory
-        let memoranda_dir = temp_path.join(".memoranda");
+        let memoranda_dir = temp_dir.path().join(".memoranda");
         fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
 
-        let store = MemoStore::new(temp_path.to_path_buf());
-
-        // Create a memo using the store (which will use proper format)
-        let _memo = store
-            .create_memo(
-                "Test Memo".to_string(),
-                "This is a test memo content.".to_string(),
-            )
+        let original = store
+            .create_memo("Original".to_string(), "first copy".to_string())
             .unwrap();
+        let original_path = original.file_path.clone().unwrap();
+        let copy_path = memoranda_dir.join("Original_copy.md");
+        fs::copy(&original_path, &copy_path).unwrap();
+
+        let expected_paths = vec![original_path.clone(), copy_path.clone()];
+        for result in [
+            store.get_memo(&original.id),
+            store.get_memo_async(&original.id).await,
+        ] {
+            match result {
+                Err(MemoStoreError::DuplicateId { id, paths }) => {
+                    assert_eq!(id, original.id.to_string());
+                    assert_eq!(paths, expected_paths);
+                }
+                other => panic!("expected duplicate id error, got {other:?}"),
+            }
+        }
+        assert_eq!(
+            store.find_duplicate_ids().unwrap(),
+            vec![DuplicateId {
+                id: original.id,
+                paths: expected_paths,
+            }]
+        );
 
-        // Test listing memos
-        let memos = store.list_memos().unwrap();
-        assert_eq!(memos.len(), 1);
-        assert_eq!(memos[0].title, "Test Memo");
-        assert_eq!(memos[0].content, "This is a test memo content.");
-
-        // Test getting a specific memo
-        let memo_id = memos[0].id;
-        let retrieved_memo = store.get_memo(&memo_id).unwrap();
-        assert!(retrieved_memo.is_some());
-        let retrieved_memo = retrieved_memo.unwrap();
-        assert_eq!(retrieved_memo.title, "Test Memo");
-        assert_eq!(retrieved_memo.id, memo_id);
+        let reassignments = store.fix_duplicate_ids().unwrap();
+        assert_eq!(reassignments.len(), 1);
+        assert_eq!(reassignments[0].file_path, copy_path);
+        assert_eq!(reassignments[0].old_id, original.id);
+
+        assert!(store.find_duplicate_ids().unwrap().is_empty());
+        let kept = store.get_memo(&original.id).unwrap().unwrap();
+        assert_eq!(kept.file_path, Some(original_path.clone()));
+        let copy = store.get_memo(&reassignments[0].new_id).unwrap().unwrap();
+        assert_eq!(copy.content, "first copy");
+        assert_eq!(copy.file_path, Some(copy_path.clone()));
+
+        // The cache serves the kept memo under the old id, and the copy only
+        // under its new one
+        let kept = store.get_memo_async(&original.id).await.unwrap().unwrap();
+        assert_eq!(kept.file_path, Some(original_path));
+        let copy = store
+            .get_memo_async(&reassignments[0].new_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(copy.file_path, Some(copy_path));
     }
 
     #[test]
-    fn test_memo_store_create_memo() {
-        use std::fs;
+    fn test_aliases_resolve_titles_and_wiki_links() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
 
-        // Create a .memoranda directory
-        let memoranda_dir = temp_path.join(".memoranda");
-        fs::create_dir(&memoranda_dir).unwrap();
+        let errors = store
+            .create_memo("Error Handling".to_string(), "Use anyhow".to_string())
+            .unwrap();
+        let logging = store
+            .create_memo("Logging".to_string(), "Use tracing".to_string())
+            .unwrap();
 
-        let store = MemoStore::new(temp_path.to_path_buf());
+        store.add_alias(&errors.id, "Errors".to_string()).unwrap();
+        assert_eq!(
+            store.get_memo(&errors.id).unwrap().unwrap().aliases,
+            vec!["Errors".to_string()]
+        );
+        assert_eq!(
+            store.get_memo_by_title("errors").unwrap().unwrap().id,
+            errors.id
+        );
+        assert_eq!(
+            store
+                .resolve_wiki_link("[[Errors|error docs]]")
+                .unwrap()
+                .unwrap()
+                .id,
+            errors.id
+        );
+        assert!(store.get_memo_by_title("Nothing").unwrap().is_none());
 
-        // Test creating a new memo
-        let memo = store
-            .create_memo("New Memo".to_string(), "This is new content".to_string())
-            .unwrap();
-        assert_eq!(memo.title, "New Memo");
-        assert_eq!(memo.content, "This is new content");
-        assert!(memo.file_path.is_some());
+        for taken in ["errors", "Error Handling"] {
+            assert!(matches!(
+                store.add_alias(&logging.id, taken.to_string()),
+                Err(MemoStoreError::AliasConflict { .. })
+            ));
+        }
 
-        // Verify the file was created
-        let file_path = memo.file_path.unwrap();
-        assert!(file_path.exists());
-        let file_content = fs::read_to_string(&file_path).unwrap();
-        assert!(file_content.contains("This is new content"));
-        assert!(file_content.starts_with("---\n"));
+        store.remove_alias(&errors.id, "ERRORS").unwrap();
+        assert!(store.get_memo_by_title("Errors").unwrap().is_none());
+        assert!(store.remove_alias(&errors.id, "Errors").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_consistency_reports_and_repairs_drift() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        let alpha = store
+            .create_memo_async("Alpha".to_string(), "alpha body".to_string())
+            .await
+            .unwrap();
+        let mut beta = store
+            .create_memo_async("Beta".to_string(), "beta body".to_string())
+            .await
+            .unwrap();
+        store.search_memos("body").unwrap();
+        assert!(store
+            .verify_consistency(false)
+            .await
+            .unwrap()
+            .is_consistent());
+
+        // Simulate a branch switch: one memo deleted, one rewritten, one added
+        let alpha_path = alpha.file_path.clone().unwrap();
+        let beta_path = beta.file_path.clone().unwrap();
+        let gamma_path = memoranda_dir.join("Gamma.md");
+        fs::remove_file(&alpha_path).unwrap();
+        beta.content = "beta rewritten on another branch".to_string();
+        write_memo_file(&beta_path, &beta);
+        let gamma = Memo::new("Gamma".to_string(), "gamma body".to_string()).unwrap();
+        write_memo_file(&gamma_path, &gamma);
+
+        let report = store.verify_consistency(false).await.unwrap();
+        assert!(!report.fixed);
+        assert_eq!(report.files_scanned, 2);
+        let mut expected_stale = vec![alpha_path.clone(), beta_path.clone()];
+        expected_stale.sort();
+        assert_eq!(report.stale_cache_entries, expected_stale);
+        assert_eq!(report.missing_indexed, vec![alpha.id]);
+        assert_eq!(report.unindexed_files, vec![gamma_path.clone()]);
+        assert_eq!(report.stale_index_entries, vec![beta_path.clone()]);
+
+        // Report-only runs leave the drift in place
+        assert_eq!(store.verify_consistency(false).await.unwrap(), report);
+
+        assert!(store.verify_consistency(true).await.unwrap().fixed);
+        assert!(store.get_memo_async(&alpha.id).await.unwrap().is_none());
+        assert_eq!(
+            store
+                .get_memo_async(&beta.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .content,
+            "beta rewritten on another branch"
+        );
+        let results = store.search_memos("gamma").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, gamma.id);
+        assert!(store
+            .verify_consistency(false)
+            .await
+            .unwrap()
+            .is_consistent());
     }
 
     #[test]
-    fn test_memo_store_update_memo() {
-        use std::fs;
+    fn test_searches_do_not_wait_for_index_rebuild() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = Arc::new(MemoStore::new(temp_dir.path().to_path_buf()).with_settings(
+            Settings {
+                search_index_debounce_ms: 0,
+                ..Default::default()
+            },
+        ));
+        for i in 0..20 {
+            store
+                .create_memo(format!("Seed {i}"), format!("shared note {i}"))
+                .unwrap();
+        }
+        assert_eq!(store.search_memos("shared").unwrap().len(), 20);
 
-        // Create a .memoranda directory
-        let memoranda_dir = temp_path.join(".memoranda");
-        fs::create_dir(&memoranda_dir).unwrap();
+        // While another rebuild holds the rebuild lock, a search answers from
+        // the previous index instead of waiting or rebuilding itself
+        INDEX_REBUILDS.with(|count| count.set(0));
+        {
+            let _rebuilding = store.last_index_rebuild.write().unwrap();
+            store
+                .create_memo("Late".to_string(), "shared note late".to_string())
+                .unwrap();
+            store.mark_index_dirty();
+            assert_eq!(store.search_memos("shared").unwrap().len(), 21);
+            assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 0);
+        }
+        assert_eq!(store.search_memos("shared").unwrap().len(), 21);
+        assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 1);
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        let results = store.search_memos("shared").unwrap();
+                        assert!((21..=31).contains(&results.len()), "{}", results.len());
+                        assert!(results
+                            .iter()
+                            .all(|result| result.memo.content.contains("shared")));
+                    }
+                })
+            })
+            .collect();
+        for i in 0..10 {
+            store
+                .create_memo(format!("Concurrent {i}"), format!("shared note c{i}"))
+                .unwrap();
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
 
-        let store = MemoStore::new(temp_path.to_path_buf());
+        assert_eq!(store.search_memos("shared").unwrap().len(), 31);
+    }
 
-        // Create a memo
-        let memo = store
-            .create_memo("Update Test".to_string(), "Original content".to_string())
-            .unwrap();
-        let memo_id = memo.id;
+    #[test]
+    fn test_search_index_is_saved_and_reused_across_stores() {
+        use super::super::search_index::SEARCH_INDEX_FILE_NAME;
+        use tempfile::TempDir;
 
-        // Update the memo
-        let updated_memo = store
-            .update_memo(&memo_id, "Updated content".to_string())
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let kept = store
+            .create_memo("Kept".to_string(), "saved index apple".to_string())
+            .unwrap();
+        let edited = store
+            .create_memo("Edited".to_string(), "saved index banana".to_string())
+            .unwrap();
+        assert_eq!(store.search_memos("saved").unwrap().len(), 2);
+        assert!(memoranda_dir.join(SEARCH_INDEX_FILE_NAME).is_file());
+
+        // Another process edits a memo while no store is running
+        let mut changed = edited.clone();
+        changed.content = "saved index cherry".to_string();
+        store
+            .save_memo_to_file(&mut changed, edited.file_path.as_ref().unwrap())
             .unwrap();
-        assert_eq!(updated_memo.content, "Updated content");
-        assert!(updated_memo.updated_at > updated_memo.created_at);
 
-        // Verify the file was updated
-        let file_path = updated_memo.file_path.unwrap();
-        let file_content = fs::read_to_string(&file_path).unwrap();
-        assert!(file_content.contains("Updated content"));
-        assert!(file_content.starts_with("---\n"));
+        SAVED_INDEX_LOADS.with(|count| count.set(0));
+        let restarted = MemoStore::new(temp_dir.path().to_path_buf());
+        assert_eq!(restarted.search_memos("cherry").unwrap().len(), 1);
+        assert!(restarted.search_memos("banana").unwrap().is_empty());
+        assert_eq!(restarted.search_memos("apple").unwrap()[0].memo.id, kept.id);
+        assert_eq!(SAVED_INDEX_LOADS.with(|count| count.get()), 1);
+
+        // An unreadable index file falls back to a full rebuild
+        fs::write(memoranda_dir.join(SEARCH_INDEX_FILE_NAME), "{").unwrap();
+        SAVED_INDEX_LOADS.with(|count| count.set(0));
+        let rebuilt = MemoStore::new(temp_dir.path().to_path_buf());
+        assert_eq!(rebuilt.search_memos("saved").unwrap().len(), 2);
+        assert_eq!(SAVED_INDEX_LOADS.with(|count| count.get()), 0);
     }
 
     #[test]
-    fn test_memo_store_delete_memo() {
-        use std::fs;
+    fn test_index_rebuilds_are_debounced() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            search_index_debounce_ms: 60_000,
+            ..Default::default()
+        });
+        INDEX_REBUILDS.with(|count| count.set(0));
 
-        // Create a .memoranda directory
-        let memoranda_dir = temp_path.join(".memoranda");
-        fs::create_dir(&memoranda_dir).unwrap();
+        for i in 0..10 {
+            store
+                .create_memo(format!("Bulk {i}"), format!("imported note {i}"))
+                .unwrap();
+        }
+        assert_eq!(store.search_memos("imported").unwrap().len(), 10);
+        assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 1);
 
-        let store = MemoStore::new(temp_path.to_path_buf());
+        // Searches interleaved with further untracked changes reuse the recent
+        // index but still see every memo
+        for i in 10..15 {
+            store
+                .create_memo(format!("Bulk {i}"), format!("imported note {i}"))
+                .unwrap();
+            store.mark_index_dirty();
+            assert_eq!(store.search_memos("imported").unwrap().len(), i + 1);
+        }
+        assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 1);
+
+        let eager = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            search_index_debounce_ms: 0,
+            ..Default::default()
+        });
+        INDEX_REBUILDS.with(|count| count.set(0));
+        for i in 0..3 {
+            eager
+                .create_memo(format!("Eager {i}"), "eager".to_string())
+                .unwrap();
+            eager.mark_index_dirty();
+            eager.search_memos("eager").unwrap();
+        }
+        assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 3);
+    }
 
-        // Create a memo
-        let memo = store
-            .create_memo("Delete Test".to_string(), "To be deleted".to_string())
+    #[test]
+    fn test_writes_update_index_incrementally() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            search_index_debounce_ms: 0,
+            ..Default::default()
+        });
+        let mut memos = Vec::new();
+        for i in 0..5 {
+            memos.push(
+                store
+                    .create_memo(format!("Note {i}"), format!("session note {i}"))
+                    .unwrap(),
+            );
+        }
+        INDEX_REBUILDS.with(|count| count.set(0));
+        INDEX_UPDATES.with(|count| count.set(0));
+        assert_eq!(store.search_memos("session").unwrap().len(), 5);
+        assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 1);
+
+        // Creating, updating and deleting memos only touches those memos
+        let added = store
+            .create_memo("Added".to_string(), "session note added".to_string())
             .unwrap();
-        let memo_id = memo.id;
-        let file_path = memo.file_path.clone().unwrap();
+        store.search_memos("session").unwrap();
+        let updated = store
+            .update_memo(&memos[0].id, "rewritten text".to_string())
+            .unwrap();
+        store.search_memos("session").unwrap();
+        store.delete_memo(&memos[1].id).unwrap();
+        store.search_memos("session").unwrap();
+        assert_eq!(INDEX_REBUILDS.with(|count| count.get()), 1);
+        assert_eq!(INDEX_UPDATES.with(|count| count.get()), 3);
+
+        let searcher = store.searcher();
+        let (index, _) = searcher.index_parts();
+        assert_eq!(searcher.indexed_ids().count(), 5);
+        assert_eq!(
+            searcher.indexed_fingerprint(&added.id),
+            Some(MemoSearcher::fingerprint(&added))
+        );
+        assert_eq!(
+            searcher.indexed_fingerprint(&updated.id),
+            Some(MemoSearcher::fingerprint(&updated))
+        );
+        assert_eq!(searcher.indexed_fingerprint(&memos[1].id), None);
+        assert_eq!(index["rewritten"], vec![updated.id]);
+        assert_eq!(index["session"].len(), 4);
+    }
 
-        // Verify the file exists
-        assert!(file_path.exists());
+    #[test]
+    fn test_identical_searches_hit_result_cache_until_mutation() {
+        use tempfile::TempDir;
 
-        // Delete the memo
-        store.delete_memo(&memo_id).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo("Cached".to_string(), "hot query target".to_string())
+            .unwrap();
+        SEARCH_CACHE_HITS.with(|count| count.set(0));
 
-        // Verify the file was deleted
-        assert!(!file_path.exists());
+        assert_eq!(store.search_memos("target").unwrap().len(), 1);
+        assert_eq!(SEARCH_CACHE_HITS.with(|count| count.get()), 0);
+        // Normalization makes differently-cased terms share an entry
+        assert_eq!(store.search_memos("TARGET").unwrap().len(), 1);
+        assert_eq!(SEARCH_CACHE_HITS.with(|count| count.get()), 1);
 
-        // Verify the memo is no longer retrievable
-        let retrieved = store.get_memo(&memo_id).unwrap();
-        assert!(retrieved.is_none());
+        store
+            .update_memo(&memo.id, "no longer matches".to_string())
+            .unwrap();
+        assert!(store.search_memos("target").unwrap().is_empty());
+        assert_eq!(SEARCH_CACHE_HITS.with(|count| count.get()), 1);
+
+        // Changes made on disk, by an editor or another process, are seen too
+        let other = MemoStore::new(temp_dir.path().to_path_buf());
+        assert!(store.search_memos("fresh").unwrap().is_empty());
+        other
+            .update_memo(&memo.id, "fresh target".to_string())
+            .unwrap();
+        assert_eq!(store.search_memos("fresh").unwrap().len(), 1);
+        fs::write(
+            temp_dir.path().join(".memoranda/Dropped In.md"),
+            "another fresh note",
+        )
+        .unwrap();
+        assert_eq!(store.search_memos("fresh").unwrap().len(), 2);
+        fs::remove_file(memo.file_path.as_ref().unwrap()).unwrap();
+        assert_eq!(store.search_memos("fresh").unwrap().len(), 1);
+        assert_eq!(SEARCH_CACHE_HITS.with(|count| count.get()), 1);
+
+        let uncached = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            search_cache_size: 0,
+            ..Default::default()
+        });
+        uncached.search_memos("matches").unwrap();
+        uncached.search_memos("matches").unwrap();
+        assert_eq!(SEARCH_CACHE_HITS.with(|count| count.get()), 1);
     }
 
     #[test]
@@ -1064,6 +5817,27 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[test]
+    fn test_invalid_search_pattern_is_a_validation_error() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        store
+            .create_memo("Regex".to_string(), "content".to_string())
+            .unwrap();
+
+        let mut query = SearchQuery::new();
+        query.regex = Some("[unclosed".to_string());
+        match store.search_memos_with_query(&query) {
+            Err(MemoStoreError::Validation { message }) => {
+                assert!(message.contains("[unclosed"), "{message}");
+            }
+            other => panic!("expected validation error, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_memo_store_search_memos_with_query() {
         use crate::memo::search::SearchQuery;
@@ -1183,101 +5957,827 @@ mod tests {
         assert_eq!(memos[0].title, "Async Test Memo");
         assert_eq!(memos[0].content, "Updated async content");
 
-        // Test async memo deletion
+        // Test async memo deletion
+        store.delete_memo_async(&memo.id).await.unwrap();
+
+        // Verify memo is deleted
+        let deleted_memo = store.get_memo_async(&memo.id).await.unwrap();
+        assert!(deleted_memo.is_none());
+
+        let memos_after_delete = store.list_memos_async().await.unwrap();
+        assert_eq!(memos_after_delete.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_find_memoranda_dirs_async() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create multiple .memoranda directories
+        let memoranda_dir1 = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir1).unwrap();
+
+        let nested_dir = temp_path.join("nested");
+        fs::create_dir(&nested_dir).unwrap();
+        let memoranda_dir2 = nested_dir.join(".memoranda");
+        fs::create_dir(&memoranda_dir2).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+        let dirs = store.find_memoranda_dirs_async().await.unwrap();
+
+        assert_eq!(dirs.len(), 2);
+        assert!(dirs.contains(&memoranda_dir1));
+        assert!(dirs.contains(&memoranda_dir2));
+    }
+
+    #[tokio::test]
+    async fn test_async_file_operations_performance() {
+        use std::fs;
+        use std::time::Instant;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = temp_dir.path();
+
+        // Create a .memoranda directory
+        let memoranda_dir = temp_path.join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        let store = MemoStore::new(temp_path.to_path_buf());
+
+        // Test async operations are faster than sync for multiple operations
+        let start_async = Instant::now();
+        let mut async_tasks = Vec::new();
+        for i in 0..10 {
+            let store_clone = &store;
+            let task = async move {
+                store_clone
+                    .create_memo_async(format!("Async Memo {i}"), format!("Async content {i}"))
+                    .await
+            };
+            async_tasks.push(task);
+        }
+
+        // Execute all async operations concurrently
+        let async_results: Vec<_> = futures::future::try_join_all(async_tasks).await.unwrap();
+        let async_duration = start_async.elapsed();
+
+        assert_eq!(async_results.len(), 10);
+
+        // Clean up async-created memos
+        for memo in async_results {
+            store.delete_memo_async(&memo.id).await.unwrap();
+        }
+
+        // Test sync operations for comparison
+        let start_sync = Instant::now();
+        for i in 0..10 {
+            store
+                .create_memo(format!("Sync Memo {i}"), format!("Sync content {i}"))
+                .unwrap();
+        }
+        let sync_duration = start_sync.elapsed();
+
+        // For small operations, async might not be significantly faster,
+        // but it should at least work correctly
+        tracing::info!(
+            async_duration = ?async_duration,
+            sync_duration = ?sync_duration,
+            "Performance comparison completed"
+        );
+
+        // Verify both approaches created the same number of memos
+        let memos = store.list_memos_async().await.unwrap();
+        assert_eq!(memos.len(), 10);
+    }
+
+    #[test]
+    fn test_update_memo_keeps_configured_versions() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            memo_history_max_versions: 2,
+            ..Default::default()
+        });
+        let memo = store
+            .create_memo("Evolving".to_string(), "draft 0".to_string())
+            .unwrap();
+        assert!(store.list_versions(&memo.id).unwrap().is_empty());
+
+        for i in 1..=3 {
+            store.update_memo(&memo.id, format!("draft {i}")).unwrap();
+        }
+
+        let versions = store.list_versions(&memo.id).unwrap();
+        assert_eq!(versions.len(), 2);
+        let contents: Vec<_> = versions
+            .iter()
+            .map(|version| {
+                store
+                    .get_version(&memo.id, &version.timestamp)
+                    .unwrap()
+                    .unwrap()
+                    .content
+            })
+            .collect();
+        assert_eq!(contents, vec!["draft 2", "draft 1"]);
+        assert!(store
+            .get_version(&memo.id, "20000101T000000.000000Z")
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            store.get_memo(&memo.id).unwrap().unwrap().content,
+            "draft 3"
+        );
+        assert_eq!(store.list_memos().unwrap().len(), 1);
+        assert_eq!(store.history_usage().unwrap().versions, 2);
+
+        // History is off by default
+        let plain = MemoStore::new(temp_dir.path().to_path_buf());
+        plain.update_memo(&memo.id, "draft 4".to_string()).unwrap();
+        assert_eq!(plain.list_versions(&memo.id).unwrap().len(), 2);
+
+        // Memos in subfolders keep their history where it is found
+        let nested = store
+            .create_memo("ops/Runbook".to_string(), "step 1".to_string())
+            .unwrap();
+        store.update_memo(&nested.id, "step 2".to_string()).unwrap();
+        let versions = store.list_versions(&nested.id).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(
+            store
+                .get_version(&nested.id, &versions[0].timestamp)
+                .unwrap()
+                .unwrap()
+                .content,
+            "step 1"
+        );
+        assert_eq!(store.history_usage().unwrap().versions, 3);
+    }
+
+    #[test]
+    fn test_import_directory_with_mixed_frontmatter() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let existing = store
+            .create_memo("Existing".to_string(), "already here".to_string())
+            .unwrap();
+
+        let notes = temp_dir.path().join("notes");
+        fs::create_dir_all(notes.join("nested")).unwrap();
+        fs::create_dir_all(notes.join(".obsidian")).unwrap();
+        fs::write(notes.join("plain.md"), "# Plain Heading\nno frontmatter").unwrap();
+        fs::write(notes.join("nested/untitled_note.md"), "just text").unwrap();
+        fs::write(
+            notes.join("yaml.md"),
+            "---\ntitle: Yaml Title\ntags: [work]\n---\nyaml body",
+        )
+        .unwrap();
+        fs::write(
+            notes.join("existing.md"),
+            fs::read_to_string(existing.file_path.as_ref().unwrap()).unwrap(),
+        )
+        .unwrap();
+        fs::write(notes.join("empty.md"), "  \n").unwrap();
+        fs::write(
+            notes.join("bad tags.md"),
+            "---\ntags: [has space]\n---\nbody",
+        )
+        .unwrap();
+        fs::write(notes.join(".obsidian/workspace.md"), "ignored").unwrap();
+        fs::write(notes.join("readme.txt"), "ignored").unwrap();
+
+        let report = store.import_directory(&notes, Some("imported")).unwrap();
+        let mut titles: Vec<_> = report
+            .imported
+            .iter()
+            .map(|memo| memo.title.as_str())
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Plain Heading", "Yaml Title", "untitled note"]);
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.failed[0].path.ends_with("bad tags.md"));
+
+        let memos = store.list_memos().unwrap();
+        assert_eq!(memos.len(), 4);
+        let yaml = memos
+            .iter()
+            .find(|memo| memo.title == "Yaml Title")
+            .unwrap();
+        assert_eq!(yaml.content, "yaml body");
+        assert_eq!(yaml.tags, vec!["work", "imported"]);
+        assert_eq!(store.search_memos("frontmatter").unwrap().len(), 1);
+
+        // Importing again only finds memos already in the store
+        let again = store.import_directory(&notes, None).unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.skipped.len(), 5);
+    }
+
+    #[test]
+    fn test_tag_settings_reject_or_slugify_new_tags() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let mut settings = Settings {
+            max_tag_length: 16,
+            tag_charset: Some("[a-z0-9_-]+".to_string()),
+            ..Settings::default()
+        };
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings.clone());
+
+        let err = store
+            .create_memo_with_tags(
+                "Rejected".to_string(),
+                "body".to_string(),
+                vec!["Team Notes".to_string()],
+            )
+            .unwrap_err();
+        assert!(matches!(err, MemoStoreError::Validation { .. }), "{err}");
+        assert!(store.set_tag_alias("Team Notes", Some("team")).is_err());
+        assert!(store.list_memos().unwrap().is_empty());
+
+        settings.tag_auto_slugify = true;
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings);
+        let memo = store
+            .create_memo_with_tags(
+                "Slugified".to_string(),
+                "body".to_string(),
+                vec![
+                    "Team Notes".to_string(),
+                    "team-notes".to_string(),
+                    "q3".to_string(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(memo.tags, vec!["team-notes", "q3"]);
+        let loaded = store.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(loaded.tags, vec!["team-notes", "q3"]);
+        assert!(store
+            .create_memo_with_tags(
+                "Still Invalid".to_string(),
+                "body".to_string(),
+                vec!["C# Notes".to_string()],
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_export_all_writes_a_deterministic_bundle() {
+        use crate::memo::export::{ExportBundle, EXPORT_FORMAT_VERSION};
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("app/.memoranda")).unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let first = store
+            .create_memo_with_tags("First".to_string(), "one".to_string(), vec!["a".into()])
+            .unwrap();
+        let second = store
+            .create_memo_in_directory("Second".to_string(), "two".to_string(), vec![], "app")
+            .unwrap();
+        fs::write(
+            temp_dir.path().join("app/.memoranda/Plain.md"),
+            "no frontmatter",
+        )
+        .unwrap();
+
+        let report = store.export_all(Path::new("exports/memos.json")).unwrap();
+        assert_eq!(report.memos, 3);
+        assert_eq!(report.path, temp_dir.path().join("exports/memos.json"));
+        let text = fs::read_to_string(&report.path).unwrap();
+        let bundle: ExportBundle = serde_json::from_str(&text).unwrap();
+        assert_eq!(bundle.format_version, EXPORT_FORMAT_VERSION);
+        let ids: Vec<_> = bundle.memos.iter().map(|memo| memo.id).collect();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(ids, sorted);
+        assert!(ids.contains(&first.id) && ids.contains(&second.id));
+        let exported = bundle.memos.iter().find(|m| m.id == first.id).unwrap();
+        assert_eq!(exported.tags, vec!["a"]);
+        assert_eq!(exported.created_at, first.created_at);
+        assert_eq!(
+            exported.path.as_deref(),
+            Some(Path::new(".memoranda/First.md"))
+        );
+
+        // Exporting again gives the same file, and leaves no temporary files
+        store.export_all(&report.path).unwrap();
+        assert_eq!(fs::read_to_string(&report.path).unwrap(), text);
+        assert_eq!(
+            fs::read_dir(temp_dir.path().join("exports"))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_import_all_applies_the_strategy_to_existing_ids() {
+        use crate::memo::export::ImportStrategy;
+        use tempfile::TempDir;
+
+        let source_dir = TempDir::new().unwrap();
+        fs::create_dir(source_dir.path().join(".memoranda")).unwrap();
+        let source = MemoStore::new(source_dir.path().to_path_buf());
+        let first = source
+            .create_memo_with_tags("First".to_string(), "one".to_string(), vec!["a".into()])
+            .unwrap();
+        let second = source
+            .create_memo("Second".to_string(), "two".to_string())
+            .unwrap();
+        let bundle = source_dir.path().join("memos.json");
+        source.export_all(&bundle).unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let report = store
+            .import_all(&bundle, ImportStrategy::SkipExisting)
+            .unwrap();
+        assert_eq!(
+            (report.created, report.overwritten, report.skipped),
+            (2, 0, 0)
+        );
+        assert!(report.failed.is_empty());
+        let imported = store.get_memo(&first.id).unwrap().unwrap();
+        assert_eq!(imported.tags, vec!["a"]);
+        assert_eq!(imported.created_at, first.created_at);
+        assert_eq!(imported.updated_at, first.updated_at);
+
+        let report = store
+            .import_all(&bundle, ImportStrategy::SkipExisting)
+            .unwrap();
+        assert_eq!(
+            (report.created, report.overwritten, report.skipped),
+            (0, 0, 2)
+        );
+
+        store
+            .update_memo(&second.id, "changed".to_string())
+            .unwrap();
+        let report = store
+            .import_all(&bundle, ImportStrategy::Overwrite)
+            .unwrap();
+        assert_eq!(
+            (report.created, report.overwritten, report.skipped),
+            (0, 2, 0)
+        );
+        assert_eq!(store.get_memo(&second.id).unwrap().unwrap().content, "two");
+        assert_eq!(store.list_memos().unwrap().len(), 2);
+
+        let report = store
+            .import_all(&bundle, ImportStrategy::DuplicateAsNew)
+            .unwrap();
+        assert_eq!(
+            (report.created, report.overwritten, report.skipped),
+            (2, 0, 0)
+        );
+        let memos = store.list_memos().unwrap();
+        assert_eq!(memos.len(), 4);
+        assert_eq!(memos.iter().filter(|memo| memo.title == "First").count(), 2);
+    }
+
+    #[test]
+    fn test_import_all_reports_bad_entries_and_rejects_bad_bundles() {
+        use crate::memo::export::ImportStrategy;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let good = MemoId::new();
+        let bad = MemoId::new();
+        let bundle = serde_json::json!({
+            "format_version": 1,
+            "memos": [
+                {"id": bad, "title": "No content"},
+                {
+                    "id": good,
+                    "title": "Kept",
+                    "content": "body",
+                    "tags": [],
+                    "created_at": "2024-01-02T03:04:05Z",
+                    "updated_at": "2024-02-03T04:05:06Z",
+                    "path": null
+                }
+            ]
+        });
+        fs::write(temp_dir.path().join("bundle.json"), bundle.to_string()).unwrap();
+
+        let report = store
+            .import_all(Path::new("bundle.json"), ImportStrategy::default())
+            .unwrap();
+        assert_eq!(report.created, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].index, 0);
+        assert_eq!(report.failed[0].id, Some(bad));
+        let kept = store.get_memo(&good).unwrap().unwrap();
+        assert_eq!(kept.updated_at.to_rfc3339(), "2024-02-03T04:05:06+00:00");
+
+        fs::write(
+            temp_dir.path().join("future.json"),
+            r#"{"format_version": 99, "memos": []}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("broken.json"), "[1, 2").unwrap();
+        for name in ["future.json", "broken.json"] {
+            assert!(matches!(
+                store.import_all(Path::new(name), ImportStrategy::default()),
+                Err(MemoStoreError::Validation { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_memos_batch_preserves_order() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let first = store
+            .create_memo("First".to_string(), "one".to_string())
+            .unwrap();
+        let second = store
+            .create_memo("Second".to_string(), "two".to_string())
+            .unwrap();
+        let unknown = MemoId::new();
+
+        let memos = store
+            .get_memos_batch(&[second.id, unknown, first.id, second.id])
+            .unwrap();
+        let titles: Vec<_> = memos
+            .iter()
+            .map(|memo| memo.as_ref().map(|memo| memo.title.as_str()))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![Some("Second"), None, Some("First"), Some("Second")]
+        );
+        assert!(store.get_memos_batch(&[]).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_listing_evicts_cached_memos_with_duplicate_ids() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo("Copied".to_string(), "duplicated".to_string())
+            .unwrap();
+        store.get_memo_async(&memo.id).await.unwrap().unwrap();
+
+        fs::copy(
+            memoranda_dir.join("Copied.md"),
+            memoranda_dir.join("Copied (1).md"),
+        )
+        .unwrap();
+        // Nothing has seen the copy yet, so the cached memo is still served
+        assert!(store.get_memo_async(&memo.id).await.unwrap().is_some());
+
+        assert_eq!(store.list_memos_async().await.unwrap().len(), 2);
+        assert!(matches!(
+            store.get_memo_async(&memo.id).await,
+            Err(MemoStoreError::DuplicateId { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_memos_batch_async_uses_cache() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let cached = store
+            .create_memo_async("Cached".to_string(), "one".to_string())
+            .await
+            .unwrap();
+        let uncached = store
+            .create_memo("Uncached".to_string(), "two".to_string())
+            .unwrap();
+        store.clear_cache().await;
+        store.get_memo_async(&cached.id).await.unwrap();
+        let hits_before = store.get_cache_stats().await.memo_hits;
+
+        let memos = store
+            .get_memos_batch_async(&[uncached.id, cached.id])
+            .await
+            .unwrap();
+        assert_eq!(memos[0].as_ref().unwrap().title, "Uncached");
+        assert_eq!(memos[1].as_ref().unwrap().title, "Cached");
+        assert_eq!(store.get_cache_stats().await.memo_hits, hits_before + 1);
+
+        // Both memos are cached now, so a second batch is served entirely from it.
+        let memos = store
+            .get_memos_batch_async(&[cached.id, uncached.id])
+            .await
+            .unwrap();
+        assert!(memos.iter().all(Option::is_some));
+        assert_eq!(store.get_cache_stats().await.memo_hits, hits_before + 3);
+    }
+
+    #[test]
+    fn test_new_with_settings_configures_memo_cache() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memo_dir = temp_dir.path().join("docs").join(".memoranda");
+        fs::create_dir_all(&memo_dir).unwrap();
+        let settings = Settings {
+            memo_cache_size: 10,
+            metadata_cache_size: 20,
+            memo_ttl_seconds: 60,
+            metadata_ttl_multiplier: 3,
+            disk_cache_max_bytes: 1 << 20,
+            ..Default::default()
+        };
+        let store = MemoStore::new_with_settings(temp_dir.path().to_path_buf(), &settings);
+
+        let config = store.cache.get_config();
+        assert_eq!(config.memo_cache_size, 10);
+        assert_eq!(config.metadata_cache_size, 20);
+        assert_eq!(config.memo_ttl_seconds, 60);
+        assert_eq!(config.metadata_ttl_multiplier, 3);
+        assert_eq!(
+            config.disk_cache_dir,
+            Some(memo_dir.join(DISK_CACHE_DIR_NAME))
+        );
+        assert_eq!(store.settings().memo_cache_size, 10);
+
+        // Without a memo directory there is nowhere to put the disk tier
+        let empty_dir = TempDir::new().unwrap();
+        let store = MemoStore::new_with_settings(empty_dir.path().to_path_buf(), &settings);
+        assert_eq!(store.cache.get_config().disk_cache_dir, None);
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_setting() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new_with_cache_config(temp_dir.path().to_path_buf(), 1, 3600)
+            .with_settings(Settings {
+                disk_cache_max_bytes: 1 << 20,
+                ..Default::default()
+            });
+        let mut ids = Vec::new();
+        for title in ["First", "Second", "Third"] {
+            let memo = store
+                .create_memo(title.to_string(), format!("{title} memo"))
+                .unwrap();
+            store.get_memo_async(&memo.id).await.unwrap().unwrap();
+            ids.push(memo.id);
+        }
+        store.cache.run_pending_tasks().await;
+        assert!(temp_dir.path().join(".memoranda/.cache").is_dir());
+
+        for id in &ids {
+            assert!(store.get_memo_async(id).await.unwrap().is_some());
+        }
+        assert!(store.get_cache_stats().await.disk_hits >= 1);
+        // Cache entries are never mistaken for memos
+        assert_eq!(store.list_memos().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_writes_fail_while_another_process_holds_the_lock() {
+        use crate::memo::lock::STORE_LOCK_FILE_NAME;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir_all(memoranda_dir.join("ops")).unwrap();
+        let settings = Settings {
+            file_lock_timeout_ms: 50,
+            ..Default::default()
+        };
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings.clone());
+        let memo = store
+            .create_memo("ops/Runbook".to_string(), "v1".to_string())
+            .unwrap();
+        let file_path = memo.file_path.clone().unwrap();
+        // Released after every write
+        assert!(!memoranda_dir.join(STORE_LOCK_FILE_NAME).exists());
+
+        // Memos in subfolders share their memo directory's lock
+        let held = StoreLock::acquire(&memoranda_dir, std::time::Duration::ZERO).unwrap();
+        let err = store.update_memo(&memo.id, "v2".to_string()).unwrap_err();
+        assert!(matches!(err, MemoStoreError::Locked { timeout_ms: 50, .. }));
+        assert!(err.to_string().contains("gave up after 50 ms"));
+        assert!(matches!(
+            store.update_memo_async(&memo.id, "v2".to_string()).await,
+            Err(MemoStoreError::Locked { .. })
+        ));
+        assert!(matches!(
+            store.delete_memo(&memo.id),
+            Err(MemoStoreError::Locked { .. })
+        ));
+        assert!(fs::read_to_string(&file_path).unwrap().ends_with("v1"));
+
+        // Locking can be turned off for single-process use
+        let unlocked = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            file_locking: false,
+            ..settings
+        });
+        unlocked.update_memo(&memo.id, "v2".to_string()).unwrap();
+
+        drop(held);
+        store.update_memo(&memo.id, "v3".to_string()).unwrap();
         store.delete_memo_async(&memo.id).await.unwrap();
+        assert!(!file_path.exists());
+        assert!(!memoranda_dir.join(STORE_LOCK_FILE_NAME).exists());
+    }
 
-        // Verify memo is deleted
-        let deleted_memo = store.get_memo_async(&memo.id).await.unwrap();
-        assert!(deleted_memo.is_none());
+    #[tokio::test]
+    async fn test_interleaved_updates_from_two_stores_are_never_lost() {
+        use tempfile::TempDir;
 
-        let memos_after_delete = store.list_memos_async().await.unwrap();
-        assert_eq!(memos_after_delete.len(), 0);
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let first = MemoStore::new(temp_dir.path().to_path_buf());
+        let second = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = first
+            .create_memo("Shared".to_string(), "v1".to_string())
+            .unwrap();
+        let file_path = memo.file_path.clone().unwrap();
+
+        // The first store reads the memo, then the second updates it before
+        // the first saves its change
+        let (mut stale, stamp) = first.memo_for_update(&memo.id).unwrap();
+        second.add_alias(&memo.id, "Common".to_string()).unwrap();
+        stale.update_content("from first".to_string()).unwrap();
+        assert!(matches!(
+            first.save_updated_memo(&mut stale, &file_path, stamp),
+            Err(MemoStoreError::WriteConflict { .. })
+        ));
+
+        let (mut stale, stamp) = first.memo_for_update_async(&memo.id).await.unwrap();
+        second
+            .update_memo_title(&memo.id, "Shared Notes".to_string())
+            .unwrap();
+        stale.add_tag("first".to_string());
+        assert!(matches!(
+            first
+                .save_updated_memo_async(&mut stale, &file_path, stamp)
+                .await,
+            Err(MemoStoreError::WriteConflict { .. })
+        ));
+
+        // Read again, the first store's update keeps the second's changes
+        first
+            .update_memo_async(&memo.id, "from first".to_string())
+            .await
+            .unwrap();
+        let stored = second.get_memo(&memo.id).unwrap().unwrap();
+        assert_eq!(stored.content, "from first");
+        assert_eq!(stored.title, "Shared Notes");
+        assert_eq!(stored.aliases, vec!["Common"]);
     }
 
     #[tokio::test]
-    async fn test_find_memoranda_dirs_async() {
-        use std::fs;
+    async fn test_sync_delete_evicts_memo_from_cache() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
-
-        // Create multiple .memoranda directories
-        let memoranda_dir1 = temp_path.join(".memoranda");
-        fs::create_dir(&memoranda_dir1).unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo_async("Doomed".to_string(), "Soon gone".to_string())
+            .await
+            .unwrap();
+        let file_path = memo.file_path.clone().unwrap();
+        assert!(store.cache.get_memo(&memo.id).await.is_some());
+        assert!(store.cache.get_metadata(&file_path).await.is_some());
 
-        let nested_dir = temp_path.join("nested");
-        fs::create_dir(&nested_dir).unwrap();
-        let memoranda_dir2 = nested_dir.join(".memoranda");
-        fs::create_dir(&memoranda_dir2).unwrap();
+        store.delete_memo(&memo.id).unwrap();
 
-        let store = MemoStore::new(temp_path.to_path_buf());
-        let dirs = store.find_memoranda_dirs_async().await.unwrap();
+        assert!(store.cache.get_memo(&memo.id).await.is_none());
+        assert!(store.cache.get_metadata(&file_path).await.is_none());
+        assert!(store.get_memo_async(&memo.id).await.unwrap().is_none());
 
-        assert_eq!(dirs.len(), 2);
-        assert!(dirs.contains(&memoranda_dir1));
-        assert!(dirs.contains(&memoranda_dir2));
+        // The sync path also works without a runtime
+        let memo = store
+            .create_memo_async("Doomed Too".to_string(), "Also gone".to_string())
+            .await
+            .unwrap();
+        let store = Arc::new(store);
+        std::thread::spawn({
+            let store = Arc::clone(&store);
+            move || store.delete_memo(&memo.id).unwrap()
+        })
+        .join()
+        .unwrap();
+        assert!(store.cache.get_memo(&memo.id).await.is_none());
     }
 
     #[tokio::test]
-    async fn test_async_file_operations_performance() {
-        use std::fs;
-        use std::time::Instant;
+    async fn test_sync_delete_and_trash_leave_no_stale_cache_entries() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        let temp_path = temp_dir.path();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
 
-        // Create a .memoranda directory
-        let memoranda_dir = temp_path.join(".memoranda");
-        fs::create_dir(&memoranda_dir).unwrap();
+        for trash in [false, true] {
+            let memo = store
+                .create_memo("Ephemeral".to_string(), "here today".to_string())
+                .unwrap();
+            assert!(store.get_memo_async(&memo.id).await.unwrap().is_some());
+            assert!(store.cache.get_memo(&memo.id).await.is_some());
 
-        let store = MemoStore::new(temp_path.to_path_buf());
+            if trash {
+                store.trash_memo(&memo.id).unwrap();
+            } else {
+                store.delete_memo(&memo.id).unwrap();
+            }
 
-        // Test async operations are faster than sync for multiple operations
-        let start_async = Instant::now();
-        let mut async_tasks = Vec::new();
-        for i in 0..10 {
-            let store_clone = &store;
-            let task = async move {
-                store_clone
-                    .create_memo_async(format!("Async Memo {i}"), format!("Async content {i}"))
-                    .await
-            };
-            async_tasks.push(task);
+            assert!(store.cache.get_memo(&memo.id).await.is_none());
+            assert!(store.get_memo_async(&memo.id).await.unwrap().is_none());
         }
+    }
 
-        // Execute all async operations concurrently
-        let async_results: Vec<_> = futures::future::try_join_all(async_tasks).await.unwrap();
-        let async_duration = start_async.elapsed();
+    #[tokio::test]
+    async fn test_sync_renames_evict_the_cached_memo() {
+        use tempfile::TempDir;
 
-        assert_eq!(async_results.len(), 10);
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memo = store
+            .create_memo_async("Draft".to_string(), "body".to_string())
+            .await
+            .unwrap();
 
-        // Clean up async-created memos
-        for memo in async_results {
-            store.delete_memo_async(&memo.id).await.unwrap();
-        }
+        store.rename_memo(&memo.id, "Final".to_string()).unwrap();
+        assert!(store.cache.get_memo(&memo.id).await.is_none());
+        let renamed = store.get_memo_async(&memo.id).await.unwrap().unwrap();
+        assert!(renamed.file_path.unwrap().ends_with("Final.md"));
 
-        // Test sync operations for comparison
-        let start_sync = Instant::now();
-        for i in 0..10 {
-            store
-                .create_memo(format!("Sync Memo {i}"), format!("Sync content {i}"))
-                .unwrap();
-        }
-        let sync_duration = start_sync.elapsed();
+        assert!(store.get_memo_async(&memo.id).await.unwrap().is_some());
+        store
+            .update_memo_title(&memo.id, "Published".to_string())
+            .unwrap();
+        assert!(store.cache.get_memo(&memo.id).await.is_none());
+        let retitled = store.get_memo_async(&memo.id).await.unwrap().unwrap();
+        assert_eq!(retitled.title, "Published");
+        store.sync_filename(&memo.id).unwrap().unwrap();
+        assert!(store.cache.get_memo(&memo.id).await.is_none());
+        assert!(store.cache_consistency().await.unwrap().is_empty());
+    }
 
-        // For small operations, async might not be significantly faster,
-        // but it should at least work correctly
-        tracing::info!(
-            async_duration = ?async_duration,
-            sync_duration = ?sync_duration,
-            "Performance comparison completed"
-        );
+    #[tokio::test]
+    async fn test_cache_consistency_reports_files_changed_behind_the_cache() {
+        use tempfile::TempDir;
 
-        // Verify both approaches created the same number of memos
-        let memos = store.list_memos_async().await.unwrap();
-        assert_eq!(memos.len(), 10);
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let mut memos = Vec::new();
+        for title in ["Edited", "Removed", "Untouched"] {
+            memos.push(
+                store
+                    .create_memo_async(title.to_string(), format!("{title} body"))
+                    .await
+                    .unwrap(),
+            );
+        }
+        let entries = store.cache_consistency().await.unwrap();
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| !entry.diverged));
+
+        // Change files without telling the store
+        let mut edited = memos[0].clone();
+        edited.content = "Changed on disk".to_string();
+        write_memo_file(edited.file_path.as_ref().unwrap(), &edited);
+        fs::remove_file(memos[1].file_path.as_ref().unwrap()).unwrap();
+
+        let entries = store.cache_consistency().await.unwrap();
+        let by_id = |id: MemoId| entries.iter().find(|entry| entry.id == id).unwrap();
+        assert!(by_id(memos[0].id).diverged);
+        assert!(!by_id(memos[0].id).file_missing);
+        assert!(by_id(memos[1].id).diverged);
+        assert!(by_id(memos[1].id).file_missing);
+        assert!(!by_id(memos[2].id).diverged);
+
+        // A diagnostic only: the stale entries are still cached
+        assert_eq!(store.cache_consistency().await.unwrap(), entries);
     }
 
     #[tokio::test]
@@ -1352,6 +6852,43 @@ mod tests {
         // but the hits prove that caching is working correctly
     }
 
+    #[tokio::test]
+    async fn test_create_and_update_do_not_restat_written_file() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+
+        METADATA_LOOKUPS.with(|count| count.set(0));
+        let memo = store
+            .create_memo_async("Stat Test".to_string(), "content".to_string())
+            .await
+            .unwrap();
+        assert_eq!(METADATA_LOOKUPS.with(|count| count.get()), 0);
+
+        // Metadata cached from the write is accurate enough to validate the memo
+        let file_path = memo.file_path.clone().unwrap();
+        let cached = store.cache.get_metadata(&file_path).await.unwrap();
+        assert_eq!(cached.file_size, fs::metadata(&file_path).unwrap().len());
+        assert!(store
+            .cache
+            .is_memo_valid(&memo.id, &file_path)
+            .await
+            .unwrap());
+
+        store
+            .update_memo_async(&memo.id, "updated content".to_string())
+            .await
+            .unwrap();
+        assert_eq!(METADATA_LOOKUPS.with(|count| count.get()), 0);
+        assert!(store
+            .cache
+            .is_memo_valid(&memo.id, &file_path)
+            .await
+            .unwrap());
+    }
+
     #[tokio::test]
     async fn test_cache_invalidation_on_update() {
         use std::fs;
@@ -1513,4 +7050,514 @@ mod tests {
         let stats = store.get_cache_stats().await;
         assert!(stats.memo_hits > 0);
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_follow_symlinks_discovers_linked_memoranda_dir() {
+        use std::os::unix::fs::symlink;
+        use tempfile::TempDir;
+
+        let shared_dir = TempDir::new().unwrap();
+        fs::write(shared_dir.path().join("shared.md"), "shared notes").unwrap();
+
+        let repo_dir = TempDir::new().unwrap();
+        symlink(shared_dir.path(), repo_dir.path().join(".memoranda")).unwrap();
+        // A cycle back to the repository root must not hang discovery
+        symlink(repo_dir.path(), repo_dir.path().join("loop")).unwrap();
+
+        let store = MemoStore::new(repo_dir.path().to_path_buf());
+        assert!(store.find_memoranda_dirs().unwrap().is_empty());
+        assert!(store.find_memoranda_dirs_async().await.unwrap().is_empty());
+
+        let settings = Settings {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let store = MemoStore::new(repo_dir.path().to_path_buf()).with_settings(settings);
+        let expected = vec![repo_dir.path().join(".memoranda")];
+        assert_eq!(store.find_memoranda_dirs().unwrap(), expected);
+        METADATA_LOOKUPS.with(|count| count.set(0));
+        assert_eq!(store.find_memoranda_dirs_async().await.unwrap(), expected);
+        // Each of the two links is followed with a lookup of its target
+        assert_eq!(METADATA_LOOKUPS.with(|count| count.get()), 2);
+
+        let memos = store.list_memos().unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].content, "shared notes");
+    }
+
+    #[tokio::test]
+    async fn test_discovery_skips_gitignored_directories() {
+        use tempfile::TempDir;
+
+        let repo_dir = TempDir::new().unwrap();
+        let root = repo_dir.path();
+        fs::write(
+            root.join(".gitignore"),
+            "target/\nnode_modules\n.memoranda/\n",
+        )
+        .unwrap();
+        fs::create_dir(root.join(".memoranda")).unwrap();
+        fs::create_dir_all(root.join("src/.memoranda")).unwrap();
+        // A large ignored tree, with stray .memoranda directories that must not be found
+        for ignored in ["target", "node_modules"] {
+            for package in 0..50 {
+                let dir = root.join(format!("{ignored}/pkg{package}/nested/deeper"));
+                fs::create_dir_all(dir.join(".memoranda")).unwrap();
+            }
+        }
+
+        let store = MemoStore::new(root.to_path_buf());
+        let mut expected = vec![root.join(".memoranda"), root.join("src/.memoranda")];
+        expected.sort();
+
+        DIRECTORIES_SCANNED.with(|count| count.set(0));
+        let mut found = store.find_memoranda_dirs().unwrap();
+        found.sort();
+        assert_eq!(found, expected);
+        // The root, src, and the two .memoranda directories
+        assert_eq!(DIRECTORIES_SCANNED.with(|count| count.get()), 4);
+
+        DIRECTORIES_SCANNED.with(|count| count.set(0));
+        let mut found = store.find_memoranda_dirs_async().await.unwrap();
+        found.sort();
+        assert_eq!(found, expected);
+        // The async walk does not descend into .memoranda directories
+        assert_eq!(DIRECTORIES_SCANNED.with(|count| count.get()), 2);
+
+        let settings = Settings {
+            respect_gitignore: false,
+            ..Default::default()
+        };
+        let store = MemoStore::new(root.to_path_buf()).with_settings(settings);
+        assert_eq!(store.find_memoranda_dirs().unwrap().len(), 102);
+        assert_eq!(store.find_memoranda_dirs_async().await.unwrap().len(), 102);
+    }
+
+    #[tokio::test]
+    async fn test_custom_memo_dir_name() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join(".memoranda")).unwrap();
+        fs::create_dir_all(root.join("docs/notes")).unwrap();
+        fs::write(root.join(".gitignore"), "notes/\n").unwrap();
+        let settings = Settings {
+            memo_dir_name: "notes".to_string(),
+            ..Default::default()
+        };
+        let store = MemoStore::new(root.to_path_buf()).with_settings(settings);
+
+        // Found even when gitignored, like .memoranda; .memoranda itself is not a memo directory
+        let expected = vec![root.join("docs/notes")];
+        assert_eq!(store.find_memoranda_dirs().unwrap(), expected);
+        assert_eq!(store.find_memoranda_dirs_async().await.unwrap(), expected);
+
+        let memo = store
+            .create_memo("Standup".to_string(), "Notes".to_string())
+            .unwrap();
+        assert_eq!(
+            memo.file_path,
+            Some(root.join("docs/notes").join("Standup.md"))
+        );
+    }
+
+    #[test]
+    fn test_select_closest_memo_dir() {
+        let root = Path::new("/repo");
+        let dirs = vec![
+            root.join("b/.memoranda"),
+            root.join("a/.memoranda"),
+            root.join(".memoranda"),
+            root.join("a/deep/.memoranda"),
+        ];
+        let select = |current_dir: &str, git_root: Option<&Path>| {
+            select_closest_memo_dir(dirs.clone(), Some(Path::new(current_dir)), git_root)
+        };
+
+        // The deepest one beside the working directory or an ancestor
+        assert_eq!(select("/repo/a/src", None), Some(root.join("a/.memoranda")));
+        assert_eq!(
+            select("/repo/a/deep", None),
+            Some(root.join("a/deep/.memoranda"))
+        );
+        assert_eq!(select("/repo", None), Some(root.join(".memoranda")));
+        // Then the git root's, then the shallowest, ties broken by path
+        assert_eq!(
+            select("/elsewhere", Some(root)),
+            Some(root.join(".memoranda"))
+        );
+        assert_eq!(
+            select("/elsewhere", Some(&root.join("b"))),
+            Some(root.join("b/.memoranda"))
+        );
+        assert_eq!(select("/elsewhere", None), Some(root.join(".memoranda")));
+        assert_eq!(
+            select_closest_memo_dir(dirs[..2].to_vec(), None, None),
+            Some(root.join("a/.memoranda"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_memo_dir_setting() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join(".memoranda")).unwrap();
+        fs::create_dir_all(root.join("docs/.memoranda")).unwrap();
+        let settings = Settings {
+            default_memo_dir: Some("docs".to_string()),
+            ..Default::default()
+        };
+        let store = MemoStore::new(root.to_path_buf()).with_settings(settings.clone());
+
+        let memo = store
+            .create_memo("Standup".to_string(), "Notes".to_string())
+            .unwrap();
+        assert_eq!(
+            memo.file_path,
+            Some(root.join("docs/.memoranda").join("Standup.md"))
+        );
+        let memo = store
+            .create_memo_async("Retro".to_string(), "Notes".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            memo.file_path,
+            Some(root.join("docs/.memoranda").join("Retro.md"))
+        );
+
+        let store = MemoStore::new(root.to_path_buf()).with_settings(Settings {
+            default_memo_dir: Some("missing".to_string()),
+            ..settings
+        });
+        assert!(matches!(
+            store.create_memo("Lost".to_string(), "Notes".to_string()),
+            Err(MemoStoreError::Validation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_memorandaignore_hides_files_from_every_scan() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let kept = store
+            .create_memo("Release checklist".to_string(), "Ship it".to_string())
+            .unwrap();
+        let draft = store
+            .create_memo("draft-checklist".to_string(), "Unfinished".to_string())
+            .unwrap();
+        let report = store
+            .create_memo("report".to_string(), "Generated checklist".to_string())
+            .unwrap();
+        fs::write(
+            memoranda_dir.join(MEMORANDA_IGNORE_FILE_NAME),
+            "# scratch files\ndraft-*.md\n/report.md\n",
+        )
+        .unwrap();
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let listed: Vec<_> = store.list_memos().unwrap().iter().map(|m| m.id).collect();
+        assert_eq!(listed, vec![kept.id]);
+        let listed: Vec<_> = store
+            .list_memos_async()
+            .await
+            .unwrap()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(listed, vec![kept.id]);
+        assert!(store.get_memo(&draft.id).unwrap().is_none());
+        assert!(store.get_memo_async(&report.id).await.unwrap().is_none());
+        assert!(store.get_memo(&kept.id).unwrap().is_some());
+
+        let results = store.search_memos("checklist").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, kept.id);
+
+        assert_eq!(
+            store.ignored_memo_files().unwrap(),
+            vec![
+                memoranda_dir.join("draft-checklist.md"),
+                memoranda_dir.join("report.md")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memo_file_extensions() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let old = MemoStore::new(temp_dir.path().to_path_buf())
+            .with_settings(Settings {
+                memo_file_extensions: vec!["markdown".to_string()],
+                ..Default::default()
+            })
+            .create_memo("Old Notes".to_string(), "Kept as markdown".to_string())
+            .unwrap();
+        assert_eq!(
+            old.file_path,
+            Some(memoranda_dir.join("Old Notes.markdown"))
+        );
+        fs::write(memoranda_dir.join("todo.txt"), "Not a memo").unwrap();
+
+        // .markdown files are memos by default
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let memos = store.list_memos().unwrap();
+        assert_eq!(memos.len(), 1);
+        assert_eq!(memos[0].id, old.id);
+        assert_eq!(store.list_memos_async().await.unwrap().len(), 1);
+        assert_eq!(store.search_memos("markdown").unwrap().len(), 1);
+        assert!(store.get_memo(&old.id).unwrap().is_some());
+
+        // Renames keep the file's extension
+        let renamed = store.rename_memo(&old.id, "Notes".to_string()).unwrap();
+        assert_eq!(
+            renamed.file_path,
+            Some(memoranda_dir.join("Notes.markdown"))
+        );
+
+        // New memos get the first extension; others are no longer memos
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            memo_file_extensions: vec!["txt".to_string()],
+            ..Default::default()
+        });
+        let created = store
+            .create_memo("Plan".to_string(), "Plain text".to_string())
+            .unwrap();
+        assert_eq!(created.file_path, Some(memoranda_dir.join("Plan.txt")));
+        let mut titles: Vec<_> = store
+            .list_memos()
+            .unwrap()
+            .into_iter()
+            .map(|memo| memo.title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Plan", "todo"]);
+    }
+
+    #[test]
+    fn test_create_memo_in_new_scope() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+        let create = |store: &MemoStore, directory: &str| {
+            store.create_memo_in_directory(
+                "Kickoff".to_string(),
+                "Goals".to_string(),
+                Vec::new(),
+                directory,
+            )
+        };
+
+        // Off by default: a clear error, and nothing is created
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let err = create(&store, "projects/web").unwrap_err();
+        assert!(err.to_string().contains("auto_create_scope_dir"));
+        assert!(!temp_dir.path().join("projects").exists());
+
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(Settings {
+            auto_create_scope_dir: true,
+            ..Default::default()
+        });
+        let memo = create(&store, "projects/web").unwrap();
+        assert_eq!(
+            memo.file_path,
+            Some(temp_dir.path().join("projects/web/.memoranda/Kickoff.md"))
+        );
+        let memo = create(&store, "projects/api/.memoranda").unwrap();
+        assert_eq!(
+            memo.file_path,
+            Some(temp_dir.path().join("projects/api/.memoranda/Kickoff.md"))
+        );
+        assert_eq!(store.list_memos().unwrap().len(), 2);
+
+        // Never outside the root or inside another memo directory
+        for directory in ["../elsewhere", ".memoranda/.drafts"] {
+            assert!(create(&store, directory).is_err());
+        }
+        assert!(!temp_dir.path().join(".memoranda/.drafts").exists());
+    }
+
+    #[tokio::test]
+    async fn test_memos_in_subfolders() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        let top = store
+            .create_memo("Overview".to_string(), "Where things are".to_string())
+            .unwrap();
+        let layers = store
+            .create_memo_in_directory(
+                "Layers".to_string(),
+                "Storage below search".to_string(),
+                Vec::new(),
+                ".memoranda/architecture",
+            )
+            .unwrap();
+        let retro = store
+            .create_memo_in_directory(
+                "Q1".to_string(),
+                "Search was slow".to_string(),
+                Vec::new(),
+                ".memoranda/retro/2024",
+            )
+            .unwrap();
+        assert_eq!(
+            layers.file_path,
+            Some(memoranda_dir.join("architecture/Layers.md"))
+        );
+        assert_eq!(
+            retro.file_path,
+            Some(memoranda_dir.join("retro/2024/Q1.md"))
+        );
+        for directory in [".memoranda/.drafts", ".memoranda/../outside"] {
+            assert!(store
+                .create_memo_in_directory("Bad".to_string(), String::new(), Vec::new(), directory)
+                .is_err());
+        }
+
+        // Hidden and ignored subfolders are not scanned
+        fs::create_dir_all(memoranda_dir.join(".drafts")).unwrap();
+        fs::write(memoranda_dir.join(".drafts/Hidden.md"), "# Hidden").unwrap();
+        fs::create_dir_all(memoranda_dir.join("scratch")).unwrap();
+        fs::write(memoranda_dir.join("scratch/Ignored.md"), "# Ignored").unwrap();
+        fs::write(memoranda_dir.join(MEMORANDA_IGNORE_FILE_NAME), "scratch/\n").unwrap();
+
+        let mut expected = vec![top.id, layers.id, retro.id];
+        expected.sort();
+        let mut listed: Vec<_> = store.list_memos().unwrap().iter().map(|m| m.id).collect();
+        listed.sort();
+        assert_eq!(listed, expected);
+        let mut listed: Vec<_> = store
+            .list_memos_async()
+            .await
+            .unwrap()
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        listed.sort();
+        assert_eq!(listed, expected);
+
+        assert_eq!(
+            store.get_memo(&retro.id).unwrap().unwrap().file_path,
+            retro.file_path
+        );
+        assert!(store.get_memo_async(&layers.id).await.unwrap().is_some());
+        let results = store.search_memos("slow").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memo.id, retro.id);
+
+        // Trashed next to where they were, and restored there
+        store.trash_memo(&layers.id).unwrap();
+        assert_eq!(store.list_trash().unwrap()[0].id, layers.id);
+        let restored = store.untrash_memo(&layers.id).unwrap();
+        assert_eq!(restored.file_path, layers.file_path);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_each_change_once() {
+        use crate::memo::audit::read_audit_log;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let memoranda_dir = temp_dir.path().join(".memoranda");
+        fs::create_dir(&memoranda_dir).unwrap();
+
+        // Off by default
+        let store = MemoStore::new(temp_dir.path().to_path_buf());
+        store
+            .create_memo("Unaudited".to_string(), "body".to_string())
+            .unwrap();
+        assert!(read_audit_log(&memoranda_dir).unwrap().is_empty());
+
+        let settings = Settings {
+            audit_log: true,
+            ..Default::default()
+        };
+        let mut store = MemoStore::new(temp_dir.path().to_path_buf()).with_settings(settings);
+        store.set_audit_actor(Some("agent".to_string()));
+        let memo = store
+            .create_memo("Audited".to_string(), "body".to_string())
+            .unwrap();
+        let id = memo.id;
+        store.update_memo(&id, "changed".to_string()).unwrap();
+        store
+            .update_memo_title_async(&id, "Retitled".to_string())
+            .await
+            .unwrap();
+        store.rename_memo(&id, "Renamed".to_string()).unwrap();
+        store.add_alias(&id, "alias".to_string()).unwrap();
+        store.remove_alias(&id, "alias").unwrap();
+        store.set_tag_meta("rust", TagMeta::default()).unwrap();
+        store.trash_memo(&id).unwrap();
+        store.untrash_memo(&id).unwrap();
+        store.delete_memo_async(&id).await.unwrap();
+        // Failed operations are not recorded
+        assert!(store.update_memo(&id, "gone".to_string()).is_err());
+
+        // Nor are the renames and purges of maintenance missed
+        let drifted = store
+            .create_memo("Drifted".to_string(), "body".to_string())
+            .unwrap();
+        let drifted_path = drifted.file_path.clone().unwrap();
+        fs::rename(&drifted_path, memoranda_dir.join("Elsewhere.md")).unwrap();
+        assert_eq!(store.normalize_filenames(false).unwrap().len(), 1);
+        fs::rename(&drifted_path, memoranda_dir.join("Elsewhere.md")).unwrap();
+        assert_eq!(
+            store.normalize_filenames_async(false).await.unwrap().len(),
+            1
+        );
+        store.trash_memo(&drifted.id).unwrap();
+        let options = CompactOptions::new(chrono::Duration::seconds(-1));
+        assert_eq!(store.compact(&options).unwrap().trash_purged.len(), 1);
+
+        let records = read_audit_log(&memoranda_dir).unwrap();
+        let ops: Vec<&str> = records.iter().map(|record| record.op.as_str()).collect();
+        assert_eq!(
+            ops,
+            vec![
+                "create_memo",
+                "update_memo",
+                "update_memo_title",
+                "rename_memo",
+                "add_alias",
+                "remove_alias",
+                "set_tag_meta",
+                "trash_memo",
+                "untrash_memo",
+                "delete_memo",
+                "create_memo",
+                "sync_filename",
+                "sync_filename",
+                "trash_memo",
+                "purge_memo",
+            ]
+        );
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.actor.as_deref(), Some("agent"));
+            if record.op == "set_tag_meta" {
+                assert_eq!(record.tag.as_deref(), Some("rust"));
+                assert_eq!(record.id, None);
+            } else if i < 10 {
+                assert_eq!(record.id, Some(id));
+            } else {
+                assert_eq!(record.id, Some(drifted.id));
+            }
+        }
+        assert!(records.windows(2).all(|pair| pair[0].ts <= pair[1].ts));
+    }
 }
@@ -0,0 +1,348 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+use super::models::{names_match, Memo, MAX_TAG_LENGTH};
+use super::storage::{MemoStoreError, Result};
+use crate::config::Settings;
+
+/// File (inside the primary `.memoranda` directory) mapping tags to their metadata.
+pub const TAG_META_FILE_NAME: &str = ".tags.json";
+
+/// File (inside the primary `.memoranda` directory) mapping tag aliases to
+/// their canonical tags.
+pub const TAG_ALIASES_FILE_NAME: &str = ".tag-aliases.json";
+
+/// Presentation metadata for a tag, shared by every client of the store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl TagMeta {
+    pub fn is_empty(&self) -> bool {
+        self.color.is_none() && self.description.is_none()
+    }
+}
+
+/// Tag metadata keyed by tag, as stored in `.tags.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagMetaMap(BTreeMap<String, TagMeta>);
+
+impl TagMetaMap {
+    /// Reads `.tags.json` from `memoranda_dir`; a missing file is an empty map.
+    pub fn load(memoranda_dir: &Path) -> Result<Self> {
+        let path = memoranda_dir.join(TAG_META_FILE_NAME);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        serde_json::from_str(&text).map_err(|err| MemoStoreError::Validation {
+            message: format!("Invalid {}: {err}", path.display()),
+        })
+    }
+
+    pub fn save(&self, memoranda_dir: &Path) -> Result<()> {
+        let text =
+            serde_json::to_string_pretty(self).map_err(|err| MemoStoreError::Validation {
+                message: err.to_string(),
+            })?;
+        fs::write(memoranda_dir.join(TAG_META_FILE_NAME), text + "\n")?;
+        Ok(())
+    }
+
+    /// Looks up a tag's metadata, comparing tags case-insensitively.
+    pub fn get(&self, tag: &str) -> Option<&TagMeta> {
+        self.0
+            .iter()
+            .find(|(key, _)| names_match(key, tag))
+            .map(|(_, meta)| meta)
+    }
+
+    /// Replaces a tag's metadata, removing the entry when `meta` is empty.
+    /// An entry recorded under a different case of the tag is replaced too.
+    pub fn set(&mut self, tag: &str, meta: TagMeta) {
+        self.0.retain(|key, _| !names_match(key, tag));
+        if !meta.is_empty() {
+            self.0.insert(tag.to_string(), meta);
+        }
+    }
+}
+
+/// Canonical tag keyed by alias, as stored in `.tag-aliases.json`. Searching
+/// or filtering by a tag matches memos carrying the tag, its canonical tag, or
+/// any other alias of that canonical tag, without rewriting memo files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagAliasMap(BTreeMap<String, String>);
+
+impl TagAliasMap {
+    /// Reads `.tag-aliases.json` from `memoranda_dir`; a missing file is an
+    /// empty map.
+    pub fn load(memoranda_dir: &Path) -> Result<Self> {
+        let path = memoranda_dir.join(TAG_ALIASES_FILE_NAME);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        serde_json::from_str(&text).map_err(|err| MemoStoreError::Validation {
+            message: format!("Invalid {}: {err}", path.display()),
+        })
+    }
+
+    pub fn save(&self, memoranda_dir: &Path) -> Result<()> {
+        let text =
+            serde_json::to_string_pretty(self).map_err(|err| MemoStoreError::Validation {
+                message: err.to_string(),
+            })?;
+        fs::write(memoranda_dir.join(TAG_ALIASES_FILE_NAME), text + "\n")?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The canonical tag `tag` is an alias of, or `tag` itself, comparing
+    /// tags case-insensitively.
+    pub fn canonical<'a>(&'a self, tag: &'a str) -> &'a str {
+        self.0
+            .iter()
+            .find(|(alias, _)| names_match(alias, tag))
+            .map_or(tag, |(_, canonical)| canonical.as_str())
+    }
+
+    /// `tag`'s canonical tag followed by every alias of it: the tags a search
+    /// or filter by `tag` matches.
+    pub fn equivalents(&self, tag: &str) -> Vec<String> {
+        let canonical = self.canonical(tag);
+        let mut tags = vec![canonical.to_string()];
+        tags.extend(
+            self.0
+                .iter()
+                .filter(|(_, target)| names_match(target, canonical))
+                .map(|(alias, _)| alias.clone()),
+        );
+        tags
+    }
+
+    /// Whether `memo` carries `tag` or a tag equivalent to it.
+    pub fn memo_has_tag(&self, memo: &Memo, tag: &str) -> bool {
+        self.equivalents(tag).iter().any(|tag| memo.has_tag(tag))
+    }
+
+    /// Makes `alias` resolve to `canonical`, or removes the alias when
+    /// `canonical` is `None`. When `canonical` is itself an alias, its own
+    /// canonical tag is used, and aliases of `alias` are moved over to the
+    /// new canonical tag, so lookups never need to follow a chain.
+    pub fn set(&mut self, alias: &str, canonical: Option<&str>) {
+        self.0.retain(|key, _| !names_match(key, alias));
+        let Some(canonical) = canonical else {
+            return;
+        };
+        let canonical = self.canonical(canonical).to_string();
+        if names_match(&canonical, alias) {
+            return;
+        }
+        for target in self.0.values_mut() {
+            if names_match(target, alias) {
+                *target = canonical.clone();
+            }
+        }
+        self.0.insert(alias.to_string(), canonical);
+    }
+}
+
+/// The tags a store accepts, from the `max_tag_length`, `tag_charset` and
+/// `tag_auto_slugify` settings.
+#[derive(Debug, Clone)]
+pub struct TagRules {
+    max_length: usize,
+    /// The `tag_charset` pattern, and the regex matching whole tags against it.
+    charset: Option<(String, Regex)>,
+    auto_slugify: bool,
+}
+
+impl Default for TagRules {
+    fn default() -> Self {
+        Self {
+            max_length: MAX_TAG_LENGTH,
+            charset: None,
+            auto_slugify: false,
+        }
+    }
+}
+
+impl From<&Settings> for TagRules {
+    /// Settings validation rejects an invalid `tag_charset`, so one from
+    /// settings that were never validated is logged and ignored.
+    fn from(settings: &Settings) -> Self {
+        let charset = settings.tag_charset.as_ref().and_then(|pattern| {
+            match Regex::new(&format!("^(?:{pattern})$")) {
+                Ok(regex) => Some((pattern.clone(), regex)),
+                Err(e) => {
+                    warn!(pattern = %pattern, error = %e, "Ignoring invalid tag charset");
+                    None
+                }
+            }
+        });
+        Self {
+            max_length: settings.max_tag_length,
+            charset,
+            auto_slugify: settings.tag_auto_slugify,
+        }
+    }
+}
+
+impl TagRules {
+    /// Returns `tag` as it should be stored: trimmed, and slugified with
+    /// `tag_auto_slugify` set. Fails when the result is not a valid tag
+    /// within the length limit, or does not match `tag_charset` as a whole.
+    pub fn check(&self, tag: &str) -> Result<String> {
+        let tag = if self.auto_slugify {
+            slugify_tag(tag, self.max_length)
+        } else {
+            tag.trim().to_string()
+        };
+        Memo::validate_tag_within(&tag, self.max_length)?;
+        if let Some((pattern, regex)) = &self.charset {
+            if !regex.is_match(&tag) {
+                return Err(MemoStoreError::Validation {
+                    message: format!("Tag '{tag}' does not match the tag charset {pattern}"),
+                });
+            }
+        }
+        Ok(tag)
+    }
+}
+
+/// Lowercases `tag` and joins its words with hyphens, replacing runs of
+/// whitespace, commas, control characters and hyphens, then cuts it to
+/// `max_length` characters.
+pub fn slugify_tag(tag: &str, max_length: usize) -> String {
+    let mut slug = String::new();
+    for c in tag.chars().flat_map(char::to_lowercase) {
+        if c.is_whitespace() || c.is_control() || c == ',' || c == '-' {
+            if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        } else {
+            slug.push(c);
+        }
+    }
+    let slug: String = slug.chars().take(max_length).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tag_meta_round_trips_and_matches_case_insensitively() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(
+            TagMetaMap::load(temp_dir.path()).unwrap(),
+            TagMetaMap::default()
+        );
+
+        let mut map = TagMetaMap::default();
+        map.set(
+            "Rust",
+            TagMeta {
+                color: Some("#dea584".to_string()),
+                description: None,
+            },
+        );
+        map.save(temp_dir.path()).unwrap();
+
+        let loaded = TagMetaMap::load(temp_dir.path()).unwrap();
+        assert_eq!(
+            loaded.get("rust").unwrap().color.as_deref(),
+            Some("#dea584")
+        );
+
+        let mut loaded = loaded;
+        loaded.set("RUST", TagMeta::default());
+        assert_eq!(loaded, TagMetaMap::default());
+    }
+
+    #[test]
+    fn test_tag_aliases_resolve_without_chains() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut map = TagAliasMap::load(temp_dir.path()).unwrap();
+        assert!(map.is_empty());
+
+        map.set("js", Some("javascript"));
+        map.set("ecmascript", Some("JS"));
+        map.save(temp_dir.path()).unwrap();
+
+        let mut map = TagAliasMap::load(temp_dir.path()).unwrap();
+        assert_eq!(map.canonical("JS"), "javascript");
+        assert_eq!(map.canonical("ecmascript"), "javascript");
+        assert_eq!(map.canonical("rust"), "rust");
+        assert_eq!(
+            map.equivalents("js"),
+            vec!["javascript", "ecmascript", "js"]
+        );
+
+        // Aliasing a canonical tag moves its aliases along
+        map.set("javascript", Some("web"));
+        assert_eq!(map.canonical("js"), "web");
+        assert_eq!(map.canonical("ecmascript"), "web");
+
+        let mut memo = Memo::new("Guide".to_string(), "Body".to_string()).unwrap();
+        memo.tags = vec!["javascript".to_string()];
+        assert!(map.memo_has_tag(&memo, "js"));
+        assert!(map.memo_has_tag(&memo, "web"));
+        assert!(!map.memo_has_tag(&memo, "rust"));
+
+        map.set("javascript", None);
+        map.set("js", None);
+        map.set("ecmascript", None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_tag_rules_reject_or_slugify_invalid_tags() {
+        let mut settings = Settings {
+            max_tag_length: 12,
+            tag_charset: Some("[a-z0-9_-]+".to_string()),
+            ..Default::default()
+        };
+        let rules = TagRules::from(&settings);
+        assert_eq!(rules.check(" rust-lang ").unwrap(), "rust-lang");
+        for tag in ["Rust", "c++", "two words", "much-too-long-tag"] {
+            assert!(
+                matches!(rules.check(tag), Err(MemoStoreError::Validation { .. })),
+                "{tag}"
+            );
+        }
+        // The charset must match the whole tag, not just part of it
+        assert!(rules.check("ok!").is_err());
+
+        settings.tag_auto_slugify = true;
+        let rules = TagRules::from(&settings);
+        assert_eq!(rules.check("  Big  Thing ").unwrap(), "big-thing");
+        assert_eq!(rules.check("Release, 2 -- Final").unwrap(), "release-2-fi");
+        // Slugifying cannot fix characters outside the charset
+        assert!(rules.check("C++ Notes").is_err());
+        assert!(rules.check(" - ").is_err());
+
+        assert_eq!(slugify_tag("Ünïcode Tag", 64), "ünïcode-tag");
+        assert_eq!(
+            TagRules::default().check("Mixed.Case").unwrap(),
+            "Mixed.Case"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::compact::trashed_at;
+use super::models::MemoId;
+
+/// A memo sitting in a `.trash` directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrashedMemo {
+    pub id: MemoId,
+    pub title: String,
+    pub trashed_path: PathBuf,
+    /// Where the memo lived before it was trashed, and where untrashing puts it back.
+    pub original_path: PathBuf,
+    /// From the timestamped file name; `None` for files placed in `.trash` by hand.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trashed_at: Option<DateTime<Utc>>,
+}
+
+/// Reverses the naming done when a memo is trashed:
+/// `<dir>/.trash/<stem>.<timestamp>.<ext>`, or `<stem>.<timestamp> (N).<ext>`,
+/// came from `<dir>/<stem>.<ext>`.
+///
+/// Files without a trash timestamp are assumed to keep their original name.
+pub fn original_path(trashed_path: &Path) -> PathBuf {
+    let trash_dir = trashed_path.parent().unwrap_or_else(|| Path::new("."));
+    let memo_dir = trash_dir.parent().unwrap_or(trash_dir);
+    let file_name = trashed_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let restored_name = match (trashed_at(trashed_path), trashed_path.file_stem()) {
+        (Some(_), Some(stem)) => {
+            let stem = stem.to_string_lossy();
+            let (original_stem, _) = stem.rsplit_once('.').unwrap_or((&stem, ""));
+            match trashed_path.extension() {
+                Some(extension) => format!("{original_stem}.{}", extension.to_string_lossy()),
+                None => original_stem.to_string(),
+            }
+        }
+        _ => file_name,
+    };
+    memo_dir.join(restored_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_path_strips_trash_timestamp() {
+        assert_eq!(
+            original_path(Path::new(
+                "/repo/.memoranda/.trash/Notes v1.2.20240310T070000Z.md"
+            )),
+            PathBuf::from("/repo/.memoranda/Notes v1.2.md")
+        );
+        assert_eq!(
+            original_path(Path::new(
+                "/repo/.memoranda/.trash/Notes.20240310T070000Z (2).md"
+            )),
+            PathBuf::from("/repo/.memoranda/Notes.md")
+        );
+        assert_eq!(
+            original_path(Path::new("/repo/.memoranda/.trash/hand placed.md")),
+            PathBuf::from("/repo/.memoranda/hand placed.md")
+        );
+    }
+}
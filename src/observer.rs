@@ -0,0 +1,117 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::memo::{Memo, MemoId};
+
+/// Hooks for plugging custom telemetry into memoranda.
+///
+/// Every method has a no-op default, so implementors only override the events
+/// they care about. Observers are called synchronously at the point the event
+/// happens and should return quickly.
+pub trait EventObserver: Send + Sync {
+    /// Called after a memo has been written to disk for the first time.
+    fn on_memo_created(&self, _memo: &Memo) {}
+
+    /// Called after an existing memo has been rewritten.
+    fn on_memo_updated(&self, _memo: &Memo) {}
+
+    /// Called after a memo has been removed from the store.
+    fn on_memo_deleted(&self, _id: &MemoId) {}
+
+    /// Called after a search completes with the number of results returned.
+    fn on_search(&self, _query: &str, _result_count: usize) {}
+
+    /// Called after an MCP tool call completes, successfully or not.
+    fn on_tool_call(&self, _tool_name: &str, _duration: Duration, _success: bool) {}
+
+    /// Called when a store operation or tool call fails.
+    fn on_error(&self, _operation: &str, _error: &str) {}
+}
+
+impl fmt::Debug for dyn EventObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventObserver")
+    }
+}
+
+/// Observer that ignores every event.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopObserver;
+
+impl EventObserver for NoopObserver {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memo::MemoStore;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl EventObserver for RecordingObserver {
+        fn on_memo_created(&self, memo: &Memo) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("created:{}", memo.title));
+        }
+
+        fn on_search(&self, query: &str, result_count: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("search:{query}:{result_count}"));
+        }
+
+        fn on_error(&self, operation: &str, _error: &str) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("error:{operation}"));
+        }
+    }
+
+    #[test]
+    fn test_recording_observer_receives_create_and_search() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+
+        let observer = Arc::new(RecordingObserver::default());
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_observer(observer.clone());
+
+        store
+            .create_memo("Rust Notes".to_string(), "ownership".to_string())
+            .unwrap();
+        store.search_memos("ownership").unwrap();
+
+        let events = observer.events.lock().unwrap().clone();
+        assert_eq!(events, vec!["created:Rust Notes", "search:ownership:1"]);
+    }
+
+    #[test]
+    fn test_recording_observer_receives_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let observer = Arc::new(RecordingObserver::default());
+        let store = MemoStore::new(temp_dir.path().to_path_buf()).with_observer(observer.clone());
+
+        // No .memoranda directory, so creation fails
+        assert!(store
+            .create_memo("Title".to_string(), "content".to_string())
+            .is_err());
+
+        let events = observer.events.lock().unwrap().clone();
+        assert_eq!(events, vec!["error:create_memo"]);
+    }
+
+    #[test]
+    fn test_noop_observer() {
+        let observer: Arc<dyn EventObserver> = Arc::new(NoopObserver);
+        observer.on_search("query", 0);
+        assert_eq!(format!("{observer:?}"), "EventObserver");
+    }
+}
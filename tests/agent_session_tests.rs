@@ -0,0 +1,422 @@
+//! Golden snapshot of a complete MCP agent session.
+//!
+//! Drives every registered tool and the memo resources through `handle_message`
+//! and compares the responses, with volatile fields masked, against
+//! `tests/golden/agent_session.json`.
+//! Any change to a tool's JSON shape or schema fails this test.
+//!
+//! After an intentional change, regenerate the snapshot with:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test agent_session_tests
+//! ```
+
+use memoranda::mcp::server::McpServer;
+use memoranda::memo::MemoStore;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const GOLDEN_PATH: &str = "tests/golden/agent_session.json";
+
+/// Object keys whose values depend on the file system or clock rather than
+/// on the tool contract.
+const VOLATILE_KEYS: &[&str] = &[
+    "size",
+    "mtime",
+    "version",
+    "bytes_reclaimed",
+    "duration_ms",
+    "date",
+];
+
+/// Records each request and its masked response.
+struct Session {
+    server: McpServer,
+    initialized: bool,
+    next_id: u64,
+    masker: Masker,
+    steps: Vec<Value>,
+}
+
+impl Session {
+    async fn send(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": method,
+            "params": params
+        });
+        let response = self
+            .server
+            .handle_message(request.clone(), &mut self.initialized)
+            .await
+            .expect("every request in the session expects a response");
+
+        self.steps.push(json!({
+            "request": self.masker.mask(request),
+            "response": self.masker.mask(parse_tool_text(response.clone())),
+        }));
+        response
+    }
+
+    /// Calls a tool and returns its text result parsed as JSON, if it is JSON.
+    async fn call(&mut self, name: &str, arguments: Value) -> Value {
+        let response = self
+            .send(
+                "tools/call",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await;
+        assert!(
+            response.get("error").is_none(),
+            "{name} failed: {}",
+            response["error"]
+        );
+        let text = response["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap_or_default();
+        serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))
+    }
+
+    fn called_tools(&self) -> BTreeSet<String> {
+        self.steps
+            .iter()
+            .filter(|step| step["request"]["method"] == "tools/call")
+            .filter_map(|step| step["request"]["params"]["name"].as_str())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Replaces tool result text that holds JSON with the parsed value, so the
+/// snapshot shows the result's structure instead of one escaped string.
+fn parse_tool_text(mut response: Value) -> Value {
+    if let Some(content) = response.pointer_mut("/result/content/0/text") {
+        if let Some(parsed) = content
+            .as_str()
+            .and_then(|text| serde_json::from_str::<Value>(text).ok())
+        {
+            *content = parsed;
+        }
+    }
+    response
+}
+
+/// Masks ids, timestamps, and temporary paths that differ between runs.
+struct Masker {
+    roots: Vec<String>,
+    ulid: Regex,
+    timestamp: Regex,
+}
+
+impl Masker {
+    fn new(root: &Path) -> Self {
+        let mut roots = vec![root.display().to_string()];
+        if let Ok(canonical) = root.canonicalize() {
+            roots.push(canonical.display().to_string());
+        }
+        // Longest first so a canonical path containing the raw one is replaced whole
+        roots.sort_by_key(|root| std::cmp::Reverse(root.len()));
+
+        Self {
+            roots,
+            ulid: Regex::new(r"\b[0-9A-HJKMNP-TV-Z]{26}\b").unwrap(),
+            timestamp: Regex::new(
+                // RFC 3339 and display timestamps, plus the compact form in trash file names
+                r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(\.\d+)?( ?(Z|UTC|[+-]\d{2}:?\d{2}))?|\d{8}T\d{6}Z",
+            )
+            .unwrap(),
+        }
+    }
+
+    fn mask(&self, value: Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.mask_str(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.mask(v)).collect()),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let masked = if VOLATILE_KEYS.contains(&key.as_str()) && !value.is_null() {
+                            Value::String(format!("<{key}>"))
+                        } else {
+                            self.mask(value)
+                        };
+                        (key, masked)
+                    })
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    fn mask_str(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for root in &self.roots {
+            text = text.replace(root.as_str(), "<ROOT>");
+        }
+        let text = self.ulid.replace_all(&text, "<ULID>");
+        self.timestamp
+            .replace_all(&text, "<TIMESTAMP>")
+            .into_owned()
+    }
+}
+
+fn golden_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_PATH)
+}
+
+#[tokio::test]
+async fn test_agent_session_matches_golden_snapshot() -> anyhow::Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::create_dir(temp_dir.path().join(".memoranda"))?;
+    fs::create_dir(temp_dir.path().join(".git"))?;
+
+    let mut session = Session {
+        server: McpServer::new_with_memo_store(
+            "agent-session".to_string(),
+            MemoStore::new(temp_dir.path().to_path_buf()),
+        ),
+        initialized: false,
+        next_id: 0,
+        masker: Masker::new(temp_dir.path()),
+        steps: Vec::new(),
+    };
+
+    session
+        .send(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "agent-session-test", "version": "1.0.0" }
+            }),
+        )
+        .await;
+    let tools = session.send("tools/list", json!({})).await;
+    let registered: BTreeSet<String> = tools["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect();
+
+    let memo = session
+        .call(
+            "create_memo",
+            json!({
+                "title": "Error Handling",
+                "content": "Use anyhow for application errors and thiserror for library errors."
+            }),
+        )
+        .await;
+    let id = memo["id"].clone();
+    let scratch = session
+        .call(
+            "create_memo",
+            json!({ "title": "Scratch", "content": "Temporary notes" }),
+        )
+        .await;
+    session
+        .call(
+            "rename_memo",
+            json!({ "id": scratch["id"], "title": "Scratch Pad" }),
+        )
+        .await;
+    session
+        .call("sync_filename", json!({ "id": scratch["id"] }))
+        .await;
+
+    session
+        .call(
+            "update_memo",
+            json!({
+                "id": id,
+                "content": "Use anyhow for application errors and thiserror for library errors.\nSee src/main.rs for the top-level handler."
+            }),
+        )
+        .await;
+    session.call("list_memos", json!({})).await;
+    session.call("get_memo", json!({ "id": id })).await;
+    session.send("resources/list", json!({})).await;
+    session
+        .send(
+            "resources/read",
+            json!({ "uri": format!("memo://{}", id.as_str().unwrap()) }),
+        )
+        .await;
+    session
+        .call("search_memos", json!({ "query": "thiserror" }))
+        .await;
+    session.call("get_all_context", json!({})).await;
+    session
+        .call("memos_mentioning_path", json!({ "path": "src/main.rs" }))
+        .await;
+    session.call("get_memo_numbered", json!({ "id": id })).await;
+    session
+        .call(
+            "add_tags",
+            json!({ "id": id, "tags": ["errors", "rust", "Errors"] }),
+        )
+        .await;
+    session
+        .call("remove_tags", json!({ "id": id, "tags": ["RUST"] }))
+        .await;
+    session
+        .call("add_alias", json!({ "id": id, "alias": "errors" }))
+        .await;
+    session
+        .call("remove_alias", json!({ "id": id, "alias": "errors" }))
+        .await;
+    session
+        .call(
+            "link_path",
+            json!({ "from_id": id, "to_id": scratch["id"] }),
+        )
+        .await;
+    session.call("hub_memos", json!({})).await;
+    session
+        .call("link_graph", json!({ "include_unresolved": true }))
+        .await;
+
+    let begun = session
+        .call(
+            "begin_memo_write",
+            json!({ "title": "Chunked", "tags": ["large"] }),
+        )
+        .await;
+    let token = begun["token"].clone();
+    session
+        .call(
+            "write_memo_chunk",
+            json!({ "token": token, "data": "First part. " }),
+        )
+        .await;
+    session
+        .call(
+            "write_memo_chunk",
+            json!({ "token": token, "data": "Second part." }),
+        )
+        .await;
+    let chunked = session
+        .call("commit_memo_write", json!({ "token": token }))
+        .await;
+    let abandoned = session
+        .call("begin_memo_write", json!({ "title": "Abandoned" }))
+        .await;
+    session
+        .call("abort_memo_write", json!({ "token": abandoned["token"] }))
+        .await;
+
+    session.call("session_memos", json!({})).await;
+    session
+        .call("activity", json!({ "days": 1, "granularity": "day" }))
+        .await;
+    session.call("store_layout", json!({})).await;
+    session.call("check_duplicate_ids", json!({})).await;
+    session.call("verify_store", json!({})).await;
+    session.call("cache_consistency", json!({})).await;
+    session
+        .call("trash_memo", json!({ "id": scratch["id"] }))
+        .await;
+    session.call("list_trash", json!({})).await;
+    session
+        .call(
+            "set_tag_meta",
+            json!({ "tag": "deploy", "color": "#2f855a", "description": "Release work" }),
+        )
+        .await;
+    session
+        .call("get_tag_meta", json!({ "tag": "deploy" }))
+        .await;
+    session.call("list_tags", json!({})).await;
+    session
+        .call(
+            "set_tag_alias",
+            json!({ "alias": "big", "canonical": "large" }),
+        )
+        .await;
+    session
+        .call("search_memos", json!({ "query": "tag:big" }))
+        .await;
+    session
+        .call(
+            "context_for_tag",
+            json!({ "tag": "large", "max_bytes": 4096 }),
+        )
+        .await;
+    session
+        .call("untrash_memo", json!({ "id": scratch["id"] }))
+        .await;
+    session
+        .call("delete_memo", json!({ "id": scratch["id"] }))
+        .await;
+    session
+        .call("delete_memos", json!({ "ids": [chunked["id"]] }))
+        .await;
+    session
+        .call("compact_store", json!({ "confirm": true, "dry_run": true }))
+        .await;
+
+    let notes_dir = temp_dir.path().join("notes");
+    fs::create_dir(&notes_dir)?;
+    fs::write(
+        notes_dir.join("standup.md"),
+        "# Standup\nShipped the importer.\n",
+    )?;
+    session
+        .call(
+            "import_directory",
+            json!({ "path": "notes", "tag": "imported" }),
+        )
+        .await;
+    session
+        .call("export_memos", json!({ "path": "exports/memos.json" }))
+        .await;
+    session
+        .call(
+            "import_memos",
+            json!({ "path": "exports/memos.json", "strategy": "skip_existing" }),
+        )
+        .await;
+    session.call("server_status", json!({})).await;
+    session.call("call_history", json!({})).await;
+    session.call("diagnostics", json!({})).await;
+    session
+        .call("validate_regex", json!({ "pattern": "fn\\s+\\w+" }))
+        .await;
+
+    let called = session.called_tools();
+    let uncovered: Vec<_> = registered.difference(&called).collect();
+    assert!(
+        uncovered.is_empty(),
+        "registered tools missing from the session: {uncovered:?}"
+    );
+
+    let actual = Value::Array(session.steps);
+    let golden_path = golden_path();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(golden_path.parent().unwrap())?;
+        fs::write(&golden_path, serde_json::to_string_pretty(&actual)? + "\n")?;
+        return Ok(());
+    }
+
+    let expected: Value = serde_json::from_str(&fs::read_to_string(&golden_path)?)?;
+    let expected_steps = expected.as_array().unwrap();
+    let actual_steps = actual.as_array().unwrap();
+    for (expected_step, actual_step) in expected_steps.iter().zip(actual_steps) {
+        assert_eq!(
+            actual_step, expected_step,
+            "response for {} changed; rerun with UPDATE_GOLDEN=1 if intended",
+            expected_step["request"]
+        );
+    }
+    assert_eq!(actual_steps.len(), expected_steps.len());
+
+    Ok(())
+}
@@ -138,6 +138,76 @@ fn test_cli_serve_help() {
         .stdout(predicate::str::contains("Start the MCP server"));
 }
 
+#[test]
+fn test_cli_maintain_help() {
+    let mut cmd = Command::cargo_bin("memoranda").unwrap();
+    cmd.arg("maintain")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Apply the memo retention policy"))
+        .stdout(predicate::str::contains("--dry-run"));
+}
+
+#[test]
+fn test_cli_maintain_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join(".git")).unwrap();
+    fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+
+    let mut cmd = Command::cargo_bin("memoranda").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("maintain")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Memoranda Maintenance - Retention Policy",
+        ))
+        .stdout(predicate::str::contains("Scanned 0 memo(s)"));
+}
+
+#[test]
+fn test_cli_import_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join(".git")).unwrap();
+    fs::create_dir(temp_dir.path().join(".memoranda")).unwrap();
+    let notes_dir = temp_dir.path().join("notes");
+    fs::create_dir(&notes_dir).unwrap();
+    fs::write(notes_dir.join("plain.md"), "# Plain\nNo frontmatter").unwrap();
+    fs::write(
+        notes_dir.join("tagged.md"),
+        "---\ntitle: Tagged\ntags: [work]\n---\nWith frontmatter",
+    )
+    .unwrap();
+    fs::write(notes_dir.join("empty.md"), "").unwrap();
+
+    let mut cmd = Command::cargo_bin("memoranda").unwrap();
+    cmd.current_dir(temp_dir.path())
+        .arg("import-dir")
+        .arg("notes")
+        .arg("--tag")
+        .arg("imported")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Memoranda Import"))
+        .stdout(predicate::str::contains("2 imported, 1 skipped, 0 failed"));
+
+    let memo_files = fs::read_dir(temp_dir.path().join(".memoranda"))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .path()
+                .extension()
+                .and_then(|s| s.to_str())
+                == Some("md")
+        })
+        .count();
+    assert_eq!(memo_files, 2);
+}
+
 #[test]
 fn test_cli_doctor_in_temporary_directory() {
     let temp_dir = TempDir::new().unwrap();
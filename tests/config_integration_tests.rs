@@ -28,6 +28,7 @@ fn test_settings_with_extreme_values() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_ok());
 
@@ -38,6 +39,7 @@ fn test_settings_with_extreme_values() {
         mcp_server_port: 1024, // Minimum valid port
         minimum_rust_version: "1.0.0".to_string(),
         max_memo_file_size: 1,
+        memo_size_warn_threshold: 1,
         search_recency_boost_days: 365.0,
         search_snippet_length: 100,
         search_snippet_context_padding: 2,
@@ -50,6 +52,7 @@ fn test_settings_with_extreme_values() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_ok());
 }
@@ -75,6 +78,7 @@ fn test_settings_validation_edge_cases() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_err());
 
@@ -97,6 +101,7 @@ fn test_settings_validation_edge_cases() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_ok());
 }
@@ -125,6 +130,7 @@ fn test_settings_rust_version_validation() {
                 "search_memos".to_string(),
                 "get_all_context".to_string(),
             ],
+            ..Default::default()
         };
         assert!(
             settings.validate().is_ok(),
@@ -171,6 +177,7 @@ fn test_settings_rust_version_validation() {
                 "search_memos".to_string(),
                 "get_all_context".to_string(),
             ],
+            ..Default::default()
         };
         assert!(
             settings.validate().is_err(),
@@ -205,6 +212,7 @@ fn test_settings_log_level_validation() {
                 "search_memos".to_string(),
                 "get_all_context".to_string(),
             ],
+            ..Default::default()
         };
         assert!(
             settings.validate().is_ok(),
@@ -231,6 +239,7 @@ fn test_settings_log_level_validation() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_err());
 
@@ -253,6 +262,7 @@ fn test_settings_log_level_validation() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     // Note: Current implementation doesn't trim whitespace, so this passes validation
     // This test documents the current behavior
@@ -280,6 +290,7 @@ fn test_settings_file_operations_with_unicode() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     // Save and load settings with unicode paths
@@ -449,6 +460,7 @@ fn test_settings_load_from_large_file() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     large_settings.save_to_file(&path).unwrap();
@@ -476,6 +488,7 @@ fn test_settings_serialization_roundtrip() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     // Serialize to JSON
@@ -515,6 +528,7 @@ fn test_settings_validation_with_realistic_scenarios() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(dev_settings.validate().is_ok());
 
@@ -537,6 +551,7 @@ fn test_settings_validation_with_realistic_scenarios() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(prod_settings.validate().is_ok());
 
@@ -559,6 +574,7 @@ fn test_settings_validation_with_realistic_scenarios() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(low_resource_settings.validate().is_ok());
 }
@@ -597,6 +613,7 @@ fn test_settings_concurrent_file_operations() {
                     "search_memos".to_string(),
                     "get_all_context".to_string(),
                 ],
+                ..Default::default()
             };
 
             let path = temp_dir.join(format!("settings-{i}.json"));
@@ -638,6 +655,7 @@ fn test_settings_path_handling_edge_cases() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_ok());
 
@@ -660,6 +678,7 @@ fn test_settings_path_handling_edge_cases() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_ok());
 
@@ -682,6 +701,7 @@ fn test_settings_path_handling_edge_cases() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
     assert!(settings.validate().is_ok());
 }
@@ -707,6 +727,7 @@ fn test_settings_error_message_quality() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     match settings.validate() {
@@ -737,6 +758,7 @@ fn test_settings_error_message_quality() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     match settings.validate() {
@@ -785,6 +807,7 @@ fn test_settings_property_based_validation() {
                 "search_memos".to_string(),
                 "get_all_context".to_string(),
             ],
+            ..Default::default()
         };
 
         assert!(settings.validate().is_ok());
@@ -814,6 +837,7 @@ fn test_settings_advanced_error_scenarios() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     let result = settings.validate();
@@ -853,6 +877,7 @@ fn test_settings_advanced_error_scenarios() {
                 "search_memos".to_string(),
                 "get_all_context".to_string(),
             ],
+            ..Default::default()
         };
 
         let result = settings.validate();
@@ -883,6 +908,7 @@ fn test_settings_advanced_error_scenarios() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     let result = settings.validate();
@@ -909,6 +935,7 @@ fn test_settings_advanced_error_scenarios() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     let result = settings.validate();
@@ -1019,6 +1046,7 @@ fn test_settings_edge_case_values() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     assert!(settings.validate().is_ok());
@@ -1030,6 +1058,7 @@ fn test_settings_edge_case_values() {
         mcp_server_port: 1024,                     // Minimum valid port
         minimum_rust_version: "0.1.0".to_string(), // Very early version
         max_memo_file_size: 1,                     // Minimum file size
+        memo_size_warn_threshold: 1,               // At most the file size
         search_recency_boost_days: 365.0,
         search_snippet_length: 100,
         search_snippet_context_padding: 2,
@@ -1042,6 +1071,7 @@ fn test_settings_edge_case_values() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     assert!(settings.validate().is_ok());
@@ -1065,6 +1095,7 @@ fn test_settings_edge_case_values() {
             "search_memos".to_string(),
             "get_all_context".to_string(),
         ],
+        ..Default::default()
     };
 
     assert!(settings.validate().is_ok());
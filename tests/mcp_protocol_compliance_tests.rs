@@ -187,17 +187,54 @@ async fn test_tool_discovery_and_schema_validation() -> anyhow::Result<()> {
     let tools = result.get("tools").unwrap().as_array().unwrap();
 
     // Should have all expected tools
-    assert_eq!(tools.len(), 7);
+    assert_eq!(tools.len(), 44);
 
     // Verify each tool has proper schema
     let expected_tools = [
         "create_memo",
         "update_memo",
+        "rename_memo",
+        "sync_filename",
         "list_memos",
         "get_memo",
         "delete_memo",
         "search_memos",
         "get_all_context",
+        "memos_mentioning_path",
+        "get_memo_numbered",
+        "delete_memos",
+        "compact_store",
+        "import_directory",
+        "export_memos",
+        "import_memos",
+        "add_tags",
+        "remove_tags",
+        "add_alias",
+        "remove_alias",
+        "verify_store",
+        "store_layout",
+        "cache_consistency",
+        "check_duplicate_ids",
+        "begin_memo_write",
+        "write_memo_chunk",
+        "commit_memo_write",
+        "abort_memo_write",
+        "call_history",
+        "diagnostics",
+        "session_memos",
+        "trash_memo",
+        "untrash_memo",
+        "list_trash",
+        "list_tags",
+        "get_tag_meta",
+        "set_tag_meta",
+        "set_tag_alias",
+        "context_for_tag",
+        "link_path",
+        "hub_memos",
+        "link_graph",
+        "activity",
+        "validate_regex",
     ];
 
     for tool in tools {
@@ -227,7 +264,8 @@ async fn test_tool_discovery_and_schema_validation() -> anyhow::Result<()> {
                 assert!(required.contains(&json!("title")));
                 assert!(required.contains(&json!("content")));
             }
-            "get_memo" | "update_memo" | "delete_memo" => {
+            "get_memo" | "update_memo" | "delete_memo" | "get_memo_numbered" | "trash_memo"
+            | "untrash_memo" | "sync_filename" => {
                 let required = input_schema.get("required").unwrap().as_array().unwrap();
                 assert!(required.contains(&json!("id")));
             }
@@ -235,7 +273,81 @@ async fn test_tool_discovery_and_schema_validation() -> anyhow::Result<()> {
                 let required = input_schema.get("required").unwrap().as_array().unwrap();
                 assert!(required.contains(&json!("query")));
             }
-            "list_memos" | "get_all_context" => {
+            "memos_mentioning_path" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("path")));
+            }
+            "delete_memos" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("ids")));
+            }
+            "rename_memo" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("id")));
+                assert!(required.contains(&json!("title")));
+            }
+            "add_alias" | "remove_alias" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("id")));
+                assert!(required.contains(&json!("alias")));
+            }
+            "add_tags" | "remove_tags" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("id")));
+                assert!(required.contains(&json!("tags")));
+            }
+            "begin_memo_write" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("title")));
+            }
+            "write_memo_chunk" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("token")));
+                assert!(required.contains(&json!("data")));
+            }
+            "commit_memo_write" | "abort_memo_write" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("token")));
+            }
+            "context_for_tag" | "get_tag_meta" | "set_tag_meta" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("tag")));
+            }
+            "set_tag_alias" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("alias")));
+            }
+            "link_path" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("from_id")));
+                assert!(required.contains(&json!("to_id")));
+            }
+            "validate_regex" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("pattern")));
+            }
+            "compact_store" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("confirm")));
+            }
+            "import_directory" | "export_memos" | "import_memos" => {
+                let required = input_schema.get("required").unwrap().as_array().unwrap();
+                assert!(required.contains(&json!("path")));
+            }
+            "list_memos"
+            | "get_all_context"
+            | "verify_store"
+            | "cache_consistency"
+            | "store_layout"
+            | "check_duplicate_ids"
+            | "call_history"
+            | "diagnostics"
+            | "session_memos"
+            | "list_trash"
+            | "list_tags"
+            | "hub_memos"
+            | "link_graph"
+            | "activity" => {
                 // These tools don't require parameters
                 let required = input_schema.get("required").unwrap().as_array().unwrap();
                 assert!(required.is_empty());
@@ -451,6 +563,7 @@ async fn test_protocol_version_compliance() -> anyhow::Result<()> {
         .unwrap()
         .as_bool()
         .unwrap());
+    assert!(capabilities.get("resources").is_some());
 
     Ok(())
 }
@@ -538,3 +651,182 @@ async fn test_concurrent_message_handling() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Writes a memo file with tags directly into a `.memoranda` directory
+fn write_tagged_memo(dir: &std::path::Path, title: &str, content: &str, tags: &[&str]) {
+    let mut memo = memoranda::memo::Memo::new(title.to_string(), content.to_string()).unwrap();
+    memo.tags = tags.iter().map(|tag| tag.to_string()).collect();
+    let frontmatter = serde_json::to_string_pretty(&memo).unwrap();
+    fs::write(
+        dir.join(format!("{title}.md")),
+        format!("---\n{frontmatter}\n---\n{content}"),
+    )
+    .unwrap();
+}
+
+/// Calls get_all_context over JSON-RPC and returns the text content
+async fn call_get_all_context(server: &mut McpServer, arguments: Value) -> String {
+    let tools_call_msg = json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": {
+            "name": "get_all_context",
+            "arguments": arguments
+        }
+    });
+
+    let mut initialized = true;
+    let response = server
+        .handle_message(tools_call_msg, &mut initialized)
+        .await
+        .unwrap();
+    assert!(response.get("error").is_none(), "{response}");
+
+    response["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// Test get_all_context tag and directory scoping
+#[tokio::test]
+async fn test_get_all_context_scoping() -> anyhow::Result<()> {
+    let (mut server, temp_dir) = create_test_server()?;
+    let root_dir = temp_dir.path().join(".memoranda");
+    let nested_dir = temp_dir.path().join("packages/web/.memoranda");
+    fs::create_dir_all(&nested_dir)?;
+
+    write_tagged_memo(&root_dir, "Release Notes", "release body", &["Release"]);
+    write_tagged_memo(&root_dir, "Root Styling", "root styling body", &["css"]);
+    write_tagged_memo(&nested_dir, "Web Styling", "web styling body", &["CSS"]);
+
+    let context = call_get_all_context(&mut server, json!({ "tags": ["css"] })).await;
+    assert!(context.contains("# Root Styling"));
+    assert!(context.contains("# Web Styling"));
+    assert!(!context.contains("# Release Notes"));
+
+    let context = call_get_all_context(&mut server, json!({ "directory": "packages/web" })).await;
+    assert!(context.contains("# Web Styling"));
+    assert!(!context.contains("# Root Styling"));
+    assert!(!context.contains("# Release Notes"));
+
+    let context = call_get_all_context(
+        &mut server,
+        json!({ "tags": ["release"], "directory": "packages\\web" }),
+    )
+    .await;
+    assert_eq!(context, memoranda::memo::NO_MATCHING_MEMOS);
+
+    let context = call_get_all_context(&mut server, json!({})).await;
+    assert!(context.contains("# Release Notes"));
+    assert!(context.contains("# Root Styling"));
+    assert!(context.contains("# Web Styling"));
+
+    Ok(())
+}
+
+/// Initializes a fresh server over JSON-RPC as the named client
+async fn initialize_as(temp_dir: &TempDir, client_name: &str) -> McpServer {
+    let mut server = McpServer::new_with_memo_store(
+        "test-server".to_string(),
+        MemoStore::new(temp_dir.path().to_path_buf()),
+    );
+    let initialize_msg = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": { "name": client_name, "version": "1.0.0" }
+        }
+    });
+
+    let mut initialized = false;
+    server
+        .handle_message(initialize_msg, &mut initialized)
+        .await
+        .unwrap();
+    assert!(initialized);
+    server
+}
+
+/// Calls a tool over JSON-RPC and returns the full response
+async fn call_tool(server: &mut McpServer, name: &str, arguments: Value) -> Value {
+    let tools_call_msg = json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": { "name": name, "arguments": arguments }
+    });
+
+    let mut initialized = true;
+    server
+        .handle_message(tools_call_msg, &mut initialized)
+        .await
+        .unwrap()
+}
+
+/// Test per-client tool policy enforcement
+#[tokio::test]
+async fn test_client_tool_policy() -> anyhow::Result<()> {
+    let (_server, temp_dir) = create_test_server()?;
+    fs::write(
+        temp_dir.path().join(".memoranda/policy.json"),
+        json!({
+            "default": "read",
+            "clients": {
+                "experimental-agent": ["list_memos", "get_memo", "search_memos", "create_memo"],
+                "main-session": "admin"
+            }
+        })
+        .to_string(),
+    )?;
+    let create_args = json!({ "title": "Agent Note", "content": "from the agent" });
+
+    let mut agent = initialize_as(&temp_dir, "experimental-agent").await;
+    let response = call_tool(&mut agent, "create_memo", create_args.clone()).await;
+    assert!(response.get("error").is_none(), "{response}");
+    let response = call_tool(&mut agent, "delete_memos", json!({ "ids": [] })).await;
+    assert_eq!(response["error"]["code"], -32003, "{response}");
+    assert!(response["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("forbidden by policy"));
+
+    let response = call_tool(&mut agent, "server_status", json!({})).await;
+    let status: Value =
+        serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())?;
+    assert_eq!(status["client_name"], "experimental-agent");
+    assert_eq!(status["effective_role"], "custom");
+
+    let mut main = initialize_as(&temp_dir, "main-session").await;
+    let response = call_tool(&mut main, "verify_store", json!({})).await;
+    assert!(response.get("error").is_none(), "{response}");
+
+    let mut other = initialize_as(&temp_dir, "someone-else").await;
+    let response = call_tool(&mut other, "list_memos", json!({})).await;
+    assert!(response.get("error").is_none(), "{response}");
+    let response = call_tool(&mut other, "create_memo", create_args).await;
+    assert_eq!(response["error"]["code"], -32003, "{response}");
+
+    Ok(())
+}
+
+/// Test that a server without a policy file allows every tool
+#[tokio::test]
+async fn test_missing_policy_allows_all_tools() -> anyhow::Result<()> {
+    let (_server, temp_dir) = create_test_server()?;
+    let mut server = initialize_as(&temp_dir, "any-client").await;
+
+    let response = call_tool(&mut server, "verify_store", json!({})).await;
+    assert!(response.get("error").is_none(), "{response}");
+
+    let response = call_tool(&mut server, "server_status", json!({})).await;
+    let status: Value =
+        serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap())?;
+    assert_eq!(status["effective_role"], "admin");
+
+    Ok(())
+}